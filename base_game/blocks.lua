@@ -395,8 +395,72 @@ function register_blocks()
             false,
             -- clickable
             false,
+            -- inventory_slots: input, fuel, output
+            {{-35, 150}, {-35, 225}, {35, 185}}
+    )
+
+    blocks.bed = terralistic_register_block_type(
+            -- effective_tool
+            tools.axe,
+            -- required_tool_power
+            0,
+            -- ghost
+            true,
+            -- transparent
+            true,
+            -- name
+            "bed",
+            -- connects_to
+            {},
+            -- break_time
+            1000,
+            -- light_emission
+            0, 0, 0,
+            -- size
+            2, 1,
+            -- can_update_states
+            false,
+            -- feet_collidable
+            false,
+            -- clickable
+            true,
             -- inventory_slots
-            {{0, 150}, {0, 210}}
+            {}
+    )
+    -- right clicking a bed sets the spawn point of the player
+    terralistic_set_block_spawn_point(blocks.bed)
+
+    -- GRAVE, holds the items of a player who died when the death mode is grave
+    blocks.grave = terralistic_register_block_type(
+            -- effective_tool
+            nil,
+            -- required_tool_power
+            0,
+            -- ghost
+            true,
+            -- transparent
+            true,
+            -- name
+            "grave",
+            -- connects_to
+            {},
+            -- break_time
+            1000,
+            -- light_emission
+            0, 0, 0,
+            -- size
+            1, 1,
+            -- can_update_states
+            false,
+            -- feet_collidable
+            false,
+            -- clickable
+            false,
+            -- inventory_slots: two rows of ten, like the player's inventory
+            {
+                {-300, 150}, {-240, 150}, {-180, 150}, {-120, 150}, {-60, 150}, {0, 150}, {60, 150}, {120, 150}, {180, 150}, {240, 150},
+                {-300, 210}, {-240, 210}, {-180, 210}, {-120, 210}, {-60, 210}, {0, 210}, {60, 210}, {120, 210}, {180, 210}, {240, 210},
+            }
     )
 end
 
@@ -75,8 +75,11 @@ function register_items()
             -- tool
             tools.axe,
             -- tool_power
-            10
+            10,
+            -- max_durability
+            200
     )
+    terralistic_set_item_weapon(items.hatchet, { damage = 4, reach = 2.5, cooldown = 500 })
 
     -- HAMMER
     items.hammer = terralistic_register_item_type(
@@ -93,7 +96,9 @@ function register_items()
             -- tool
             tools.hammer,
             -- tool_power
-            10
+            10,
+            -- max_durability
+            200
     )
     
     -- PICKAXE
@@ -111,7 +116,9 @@ function register_items()
             -- tool
             tools.pickaxe,
             -- tool_power
-            10
+            10,
+            -- max_durability
+            200
     )
     
     -- SHOVEL
@@ -129,7 +136,9 @@ function register_items()
             -- tool
             tools.shovel,
             -- tool_power
-            10
+            10,
+            -- max_durability
+            200
     )
 
     -- BRANCH
@@ -169,6 +178,8 @@ function register_items()
             0
     )
     terralistic_set_block_drop(blocks.wood, items.wood_planks, 1)
+    -- wood cut with a hatchet sometimes also drops a branch
+    terralistic_add_block_loot(blocks.wood, 1, { { item = items.branch, weight = 1, tool = tools.axe }, { weight = 4 } })
     terralistic_set_block_drop(blocks.wood_planks, items.wood_planks, 1)
 
     -- WOOD PLANK WALL
@@ -188,6 +199,7 @@ function register_items()
             -- tool_power
             0
     )
+    terralistic_set_wall_drop(walls.wood_planks, items.wood_plank_wall, 1)
 
     -- TORCH
     items.torch = terralistic_register_item_type(
@@ -264,4 +276,23 @@ function register_items()
             0
     )
     terralistic_set_block_drop(blocks.furnace, items.furnace, 1)
+
+    -- BED
+    items.bed = terralistic_register_item_type(
+            -- name
+            "bed",
+            -- display_name
+            "Bed",
+            -- max_stack
+            1,
+            -- places_block
+            blocks.bed,
+            -- places_wall
+            nil,
+            -- tool
+            nil,
+            -- tool_power
+            0
+    )
+    terralistic_set_block_drop(blocks.bed, items.bed, 1)
 end
\ No newline at end of file
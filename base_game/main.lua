@@ -20,13 +20,39 @@ function init()
     register_walls()
     register_items()
     register_recipes()
+    register_mobs()
 
     terralistic_print("base_game mod loaded.")
 end
 
 -- This function is called when the mod is loaded on a server.
+-- Commands can be registered here with terralistic_register_command(name, description, function),
+-- the function gets the name of the player who executed the command, or nil for the console,
+-- and a table of the words after the command, and returns the text shown to the executor.
+-- Functions can be subscribed to events here with terralistic_on(event_name, function),
+-- the function gets a table with the fields of the event:
+--   player_join, player_leave: player
+--   chat: player, message
+--   block_place: player, x, y, block
+--   block_change: x, y, block, previous_block
+--   item_pickup: player, item, count
+--   health_change: entity, player or mob, health, max_health
+--   entity_death: entity, player or mob
+--   tick: delta_time in milliseconds
+-- Chat messages and block placement are cancelled by setting the cancelled field to true.
 function init_server()
     register_biomes()
+    register_mob_spawns()
+
+    -- players slowly heal, one health point every five seconds
+    terralistic_set_stat_regen(terralistic_get_stat_id_by_name("health"), 0.2)
+end
+
+-- This function is called when the mod is loaded on a client.
+-- Key bindings can be registered here with terralistic_register_key_binding(action, text, key),
+-- the key can then be changed in the controls settings and on_key_press(action) is called when it is pressed.
+function init_client()
+
 end
 
 -- This function is called when the mod is unloaded.
@@ -34,7 +60,8 @@ function stop()
     terralistic_print("base_game mod stopped.")
 end
 
--- This function is called every frame.
+-- This function is called every frame, mods that don't need it can leave it out
+-- and the server side can subscribe to the tick event instead.
 function update()
 
 end
@@ -0,0 +1,36 @@
+mobs = {}
+
+function register_mobs()
+    -- SLIME
+    mobs.slime = terralistic_register_mob_type({
+        name = "slime",
+        width = 1.5,
+        height = 1,
+        max_health = 20,
+        speed = 4,
+        behaviour = "follow_player",
+        sight_range = 12,
+        damage = 2,
+        ai_function = "slime_ai",
+    })
+    terralistic_add_mob_loot(mobs.slime, 1, {
+        { item = items.fiber, min_count = 1, max_count = 2, weight = 3 },
+        { weight = 1 },
+    })
+end
+
+-- spawn rules use biomes, which only exist on the server
+function register_mob_spawns()
+    terralistic_add_mob_spawn_rule(mobs.slime, {
+        biomes = { biomes.plains, biomes.hills },
+        min_light = 150,
+        time = "day",
+        chance = 0.3,
+        max_count = 5,
+    })
+end
+
+-- slimes hop around, the built-in behaviour decides where they go
+function slime_ai(x, y, player_x, player_y)
+    return nil, math.random() < 0.05
+end
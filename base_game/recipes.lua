@@ -24,4 +24,17 @@ function register_recipes()
     
     -- STONE_BLOCKS TO FURNACE
     terralistic_register_recipe(items.furnace, 1, { items.stone_block }, { 10 })
+
+    -- WOOD_PLANKS AND FIBER TO BED
+    terralistic_register_recipe(items.bed, 1, { items.wood_planks, items.fiber }, { 8, 5 })
+
+    -- FURNACE: slot 0 is input, slot 2 is output and slot 1 is fuel
+    terralistic_register_processing_block(blocks.furnace, 0, 2, 1)
+
+    -- FUELS, burn time in ms
+    terralistic_register_fuel(items.wood_planks, 8000)
+    terralistic_register_fuel(items.branch, 4000)
+
+    -- STONE TO STONE_BLOCK IN FURNACE, takes 4 seconds
+    terralistic_register_processing_recipe(blocks.furnace, items.stone_block, 1, items.stone, 2, 4000)
 end
\ No newline at end of file
@@ -1,6 +1,6 @@
 #![allow(clippy::all)]
 
-use crate::build_project::compile_mod::compile_mod;
+use crate::build_project::compile_mod::{compile_mod, compress_mod};
 use crate::build_project::compile_resource_pack::compile_resource_pack;
 #[cfg(target_os = "windows")]
 use winres::WindowsResource;
@@ -34,5 +34,19 @@ fn main() {
     compile_resource_pack(std::path::PathBuf::from("resources"), std::path::PathBuf::from("Build/Resources"));
 
     // compile mod base_game
-    compile_mod(std::path::PathBuf::from("base_game"));
+    build_mod(std::path::PathBuf::from("base_game"));
+}
+
+/// This function compiles the mod in the directory and writes it to
+/// a file that has the same name as the directory and a .mod extension.
+fn build_mod(mod_path: std::path::PathBuf) {
+    // make sure that cargo reruns this script if the input mod changes (or any of its files)
+    println!("cargo:rerun-if-changed={}", mod_path.to_str().unwrap());
+    for entry in std::fs::read_dir(mod_path.clone()).unwrap() {
+        let path = entry.unwrap().path();
+        println!("cargo:rerun-if-changed={}", path.to_str().unwrap());
+    }
+
+    let mod_bytes = compress_mod(&compile_mod(&mod_path).unwrap()).unwrap();
+    std::fs::write(mod_path.join(format!("{}.mod", mod_path.file_name().unwrap().to_str().unwrap())), mod_bytes).unwrap();
 }
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::Path;
 
+use anyhow::{anyhow, bail, Result};
 use darklua_core::generator::{DenseLuaGenerator, LuaGenerator};
 use darklua_core::Parser;
 
@@ -10,107 +11,113 @@ use crate::libraries::graphics as gfx;
 use crate::shared::mod_manager::GameMod;
 
 /// This function compiles a game mod from a directory.
-/// It takes the path to the directory as input.
-pub fn compile_mod(mod_path: PathBuf) {
-    // make sure that cargo reruns this script if the input mod changes (or any of its files)
-    println!("cargo:rerun-if-changed={}", mod_path.to_str().unwrap());
-    for entry in std::fs::read_dir(mod_path.clone()).unwrap() {
-        let path = entry.unwrap().path();
-        println!("cargo:rerun-if-changed={}", path.to_str().unwrap());
+/// It takes the path to the directory as input. It is used by the build
+/// script for the base game and by the server to reload mods in dev mode.
+pub fn compile_mod(mod_path: &Path) -> Result<GameMod> {
+    // all .lua files in the mod directory are joined in alphabetical order
+    let mut lua_files = Vec::new();
+    for entry in std::fs::read_dir(mod_path)? {
+        let path = entry?.path();
+        if path.extension() == Some(OsStr::new("lua")) {
+            lua_files.push(path);
+        }
     }
+    lua_files.sort();
 
     let mut lua_code = String::new();
-    // iterate through all files in the mod directory
-    // if the file ends with .lua then add it to the lua code
-    for entry in std::fs::read_dir(mod_path.clone()).unwrap() {
-        let path = entry.unwrap().path();
-        if path.extension().unwrap_or(OsStr::new("")) == "lua" {
-            lua_code.push_str(&std::fs::read_to_string(path).unwrap());
-            lua_code.push('\n');
-        }
+    for path in lua_files {
+        lua_code.push_str(&std::fs::read_to_string(path)?);
+        lua_code.push('\n');
     }
 
     //Use darklua to minify the mod's lua code. Use DenseLuaGenerator to generate the minified code.
     let parser = Parser::default();
-    let block = std::thread::Builder::new()
+    let parse_thread = std::thread::Builder::new()
         .stack_size(100_000_000)
-        .spawn(move || parser.parse(&lua_code))
-        .unwrap()
-        .join()
-        .unwrap()
-        .unwrap();
+        .spawn(move || parser.parse(&lua_code).map_err(|error| anyhow!("could not parse lua code: {error}")))?;
+    let Ok(block) = parse_thread.join() else {
+        bail!("lua parser crashed");
+    };
+    let block = block?;
 
     let mut generator = DenseLuaGenerator::default();
     generator.write_block(&block);
 
     let minified_lua_code = generator.into_string();
-    let resources = generate_resources(mod_path.join("resources"), String::new());
-    let mod_obj = GameMod::new(minified_lua_code, resources);
+    // mods don't need to have any resources
+    let resources_path = mod_path.join("resources");
+    let resources = if resources_path.exists() { generate_resources(&resources_path, "")? } else { HashMap::new() };
+    Ok(GameMod::new(minified_lua_code, resources))
+}
 
-    // serialize the mod to a byte array
-    let mod_bytes = bincode::serialize(&mod_obj).unwrap();
-    // compress the mod with snap
-    let mod_bytes = snap::raw::Encoder::new().compress_vec(&mod_bytes).unwrap();
+/// This function serializes the mod and compresses it with snap,
+/// that is how mods are stored in .mod files and sent to the server.
+pub fn compress_mod(game_mod: &GameMod) -> Result<Vec<u8>> {
+    Ok(snap::raw::Encoder::new().compress_vec(&bincode::serialize(game_mod)?)?)
+}
 
-    // write the mod to a file that has the same name as the mod's directory and a .mod extension
-    std::fs::write(mod_path.join(format!("{}.mod", mod_path.file_name().unwrap().to_str().unwrap())), mod_bytes).unwrap();
+/// This function decompresses and deserializes a mod, every call creates it with a fresh lua state.
+pub fn decompress_mod(mod_bytes: &[u8]) -> Result<GameMod> {
+    Ok(bincode::deserialize(&snap::raw::Decoder::new().decompress_vec(mod_bytes)?)?)
 }
 
 /// This function takes the resources folder, goes through all of the recursively,
 /// changes the file paths to use : instead of / and adds the files to a map.
-fn generate_resources(resources_path: PathBuf, prefix: String) -> HashMap<String, Vec<u8>> {
-    println!("Generating resource pack... {}", resources_path.to_str().unwrap());
-
+fn generate_resources(resources_path: &Path, prefix: &str) -> Result<HashMap<String, Vec<u8>>> {
     let mut resources = HashMap::new();
 
-    for entry in std::fs::read_dir(resources_path).unwrap() {
-        let path = entry.unwrap().path();
+    for entry in std::fs::read_dir(resources_path)? {
+        let path = entry?.path();
 
         if path.is_dir() {
-            resources.extend(generate_resources(path.clone(), format!("{}{}:", prefix, path.file_name().unwrap().to_str().unwrap())));
+            resources.extend(generate_resources(&path, &format!("{prefix}{}:", get_file_name(&path)?))?);
         } else {
-            let (file_name, data) = process_file(path);
+            let (file_name, data) = process_file(&path)?;
             resources.insert(format!("{prefix}{file_name}"), data);
         }
     }
 
-    resources
+    Ok(resources)
+}
+
+fn get_file_name(path: &Path) -> Result<String> {
+    Ok(path.file_name().and_then(OsStr::to_str).ok_or_else(|| anyhow!("invalid file name {}", path.display()))?.to_owned())
 }
 
 /// This function processes a file in the resources folder.
 /// It takes the path to the file as input. It returns the
 /// new file name and the file contents.
-fn process_file(file_path: PathBuf) -> (String, Vec<u8>) {
-    let mut file_name = file_path.file_name().unwrap().to_str().unwrap().to_string();
+fn process_file(file_path: &Path) -> Result<(String, Vec<u8>)> {
+    let mut file_name = get_file_name(file_path)?;
     // if file name has .png extension, change it to .opa extension
-    if file_name.ends_with(".png") {
+    if file_path.extension() == Some(OsStr::new("png")) {
         file_name = file_name.replace(".png", ".opa");
 
         // convert the png to opa
-        let mut data = png_file_to_opa_bytes(file_path);
+        let mut data = png_file_to_opa_bytes(file_path)?;
 
         // if the file name starts with Template_, then process it as a template and remove the Template_ prefix
         if file_name.starts_with("Template_") {
             file_name = file_name.replace("Template_", "");
-            data = process_template(data);
+            data = process_template(&data)?;
         }
 
-        (file_name, data)
+        Ok((file_name, data))
     } else {
-        let data = std::fs::read(file_path).unwrap();
-        (file_name, data)
+        let data = std::fs::read(file_path)?;
+        Ok((file_name, data))
     }
 }
 
-fn process_template(data: Vec<u8>) -> Vec<u8> {
-    let surface = gfx::Surface::deserialize_from_bytes(&data).unwrap();
+fn process_template(data: &[u8]) -> Result<Vec<u8>> {
+    let surface = gfx::Surface::deserialize_from_bytes(data)?;
     let mut new_surface = gfx::Surface::new(gfx::IntSize(8, 8 * 16));
 
     // first take first 8x8 area from surface and copy it to 16 times in the new surface
     for step in 0..16 {
         for y in 0..8 {
             for x in 0..8 {
-                *new_surface.get_pixel_mut(gfx::IntPos(x, y + step * 8)).unwrap() = *surface.get_pixel(gfx::IntPos(x, y)).unwrap();
+                *new_surface.get_pixel_mut(gfx::IntPos(x, y + step * 8))? = *surface.get_pixel(gfx::IntPos(x, y))?;
             }
         }
     }
@@ -127,41 +134,42 @@ fn process_template(data: Vec<u8>) -> Vec<u8> {
     for i in 0..num_textures {
         for step in 0..16 {
             if step & (1 << (i % 4)) == 0 {
-                copy_edge(&surface, 0, 8 + 8 * i as i32, &mut new_surface, 0, step * 8);
+                copy_edge(&surface, 0, 8 + 8 * i as i32, &mut new_surface, 0, step * 8)?;
             }
         }
     }
 
     for step in 0..16 {
         if step & 8 == 0 && step & 1 == 0 {
-            *new_surface.get_pixel_mut(gfx::IntPos(0, step * 8)).unwrap() = gfx::Color::new(0, 0, 0, 0);
+            *new_surface.get_pixel_mut(gfx::IntPos(0, step * 8))? = gfx::Color::new(0, 0, 0, 0);
         }
 
         if step & 1 == 0 && step & 2 == 0 {
-            *new_surface.get_pixel_mut(gfx::IntPos(7, step * 8)).unwrap() = gfx::Color::new(0, 0, 0, 0);
+            *new_surface.get_pixel_mut(gfx::IntPos(7, step * 8))? = gfx::Color::new(0, 0, 0, 0);
         }
 
         if step & 2 == 0 && step & 4 == 0 {
-            *new_surface.get_pixel_mut(gfx::IntPos(7, step * 8 + 7)).unwrap() = gfx::Color::new(0, 0, 0, 0);
+            *new_surface.get_pixel_mut(gfx::IntPos(7, step * 8 + 7))? = gfx::Color::new(0, 0, 0, 0);
         }
 
         if step & 4 == 0 && step & 8 == 0 {
-            *new_surface.get_pixel_mut(gfx::IntPos(0, step * 8 + 7)).unwrap() = gfx::Color::new(0, 0, 0, 0);
+            *new_surface.get_pixel_mut(gfx::IntPos(0, step * 8 + 7))? = gfx::Color::new(0, 0, 0, 0);
         }
     }
 
-    new_surface.serialize_to_bytes().unwrap()
+    new_surface.serialize_to_bytes()
 }
 
-fn copy_edge(source: &gfx::Surface, source_x: i32, source_y: i32, target: &mut gfx::Surface, target_x: i32, target_y: i32) {
+fn copy_edge(source: &gfx::Surface, source_x: i32, source_y: i32, target: &mut gfx::Surface, target_x: i32, target_y: i32) -> Result<()> {
     for y in 0..8 {
         for x in 0..8 {
-            let pixel = *source.get_pixel(gfx::IntPos(source_x + x, source_y + y)).unwrap();
+            let pixel = *source.get_pixel(gfx::IntPos(source_x + x, source_y + y))?;
             if pixel.a != 0 {
                 let applied_pixel = if pixel == gfx::Color::new(0, 255, 0, 255) { gfx::Color::new(0, 0, 0, 0) } else { pixel };
 
-                *target.get_pixel_mut(gfx::IntPos(target_x + x, target_y + y)).unwrap() = applied_pixel;
+                *target.get_pixel_mut(gfx::IntPos(target_x + x, target_y + y))? = applied_pixel;
             }
         }
     }
+    Ok(())
 }
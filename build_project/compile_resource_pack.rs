@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::build_project::png_to_opa::png_file_to_opa_file;
+use crate::build_project::png_to_opa::png_file_to_opa_bytes;
 
 pub fn compile_resource_pack(input_resource_pack: PathBuf, output_resource_pack: PathBuf) {
     // make sure that cargo reruns this script if the input resource pack changes
@@ -33,3 +33,8 @@ pub fn compile_resource_pack(input_resource_pack: PathBuf, output_resource_pack:
         }
     }
 }
+
+fn png_file_to_opa_file(input_file: PathBuf, output_file: PathBuf) {
+    let serialized = png_file_to_opa_bytes(&input_file).unwrap();
+    std::fs::write(output_file, serialized).unwrap();
+}
@@ -0,0 +1,83 @@
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::build_project::compile_mod::{compile_mod, compress_mod, decompress_mod};
+use crate::shared::mod_manager::GameMod;
+
+const USAGE: &str = "usage: terralistic mod build <mod directory> [-o <output file>]\n       terralistic mod inspect <mod file>";
+
+/// This function runs the mod subcommand. It builds a mod from its directory
+/// into a .mod file or shows what is inside of a .mod file.
+pub fn run_mod_command(args: &[String]) -> Result<()> {
+    match args.split_first() {
+        Some((command, args)) if command == "build" => build_mod(args),
+        Some((command, args)) if command == "inspect" => inspect_mod(args),
+        _ => bail!(USAGE),
+    }
+}
+
+/// This function compiles the mod directory and writes the mod to the output file,
+/// by default it is next to the lua files and named like the directory.
+fn build_mod(args: &[String]) -> Result<()> {
+    let mut mod_path = None;
+    let mut output_path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "-o" {
+            output_path = Some(PathBuf::from(args.next().ok_or_else(|| anyhow!("-o needs an output file"))?));
+        } else if mod_path.is_none() {
+            mod_path = Some(PathBuf::from(arg));
+        } else {
+            bail!("unexpected argument {arg}\n{USAGE}");
+        }
+    }
+    let mod_path = mod_path.ok_or_else(|| anyhow!(USAGE))?;
+    let output_path = if let Some(output_path) = output_path { output_path } else { get_default_output_path(&mod_path)? };
+
+    let game_mod = compile_mod(&mod_path)?;
+    if !print_mod_info(&game_mod)? {
+        bail!("mod has no init function, so the game can't load it");
+    }
+
+    let mod_bytes = compress_mod(&game_mod)?;
+    std::fs::write(&output_path, &mod_bytes)?;
+    println!("built {} ({} bytes)", output_path.display(), mod_bytes.len());
+    Ok(())
+}
+
+/// this function returns where the build script would put the mod
+fn get_default_output_path(mod_path: &Path) -> Result<PathBuf> {
+    let mod_path = mod_path.canonicalize()?;
+    let name = mod_path.file_name().and_then(OsStr::to_str).ok_or_else(|| anyhow!("invalid mod directory {}", mod_path.display()))?;
+    Ok(mod_path.join(format!("{name}.mod")))
+}
+
+fn inspect_mod(args: &[String]) -> Result<()> {
+    let [mod_file] = args else {
+        bail!(USAGE);
+    };
+    let game_mod = decompress_mod(&std::fs::read(mod_file)?)?;
+    if !print_mod_info(&game_mod)? {
+        println!("warning: mod has no init function, so the game can't load it");
+    }
+    Ok(())
+}
+
+/// This function prints the manifest and the resources of the mod.
+/// It returns whether the mod has an init function.
+fn print_mod_info(game_mod: &GameMod) -> Result<bool> {
+    let manifest = game_mod.read_manifest()?;
+    println!("name: {}", manifest.name.as_deref().unwrap_or("-"));
+    println!("description: {}", manifest.description.as_deref().unwrap_or("-"));
+    println!("version: {}", manifest.version.as_deref().unwrap_or("-"));
+
+    let mut resources: Vec<(&String, &Vec<u8>)> = game_mod.get_resources().iter().collect();
+    resources.sort();
+    println!("resources ({}):", resources.len());
+    for (path, data) in resources {
+        println!("  {path} ({} bytes)", data.len());
+    }
+    Ok(manifest.has_init)
+}
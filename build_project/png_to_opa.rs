@@ -1,37 +1,25 @@
 use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 
 use crate::libraries::graphics as gfx;
 
-#[must_use]
-pub fn png_file_to_opa_bytes(png_path: PathBuf) -> Vec<u8> {
-    let decoder = png::Decoder::new(File::open(png_path).unwrap());
-    let mut reader = decoder.read_info().unwrap();
+pub fn png_file_to_opa_bytes(png_path: &Path) -> Result<Vec<u8>> {
+    let decoder = png::Decoder::new(File::open(png_path)?);
+    let mut reader = decoder.read_info()?;
     let mut buf = vec![0; reader.output_buffer_size()];
-    let info = reader.next_frame(&mut buf).unwrap();
-    let bytes = &buf[..info.buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    let bytes = buf.get(..info.buffer_size()).ok_or_else(|| anyhow!("png frame is bigger than its buffer"))?;
     // create surface from pixels
     let mut surface = gfx::Surface::new(gfx::IntSize(info.width, info.height));
-    for y in 0..info.height {
-        for x in 0..info.width {
-            let index = (y * info.width + x) as usize * 4;
-            let color = gfx::Color {
-                r: bytes[index],
-                g: bytes[index + 1],
-                b: bytes[index + 2],
-                a: bytes[index + 3],
-            };
-            *surface.get_pixel_mut(gfx::IntPos(x as i32, y as i32)).unwrap() = color;
+    for (index, pixel) in bytes.chunks_exact(4).enumerate() {
+        let x = index as u32 % info.width;
+        let y = index as u32 / info.width;
+        if let [r, g, b, a] = *pixel {
+            *surface.get_pixel_mut(gfx::IntPos(x as i32, y as i32))? = gfx::Color { r, g, b, a };
         }
     }
-    // serialize surface and write to file
-    surface.serialize_to_bytes().unwrap()
-}
-
-pub fn png_file_to_opa_file(input_file: PathBuf, output_file: PathBuf) {
-    let serialized = png_file_to_opa_bytes(input_file);
-
-    let mut file = File::create(output_file).unwrap();
-    file.write_all(&serialized).unwrap();
+    // serialize surface
+    surface.serialize_to_bytes()
 }
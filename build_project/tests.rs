@@ -0,0 +1,49 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::build_project::compile_mod::decompress_mod;
+    use crate::build_project::mod_cli::run_mod_command;
+
+    /// this function creates an empty directory for the test, which is removed when the test is run again
+    fn create_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("terralistic_{name}_{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_build_and_inspect_test_mod() {
+        let dir = create_test_dir("mod_cli");
+        let mod_dir = dir.join("test_mod");
+        std::fs::create_dir_all(&mod_dir).unwrap();
+        std::fs::write(mod_dir.join("test_mod.lua"), include_str!("../server/server_core/test_mod.lua")).unwrap();
+        let mod_file = dir.join("test_mod.mod");
+
+        let args = ["build", mod_dir.to_str().unwrap(), "-o", mod_file.to_str().unwrap()].map(str::to_owned);
+        run_mod_command(&args).unwrap();
+        run_mod_command(&["inspect", mod_file.to_str().unwrap()].map(str::to_owned)).unwrap();
+
+        let manifest = decompress_mod(&std::fs::read(&mod_file).unwrap()).unwrap().read_manifest().unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("test_mod"));
+        assert_eq!(manifest.version.as_deref(), Some("1.0"));
+        assert!(manifest.has_init);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_mod_without_init_is_not_built() {
+        let dir = create_test_dir("mod_cli_no_init");
+        std::fs::write(dir.join("broken.lua"), "MOD_NAME = \"broken\"").unwrap();
+        let mod_file = dir.join("broken.mod");
+
+        let args = ["build", dir.to_str().unwrap(), "-o", mod_file.to_str().unwrap()].map(str::to_owned);
+        let error = run_mod_command(&args).unwrap_err();
+        assert!(error.to_string().contains("no init function"));
+        assert!(!mod_file.exists());
+        assert!(run_mod_command(&["unknown".to_owned()]).unwrap_err().to_string().starts_with("usage"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,10 +1,12 @@
 use anyhow::Result;
 
 use crate::libraries::graphics as gfx;
+use crate::shared::lights::LightColor;
 
 use super::camera::Camera;
 
-/// Background is a struct that holds the background image and renders it.
+/// Background is a struct that holds the background image and renders it
+/// tinted with the color of the sky light.
 pub struct Background {
     image: gfx::Texture,
 }
@@ -19,7 +21,8 @@ impl Background {
         Ok(())
     }
 
-    pub fn render(&self, graphics: &gfx::GraphicsContext, camera: &Camera) {
+    pub fn render(&self, graphics: &gfx::GraphicsContext, camera: &Camera, sky_color: LightColor) {
+        let tint = gfx::Color::new(sky_color.r, sky_color.g, sky_color.b, 255);
         //float scale = (float)gfx::getWindowHeight() / background.getTextureHeight();
         let scale = graphics.get_window_size().1 / self.image.get_texture_size().1;
         //int position_x = -int(camera->getX() * scale / 20) % int(background.getTextureWidth() * scale);
@@ -33,7 +36,7 @@ impl Background {
                 gfx::FloatPos(position_x + i as f32 * (self.image.get_texture_size().0 * scale), 0.0),
                 None,
                 false,
-                None,
+                Some(tint),
             );
         }
     }
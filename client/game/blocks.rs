@@ -9,8 +9,8 @@ use anyhow::{anyhow, bail, Result};
 use crate::libraries::events::{Event, EventManager};
 use crate::libraries::graphics as gfx;
 use crate::shared::blocks::{
-    handle_event_for_blocks_interface, init_blocks_mod_interface, BlockBreakStartPacket, BlockBreakStopPacket, BlockChangeEvent, BlockChangePacket, BlockId, BlockInventoryUpdatePacket,
-    BlockRightClickPacket,
+    handle_event_for_blocks_interface, init_blocks_mod_interface, BlockBreakStartPacket, BlockBreakStopPacket, BlockChangeEvent, BlockChangePacket, BlockDataUpdatePacket, BlockId,
+    BlockInventoryUpdatePacket, BlockRightClickPacket,
 };
 use crate::shared::blocks::{Blocks, BlocksWelcomePacket, BLOCK_WIDTH, RENDER_BLOCK_WIDTH, RENDER_SCALE};
 use crate::shared::mod_manager::ModManager;
@@ -125,7 +125,12 @@ impl ClientBlocks {
         }
     }
 
-    pub fn get_blocks(&self) -> MutexGuard<Blocks> {
+    /// this function returns the shared blocks, so other mod interfaces can look up block types
+    pub const fn get_shared_blocks(&self) -> &Arc<Mutex<Blocks>> {
+        &self.blocks
+    }
+
+    pub fn get_blocks(&self) -> MutexGuard<'_, Blocks> {
         self.blocks.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
@@ -157,6 +162,8 @@ impl ClientBlocks {
                 self.get_blocks().set_big_block(events, packet.x, packet.y, packet.block, (packet.from_main_x, packet.from_main_y))?;
             } else if let Some(packet) = event.try_deserialize::<BlockInventoryUpdatePacket>() {
                 self.get_blocks().set_block_inventory_data(packet.x, packet.y, packet.inventory, events)?;
+            } else if let Some(packet) = event.try_deserialize::<BlockDataUpdatePacket>() {
+                self.get_blocks().set_block_data(packet.x, packet.y, packet.data, events)?;
             }
         } else if let Some(event) = event.downcast::<BlockChangeEvent>() {
             for (x, y) in [(event.x, event.y), (event.x - 1, event.y), (event.x + 1, event.y), (event.x, event.y - 1), (event.x, event.y + 1)] {
@@ -252,7 +259,7 @@ impl ClientBlocks {
         Ok(())
     }
 
-    fn flush_mod_events(&mut self, events: &mut EventManager) {
+    fn flush_mod_events(&self, events: &mut EventManager) {
         if let Some(receiver) = &self.event_receiver {
             while let Ok(event) = receiver.try_recv() {
                 events.push_event(event);
@@ -260,7 +267,7 @@ impl ClientBlocks {
         }
     }
 
-    pub fn update(&mut self, frame_length: f32, events: &mut EventManager) -> Result<()> {
+    pub fn update(&self, frame_length: f32, events: &mut EventManager) -> Result<()> {
         self.flush_mod_events(events);
 
         self.get_blocks().update_breaking_blocks(events, frame_length)
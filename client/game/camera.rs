@@ -1,9 +1,9 @@
+use crate::client::settings::Settings;
 use crate::libraries::events::Event;
 use crate::libraries::graphics as gfx;
 use crate::shared::blocks::RENDER_BLOCK_WIDTH;
 
 /// Camera is a struct that handles the camera position.
-
 pub struct Camera {
     target_position_x: f32,
     target_position_y: f32,
@@ -33,7 +33,7 @@ impl Camera {
         self.detached_text.color = gfx::Color::new(255, 0, 0, 255);
     }
 
-    pub fn set_position(&mut self, x: f32, y: f32) {
+    pub const fn set_position(&mut self, x: f32, y: f32) {
         if !self.detached {
             self.target_position_x = x;
             self.target_position_y = y;
@@ -44,24 +44,24 @@ impl Camera {
         gfx::FloatPos(self.position_x, self.position_y)
     }
 
-    pub fn update_ms(&mut self, graphics: &gfx::GraphicsContext) {
+    pub fn update_ms(&mut self, graphics: &gfx::GraphicsContext, settings: &Settings) {
         self.position_x += (self.target_position_x - self.position_x) * 0.03;
         self.position_y += (self.target_position_y - self.position_y) * 0.03;
 
         if self.detached {
-            if graphics.get_key_state(gfx::Key::W) {
+            if settings.is_key_binding_pressed(graphics, "move_up") {
                 self.target_position_y -= 0.5;
             }
 
-            if graphics.get_key_state(gfx::Key::S) {
+            if settings.is_key_binding_pressed(graphics, "move_down") {
                 self.target_position_y += 0.5;
             }
 
-            if graphics.get_key_state(gfx::Key::A) {
+            if settings.is_key_binding_pressed(graphics, "move_left") {
                 self.target_position_x -= 0.5;
             }
 
-            if graphics.get_key_state(gfx::Key::D) {
+            if settings.is_key_binding_pressed(graphics, "move_right") {
                 self.target_position_x += 0.5;
             }
         }
@@ -87,9 +87,9 @@ impl Camera {
         (self.position_x + width / 2.0, self.position_y + height / 2.0)
     }
 
-    pub fn on_event(&mut self, event: &Event) {
-        if let Some(event) = event.downcast::<gfx::Event>() {
-            if matches!(event, gfx::Event::KeyPress(gfx::Key::C, false)) {
+    pub fn on_event(&mut self, event: &Event, settings: &Settings) {
+        if let Some(gfx::Event::KeyPress(key, false)) = event.downcast::<gfx::Event>() {
+            if settings.is_key_bound_to("detach_camera", *key) {
                 self.detached = !self.detached;
             }
         }
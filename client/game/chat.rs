@@ -3,34 +3,52 @@ use std::cmp::Ordering;
 use anyhow::Result;
 
 use crate::client::game::networking::ClientNetworking;
+use crate::client::settings::Settings;
 use crate::libraries::events::Event;
 use crate::libraries::graphics as gfx;
-use crate::shared::chat::ChatPacket;
+use crate::shared::chat::{complete_last_word, ChatMessage, ChatMessagePacket, ChatPacket, CommandCompletionPacket, CommandCompletionRequestPacket};
 use crate::shared::packet::Packet;
 
+// how many lines are kept, older lines are removed
+const CHAT_HISTORY_SIZE: usize = 100;
+// how many lines are shown at once, older ones can be scrolled to while the chat is open
+const CHAT_VISIBLE_LINES: usize = 12;
+// how long a line is shown after it was received if the chat is closed
+const CHAT_LINE_FADE_MS: u128 = 5000;
+
 pub struct ChatLine {
-    texture: gfx::Texture,
+    // every span has its own texture, so it can be drawn in its own color
+    spans: Vec<(gfx::Texture, gfx::Color)>,
     back_rect: gfx::RenderRect,
     creation_time: std::time::Instant,
     transparency: i32,
 }
 
 impl ChatLine {
-    pub fn new(graphics: &gfx::GraphicsContext, text: &str, pos: gfx::FloatPos) -> Self {
-        let texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface(text, None));
-        let mut back_rect = gfx::RenderRect::new(pos + gfx::FloatPos(-texture.get_texture_size().0 * 3.0, -texture.get_texture_size().1 * 3.0), gfx::FloatSize(0.0, 0.0));
-        back_rect.smooth_factor = 60.0;
+    pub fn new(graphics: &gfx::GraphicsContext, message: &ChatMessage, pos: gfx::FloatPos) -> Self {
+        let spans: Vec<(gfx::Texture, gfx::Color)> = message
+            .spans
+            .iter()
+            .map(|span| {
+                let texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface(&span.text, None));
+                let color = span.color.map_or(gfx::WHITE, |[r, g, b]| gfx::Color::new(r, g, b, 255));
+                (texture, color)
+            })
+            .collect();
 
-        Self {
-            texture,
-            back_rect,
+        let mut line = Self {
+            spans,
+            back_rect: gfx::RenderRect::new(pos, gfx::FloatSize(0.0, 0.0)),
             creation_time: std::time::Instant::now(),
             transparency: 255,
-        }
+        };
+        line.back_rect.pos = pos + gfx::FloatPos(-line.get_size().0, -line.get_size().1);
+        line.back_rect.smooth_factor = 60.0;
+        line
     }
 
     pub fn render(&mut self, graphics: &gfx::GraphicsContext, focused: bool) {
-        let target_transparency = if focused || (self.creation_time.elapsed().as_millis() as i32) < 5000 { 255 } else { 0 };
+        let target_transparency = if focused || self.creation_time.elapsed().as_millis() < CHAT_LINE_FADE_MS { 255 } else { 0 };
 
         match self.transparency.cmp(&target_transparency) {
             Ordering::Greater => self.transparency -= 10,
@@ -45,16 +63,21 @@ impl ChatLine {
         }
 
         self.back_rect.render(graphics, None);
-        let pos = self.back_rect.get_container(graphics, None).rect.pos;
-        self.texture.render(graphics, 3.0, pos, None, false, Some(gfx::Color::new(255, 255, 255, self.transparency as u8)));
+        let mut pos = self.back_rect.get_container(graphics, None).rect.pos;
+        for (texture, color) in &self.spans {
+            texture.render(graphics, 3.0, pos, None, false, Some(color.set_a(self.transparency as u8)));
+            pos.0 += texture.get_texture_size().0 * 3.0;
+        }
     }
 
-    pub fn set_pos(&mut self, pos: gfx::FloatPos) {
+    pub const fn set_pos(&mut self, pos: gfx::FloatPos) {
         self.back_rect.pos = pos;
     }
 
     pub fn get_size(&self) -> gfx::FloatSize {
-        gfx::FloatSize(self.texture.get_texture_size().0 * 3.0, self.texture.get_texture_size().1 * 3.0)
+        let width = self.spans.iter().map(|(texture, _)| texture.get_texture_size().0 * 3.0).sum();
+        let height = self.spans.iter().map(|(texture, _)| texture.get_texture_size().1 * 3.0).fold(0.0, f32::max);
+        gfx::FloatSize(width, height)
     }
 }
 
@@ -62,7 +85,13 @@ pub struct ClientChat {
     back_rect: gfx::RenderRect,
     text_input: gfx::TextInput,
     chat_lines: Vec<ChatLine>,
-    waiting_for_t: bool,
+    // how many of the newest lines are scrolled past while the chat is open
+    scroll: usize,
+    // the messages the player sent, the up and down arrows go through them
+    sent_messages: Vec<String>,
+    history_index: Option<usize>,
+    // the key that opened the chat also sends text input, which must not be typed into it
+    waiting_for_key_text: bool,
 }
 
 impl ClientChat {
@@ -71,7 +100,10 @@ impl ClientChat {
             text_input: gfx::TextInput::new(graphics),
             back_rect: gfx::RenderRect::new(gfx::FloatPos(0.0, 0.0), gfx::FloatSize(0.0, 0.0)),
             chat_lines: Vec::new(),
-            waiting_for_t: false,
+            scroll: 0,
+            sent_messages: Vec::new(),
+            history_index: None,
+            waiting_for_key_text: false,
         }
     }
 
@@ -102,19 +134,23 @@ impl ClientChat {
         self.text_input.width = self.back_rect.get_container(graphics, None).rect.size.0 / self.text_input.scale;
         self.text_input.render(graphics, None);
 
+        if !self.text_input.selected {
+            self.scroll = 0;
+        }
+
         let mut curr_y = graphics.get_window_size().1 - gfx::SPACING - self.text_input.get_size().1;
-        for line in self.chat_lines.iter_mut().rev() {
+        for line in self.chat_lines.iter_mut().rev().skip(self.scroll).take(CHAT_VISIBLE_LINES) {
             curr_y -= line.get_size().1;
             line.set_pos(gfx::FloatPos(gfx::SPACING, curr_y));
             line.render(graphics, self.text_input.selected);
         }
     }
 
-    pub fn on_event(&mut self, event: &Event, graphics: &mut gfx::GraphicsContext, networking: &mut ClientNetworking) -> Result<bool> {
+    pub fn on_event(&mut self, event: &Event, graphics: &mut gfx::GraphicsContext, networking: &mut ClientNetworking, settings: &Settings) -> Result<bool> {
         if let Some(event) = event.downcast::<gfx::Event>() {
             if let gfx::Event::TextInput(..) = event {
-                if self.waiting_for_t {
-                    self.waiting_for_t = false;
+                if self.waiting_for_key_text {
+                    self.waiting_for_key_text = false;
                     return Ok(true);
                 }
             }
@@ -123,35 +159,98 @@ impl ClientChat {
 
             if let gfx::Event::KeyPress(gfx::Key::Enter, ..) = event {
                 if self.text_input.selected && !self.text_input.get_text().is_empty() {
-                    networking.send_packet(Packet::new(ChatPacket {
-                        message: self.text_input.get_text().clone(),
-                    })?)?;
+                    let message = self.text_input.get_text().clone();
+                    networking.send_packet(Packet::new(ChatPacket { message: message.clone() })?)?;
 
+                    if self.sent_messages.last() != Some(&message) {
+                        self.sent_messages.push(message);
+                    }
+                    self.history_index = None;
                     self.text_input.set_text(String::new());
                 }
-            } else if let gfx::Event::KeyPress(gfx::Key::T, ..) = event {
-                if !self.is_selected() {
-                    self.text_input.selected = true;
-                    self.waiting_for_t = true;
+            } else if let gfx::Event::KeyPress(gfx::Key::Up, ..) = event {
+                if self.is_selected() && !self.sent_messages.is_empty() {
+                    let index = self.history_index.map_or(self.sent_messages.len() - 1, |index| index.saturating_sub(1));
+                    self.show_sent_message(Some(index));
+                }
+            } else if let gfx::Event::KeyPress(gfx::Key::Down, ..) = event {
+                if let Some(index) = self.history_index {
+                    self.show_sent_message((index + 1 < self.sent_messages.len()).then_some(index + 1));
+                }
+            } else if let gfx::Event::MouseScroll(delta) = event {
+                if self.is_selected() {
+                    let max_scroll = self.chat_lines.len().saturating_sub(CHAT_VISIBLE_LINES);
+                    self.scroll = if *delta > 0.0 { usize::min(self.scroll + 1, max_scroll) } else { self.scroll.saturating_sub(1) };
+                }
+            } else if let gfx::Event::KeyPress(gfx::Key::Tab, ..) = event {
+                // only commands are completed, by the server who knows them
+                if self.is_selected() && self.text_input.get_text().starts_with('/') {
+                    networking.send_packet(Packet::new(CommandCompletionRequestPacket {
+                        text: self.text_input.get_text().clone(),
+                    })?)?;
                 }
             } else if let gfx::Event::KeyPress(gfx::Key::Escape, ..) = event {
                 if self.is_selected() {
                     self.text_input.selected = false;
                     return Ok(true);
                 }
+            } else if let gfx::Event::KeyPress(key, ..) = event {
+                if !self.is_selected() && settings.is_key_bound_to("open_chat", *key) {
+                    self.text_input.selected = true;
+                    self.waiting_for_key_text = true;
+                }
             }
         } else if let Some(event) = event.downcast::<Packet>() {
-            if let Some(packet) = event.try_deserialize::<ChatPacket>() {
-                self.chat_lines.push(ChatLine::new(
-                    graphics,
-                    &packet.message,
-                    gfx::FloatPos(0.0, graphics.get_window_size().1 - gfx::SPACING - self.text_input.get_size().1),
-                ));
+            if let Some(packet) = event.try_deserialize::<ChatMessagePacket>() {
+                self.add_line(graphics, &packet.message);
+            }
+            if let Some(packet) = event.try_deserialize::<CommandCompletionPacket>() {
+                self.on_completion(graphics, &packet);
             }
         }
         Ok(self.is_selected())
     }
 
+    fn add_line(&mut self, graphics: &gfx::GraphicsContext, message: &ChatMessage) {
+        self.chat_lines.push(ChatLine::new(
+            graphics,
+            message,
+            gfx::FloatPos(0.0, graphics.get_window_size().1 - gfx::SPACING - self.text_input.get_size().1),
+        ));
+        if self.chat_lines.len() > CHAT_HISTORY_SIZE {
+            self.chat_lines.remove(0);
+        }
+        // the lines the player is reading stay in place
+        if self.scroll > 0 {
+            self.scroll = usize::min(self.scroll + 1, self.chat_lines.len().saturating_sub(CHAT_VISIBLE_LINES));
+        }
+    }
+
+    /// this function completes the command in the input, if there are
+    /// several completions they are shown in the chat
+    fn on_completion(&mut self, graphics: &gfx::GraphicsContext, packet: &CommandCompletionPacket) {
+        // the player could have typed more since asking
+        if !self.is_selected() || packet.text != *self.text_input.get_text() {
+            return;
+        }
+        let command = packet.text.strip_prefix('/').unwrap_or(&packet.text);
+        if let Some(completed) = complete_last_word(command, &packet.completions) {
+            self.text_input.set_text(format!("/{completed}"));
+            self.text_input.move_cursor_to_end();
+        }
+        if packet.completions.len() > 1 {
+            self.add_line(graphics, &ChatMessage::system(&packet.completions.join(" ")));
+        }
+    }
+
+    /// this function puts a sent message into the input, None empties it
+    fn show_sent_message(&mut self, index: Option<usize>) {
+        self.history_index = index;
+        let text = index.and_then(|index| self.sent_messages.get(index)).cloned().unwrap_or_default();
+        self.text_input.set_text(text);
+        self.text_input.move_cursor_to_end();
+    }
+
     pub const fn is_selected(&self) -> bool {
         self.text_input.selected
     }
@@ -0,0 +1,79 @@
+use anyhow::Result;
+
+use crate::client::game::camera::Camera;
+use crate::client::game::floating_text::{FloatingText, FloatingTextManager};
+use crate::client::game::networking::ClientNetworking;
+use crate::client::game::players::ClientPlayers;
+use crate::libraries::events::{Event, EventManager};
+use crate::libraries::graphics as gfx;
+use crate::shared::blocks::RENDER_BLOCK_WIDTH;
+use crate::shared::combat::{get_entity_center, spawn_projectile, AttackPacket, EntityDamagePacket, ProjectileComponent, ProjectileSpawnPacket, PROJECTILE_SIZE};
+use crate::shared::entities::{Entities, PositionComponent};
+use crate::shared::packet::Packet;
+
+const PROJECTILE_COLOR: gfx::Color = gfx::Color::new(200, 200, 200, 255);
+const DAMAGE_TEXT_COLOR: gfx::Color = gfx::Color::new(200, 0, 0, 255);
+
+/// Client combat sends the attacks of the player to the server,
+/// renders projectiles and shows the damage entities take.
+pub struct ClientCombat;
+
+impl ClientCombat {
+    pub fn render(graphics: &gfx::GraphicsContext, camera: &Camera, entities: &mut Entities) {
+        let top_left = camera.get_top_left(graphics);
+        for (_entity, (position, _projectile)) in entities.ecs.query_mut::<(&PositionComponent, &ProjectileComponent)>() {
+            let x = ((position.x() - top_left.0) * RENDER_BLOCK_WIDTH).round();
+            let y = ((position.y() - top_left.1) * RENDER_BLOCK_WIDTH).round();
+            gfx::Rect::new(gfx::FloatPos(x, y), gfx::FloatSize(PROJECTILE_SIZE * RENDER_BLOCK_WIDTH, PROJECTILE_SIZE * RENDER_BLOCK_WIDTH)).render(graphics, PROJECTILE_COLOR);
+        }
+    }
+
+    pub fn on_event(
+        event: &Event,
+        graphics: &gfx::GraphicsContext,
+        networking: &mut ClientNetworking,
+        camera: &Camera,
+        floating_texts: &mut FloatingTextManager,
+        players: &ClientPlayers,
+        entities: &mut Entities,
+        events: &mut EventManager,
+    ) -> Result<()> {
+        if let Some(gfx::Event::KeyPress(gfx::Key::MouseLeft, ..)) = event.downcast::<gfx::Event>() {
+            // the server decides if the attack hits anything
+            let top_left = camera.get_top_left(graphics);
+            let x = graphics.get_mouse_pos().0 / RENDER_BLOCK_WIDTH + top_left.0;
+            let y = graphics.get_mouse_pos().1 / RENDER_BLOCK_WIDTH + top_left.1;
+            networking.send_packet(Packet::new(AttackPacket { x, y })?)?;
+        }
+
+        if let Some(packet) = event.downcast::<Packet>() {
+            if let Some(packet) = packet.try_deserialize::<ProjectileSpawnPacket>() {
+                // the client only moves the projectile, the server decides what it hits
+                spawn_projectile(
+                    entities,
+                    events,
+                    packet.x,
+                    packet.y,
+                    packet.velocity_x,
+                    packet.velocity_y,
+                    ProjectileComponent::new(0, 0.0, None),
+                    packet.id,
+                )?;
+            }
+
+            if let Some(packet) = packet.try_deserialize::<EntityDamagePacket>() {
+                // the damage of the main player is already shown by the health bar
+                let Ok(entity) = entities.get_entity_from_id(packet.id) else {
+                    return Ok(());
+                };
+                if players.get_main_player() == Some(entity) {
+                    return Ok(());
+                }
+                if let Ok((x, y)) = get_entity_center(entities, entity) {
+                    floating_texts.spawn_text(FloatingText::new(graphics, &packet.damage.to_string(), x, y - 0.5, 1000, DAMAGE_TEXT_COLOR, 2.0));
+                }
+            }
+        }
+        Ok(())
+    }
+}
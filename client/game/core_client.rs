@@ -4,17 +4,20 @@ use std::sync::{Mutex, PoisonError};
 use anyhow::{bail, Result};
 
 use crate::client::game::chat::ClientChat;
+use crate::client::game::combat::ClientCombat;
 use crate::client::game::debug_menu::DebugMenu;
 use crate::client::game::entities::ClientEntities;
 use crate::client::game::floating_text::FloatingTextManager;
 use crate::client::game::framerate_measurer::FramerateMeasurer;
-use crate::client::game::health::ClientHealth;
 use crate::client::game::inventory::ClientInventory;
 use crate::client::game::items::ClientItems;
 use crate::client::game::lights::ClientLights;
+use crate::client::game::mobs::ClientMobs;
 use crate::client::game::pause_menu::PauseMenu;
 use crate::client::game::players::ClientPlayers;
 use crate::client::game::respawn_screen::RespawnScreen;
+use crate::client::game::stats::ClientStats;
+use crate::client::game::world_time::ClientWorldTime;
 use crate::client::global_settings::GlobalSettings;
 use crate::client::menus::{run_loading_screen, BackgroundRect};
 use crate::client::settings::Settings;
@@ -22,6 +25,10 @@ use crate::libraries::events;
 use crate::libraries::events::EventManager;
 use crate::libraries::graphics as gfx;
 use crate::shared::entities::PositionComponent;
+use crate::shared::items::update_item_timers;
+use crate::shared::mod_manager::ModsReloadPacket;
+use crate::shared::packet::Packet;
+use crate::shared::players::{PLAYER_HEIGHT, PLAYER_WIDTH};
 
 use super::background::Background;
 use super::block_selector::BlockSelector;
@@ -31,19 +38,35 @@ use super::mod_manager::ClientModManager;
 use super::networking::ClientNetworking;
 use super::walls::ClientWalls;
 
-#[allow(clippy::too_many_lines)]
 pub fn run_game(
     graphics: &mut gfx::GraphicsContext,
     menu_back: &mut dyn BackgroundRect,
     server_port: u16,
-    server_address: String,
+    server_address: &str,
     player_name: &str,
     settings: &mut Settings,
     global_settings: &mut GlobalSettings,
 ) -> Result<()> {
+    // the game is joined again every time the server reloads its mods, so it gets the new ones
+    while join_game(graphics, menu_back, server_port, server_address, player_name, settings, global_settings)? {}
+    Ok(())
+}
+
+/// this function joins the server and runs the game until the player leaves,
+/// it returns true if the game has to be joined again because the server reloaded its mods
+#[allow(clippy::too_many_lines, reason = "it runs every part of the game")]
+fn join_game(
+    graphics: &mut gfx::GraphicsContext,
+    menu_back: &mut dyn BackgroundRect,
+    server_port: u16,
+    server_address: &str,
+    player_name: &str,
+    settings: &mut Settings,
+    global_settings: &mut GlobalSettings,
+) -> Result<bool> {
     // load base game mod
     let mut pre_events = EventManager::new();
-    let mut networking = ClientNetworking::new(server_port, server_address);
+    let mut networking = ClientNetworking::new(server_port, server_address.to_owned());
     networking.init(player_name.to_owned())?;
     while networking.is_welcoming() {
         // wait 1 ms
@@ -59,13 +82,16 @@ pub fn run_game(
     let loading_text2 = loading_text.clone();
 
     let init_thread = std::thread::spawn(move || {
-        let temp_fn = || -> Result<(ClientModManager, ClientBlocks, ClientWalls, ClientEntities, ClientItems, ClientNetworking)> {
-            *loading_text2.lock().unwrap_or_else(PoisonError::into_inner) = "Loading mods".to_owned();
+        let temp_fn = || -> Result<(ClientModManager, ClientBlocks, ClientWalls, ClientEntities, ClientItems, ClientMobs, ClientWorldTime, ClientStats, ClientNetworking)> {
+            "Loading mods".clone_into(&mut loading_text2.lock().unwrap_or_else(PoisonError::into_inner));
             let mut mods = ClientModManager::new();
             let mut blocks = ClientBlocks::new();
-            let mut walls = ClientWalls::new(&mut blocks.get_blocks());
+            let walls = ClientWalls::new(&mut blocks.get_blocks());
             let mut entities = ClientEntities::new();
-            let mut items = ClientItems::new();
+            let items = ClientItems::new();
+            let mobs = ClientMobs::new();
+            let world_time = ClientWorldTime::new();
+            let stats = ClientStats::new();
 
             while let Some(event) = pre_events.pop_event() {
                 mods.on_event(&event)?;
@@ -76,12 +102,15 @@ pub fn run_game(
 
             blocks.init(&mut mods.mod_manager)?;
             walls.init(&mut mods.mod_manager)?;
-            items.init(&mut mods.mod_manager)?;
+            items.init(blocks.get_shared_blocks(), &mut mods.mod_manager)?;
+            mobs.init(&mut mods.mod_manager)?;
+            world_time.init(&mut mods.mod_manager)?;
+            stats.init(&mut mods.mod_manager)?;
 
-            *loading_text2.lock().unwrap_or_else(PoisonError::into_inner) = "Initializing mods".to_owned();
+            "Initializing mods".clone_into(&mut loading_text2.lock().unwrap_or_else(PoisonError::into_inner));
             mods.init()?;
 
-            anyhow::Ok((mods, blocks, walls, entities, items, networking))
+            anyhow::Ok((mods, blocks, walls, entities, items, mobs, world_time, stats, networking))
         };
         // if the init fails, we clear the loading text so the error can be displayed
         let result = temp_fn();
@@ -102,7 +131,10 @@ pub fn run_game(
     let mut walls = result.2;
     let mut entities = result.3;
     let mut items = result.4;
-    let mut networking = result.5;
+    let mut mobs = result.5;
+    let mut world_time = result.6;
+    let mut stats = result.7;
+    let mut networking = result.8;
 
     let mut background = Background::new();
     let mut inventory = ClientInventory::new();
@@ -115,10 +147,10 @@ pub fn run_game(
     let mut debug_menu = DebugMenu::new();
     let mut framerate_measurer = FramerateMeasurer::new();
     let mut chat = ClientChat::new(graphics);
-    let mut health = ClientHealth::new();
     let mut floating_text = FloatingTextManager::new();
     let mut respawn_screen = RespawnScreen::new();
 
+    mods.register_key_bindings(settings);
     background.init()?;
     inventory.init();
     lights.init(&blocks.get_blocks(), settings)?;
@@ -126,9 +158,10 @@ pub fn run_game(
     blocks.load_resources(&mods.mod_manager)?;
     walls.load_resources(&mods.mod_manager)?;
     items.load_resources(&mods.mod_manager)?;
+    mobs.load_resources(&mods.mod_manager)?;
     camera.load_resources(graphics);
     players.load_resources(&mods.mod_manager)?;
-    health.load_resources(&mods.mod_manager)?;
+    stats.load_resources(&mods.mod_manager)?;
 
     pause_menu.init(graphics, settings);
     debug_menu.init();
@@ -138,6 +171,7 @@ pub fn run_game(
     // print the time it took to initialize
     println!("Game joined in {}ms", timer.elapsed().as_millis());
 
+    let mut mods_reloaded = false;
     'main_loop: while graphics.is_window_open() {
         framerate_measurer.update();
 
@@ -151,6 +185,9 @@ pub fn run_game(
         mods.update()?;
         blocks.update(framerate_measurer.get_delta_time(), &mut events)?;
         walls.update(framerate_measurer.get_delta_time(), &mut events)?;
+        world_time.update(framerate_measurer.get_delta_time());
+        let sky_color = world_time.get_time().get_sky_color();
+        lights.lights.set_sky_color(sky_color);
 
         if let Some(main_player) = players.get_main_player() {
             let player_pos = entities.entities.ecs.get::<&PositionComponent>(main_player)?;
@@ -159,25 +196,34 @@ pub fn run_game(
         }
 
         while framerate_measurer.has_5ms_passed() {
-            camera.update_ms(graphics);
+            camera.update_ms(graphics, settings);
             players.controls_enabled = !camera.is_detached();
-            players.update(graphics, &mut entities.entities, &mut networking, &blocks.get_blocks())?;
+            players.update(graphics, settings, &mut entities.entities, &mut networking, &blocks.get_blocks())?;
             entities.entities.update_entities_ms(&blocks.get_blocks(), &mut events)?;
+            update_item_timers(&mut entities.entities, 5);
         }
 
+        let main_player_pos = players
+            .get_main_player()
+            .and_then(|main_player| entities.entities.ecs.get::<&PositionComponent>(main_player).ok())
+            .map(|player_pos| (player_pos.x() + PLAYER_WIDTH / 2.0, player_pos.y() + PLAYER_HEIGHT / 2.0));
+        inventory.update(&items, &blocks.get_blocks(), main_player_pos)?;
+
         respawn_screen.is_shown = players.get_main_player().is_none() && !players.is_waiting_for_player();
 
-        background.render(graphics, &camera);
+        background.render(graphics, &camera, sky_color);
         walls.render(graphics, &camera)?;
         blocks.render(graphics, &camera)?;
         players.render(graphics, &mut entities.entities, &camera);
         items.render(graphics, &camera, &mut entities.entities)?;
+        mobs.render(graphics, &camera, &mut entities.entities);
+        ClientCombat::render(graphics, &camera, &mut entities.entities);
         floating_text.render(graphics, &camera);
         lights.render(graphics, &camera, &blocks.get_blocks(), settings)?;
         camera.render(graphics);
         block_selector.render(graphics, &mut networking, &camera)?;
         inventory.render(graphics, &items, &mut networking, &blocks.get_blocks())?;
-        health.render(graphics);
+        stats.render(graphics);
         chat.render(graphics);
         respawn_screen.render(graphics);
 
@@ -193,24 +239,34 @@ pub fn run_game(
         );
 
         while let Some(event) = events.pop_event() {
-            if chat.on_event(&event, graphics, &mut networking)? {
+            if event.downcast::<Packet>().is_some_and(|packet| packet.try_deserialize::<ModsReloadPacket>().is_some()) {
+                mods_reloaded = true;
+                break 'main_loop;
+            }
+            if chat.on_event(&event, graphics, &mut networking, settings)? {
+                continue;
+            }
+            if inventory.on_event(&event, graphics, settings, &mut networking, &mut blocks.get_blocks(), &mut events)? {
                 continue;
             }
-            inventory.on_event(&event, &mut networking, &items, &mut blocks.get_blocks(), &mut events)?;
             mods.on_event(&event)?;
+            mods.on_key_event(&event, settings)?;
             blocks.on_event(&event, &mut events, &mut mods.mod_manager, &mut networking)?;
             walls.on_event(&event)?;
             entities.on_event(&event, &mut events)?;
             items.on_event(&event, &mut entities.entities, &mut events)?;
+            mobs.on_event(&event, &mut entities.entities, &mut events)?;
+            world_time.on_event(&event);
+            ClientCombat::on_event(&event, graphics, &mut networking, &camera, &mut floating_text, &players, &mut entities.entities, &mut events)?;
             block_selector.on_event(graphics, &mut networking, &camera, &event, &mut events)?;
             players.on_event(&event, &mut entities.entities)?;
             lights.on_event(&event, &blocks.get_blocks())?;
-            camera.on_event(&event);
-            health.on_event(&event, graphics, &mut floating_text, &players, &entities.entities);
+            camera.on_event(&event, settings);
+            stats.on_event(&event, graphics, &mut floating_text, &players, &entities.entities);
             if pause_menu.on_event(&event, graphics, settings) {
                 break 'main_loop;
             }
-            debug_menu.on_event(&event);
+            debug_menu.on_event(&event, settings);
             respawn_screen.on_event(&event, graphics, &mut networking)?;
         }
 
@@ -221,7 +277,7 @@ pub fn run_game(
 
     lights.stop(settings)?;
     networking.stop()?;
-    mods.stop()?;
+    mods.stop(settings)?;
 
-    Ok(())
+    Ok(mods_reloaded)
 }
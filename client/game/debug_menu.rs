@@ -1,3 +1,4 @@
+use crate::client::settings::Settings;
 use crate::libraries::events::Event;
 use crate::libraries::graphics as gfx;
 
@@ -65,9 +66,11 @@ impl DebugMenu {
         }
     }
 
-    pub fn on_event(&mut self, event: &Event) {
-        if matches!(event.downcast::<gfx::Event>(), Some(gfx::Event::KeyPress(gfx::Key::M, false))) {
-            self.open = !self.open;
+    pub fn on_event(&mut self, event: &Event, settings: &Settings) {
+        if let Some(gfx::Event::KeyPress(key, false)) = event.downcast::<gfx::Event>() {
+            if settings.is_key_bound_to("debug_menu", *key) {
+                self.open = !self.open;
+            }
         }
     }
 
@@ -3,11 +3,15 @@ use anyhow::{anyhow, Result};
 
 use crate::client::game::items::ClientItems;
 use crate::client::game::networking::ClientNetworking;
+use crate::client::settings::Settings;
 use crate::libraries::events::{Event, EventManager};
 use crate::libraries::graphics as gfx;
-use crate::shared::blocks::Blocks;
-use crate::shared::inventory::{Inventory, InventoryCraftPacket, InventoryPacket, InventorySelectPacket, InventorySwapPacket, Slot};
-use crate::shared::items::{ItemStack, RecipeId};
+use crate::shared::blocks::{BlockChangeEvent, Blocks};
+use crate::shared::inventory::{
+    is_block_inventory_in_range, Inventory, InventoryCraftPacket, InventoryDropPacket, InventoryMergePacket, InventoryPacket, InventoryQuickMovePacket, InventorySelectPacket, InventorySortPacket,
+    InventorySplitPacket, InventorySwapPacket, InventoryTakeOnePacket, Slot,
+};
+use crate::shared::items::{ItemStack, ProcessingState, RecipeId, CRAFTING_STATION_RANGE};
 use crate::shared::packet::Packet;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -33,12 +37,20 @@ pub struct ClientInventory {
     hovered_slot_rect: gfx::RenderRect,
     lower_slots_pos: [f32; 10],
     craftable_recipes: Vec<RecipeId>,
+    // the craftable recipes are only found again when the inventory, nearby blocks
+    // or the block the player is in change, they were last found for this block
+    craftable_recipes_block: Option<(i32, i32)>,
+    craftable_recipes_outdated: bool,
     crafting_back_rect: gfx::RenderRect,
     hover_back_rect: gfx::RenderRect,
+    // the selected item was picked up in the open inventory and not just selected in the hotbar
+    picked_up_item: bool,
 }
 
 const INVENTORY_SLOT_SIZE: f32 = 50.0;
 const INVENTORY_SPACING: f32 = 10.0;
+const PROGRESS_BAR_HEIGHT: f32 = 6.0;
+const DURABILITY_BAR_HEIGHT: f32 = 3.0;
 
 fn render_item_stack(graphics: &gfx::GraphicsContext, items: &ClientItems, pos: gfx::FloatPos, item: Option<&ItemStack>) {
     let pos = gfx::FloatPos(pos.0.round(), pos.1.round());
@@ -61,10 +73,28 @@ fn render_item_stack(graphics: &gfx::GraphicsContext, items: &ClientItems, pos:
                 let text_pos = pos + gfx::FloatPos(INVENTORY_SLOT_SIZE - 2.0, INVENTORY_SLOT_SIZE - 2.0) - text_size;
                 graphics.font.render_text(graphics, &text, text_pos, text_scale);
             }
+
+            // used items show how much durability they have left
+            let max_durability = items.get_items().get_item_type(item.item).ok().and_then(|item_type| item_type.max_durability);
+            if let (Some(durability), Some(max_durability)) = (item.metadata.durability, max_durability) {
+                let progress = (durability as f32 / max_durability as f32).clamp(0.0, 1.0);
+                let bar_pos = pos + gfx::FloatPos(4.0, INVENTORY_SLOT_SIZE - 4.0 - DURABILITY_BAR_HEIGHT);
+                let bar_width = INVENTORY_SLOT_SIZE - 8.0;
+                let color = gfx::Color::new(((1.0 - progress) * 255.0) as u8, (progress * 255.0) as u8, 0, 255);
+                gfx::Rect::new(bar_pos, gfx::FloatSize(bar_width, DURABILITY_BAR_HEIGHT)).render(graphics, gfx::BLACK);
+                gfx::Rect::new(bar_pos, gfx::FloatSize((bar_width * progress).round(), DURABILITY_BAR_HEIGHT)).render(graphics, color);
+            }
         }
     }
 }
 
+/// renders a bar under the slot at pos, that is filled by the progress (0 - 1)
+fn render_progress_bar(graphics: &gfx::GraphicsContext, pos: gfx::FloatPos, progress: f32, color: gfx::Color) {
+    let pos = gfx::FloatPos(pos.0, pos.1 + INVENTORY_SLOT_SIZE + INVENTORY_SPACING / 2.0);
+    gfx::Rect::new(pos, gfx::FloatSize(INVENTORY_SLOT_SIZE, PROGRESS_BAR_HEIGHT)).render(graphics, gfx::DARK_GREY);
+    gfx::Rect::new(pos, gfx::FloatSize((INVENTORY_SLOT_SIZE * progress).round(), PROGRESS_BAR_HEIGHT)).render(graphics, color);
+}
+
 fn render_inventory_slot(graphics: &gfx::GraphicsContext, items: &ClientItems, pos: gfx::FloatPos, item: Option<&ItemStack>) -> bool {
     let rect = gfx::Rect::new(pos, gfx::FloatSize(INVENTORY_SLOT_SIZE, INVENTORY_SLOT_SIZE));
     let hovered = rect.contains(graphics.get_mouse_pos());
@@ -87,8 +117,11 @@ impl ClientInventory {
             hovered_slot_rect: gfx::RenderRect::new(gfx::FloatPos(0.0, 0.0), gfx::FloatSize(0.0, 0.0)),
             lower_slots_pos: [0.0; 10],
             craftable_recipes: Vec::new(),
+            craftable_recipes_block: None,
+            craftable_recipes_outdated: true,
             crafting_back_rect: gfx::RenderRect::new(gfx::FloatPos(0.0, 0.0), gfx::FloatSize(0.0, 0.0)),
             hover_back_rect: gfx::RenderRect::new(gfx::FloatPos(0.0, 0.0), gfx::FloatSize(0.0, INVENTORY_SLOT_SIZE + 2.0 * INVENTORY_SPACING)),
+            picked_up_item: false,
         }
     }
 
@@ -118,15 +151,40 @@ impl ClientInventory {
         self.hover_back_rect.shadow_intensity = gfx::SHADOW_INTENSITY / 2;
     }
 
-    fn update_craftable_recipes(&mut self, items: &ClientItems) {
+    /// updates the craftable recipes, recipes whose crafting station
+    /// is not near the player are left out
+    fn update_craftable_recipes(&mut self, items: &ClientItems, blocks: &Blocks, player_pos: Option<(f32, f32)>) -> Result<()> {
+        let player_block = player_pos.map(|(player_x, player_y)| (player_x as i32, player_y as i32));
+        if !self.craftable_recipes_outdated && player_block == self.craftable_recipes_block {
+            return Ok(());
+        }
+        self.craftable_recipes_outdated = false;
+        self.craftable_recipes_block = player_block;
+
         self.craftable_recipes.clear();
+        let Some((player_x, player_y)) = player_pos else {
+            return Ok(());
+        };
+
         let items = items.get_items();
         let recipes = items.get_recipes();
         for recipe in recipes {
-            if self.inventory.can_craft(recipe) {
+            if self.inventory.can_craft(recipe) && recipe.is_station_in_range(blocks, player_x, player_y)? {
                 self.craftable_recipes.push(recipe.get_id());
             }
         }
+        Ok(())
+    }
+
+    /// `player_pos` is the center of the main player in blocks, None if there is no main player
+    pub fn update(&mut self, items: &ClientItems, blocks: &Blocks, player_pos: Option<(f32, f32)>) -> Result<()> {
+        // the server ignores block slots that are out of range, so the block is closed when the player walks away
+        if let (OpenState::OpenedBlock { x, y }, Some((player_x, player_y))) = (self.open_state, player_pos) {
+            if !is_block_inventory_in_range(x, y, player_x, player_y) {
+                self.open_state = OpenState::Closed;
+            }
+        }
+        self.update_craftable_recipes(items, blocks, player_pos)
     }
 
     fn render_inventory(&mut self, graphics: &gfx::GraphicsContext, items: &ClientItems) -> Result<()> {
@@ -201,7 +259,7 @@ impl ClientInventory {
         Ok(())
     }
 
-    fn render_crafting(&mut self, graphics: &gfx::GraphicsContext, items: &ClientItems) -> Result<()> {
+    fn render_crafting(&mut self, graphics: &gfx::GraphicsContext, items: &ClientItems, blocks: &Blocks) -> Result<()> {
         if self.open_progress > 0.0 {
             self.crafting_back_rect.pos.0 = INVENTORY_SPACING * self.open_progress + (-self.crafting_back_rect.size.0 - INVENTORY_SPACING) * (1.0 - self.open_progress);
 
@@ -215,9 +273,7 @@ impl ClientInventory {
             let mut y = self.crafting_back_rect.pos.1 + INVENTORY_SPACING;
 
             for recipe_id in &self.craftable_recipes {
-                let items2 = items.get_items();
-                let recipe = items2.get_recipe(*recipe_id)?;
-                let item = recipe.result.clone();
+                let item = items.get_items().get_recipe(*recipe_id)?.result.clone();
                 let hovered = render_inventory_slot(graphics, items, gfx::FloatPos(x, y), Some(&item));
 
                 if hovered {
@@ -229,8 +285,7 @@ impl ClientInventory {
         }
 
         if let HoveredSlot::Recipe(recipe_id) = self.hovered_slot {
-            let items2 = items.get_items();
-            let recipe = items2.get_recipe(recipe_id)?;
+            let recipe = items.get_items().get_recipe(recipe_id)?.clone();
             let num_recipes = recipe.ingredients.len() as i32;
 
             self.hover_back_rect.pos = graphics.get_mouse_pos();
@@ -245,12 +300,48 @@ impl ClientInventory {
                 render_inventory_slot(graphics, items, gfx::FloatPos(x, y), Some(&ItemStack::new(*item, *count)));
                 x += INVENTORY_SLOT_SIZE + INVENTORY_SPACING;
             }
+
+            // recipes that need a crafting station show its name under the ingredients
+            if let Some(station) = recipe.station {
+                let text = format!("Crafted at {}", blocks.get_block_type(station)?.name);
+                let text_pos = gfx::FloatPos(graphics.get_mouse_pos().0, y + INVENTORY_SLOT_SIZE + 2.0 * INVENTORY_SPACING);
+                graphics.font.render_text(graphics, &text, text_pos, 1.0);
+            }
         }
 
         Ok(())
     }
 
-    fn render_mouse_item(&mut self, graphics: &gfx::GraphicsContext, items: &ClientItems) {
+    /// renders the name of the hovered item next to the mouse, stacks
+    /// with a custom display name show it instead of the item type name
+    fn render_item_tooltip(&self, graphics: &gfx::GraphicsContext, items: &ClientItems, blocks: &Blocks) -> Result<()> {
+        // the held item is rendered at the mouse
+        if self.open_state != OpenState::Closed && self.inventory.get_selected_item().is_some() {
+            return Ok(());
+        }
+
+        let item = match (&self.hovered_slot, self.open_state) {
+            (HoveredSlot::Inventory(slot), _) => self.inventory.get_item(*slot)?,
+            (HoveredSlot::Block(slot), OpenState::OpenedBlock { x, y }) => blocks.get_block_inventory_data(x, y)?.and_then(|slots| slots.get(*slot).cloned().flatten()),
+            _ => None,
+        };
+        let Some(item) = item else {
+            return Ok(());
+        };
+
+        let name = if let Some(display_name) = item.metadata.display_name {
+            display_name
+        } else {
+            items.get_items().get_item_type(item.item)?.display_name
+        };
+        let text_size = graphics.font.get_text_size_scaled(&name, 1.0, None);
+        let pos = graphics.get_mouse_pos() + gfx::FloatPos(INVENTORY_SPACING, INVENTORY_SPACING);
+        gfx::Rect::new(pos, gfx::FloatSize(text_size.0 + 2.0 * INVENTORY_SPACING, text_size.1 + 2.0 * INVENTORY_SPACING)).render(graphics, gfx::BLACK.set_a(gfx::TRANSPARENCY));
+        graphics.font.render_text(graphics, &name, pos + gfx::FloatPos(INVENTORY_SPACING, INVENTORY_SPACING), 1.0);
+        Ok(())
+    }
+
+    fn render_mouse_item(&self, graphics: &gfx::GraphicsContext, items: &ClientItems) {
         if self.open_state != OpenState::Closed {
             render_item_stack(graphics, items, graphics.get_mouse_pos(), self.inventory.get_selected_item().as_ref());
         }
@@ -260,21 +351,30 @@ impl ClientInventory {
         if let OpenState::OpenedBlock { x, y } = self.open_state {
             let slots = blocks.get_block_inventory_data(x, y)?;
             let slots_pos = blocks.get_block_type_at(x, y)?.inventory_slots;
+            let get_slot_pos = |pos: &(i32, i32)| gfx::FloatPos(pos.0 as f32 + graphics.get_window_size().0 / 2.0 - INVENTORY_SLOT_SIZE / 2.0, pos.1 as f32);
             if let Some(slots) = slots {
                 for (slot, (item, pos)) in slots.iter().zip(slots_pos.iter()).enumerate() {
                     let item = item.as_ref();
 
-                    let hovered = render_inventory_slot(
-                        graphics,
-                        items,
-                        gfx::FloatPos(pos.0 as f32 + graphics.get_window_size().0 / 2.0 - INVENTORY_SLOT_SIZE / 2.0, pos.1 as f32),
-                        item,
-                    );
+                    let hovered = render_inventory_slot(graphics, items, get_slot_pos(pos), item);
 
                     if hovered {
                         self.hovered_slot = HoveredSlot::Block(slot);
                     }
                 }
+
+                // processing blocks also show the progress of the current recipe and the fuel left
+                if let Some(processing_block) = items.get_items().get_processing_block(blocks.get_block(x, y)?) {
+                    let state = ProcessingState::from_block_data(&blocks.get_block_data(x, y)?)?;
+
+                    if let Some(pos) = slots_pos.get(processing_block.input_slot) {
+                        render_progress_bar(graphics, get_slot_pos(pos), state.get_progress(), gfx::WHITE);
+                    }
+
+                    if let Some(pos) = processing_block.fuel_slot.and_then(|fuel_slot| slots_pos.get(fuel_slot)) {
+                        render_progress_bar(graphics, get_slot_pos(pos), state.get_fuel(), gfx::Color::new(230, 120, 40, 255));
+                    }
+                }
             } else {
                 self.open_state = OpenState::Closed;
             }
@@ -282,7 +382,7 @@ impl ClientInventory {
         Ok(())
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, reason = "it renders the inventory, the crafting list and the opened block")]
     pub fn render(&mut self, graphics: &gfx::GraphicsContext, items: &ClientItems, networking: &mut ClientNetworking, blocks: &Blocks) -> Result<()> {
         let open_target = if self.open_state == OpenState::Closed { 0.0 } else { 1.0 };
         self.open_progress += (open_target - self.open_progress) / 5.0;
@@ -303,13 +403,15 @@ impl ClientInventory {
         self.render_inventory(graphics, items)?;
         self.render_block_ui(graphics, items, blocks)?;
         self.render_mouse_item(graphics, items);
-        self.render_crafting(graphics, items)?;
+        self.render_crafting(graphics, items, blocks)?;
+        self.render_item_tooltip(graphics, items, blocks)?;
 
         Ok(())
     }
 
     fn select_slot(&mut self, slot: Option<usize>, networking: &mut ClientNetworking) -> Result<()> {
         self.inventory.selected_slot = slot;
+        self.picked_up_item = false;
         let packet = InventorySelectPacket { slot };
         networking.send_packet(Packet::new(packet)?)
     }
@@ -320,8 +422,51 @@ impl ClientInventory {
         networking.send_packet(Packet::new(packet)?)
     }
 
-    pub fn on_event(&mut self, event: &Event, networking: &mut ClientNetworking, items: &ClientItems, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+    /// selects the first empty slot if no slot is selected
+    fn select_free_slot(&mut self, networking: &mut ClientNetworking) -> Result<()> {
+        if self.inventory.selected_slot.is_none() {
+            let free_slot = self.inventory.iter().position(Option::is_none);
+            self.select_slot(free_slot, networking)?;
+        }
+        Ok(())
+    }
+
+    /// returns true if the mouse is over a slot or an inventory window,
+    /// clicks there are meant for the inventory and not for the world
+    fn is_mouse_over_ui(&self, graphics: &gfx::GraphicsContext) -> bool {
+        if !matches!(self.hovered_slot, HoveredSlot::None) {
+            return true;
+        }
+        let mouse_pos = graphics.get_mouse_pos();
+        let back_rect = *self.back_rect.get_container(graphics, None).get_absolute_rect();
+        let crafting_back_rect = *self.crafting_back_rect.get_container(graphics, None).get_absolute_rect();
+        back_rect.contains(mouse_pos) || (self.open_state != OpenState::Closed && crafting_back_rect.contains(mouse_pos))
+    }
+
+    /// returns true if the inventory is open, an item was picked up in it and the
+    /// mouse is outside of all inventory windows, so clicking drops the item
+    fn is_dropping_outside(&self, graphics: &gfx::GraphicsContext) -> bool {
+        self.open_state != OpenState::Closed && self.picked_up_item && self.inventory.get_selected_item().is_some() && !self.is_mouse_over_ui(graphics)
+    }
+
+    /// returns the hovered inventory or block slot
+    const fn get_hovered_slot(&self) -> Option<Slot> {
+        match (&self.hovered_slot, self.open_state) {
+            (HoveredSlot::Inventory(slot), _) => Some(Slot::Inventory(*slot)),
+            (HoveredSlot::Block(slot), OpenState::OpenedBlock { x, y }) => Some(Slot::Block(x, y, *slot)),
+            _ => None,
+        }
+    }
+
+    /// handles the event and returns true if it was a click the inventory used,
+    /// so it doesn't break blocks or attack behind the inventory
+    #[allow(clippy::too_many_lines, reason = "it handles every click and key of the inventory")]
+    pub fn on_event(&mut self, event: &Event, graphics: &gfx::GraphicsContext, settings: &Settings, networking: &mut ClientNetworking, blocks: &mut Blocks, events: &mut EventManager) -> Result<bool> {
+        let mut consumed = false;
         if let Some(gfx::Event::KeyPress { 0: key, .. }) = event.downcast::<gfx::Event>() {
+            let is_click = matches!(key, gfx::Key::MouseLeft | gfx::Key::MouseRight | gfx::Key::MouseMiddle);
+            consumed = is_click && (self.is_mouse_over_ui(graphics) || self.is_dropping_outside(graphics));
+            let shift_pressed = graphics.get_key_state(gfx::Key::LeftShift) || graphics.get_key_state(gfx::Key::RightShift);
             match *key {
                 gfx::Key::Num1 => self.select_slot(Some(0), networking)?,
                 gfx::Key::Num2 => self.select_slot(Some(1), networking)?,
@@ -333,6 +478,47 @@ impl ClientInventory {
                 gfx::Key::Num8 => self.select_slot(Some(7), networking)?,
                 gfx::Key::Num9 => self.select_slot(Some(8), networking)?,
                 gfx::Key::Num0 => self.select_slot(Some(9), networking)?,
+                // the drop key drops a single item and with ctrl the whole stack
+                key if settings.is_key_bound_to("drop_item", key) => {
+                    let ctrl_pressed = graphics.get_key_state(gfx::Key::LeftControl) || graphics.get_key_state(gfx::Key::RightControl);
+                    networking.send_packet(Packet::new(InventoryDropPacket { whole_stack: ctrl_pressed })?)?;
+                }
+                // dragging the held item outside of the inventory drops it, right click drops only one
+                gfx::Key::MouseLeft if self.is_dropping_outside(graphics) => {
+                    networking.send_packet(Packet::new(InventoryDropPacket { whole_stack: true })?)?;
+                    self.select_slot(None, networking)?;
+                }
+                gfx::Key::MouseRight if self.is_dropping_outside(graphics) => {
+                    networking.send_packet(Packet::new(InventoryDropPacket { whole_stack: false })?)?;
+                }
+                // shift click moves the stack to the other inventory
+                gfx::Key::MouseLeft if shift_pressed && self.get_hovered_slot().is_some() => {
+                    if let Some(slot) = self.get_hovered_slot() {
+                        let block = if let OpenState::OpenedBlock { x, y } = self.open_state { Some((x, y)) } else { None };
+                        networking.send_packet(Packet::new(InventoryQuickMovePacket { slot, block })?)?;
+                    }
+                }
+                // right click splits the stack into the selected slot if it is empty, otherwise it takes one item
+                gfx::Key::MouseRight => {
+                    if let Some(slot) = self.get_hovered_slot() {
+                        self.select_free_slot(networking)?;
+                        if self.inventory.get_selected_item().is_none() {
+                            networking.send_packet(Packet::new(InventorySplitPacket { slot })?)?;
+                        } else {
+                            networking.send_packet(Packet::new(InventoryTakeOnePacket { slot })?)?;
+                        }
+                        self.picked_up_item = self.open_state != OpenState::Closed;
+                    }
+                }
+                // middle click fills the stack with the same items from the rest of the inventory
+                gfx::Key::MouseMiddle => {
+                    if let HoveredSlot::Inventory(slot) = self.hovered_slot {
+                        networking.send_packet(Packet::new(InventoryMergePacket { slot })?)?;
+                    }
+                }
+                key if self.open_state != OpenState::Closed && settings.is_key_bound_to("sort_inventory", key) => {
+                    networking.send_packet(Packet::new(InventorySortPacket)?)?;
+                }
                 gfx::Key::MouseLeft => {
                     let mut already_deselected = false;
                     if let Some(selected_slot) = self.inventory.selected_slot {
@@ -351,6 +537,7 @@ impl ClientInventory {
                                 self.select_slot(None, networking)?;
                             } else {
                                 self.select_slot(Some(hovered_slot), networking)?;
+                                self.picked_up_item = self.open_state != OpenState::Closed;
                             }
                         }
                     }
@@ -360,17 +547,7 @@ impl ClientInventory {
                     }
 
                     if let HoveredSlot::Block(slot) = self.hovered_slot {
-                        if self.inventory.selected_slot.is_none() {
-                            let mut free_slot = None;
-                            for (index, inventory_slot) in self.inventory.iter().enumerate() {
-                                if inventory_slot.is_none() {
-                                    free_slot = Some(index);
-                                    break;
-                                }
-                            }
-
-                            self.select_slot(free_slot, networking)?;
-                        }
+                        self.select_free_slot(networking)?;
 
                         if let Some(selected_slot) = self.inventory.selected_slot {
                             if let OpenState::OpenedBlock { x, y } = self.open_state {
@@ -383,11 +560,13 @@ impl ClientInventory {
 
                                 let packet = InventorySwapPacket { slot: Slot::Block(x, y, slot) };
                                 networking.send_packet(Packet::new(packet)?)?;
+                                self.picked_up_item = true;
                             }
                         }
                     }
                 }
-                gfx::Key::E => {
+                key if settings.is_key_bound_to("open_inventory", key) => {
+                    self.picked_up_item = false;
                     if self.open_state == OpenState::Closed {
                         self.open_state = OpenState::Open;
                     } else {
@@ -399,13 +578,21 @@ impl ClientInventory {
         } else if let Some(packet) = event.downcast::<Packet>() {
             if let Some(packet) = packet.try_deserialize::<InventoryPacket>() {
                 self.inventory.transfer_items_from(packet.inventory);
-                self.update_craftable_recipes(items);
+                self.craftable_recipes_outdated = true;
+            }
+        } else if let Some(event) = event.downcast::<BlockChangeEvent>() {
+            // a crafting station could have been placed or broken near the player
+            if let Some((block_x, block_y)) = self.craftable_recipes_block {
+                if (event.x - block_x).abs() <= CRAFTING_STATION_RANGE && (event.y - block_y).abs() <= CRAFTING_STATION_RANGE {
+                    self.craftable_recipes_outdated = true;
+                }
             }
         } else if let Some(event) = event.downcast::<BlockRightClickEvent>() {
             let has_inventory = !blocks.get_block_type_at(event.x, event.y)?.inventory_slots.is_empty();
 
             if has_inventory {
                 let offset = blocks.get_block_from_main(event.x, event.y)?;
+                self.picked_up_item = false;
 
                 self.open_state = OpenState::OpenedBlock {
                     x: event.x - offset.0,
@@ -414,6 +601,6 @@ impl ClientInventory {
             }
         }
 
-        Ok(())
+        Ok(consumed)
     }
 }
@@ -6,9 +6,9 @@ use anyhow::{anyhow, Result};
 use crate::client::game::camera::Camera;
 use crate::libraries::events::{Event, EventManager};
 use crate::libraries::graphics as gfx;
-use crate::shared::blocks::{RENDER_BLOCK_WIDTH, RENDER_SCALE};
+use crate::shared::blocks::{Blocks, RENDER_BLOCK_WIDTH, RENDER_SCALE};
 use crate::shared::entities::{Entities, PhysicsComponent, PositionComponent};
-use crate::shared::items::{init_items_mod_interface, ItemComponent, ItemId, ItemSpawnPacket, Items};
+use crate::shared::items::{init_items_mod_interface, ItemComponent, ItemCountChangePacket, ItemId, ItemSpawnPacket, ItemStack, Items};
 use crate::shared::mod_manager::ModManager;
 use crate::shared::packet::Packet;
 
@@ -25,8 +25,8 @@ impl ClientItems {
         }
     }
 
-    pub fn init(&mut self, mods: &mut ModManager) -> Result<()> {
-        init_items_mod_interface(&self.items, mods)
+    pub fn init(&self, blocks: &Arc<Mutex<Blocks>>, mods: &mut ModManager) -> Result<()> {
+        init_items_mod_interface(&self.items, blocks, mods)
     }
 
     pub fn load_resources(&mut self, mods: &ModManager) -> Result<()> {
@@ -47,15 +47,21 @@ impl ClientItems {
         Ok(())
     }
 
-    pub fn on_event(&mut self, event: &Event, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+    pub fn on_event(&self, event: &Event, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
         if let Some(packet) = event.downcast::<Packet>() {
             if let Some(packet) = packet.try_deserialize::<ItemSpawnPacket>() {
-                let item = self.get_items().spawn_item(events, entities, packet.item_type, packet.x, packet.y, packet.id)?;
+                let stack = ItemStack::new(packet.item_type, packet.count);
+                let item = self.get_items().spawn_item_stack(events, entities, &stack, packet.x, packet.y, packet.id)?;
+                entities.ecs.get::<&mut ItemComponent>(item)?.set_pickup_delay_ms(packet.pickup_delay_ms);
 
                 let mut physics = entities.ecs.get::<&mut PhysicsComponent>(item)?;
                 physics.velocity_x = packet.velocity_x;
                 physics.velocity_y = packet.velocity_y;
             }
+            if let Some(packet) = packet.try_deserialize::<ItemCountChangePacket>() {
+                let item = entities.get_entity_from_id(packet.id)?;
+                entities.ecs.get::<&mut ItemComponent>(item)?.set_count(packet.count, events, item);
+            }
         }
         Ok(())
     }
@@ -77,6 +83,17 @@ impl ClientItems {
                 false,
                 None,
             );
+
+            // merged items show how many of them there are
+            if item.get_count() > 1 {
+                let text = format!("{}", item.get_count());
+                let text_size = graphics.font.get_text_size_scaled(&text, 1.0, None);
+                let text_pos = gfx::FloatPos(
+                    (position.x() * RENDER_BLOCK_WIDTH - top_left.0 * RENDER_BLOCK_WIDTH + 0.5 * RENDER_BLOCK_WIDTH + src_rect.size.0 / 2.0 * RENDER_SCALE - text_size.0).round(),
+                    (position.y() * RENDER_BLOCK_WIDTH - top_left.1 * RENDER_BLOCK_WIDTH + 0.5 * RENDER_BLOCK_WIDTH + src_rect.size.1 / 2.0 * RENDER_SCALE - text_size.1).round(),
+                );
+                graphics.font.render_text(graphics, &text, text_pos, 1.0);
+            }
         }
 
         Ok(())
@@ -87,7 +104,7 @@ impl ClientItems {
         &self.atlas
     }
 
-    pub fn get_items(&self) -> MutexGuard<Items> {
+    pub fn get_items(&self) -> MutexGuard<'_, Items> {
         self.items.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
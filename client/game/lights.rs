@@ -132,15 +132,7 @@ impl ClientLights {
             for chunk_x in extended_start_x / CHUNK_SIZE..=extended_end_x / CHUNK_SIZE {
                 for chunk_y in extended_start_y / CHUNK_SIZE..=extended_end_y / CHUNK_SIZE {
                     if self.lights.get_light_chunk(chunk_x, chunk_y)?.scheduled_light_update_count != 0 {
-                        for x in chunk_x * CHUNK_SIZE..(chunk_x + 1) * CHUNK_SIZE {
-                            for y in chunk_y * CHUNK_SIZE..(chunk_y + 1) * CHUNK_SIZE {
-                                self.lights.update_light_emitter(x, y, blocks)?;
-                                if self.lights.get_light(x, y)?.scheduled_light_update {
-                                    self.lights.update_light(x, y, blocks)?;
-                                    updated = true;
-                                }
-                            }
-                        }
+                        updated |= self.lights.update_chunk(chunk_x, chunk_y, blocks)?;
 
                         let pos = [(chunk_x, chunk_y), (chunk_x + 1, chunk_y), (chunk_x, chunk_y + 1), (chunk_x + 1, chunk_y + 1)];
 
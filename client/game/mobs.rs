@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+
+use crate::client::game::camera::Camera;
+use crate::libraries::events::{Event, EventManager};
+use crate::libraries::graphics as gfx;
+use crate::shared::blocks::{RENDER_BLOCK_WIDTH, RENDER_SCALE};
+use crate::shared::entities::{Entities, PhysicsComponent, PositionComponent};
+use crate::shared::mobs::{init_mobs_mod_interface, MobComponent, MobId, MobSpawnPacket, Mobs};
+use crate::shared::mod_manager::ModManager;
+use crate::shared::packet::Packet;
+
+// mobs without a texture are rendered as a rectangle of this color
+const MISSING_TEXTURE_COLOR: gfx::Color = gfx::Color::new(255, 0, 255, 255);
+
+pub struct ClientMobs {
+    mobs: Arc<Mutex<Mobs>>,
+    atlas: gfx::TextureAtlas<MobId>,
+}
+
+impl ClientMobs {
+    pub fn new() -> Self {
+        Self {
+            mobs: Arc::new(Mutex::new(Mobs::new())),
+            atlas: gfx::TextureAtlas::new(&HashMap::new()),
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_mobs_mod_interface(&self.mobs, mods)
+    }
+
+    pub fn load_resources(&mut self, mods: &ModManager) -> Result<()> {
+        // go through all the mob types get their images and load them
+        let mut surfaces = HashMap::new();
+        for mob_type in &self.get_mobs().mob_types {
+            if let Some(image_resource) = mods.get_resource(&format!("mobs:{}.opa", mob_type.name)) {
+                let image = gfx::Surface::deserialize_from_bytes(&image_resource.clone())?;
+                surfaces.insert(mob_type.get_id(), image);
+            }
+        }
+
+        self.atlas = gfx::TextureAtlas::new(&surfaces);
+
+        Ok(())
+    }
+
+    pub fn on_event(&self, event: &Event, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        if let Some(packet) = event.downcast::<Packet>() {
+            if let Some(packet) = packet.try_deserialize::<MobSpawnPacket>() {
+                let mob = self.get_mobs().spawn_mob(events, entities, packet.mob_type, packet.x, packet.y, packet.id)?;
+
+                let mut physics = entities.ecs.get::<&mut PhysicsComponent>(mob)?;
+                physics.velocity_x = packet.velocity_x;
+                physics.velocity_y = packet.velocity_y;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn render(&self, graphics: &gfx::GraphicsContext, camera: &Camera, entities: &mut Entities) {
+        let top_left = camera.get_top_left(graphics);
+        for (_entity, (position, physics, mob)) in entities.ecs.query_mut::<(&PositionComponent, &PhysicsComponent, &MobComponent)>() {
+            let x = ((position.x() - top_left.0) * RENDER_BLOCK_WIDTH).round();
+            let y = ((position.y() - top_left.1) * RENDER_BLOCK_WIDTH).round();
+
+            if let Some(src_rect) = self.atlas.get_rect(&mob.get_mob_type()) {
+                // the texture is aligned to the bottom center of the mob
+                let width = physics.collision_width() * RENDER_BLOCK_WIDTH;
+                let height = physics.collision_height() * RENDER_BLOCK_WIDTH;
+                let pos = gfx::FloatPos((x + width / 2.0 - src_rect.size.0 * RENDER_SCALE / 2.0).round(), (y + height - src_rect.size.1 * RENDER_SCALE).round());
+                self.atlas.get_texture().render(graphics, RENDER_SCALE, pos, Some(*src_rect), physics.velocity_x < 0.0, None);
+            } else {
+                gfx::Rect::new(
+                    gfx::FloatPos(x, y),
+                    gfx::FloatSize(physics.collision_width() * RENDER_BLOCK_WIDTH, physics.collision_height() * RENDER_BLOCK_WIDTH),
+                )
+                .render(graphics, MISSING_TEXTURE_COLOR);
+            }
+        }
+    }
+
+    pub fn get_mobs(&self) -> MutexGuard<'_, Mobs> {
+        self.mobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -3,19 +3,22 @@ mod block_selector;
 mod blocks;
 mod camera;
 mod chat;
+mod combat;
 pub mod core_client;
 mod debug_menu;
 mod entities;
 mod floating_text;
 mod framerate_measurer;
-mod health;
 mod inventory;
 mod items;
 mod lights;
+mod mobs;
 mod mod_manager;
 mod networking;
 mod pause_menu;
 mod players;
 pub mod private_world;
 mod respawn_screen;
+mod stats;
 mod walls;
+mod world_time;
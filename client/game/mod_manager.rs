@@ -1,6 +1,10 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
 use anyhow::Result;
 
+use crate::client::settings::Settings;
 use crate::libraries::events::Event;
+use crate::libraries::graphics as gfx;
 use crate::shared::mod_manager::{ModManager, ModsWelcomePacket};
 
 use super::networking::WelcomePacketEvent;
@@ -12,13 +16,18 @@ use super::networking::WelcomePacketEvent;
 /// and always loads the `base_game` mod.
 pub struct ClientModManager {
     pub mod_manager: ModManager,
+    // key bindings registered by mods, they are added to the settings once the game has started
+    new_key_bindings: Arc<Mutex<Vec<(String, String, gfx::Key)>>>,
+    key_binding_actions: Vec<String>,
 }
 
 impl ClientModManager {
     /// Creates a new client mod manager.
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             mod_manager: ModManager::new(Vec::new()),
+            new_key_bindings: Arc::new(Mutex::new(Vec::new())),
+            key_binding_actions: Vec::new(),
         }
     }
 
@@ -30,13 +39,51 @@ impl ClientModManager {
             Ok(())
         })?;
 
-        self.mod_manager.init()
+        // a method to register an action that the player can bind to a key,
+        // the mod gets on_key_press(action) called when the key is pressed
+        let new_key_bindings = self.new_key_bindings.clone();
+        self.mod_manager.add_global_function("register_key_binding", move |_, (action, text, key): (String, String, String)| {
+            let key = gfx::Key::from_name(&key).ok_or_else(|| rlua::Error::RuntimeError(format!("unknown key {key}")))?;
+            new_key_bindings.lock().unwrap_or_else(PoisonError::into_inner).push((action, text, key));
+            Ok(())
+        })?;
+
+        self.mod_manager.init()?;
+        self.mod_manager.call_function_in_mods("init_client", ())?;
+        self.print_errors();
+        Ok(())
+    }
+
+    /// This function adds the key bindings registered by mods to the settings,
+    /// so they can be changed in the controls settings.
+    pub fn register_key_bindings(&mut self, settings: &mut Settings) {
+        let new_key_bindings = std::mem::take(&mut *self.new_key_bindings.lock().unwrap_or_else(PoisonError::into_inner));
+        for (action, text, key) in new_key_bindings {
+            // two mods using the same action name shouldn't stop the game from loading
+            if let Err(error) = settings.register_key_binding(&action, &text, key) {
+                println!("Error registering key binding: {error}");
+                continue;
+            }
+            self.key_binding_actions.push(action);
+        }
     }
 
     /// This function updates the client mod manager.
     /// It updates the shared mod manager.
     pub fn update(&mut self) -> Result<()> {
-        self.mod_manager.update()
+        self.mod_manager.update()?;
+        self.print_errors();
+        Ok(())
+    }
+
+    /// This function prints the errors of the mods, the game keeps running without them.
+    fn print_errors(&mut self) {
+        for error in self.mod_manager.take_errors() {
+            println!("[client mod] {}", error.message);
+            if error.disabled {
+                println!("[client mod] mod {} was disabled", error.mod_name);
+            }
+        }
     }
 
     pub fn on_event(&mut self, event: &Event) -> Result<()> {
@@ -53,9 +100,25 @@ impl ClientModManager {
         Ok(())
     }
 
+    /// This function lets the mods know that a key bound to one of their actions was pressed.
+    pub fn on_key_event(&mut self, event: &Event, settings: &Settings) -> Result<()> {
+        if let Some(gfx::Event::KeyPress(key, false)) = event.downcast::<gfx::Event>() {
+            for action in settings.get_actions_bound_to(*key) {
+                if !self.key_binding_actions.iter().any(|mod_action| mod_action == action) {
+                    continue;
+                }
+                self.mod_manager.call_function_in_mods("on_key_press", action.to_owned())?;
+            }
+        }
+        Ok(())
+    }
+
     /// This function stops the client mod manager.
-    /// It stops the shared mod manager.
-    pub fn stop(&mut self) -> Result<()> {
+    /// It stops the shared mod manager and removes the key bindings of the mods.
+    pub fn stop(&mut self, settings: &mut Settings) -> Result<()> {
+        for action in self.key_binding_actions.drain(..) {
+            settings.remove_key_binding(&action)?;
+        }
         self.mod_manager.stop()
     }
 }
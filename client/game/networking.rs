@@ -142,7 +142,7 @@ impl ClientNetworking {
                             );
                         }
                     }
-                };
+                }
             } else {
                 // normal loop
                 match event {
@@ -178,14 +178,14 @@ impl ClientNetworking {
 
                         handler.signals().send_with_timer((), std::time::Duration::from_millis(1));
                     }
-                };
+                }
             }
         });
 
         Ok(())
     }
 
-    pub fn update(&mut self, events: &mut EventManager) -> Result<()> {
+    pub fn update(&self, events: &mut EventManager) -> Result<()> {
         if let Some(receiver) = &self.event_receiver {
             while let Ok(event) = receiver.try_recv() {
                 events.push_event(event);
@@ -219,7 +219,7 @@ impl ClientNetworking {
                     std::thread::sleep(std::time::Duration::from_millis(1));
                     // just try again
                 }
-            };
+            }
         }
         Ok(())
     }
@@ -233,7 +233,7 @@ impl ClientNetworking {
         self.is_welcoming.load(Ordering::Relaxed)
     }
 
-    pub fn start_receiving(&mut self) {
+    pub fn start_receiving(&self) {
         self.should_start_receiving.store(true, Ordering::Relaxed);
     }
 
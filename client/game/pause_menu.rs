@@ -107,20 +107,16 @@ impl PauseMenu {
             }
 
             match event {
-                gfx::Event::KeyPress(key, false) => {
-                    if *key == gfx::Key::Escape {
-                        self.open = !self.open;
-                    }
+                gfx::Event::KeyPress(key, false) if *key == gfx::Key::Escape => {
+                    self.open = !self.open;
                 }
-                gfx::Event::KeyRelease(key, false) => {
-                    if *key == gfx::Key::MouseLeft && self.open {
-                        if self.resume_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
-                            self.open = false;
-                        } else if self.settings_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
-                            self.in_settings = true;
-                        } else if self.quit_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
-                            return true;
-                        }
+                gfx::Event::KeyRelease(key, false) if *key == gfx::Key::MouseLeft && self.open => {
+                    if self.resume_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
+                        self.open = false;
+                    } else if self.settings_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
+                        self.in_settings = true;
+                    } else if self.quit_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
+                        return true;
                     }
                 }
                 _ => {}
@@ -3,6 +3,7 @@ use hecs::Entity;
 
 use crate::client::game::camera::Camera;
 use crate::client::game::networking::ClientNetworking;
+use crate::client::settings::Settings;
 use crate::libraries::events::Event;
 use crate::libraries::graphics as gfx;
 use crate::shared::blocks::{Blocks, BLOCK_WIDTH, RENDER_BLOCK_WIDTH, RENDER_SCALE};
@@ -85,15 +86,15 @@ impl ClientPlayers {
         Ok(())
     }
 
-    pub fn update(&mut self, graphics: &gfx::GraphicsContext, entities: &mut Entities, networking: &mut ClientNetworking, blocks: &Blocks) -> Result<()> {
+    pub fn update(&self, graphics: &gfx::GraphicsContext, settings: &Settings, entities: &mut Entities, networking: &mut ClientNetworking, blocks: &Blocks) -> Result<()> {
         if let Some(main_player) = self.main_player {
             if let Ok((physics, player_component)) = entities.ecs.query_one_mut::<(&mut PhysicsComponent, &mut PlayerComponent)>(main_player) {
-                Self::set_jumping(networking, player_component, graphics.get_key_state(gfx::Key::Space) && self.controls_enabled)?;
+                Self::set_jumping(networking, player_component, settings.is_key_binding_pressed(graphics, "jump") && self.controls_enabled)?;
 
-                let key_a_pressed = graphics.get_key_state(gfx::Key::A) && self.controls_enabled;
-                let key_d_pressed = graphics.get_key_state(gfx::Key::D) && self.controls_enabled;
+                let left_pressed = settings.is_key_binding_pressed(graphics, "move_left") && self.controls_enabled;
+                let right_pressed = settings.is_key_binding_pressed(graphics, "move_right") && self.controls_enabled;
 
-                let moving_type = match (key_a_pressed, key_d_pressed) {
+                let moving_type = match (left_pressed, right_pressed) {
                     (true, false) => MovingType::MovingLeft,
                     (false, true) => MovingType::MovingRight,
                     _ => MovingType::Standing,
@@ -37,12 +37,12 @@ pub fn run_private_world(graphics: &mut gfx::GraphicsContext, menu_back: &mut dy
     run_loading_screen(graphics, menu_back, &loading_text);
 
     if server_running.load(Ordering::Relaxed) {
-        run_game(graphics, menu_back, SINGLEPLAYER_PORT, String::from("127.0.0.1"), "_", settings, global_settings)?;
+        run_game(graphics, menu_back, SINGLEPLAYER_PORT, "127.0.0.1", "_", settings, global_settings)?;
 
         // stop server
         server_running.store(false, Ordering::Relaxed);
 
-        *loading_text.lock().unwrap_or_else(PoisonError::into_inner) = "Waiting for server".to_owned();
+        "Waiting for server".clone_into(&mut loading_text.lock().unwrap_or_else(PoisonError::into_inner));
         run_loading_screen(graphics, menu_back, &loading_text);
     }
 
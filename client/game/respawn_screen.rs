@@ -54,7 +54,7 @@ impl RespawnScreen {
         }
     }
 
-    pub fn on_event(&mut self, event: &Event, graphics: &gfx::GraphicsContext, networking: &mut ClientNetworking) -> Result<()> {
+    pub fn on_event(&self, event: &Event, graphics: &gfx::GraphicsContext, networking: &mut ClientNetworking) -> Result<()> {
         if self.is_shown {
             if let Some(gfx::Event::KeyPress(gfx::Key::MouseLeft, ..)) = event.downcast::<gfx::Event>() {
                 if self.respawn_button.is_hovered(graphics, Some(&self.back_rect.get_container(graphics, None))) {
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+
+use crate::client::game::floating_text::{FloatingText, FloatingTextManager};
+use crate::client::game::players::ClientPlayers;
+use crate::gfx;
+use crate::libraries::events::Event;
+use crate::shared::entities::{Entities, PositionComponent};
+use crate::shared::mod_manager::ModManager;
+use crate::shared::packet::Packet;
+use crate::shared::players::PLAYER_WIDTH;
+use crate::shared::stats::{init_stats_mod_interface, StatChangePacket, StatId, Stats, HEALTH_STAT};
+
+const ICON_WIDTH: f32 = 33.0;
+// every icon shows this many points of the stat
+const ICON_VALUE: i32 = 5;
+const ICONS_PER_ROW: i32 = 10;
+
+/// Stat bar shows the value of a stat as rows of icons, like hearts for health.
+/// The texture has a column of icons, from the full one to the almost empty one.
+struct StatBar {
+    texture: gfx::Texture,
+    value: i32,
+    max_value: i32,
+    rect_array: gfx::RectArray,
+}
+
+impl StatBar {
+    fn new(texture: gfx::Texture) -> Self {
+        Self {
+            texture,
+            value: 0,
+            max_value: 0,
+            rect_array: gfx::RectArray::new(),
+        }
+    }
+
+    const fn get_rows(&self) -> i32 {
+        (self.max_value / ICON_VALUE + ICONS_PER_ROW - 1) / ICONS_PER_ROW
+    }
+
+    fn generate_rect_array(&mut self) {
+        self.rect_array = gfx::RectArray::new();
+        let mut value_remaining = self.value;
+        for i in 0..self.max_value / ICON_VALUE {
+            let icon_pos = gfx::FloatPos(ICON_WIDTH * (i % ICONS_PER_ROW) as f32, ICON_WIDTH * (i / ICONS_PER_ROW) as f32);
+            let state = if value_remaining >= ICON_VALUE {
+                value_remaining -= ICON_VALUE;
+                0
+            } else if value_remaining > 0 {
+                let res = ICON_VALUE - value_remaining;
+                value_remaining = 0;
+                res
+            } else {
+                break;
+            };
+
+            self.rect_array.add_rect(
+                &gfx::Rect::new(icon_pos, gfx::FloatSize(ICON_WIDTH, ICON_WIDTH)),
+                &[
+                    gfx::Color::new(255, 255, 255, 255),
+                    gfx::Color::new(255, 255, 255, 255),
+                    gfx::Color::new(255, 255, 255, 255),
+                    gfx::Color::new(255, 255, 255, 255),
+                ],
+                &gfx::Rect::new(gfx::FloatPos(0.0, 11.0 * state as f32), gfx::FloatSize(11.0, 11.0)),
+            );
+        }
+        self.rect_array.update();
+    }
+
+    fn set_value(&mut self, value: i32, max_value: i32) {
+        self.value = value;
+        self.max_value = max_value;
+        self.generate_rect_array();
+    }
+}
+
+pub struct ClientStats {
+    stats: Arc<Mutex<Stats>>,
+    // only stats that have a texture are shown
+    bars: BTreeMap<StatId, StatBar>,
+}
+
+impl ClientStats {
+    pub fn new() -> Self {
+        Self {
+            stats: Arc::new(Mutex::new(Stats::new())),
+            bars: BTreeMap::new(),
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_stats_mod_interface(&self.stats, mods)
+    }
+
+    /// health uses the hearts texture, the textures of mod stats are in the stats folder of the mod resources
+    pub fn load_resources(&mut self, mods: &ModManager) -> Result<()> {
+        let hearts_surface = gfx::Surface::deserialize_from_bytes(mods.get_resource("misc:hearts.opa").ok_or_else(|| anyhow::anyhow!("Failed to load misc:hearts.opa from mod manager"))?)?;
+        self.bars.insert(HEALTH_STAT, StatBar::new(gfx::Texture::load_from_surface(&hearts_surface)));
+
+        let stat_ids = self.get_stats().get_all_stat_ids();
+        for id in stat_ids {
+            if id == HEALTH_STAT {
+                continue;
+            }
+            let name = self.get_stats().get_stat_type(id)?.name.clone();
+            if let Some(resource) = mods.get_resource(&format!("stats:{name}.opa")) {
+                let surface = gfx::Surface::deserialize_from_bytes(resource)?;
+                self.bars.insert(id, StatBar::new(gfx::Texture::load_from_surface(&surface)));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn render(&self, graphics: &gfx::GraphicsContext) {
+        let pos_x = graphics.get_window_size().0 - ICONS_PER_ROW as f32 * ICON_WIDTH - gfx::SPACING;
+        let mut pos_y = gfx::SPACING;
+        for bar in self.bars.values() {
+            bar.rect_array.render(graphics, Some(&bar.texture), gfx::FloatPos(pos_x, pos_y));
+            pos_y += bar.get_rows() as f32 * ICON_WIDTH;
+        }
+    }
+
+    pub fn on_event(&mut self, event: &Event, graphics: &gfx::GraphicsContext, floating_texts: &mut FloatingTextManager, players: &ClientPlayers, entities: &Entities) {
+        if let Some(packet) = event.downcast::<Packet>() {
+            if let Some(packet) = packet.try_deserialize::<StatChangePacket>() {
+                let Some(bar) = self.bars.get_mut(&packet.stat) else {
+                    return;
+                };
+
+                if packet.stat == HEALTH_STAT && bar.max_value != 0 && bar.value != packet.value {
+                    let player_pos = players.get_main_player().and_then(|player| entities.ecs.get::<&PositionComponent>(player).ok());
+
+                    if let Some(player_pos) = player_pos {
+                        let x = player_pos.x() + PLAYER_WIDTH / 2.0;
+                        let y = player_pos.y() - 0.5;
+
+                        let (val, color) = if bar.value < packet.value {
+                            (packet.value - bar.value, gfx::Color::new(0, 200, 0, 255))
+                        } else {
+                            (bar.value - packet.value, gfx::Color::new(200, 0, 0, 255))
+                        };
+
+                        floating_texts.spawn_text(FloatingText::new(graphics, &(val).to_string(), x, y, 3000, color, 2.0));
+                    }
+                }
+
+                bar.set_value(packet.value, packet.max_value);
+            }
+        }
+    }
+
+    pub fn get_stats(&self) -> MutexGuard<'_, Stats> {
+        self.stats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
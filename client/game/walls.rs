@@ -115,7 +115,7 @@ impl ClientWalls {
         }
     }
 
-    pub fn get_walls(&self) -> MutexGuard<Walls> {
+    pub fn get_walls(&self) -> MutexGuard<'_, Walls> {
         self.walls.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
@@ -129,7 +129,7 @@ impl ClientWalls {
         Ok((x + y * (self.get_walls().get_width() as i32 / CHUNK_SIZE)) as usize)
     }
 
-    pub fn on_event(&mut self, event: &Event) -> Result<()> {
+    pub fn on_event(&self, event: &Event) -> Result<()> {
         if let Some(event) = event.downcast::<WelcomePacketEvent>() {
             if let Some(packet) = event.packet.try_deserialize::<WallsWelcomePacket>() {
                 self.get_walls().deserialize(&packet.data)?;
@@ -138,7 +138,7 @@ impl ClientWalls {
         Ok(())
     }
 
-    pub fn init(&mut self, mods: &mut ModManager) -> Result<()> {
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
         init_walls_mod_interface(mods, &self.walls)
     }
 
@@ -213,7 +213,7 @@ impl ClientWalls {
         Ok(())
     }
 
-    pub fn update(&mut self, frame_length: f32, events: &mut EventManager) -> Result<()> {
+    pub fn update(&self, frame_length: f32, events: &mut EventManager) -> Result<()> {
         self.get_walls().update_breaking_walls(frame_length, events)
     }
 }
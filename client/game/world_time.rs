@@ -0,0 +1,48 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+
+use crate::libraries::events::Event;
+use crate::shared::mod_manager::ModManager;
+use crate::shared::packet::Packet;
+use crate::shared::world_time::{init_world_time_mod_interface, WorldTime, WorldTimePacket};
+
+/// Client world time advances the time between the updates from the server.
+pub struct ClientWorldTime {
+    time: Arc<Mutex<WorldTime>>,
+    // the part of a millisecond that wasn't added to the time yet
+    remainder_ms: f32,
+}
+
+impl ClientWorldTime {
+    pub fn new() -> Self {
+        Self {
+            time: Arc::new(Mutex::new(WorldTime::new(0))),
+            remainder_ms: 0.0,
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_world_time_mod_interface(&self.time, mods)
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.remainder_ms += delta_time;
+        let whole_ms = self.remainder_ms.floor();
+        self.remainder_ms -= whole_ms;
+        self.get_time().add_time_ms(whole_ms as i64);
+    }
+
+    pub fn on_event(&mut self, event: &Event) {
+        if let Some(packet) = event.downcast::<Packet>() {
+            if let Some(packet) = packet.try_deserialize::<WorldTimePacket>() {
+                *self.get_time() = packet.time;
+                self.remainder_ms = 0.0;
+            }
+        }
+    }
+
+    pub fn get_time(&self) -> MutexGuard<'_, WorldTime> {
+        self.time.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
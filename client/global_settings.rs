@@ -1,6 +1,22 @@
 use crate::client::settings::{Setting, Settings, SliderSelection};
 use crate::libraries::graphics as gfx;
 
+// the actions of the game that can be rebound in the controls settings
+const KEY_BINDINGS: [(&str, &str, gfx::Key); 11] = [
+    ("move_left", "Move left", gfx::Key::A),
+    ("move_right", "Move right", gfx::Key::D),
+    ("jump", "Jump", gfx::Key::Space),
+    // only used by the detached camera
+    ("move_up", "Move camera up", gfx::Key::W),
+    ("move_down", "Move camera down", gfx::Key::S),
+    ("open_inventory", "Open inventory", gfx::Key::E),
+    ("drop_item", "Drop item", gfx::Key::Q),
+    ("sort_inventory", "Sort inventory", gfx::Key::R),
+    ("open_chat", "Open chat", gfx::Key::T),
+    ("detach_camera", "Detach camera", gfx::Key::C),
+    ("debug_menu", "Debug menu", gfx::Key::M),
+];
+
 pub struct GlobalSettings {
     blur_setting: i32,
     scale_setting: i32,
@@ -39,6 +55,12 @@ impl GlobalSettings {
             choices: vec!["VSync".to_owned(), "Unlimited".to_owned()],
             selected: SliderSelection::Choice(0),
         });
+
+        for (action, text, key) in KEY_BINDINGS {
+            if let Err(error) = settings.register_key_binding(action, text, key) {
+                println!("Error registering key binding: {error}");
+            }
+        }
     }
 
     pub fn update(&mut self, graphics: &mut gfx::GraphicsContext, settings: &Settings) {
@@ -99,5 +121,11 @@ impl GlobalSettings {
                 println!("Error removing setting: {error}");
             }
         }
+
+        for (action, ..) in KEY_BINDINGS {
+            if let Err(error) = settings.remove_key_binding(action) {
+                println!("Error removing key binding: {error}");
+            }
+        }
     }
 }
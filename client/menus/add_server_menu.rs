@@ -12,7 +12,7 @@ fn get_ip_port(server_ip_input: &str) -> (String, u16) {
     let port = if server_ip_input.contains(':') {
         server_ip_input
             .split(':')
-            .last()
+            .next_back()
             .unwrap_or("-1") //-1 so it fails on next unwrap and always defaults to MULTIPLAYER_PORT
             .to_owned()
             .parse::<u16>()
@@ -57,7 +57,7 @@ fn server_exists(name: &str, servers_list: &Vec<ServerCard>) -> bool {
 }
 
 /// this function runs the add server menu.
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, reason = "TODO: split this function up")]
 pub fn run_add_server_menu(graphics: &mut gfx::GraphicsContext, menu_back: &mut dyn BackgroundRect, servers_list: &Vec<ServerCard>) -> Option<ServerInfo> {
     let mut title = gfx::Sprite::new();
     title.scale = 3.0;
@@ -92,7 +92,7 @@ pub fn run_add_server_menu(graphics: &mut gfx::GraphicsContext, menu_back: &mut
     server_ip_input.scale = 3.0;
     server_ip_input.set_hint(graphics, "ip");
     server_ip_input.orientation = gfx::CENTER;
-    server_ip_input.pos.1 = (server_ip_input.get_size().1 + gfx::SPACING) / 2.0;
+    server_ip_input.pos.1 = f32::midpoint(server_ip_input.get_size().1, gfx::SPACING);
 
     server_name_input.text_processing = Some(Box::new(|text: char| {
         // this closure only accepts letters, numbers and _ symbol
@@ -138,11 +138,9 @@ pub fn run_add_server_menu(graphics: &mut gfx::GraphicsContext, menu_back: &mut
                             break 'render_loop;
                         }
                     }
-                    gfx::Key::Enter => {
-                        if !add_button.disabled {
-                            let (ip, port) = get_ip_port(server_ip_input.get_text());
-                            return Some(ServerInfo::new(server_name_input.get_text().clone(), ip, port));
-                        }
+                    gfx::Key::Enter if !add_button.disabled => {
+                        let (ip, port) = get_ip_port(server_ip_input.get_text());
+                        return Some(ServerInfo::new(server_name_input.get_text().clone(), ip, port));
                     }
                     _ => {}
                 }
@@ -9,6 +9,7 @@ const PROGRESS_BAR_HEIGHT: i32 = 50;
 const PROGRESS_BAR_Y_OFFSET: i32 = 100;
 
 /// Loading screen displays `back_menu` and text which describes the current loading state.
+///
 /// The text is shared through the `SharedMut`<String> which is updated by the loading thread.
 /// When the string is empty, the loading screen is closed.
 pub fn run_loading_screen(graphics: &mut gfx::GraphicsContext, menu_back: &mut dyn BackgroundRect, loading_text: &Mutex<String>) {
@@ -37,7 +38,7 @@ pub fn run_loading_screen(graphics: &mut gfx::GraphicsContext, menu_back: &mut d
         menu_back.render_back(graphics);
 
         if curr_text != *loading_text.lock().unwrap_or_else(PoisonError::into_inner) {
-            curr_text = loading_text.lock().unwrap_or_else(PoisonError::into_inner).clone();
+            curr_text.clone_from(&loading_text.lock().unwrap_or_else(PoisonError::into_inner));
             if !curr_text.is_empty() {
                 let mut progress_bar_progress = -1.0;
                 // let ending of the text be the back of the text until the space symbol
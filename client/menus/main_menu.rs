@@ -7,7 +7,7 @@ use crate::shared::versions::VERSION;
 use super::background_rect::BackgroundRect;
 use super::{run_multiplayer_selector, run_singleplayer_selector};
 
-#[allow(clippy::too_many_lines)] // TODO: split this function up
+#[allow(clippy::too_many_lines, reason = "TODO: split this function up")]
 pub fn run_main_menu(graphics: &mut gfx::GraphicsContext, menu_back: &mut dyn BackgroundRect, settings: &mut Settings, global_settings: &mut GlobalSettings) {
     let mut singleplayer_button = gfx::Button::new();
     singleplayer_button.scale = 3.0;
@@ -3,7 +3,9 @@ use crate::libraries::graphics as gfx;
 use super::background_rect::BackgroundRect;
 
 /// `MenuBack` is a struct that contains the background rectangle for
-/// the most main menus. It implements the `BackgroundRect` trait. It
+/// the most main menus.
+///
+/// It implements the `BackgroundRect` trait. It
 /// draws the background.opa image scaled to the window's height and
 /// scrolled to the left.
 pub struct MenuBack {
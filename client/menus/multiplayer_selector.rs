@@ -115,7 +115,7 @@ impl ServerCard {
     }
 
     /// This function disables/enables the server card buttons.
-    pub fn set_enabled(&mut self, enabled: bool) {
+    pub const fn set_enabled(&mut self, enabled: bool) {
         self.play_button.disabled = !enabled;
         self.delete_button.disabled = !enabled;
     }
@@ -272,7 +272,7 @@ fn update_elements(
                         {
                             let name = run_text_input_menu("Enter your name", graphics, menu_back);
                             if let Some(name) = name {
-                                let game_result = run_game(graphics, menu_back, server.server_info.port, server.server_info.ip.clone(), &name, settings, global_settings);
+                                let game_result = run_game(graphics, menu_back, server.server_info.port, &server.server_info.ip, &name, settings, global_settings);
                                 if let Err(error) = game_result {
                                     println!("Game error: {error}");
                                     run_choice_menu(&format!("Game error: {error}"), graphics, menu_back, vec!["Ok"], None, None, true);
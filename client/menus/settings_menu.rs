@@ -29,6 +29,48 @@ enum SettingUi {
         hovered_progress: f32,
         animation_timer: gfx::AnimationTimer,
     },
+    KeyBinding {
+        setting_id: i32,
+        text: gfx::Sprite,
+        key_button: gfx::Button,
+        hovered: bool,
+        // the key, if it is conflicting and if it is being rebound, the texture is updated when they change
+        shown_state: Option<(gfx::Key, bool, bool)>,
+    },
+}
+
+/// Settings are split into pages, key bindings are on the controls page and everything else is on the general page.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SettingsPage {
+    General,
+    Controls,
+}
+
+impl SettingUi {
+    const fn get_page(&self) -> SettingsPage {
+        match self {
+            Self::KeyBinding { .. } => SettingsPage::Controls,
+            _ => SettingsPage::General,
+        }
+    }
+
+    /// this function is called for settings that are not rendered, so they can't be clicked
+    fn clear_hovered(&mut self) {
+        match self {
+            Self::Toggle { hovered, .. } | Self::KeyBinding { hovered, .. } => *hovered = false,
+            Self::Choice { buttons, .. } => {
+                for (hovered, _button) in buttons {
+                    *hovered = false;
+                }
+            }
+            Self::Slider { buttons, hovered, .. } => {
+                *hovered = false;
+                for (hovered, _button) in buttons {
+                    *hovered = false;
+                }
+            }
+        }
+    }
 }
 
 const SETTINGS_WIDTH: f32 = 700.0;
@@ -42,7 +84,7 @@ const SLIDER_HEIGHT: f32 = 50.0;
 
 fn setting_to_ui(graphics: &gfx::GraphicsContext, setting: &Setting, setting_id: i32) -> SettingUi {
     let text = match setting {
-        Setting::Toggle { text, .. } | Setting::Choice { text, .. } | Setting::Slider { text, .. } => text,
+        Setting::Toggle { text, .. } | Setting::Choice { text, .. } | Setting::Slider { text, .. } | Setting::KeyBinding { text, .. } => text,
     };
 
     let mut text_sprite = gfx::Sprite::new();
@@ -116,18 +158,32 @@ fn setting_to_ui(graphics: &gfx::GraphicsContext, setting: &Setting, setting_id:
                 animation_timer: gfx::AnimationTimer::new(10),
             }
         }
+        Setting::KeyBinding { .. } => {
+            let mut key_button = gfx::Button::new();
+            key_button.scale = 2.0;
+            key_button.pos = gfx::FloatPos(-gfx::SPACING, 0.0);
+            key_button.orientation = gfx::RIGHT;
+
+            SettingUi::KeyBinding {
+                setting_id,
+                text: text_sprite,
+                key_button,
+                hovered: false,
+                shown_state: None,
+            }
+        }
     }
 }
 
-#[allow(clippy::too_many_lines)]
-fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, settings: &mut Settings, y: f32) {
+#[allow(clippy::too_many_lines, reason = "it renders every kind of setting")]
+fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, settings: &mut Settings, y: f32, capturing: Option<i32>) {
     let mut back_rect = gfx::RenderRect::new(gfx::FloatPos(0.0, y), gfx::FloatSize(SETTINGS_WIDTH, SETTINGS_BOX_HEIGHT));
     back_rect.fill_color = gfx::BLACK.set_a(gfx::TRANSPARENCY);
     back_rect.orientation = gfx::TOP;
     back_rect.render(graphics, None);
 
     let text = match setting {
-        SettingUi::Toggle { text, .. } | SettingUi::Choice { text, .. } | SettingUi::Slider { text, .. } => text,
+        SettingUi::Toggle { text, .. } | SettingUi::Choice { text, .. } | SettingUi::Slider { text, .. } | SettingUi::KeyBinding { text, .. } => text,
     };
 
     text.render(graphics, Some(&back_rect.get_container(graphics, None)), None);
@@ -141,10 +197,7 @@ fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, s
             animation_timer,
             ..
         } => {
-            let mut setting_toggled = false;
-            if let Ok(Setting::Toggle { toggled, .. }) = settings.get_setting_mut(*setting_id) {
-                setting_toggled = *toggled;
-            }
+            let setting_toggled = matches!(settings.get_setting(*setting_id), Ok(Setting::Toggle { toggled: true, .. }));
 
             while animation_timer.frame_ready() {
                 let toggle_progress_target = if setting_toggled { 1.0 } else { 0.0 };
@@ -215,10 +268,11 @@ fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, s
             toggle_button_rect.render(graphics, Some(&toggle_box_rect.get_container(graphics, Some(&back_rect.get_container(graphics, None)))));
         }
         SettingUi::Choice { buttons, setting_id, choice_rect, .. } => {
-            let mut chosen_button = 0;
-            if let Ok(Setting::Choice { selected, .. }) = settings.get_setting_mut(*setting_id) {
-                chosen_button = *selected;
-            }
+            let chosen_button = if let Ok(Setting::Choice { selected, .. }) = settings.get_setting(*setting_id) {
+                *selected
+            } else {
+                0
+            };
 
             if let Some((_hovered, button)) = buttons.get(chosen_button as usize) {
                 choice_rect.pos = button.pos;
@@ -247,17 +301,14 @@ fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, s
             animation_timer,
             ..
         } => {
-            let mut choice = SliderSelection::Choice(0);
-            let mut slider_val_low = 0;
-            let mut slider_val_high = 0;
-            if let Ok(Setting::Slider {
+            let (choice, slider_val_low, slider_val_high) = if let Ok(Setting::Slider {
                 selected, upper_limit, lower_limit, ..
-            }) = settings.get_setting_mut(*setting_id)
+            }) = settings.get_setting(*setting_id)
             {
-                choice = selected.clone();
-                slider_val_low = *lower_limit;
-                slider_val_high = *upper_limit;
-            }
+                (selected.clone(), *lower_limit, *upper_limit)
+            } else {
+                (SliderSelection::Choice(0), 0, 0)
+            };
 
             let mut slider_chosen = false;
 
@@ -318,12 +369,53 @@ fn render_setting_ui(graphics: &gfx::GraphicsContext, setting: &mut SettingUi, s
                 *hovered = button.is_hovered(graphics, Some(&back_rect.get_container(graphics, None)));
             }
         }
+        SettingUi::KeyBinding {
+            setting_id,
+            key_button,
+            hovered,
+            shown_state,
+            ..
+        } => {
+            let key = if let Ok(Setting::KeyBinding { key, .. }) = settings.get_setting(*setting_id) {
+                *key
+            } else {
+                gfx::Key::Space
+            };
+            let state = (key, settings.is_key_binding_conflicting(*setting_id), capturing == Some(*setting_id));
+
+            if *shown_state != Some(state) {
+                *shown_state = Some(state);
+                let (key, conflicting, capturing) = state;
+                let key_text = if capturing { "Press a key".to_owned() } else { key.get_name() };
+                key_button.texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface(&key_text, None));
+
+                // keys used by more than one action are shown in red
+                let default_button = gfx::Button::new();
+                if conflicting {
+                    key_button.color = gfx::Color::new(170, 0, 0, 255);
+                    key_button.hover_color = gfx::Color::new(210, 0, 0, 255);
+                } else {
+                    key_button.color = default_button.color;
+                    key_button.hover_color = default_button.hover_color;
+                }
+            }
+
+            key_button.render(graphics, Some(&back_rect.get_container(graphics, None)));
+            *hovered = key_button.is_hovered(graphics, Some(&back_rect.get_container(graphics, None)));
+        }
     }
 }
 
 pub struct SettingsMenu {
     back_button: gfx::Button,
+    general_button: gfx::Button,
+    controls_button: gfx::Button,
+    page_rect: gfx::RenderRect,
+    page: SettingsPage,
     settings: Vec<SettingUi>,
+    scrollable: gfx::Scrollable,
+    // the key binding which is waiting for a key press
+    capturing: Option<i32>,
 }
 
 impl SettingsMenu {
@@ -331,7 +423,13 @@ impl SettingsMenu {
     pub fn new() -> Self {
         Self {
             back_button: gfx::Button::new(),
+            general_button: gfx::Button::new(),
+            controls_button: gfx::Button::new(),
+            page_rect: gfx::RenderRect::new(gfx::FloatPos(0.0, 0.0), gfx::FloatSize(0.0, 0.0)),
+            page: SettingsPage::General,
             settings: Vec::new(),
+            scrollable: gfx::Scrollable::new(),
+            capturing: None,
         }
     }
 
@@ -341,6 +439,26 @@ impl SettingsMenu {
         self.back_button.pos.1 = -gfx::SPACING;
         self.back_button.orientation = gfx::BOTTOM;
 
+        self.general_button.scale = 3.0;
+        self.general_button.texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface("General", None));
+        self.general_button.pos = gfx::FloatPos(-self.general_button.get_size().0 / 2.0 - gfx::SPACING / 2.0, gfx::SPACING);
+        self.general_button.orientation = gfx::TOP;
+
+        self.controls_button.scale = 3.0;
+        self.controls_button.texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface("Controls", None));
+        self.controls_button.pos = gfx::FloatPos(self.controls_button.get_size().0 / 2.0 + gfx::SPACING / 2.0, gfx::SPACING);
+        self.controls_button.orientation = gfx::TOP;
+
+        self.page_rect.fill_color = gfx::GREY.set_a(gfx::TRANSPARENCY);
+        self.page_rect.smooth_factor = 30.0;
+        self.page_rect.orientation = gfx::TOP;
+        self.page_rect.pos = self.general_button.pos;
+        self.page_rect.size = self.general_button.get_size();
+        self.page_rect.jump_to_target();
+
+        self.scrollable.scroll_smooth_factor = 100.0;
+        self.scrollable.boundary_smooth_factor = 40.0;
+
         let mut keys: Vec<&i32> = settings.get_all_settings().keys().collect();
         keys.sort();
         for id in keys {
@@ -354,22 +472,69 @@ impl SettingsMenu {
     pub fn render(&mut self, graphics: &gfx::GraphicsContext, settings: &mut Settings) -> f32 {
         self.back_button.render(graphics, None);
 
-        let mut y = gfx::SPACING;
+        let page_button = if self.page == SettingsPage::General { &self.general_button } else { &self.controls_button };
+        self.page_rect.pos = page_button.pos;
+        self.page_rect.size = page_button.get_size();
+        self.page_rect.render(graphics, None);
+        self.general_button.render(graphics, None);
+        self.controls_button.render(graphics, None);
+
+        // the settings that don't fit between the page buttons and the back button can be scrolled to
+        let top = 2.0 * gfx::SPACING + self.general_button.get_size().1;
+        let bottom = graphics.get_window_size().1 - 2.0 * gfx::SPACING - self.back_button.get_size().1;
+        self.scrollable.rect.size.1 = bottom - top;
+
+        let mut y = top - self.scrollable.get_scroll_pos();
+        let mut height = 0.0;
         for setting in &mut self.settings {
-            render_setting_ui(graphics, setting, settings, y);
+            if setting.get_page() != self.page {
+                setting.clear_hovered();
+                continue;
+            }
+
+            if y >= top && y + SETTINGS_BOX_HEIGHT <= bottom {
+                render_setting_ui(graphics, setting, settings, y, self.capturing);
+            } else {
+                setting.clear_hovered();
+            }
             y += gfx::SPACING + SETTINGS_BOX_HEIGHT;
+            height += gfx::SPACING + SETTINGS_BOX_HEIGHT;
         }
 
+        self.scrollable.scroll_size = height;
+        self.scrollable.render();
+
         SETTINGS_WIDTH + 2.0 * gfx::SPACING
     }
 
     /// returns true, if settings menu has been closed
     pub fn on_event(&mut self, event: &gfx::Event, graphics: &gfx::GraphicsContext, settings: &mut Settings) -> bool {
+        // the next key pressed while rebinding is the new key, escape cancels it
+        if let Some(setting_id) = self.capturing {
+            if let gfx::Event::KeyPress(key, false) = event {
+                if *key != gfx::Key::Escape {
+                    if let Ok(Setting::KeyBinding { key: bound_key, .. }) = settings.get_setting_mut(setting_id) {
+                        *bound_key = *key;
+                    }
+                }
+                self.capturing = None;
+            }
+            return false;
+        }
+
+        self.scrollable.on_event(event);
+
         if let gfx::Event::KeyRelease(gfx::Key::MouseLeft, ..) = event {
             if self.back_button.is_hovered(graphics, None) {
                 return true;
             }
 
+            if self.general_button.is_hovered(graphics, None) {
+                self.page = SettingsPage::General;
+            } else if self.controls_button.is_hovered(graphics, None) {
+                self.page = SettingsPage::Controls;
+            }
+
             for setting in &mut self.settings {
                 match setting {
                     SettingUi::Toggle { hovered, setting_id, .. } => {
@@ -398,6 +563,11 @@ impl SettingsMenu {
                             }
                         }
                     }
+                    SettingUi::KeyBinding { hovered, setting_id, .. } => {
+                        if *hovered {
+                            self.capturing = Some(*setting_id);
+                        }
+                    }
                 }
             }
         } else if let gfx::Event::KeyPress(gfx::Key::MouseLeft, ..) = event {
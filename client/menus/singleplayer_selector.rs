@@ -137,7 +137,7 @@ impl World {
     }
 
     /// This function disables/enables the world card buttons.
-    pub fn set_enabled(&mut self, enabled: bool) {
+    pub const fn set_enabled(&mut self, enabled: bool) {
         self.play_button.disabled = !enabled;
         self.delete_button.disabled = !enabled;
     }
@@ -18,7 +18,7 @@ fn world_name_exists(worlds_list: &Vec<World>, name: &str) -> bool {
 }
 
 /// this function runs the world creation menu.
-#[allow(clippy::too_many_lines)] // TODO: reduce the number of lines in this function
+#[allow(clippy::too_many_lines, reason = "TODO: reduce the number of lines in this function")]
 pub fn run_world_creation(graphics: &mut gfx::GraphicsContext, menu_back: &mut dyn BackgroundRect, worlds_list: &Vec<World>, settings: &mut Settings, global_settings: &mut GlobalSettings) {
     let mut title = gfx::Sprite::new();
     title.scale = 3.0;
@@ -53,7 +53,7 @@ pub fn run_world_creation(graphics: &mut gfx::GraphicsContext, menu_back: &mut d
     world_seed_input.scale = 3.0;
     world_seed_input.set_hint(graphics, "World seed");
     world_seed_input.orientation = gfx::CENTER;
-    world_seed_input.pos.1 = (world_seed_input.get_size().1 + gfx::SPACING) / 2.0;
+    world_seed_input.pos.1 = f32::midpoint(world_seed_input.get_size().1, gfx::SPACING);
 
     world_name_input.text_processing = Some(Box::new(|text: char| {
         // this closure only accepts letters, numbers and _ symbol
@@ -108,14 +108,12 @@ pub fn run_world_creation(graphics: &mut gfx::GraphicsContext, menu_back: &mut d
                             break 'render_loop;
                         }
                     }
-                    gfx::Key::Enter => {
-                        if !create_button.disabled {
-                            let game_result = run_private_world(graphics, menu_back, &world_path, settings, global_settings);
-                            if let Err(error) = game_result {
-                                println!("Game error: {error}");
-                            }
-                            break 'render_loop;
+                    gfx::Key::Enter if !create_button.disabled => {
+                        let game_result = run_private_world(graphics, menu_back, &world_path, settings, global_settings);
+                        if let Err(error) = game_result {
+                            println!("Game error: {error}");
                         }
+                        break 'render_loop;
                     }
                     _ => {}
                 }
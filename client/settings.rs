@@ -2,7 +2,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
+
+use crate::libraries::graphics as gfx;
 
 #[derive(Clone)]
 pub enum SliderSelection {
@@ -31,6 +33,11 @@ pub enum Setting {
         choices: Vec<String>,
         selected: SliderSelection,
     },
+    KeyBinding {
+        text: String,
+        config_label: String,
+        key: gfx::Key,
+    },
 }
 
 pub struct Settings {
@@ -38,6 +45,8 @@ pub struct Settings {
     config_path: PathBuf,
     config_data: HashMap<String, i32>,
     curr_setting_id: i32,
+    // the setting id of every action that can be bound to a key
+    key_bindings: HashMap<String, i32>,
 }
 
 impl Settings {
@@ -51,13 +60,14 @@ impl Settings {
             config_path,
             config_data,
             curr_setting_id: 0,
+            key_bindings: HashMap::new(),
         }
     }
 
     /// Adds a new setting, returns the id of the setting.
     pub fn register_setting(&mut self, mut setting: Setting) -> i32 {
         let config_label = match setting.clone() {
-            Setting::Toggle { config_label, .. } | Setting::Choice { config_label, .. } | Setting::Slider { config_label, .. } => config_label,
+            Setting::Toggle { config_label, .. } | Setting::Choice { config_label, .. } | Setting::Slider { config_label, .. } | Setting::KeyBinding { config_label, .. } => config_label,
         };
 
         if let Some(config_value) = self.config_data.get(&config_label) {
@@ -76,6 +86,11 @@ impl Settings {
                         *selected = SliderSelection::Slider(config_value - choices.len() as i32 + *lower_limit);
                     }
                 }
+                Setting::KeyBinding { key, .. } => {
+                    if let Some(config_key) = gfx::Key::from_id(config_value) {
+                        *key = config_key;
+                    }
+                }
             }
         }
 
@@ -108,6 +123,8 @@ impl Settings {
 
                     (config_label.clone(), val)
                 }
+
+                Setting::KeyBinding { config_label, key, .. } => (config_label.clone(), key.get_id()),
             };
 
             self.config_data.insert(config_label, value);
@@ -120,11 +137,69 @@ impl Settings {
     }
 
     pub fn get_setting_mut(&mut self, id: i32) -> Result<&mut Setting> {
-        return self.settings.get_mut(&id).ok_or_else(|| anyhow!("Invalid setting id"));
+        self.settings.get_mut(&id).ok_or_else(|| anyhow!("Invalid setting id"))
     }
 
     pub fn get_setting(&self, id: i32) -> Result<&Setting> {
-        return self.settings.get(&id).ok_or_else(|| anyhow!("Invalid setting id"));
+        self.settings.get(&id).ok_or_else(|| anyhow!("Invalid setting id"))
+    }
+
+    /// this function registers an action that can be rebound in the controls
+    /// settings, the key is saved with the config label `key_<action>`
+    pub fn register_key_binding(&mut self, action: &str, text: &str, default_key: gfx::Key) -> Result<i32> {
+        if self.key_bindings.contains_key(action) {
+            bail!("Key binding {action} is already registered");
+        }
+
+        let id = self.register_setting(Setting::KeyBinding {
+            text: text.to_owned(),
+            config_label: format!("key_{action}"),
+            key: default_key,
+        });
+        self.key_bindings.insert(action.to_owned(), id);
+        Ok(id)
+    }
+
+    pub fn remove_key_binding(&mut self, action: &str) -> Result<()> {
+        let id = self.key_bindings.remove(action).ok_or_else(|| anyhow!("Key binding {action} does not exist"))?;
+        self.remove_setting(id)
+    }
+
+    /// this function returns the key the action is bound to
+    pub fn get_key_binding(&self, action: &str) -> Result<gfx::Key> {
+        let id = self.key_bindings.get(action).ok_or_else(|| anyhow!("Key binding {action} does not exist"))?;
+        match self.get_setting(*id)? {
+            Setting::KeyBinding { key, .. } => Ok(*key),
+            _ => bail!("Setting is not a key binding"),
+        }
+    }
+
+    #[must_use]
+    pub fn is_key_bound_to(&self, action: &str, key: gfx::Key) -> bool {
+        self.get_key_binding(action).is_ok_and(|bound_key| bound_key == key)
+    }
+
+    /// this function checks if the key of the action is held down
+    #[must_use]
+    pub fn is_key_binding_pressed(&self, graphics: &gfx::GraphicsContext, action: &str) -> bool {
+        self.get_key_binding(action).is_ok_and(|key| graphics.get_key_state(key))
+    }
+
+    /// this function returns all actions bound to the key
+    #[must_use]
+    pub fn get_actions_bound_to(&self, key: gfx::Key) -> Vec<&str> {
+        let mut actions: Vec<&str> = self.key_bindings.keys().filter(|action| self.is_key_bound_to(action, key)).map(String::as_str).collect();
+        actions.sort_unstable();
+        actions
+    }
+
+    /// this function checks if the key binding setting shares its key with another action
+    #[must_use]
+    pub fn is_key_binding_conflicting(&self, id: i32) -> bool {
+        let Ok(Setting::KeyBinding { key, .. }) = self.get_setting(id) else {
+            return false;
+        };
+        self.get_actions_bound_to(*key).len() > 1
     }
 
     pub fn save_config(&mut self) -> Result<()> {
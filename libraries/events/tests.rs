@@ -1,6 +1,7 @@
 // Tests for the events library.
 // It tests the event manager and the event struct.
 
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #![cfg(test)]
 mod tests {
     use crate::libraries::events::{Event, EventManager};
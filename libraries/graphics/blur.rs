@@ -59,20 +59,20 @@ impl BlurContext {
         Ok(Self {
             blur_shader,
             // Safety: shader is valid
-            transform_matrix_uniform: unsafe { gl::GetUniformLocation(blur_shader, "transform_matrix\0".as_ptr().cast::<i8>()) },
+            transform_matrix_uniform: unsafe { gl::GetUniformLocation(blur_shader, c"transform_matrix".as_ptr()) },
             // Safety: shader is valid
-            texture_transform_matrix_uniform: unsafe { gl::GetUniformLocation(blur_shader, "texture_transform_matrix\0".as_ptr().cast::<i8>()) },
+            texture_transform_matrix_uniform: unsafe { gl::GetUniformLocation(blur_shader, c"texture_transform_matrix".as_ptr()) },
             // Safety: shader is valid
-            texture_sampler_uniform: unsafe { gl::GetUniformLocation(blur_shader, "texture_sampler\0".as_ptr().cast::<i8>()) },
+            texture_sampler_uniform: unsafe { gl::GetUniformLocation(blur_shader, c"texture_sampler".as_ptr()) },
             // Safety: shader is valid
-            blur_offset_uniform: unsafe { gl::GetUniformLocation(blur_shader, "blur_offset\0".as_ptr().cast::<i8>()) },
+            blur_offset_uniform: unsafe { gl::GetUniformLocation(blur_shader, c"blur_offset".as_ptr()) },
             // Safety: shader is valid
-            limit_uniform: unsafe { gl::GetUniformLocation(blur_shader, "limit\0".as_ptr().cast::<i8>()) },
+            limit_uniform: unsafe { gl::GetUniformLocation(blur_shader, c"limit".as_ptr()) },
             rect_vertex_buffer: {
                 let mut buffer = 0;
                 // Safety: use of opengl functions is safe
                 unsafe {
-                    gl::GenBuffers(1, &mut buffer);
+                    gl::GenBuffers(1, &raw mut buffer);
 
                     let rect_vertex_array: [f32; 12] = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0];
 
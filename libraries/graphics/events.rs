@@ -99,6 +99,104 @@ pub enum Key {
     MouseMiddle,
 }
 
+impl Key {
+    /// all keys in the order of their ids
+    pub const ALL: [Self; 70] = [
+        Self::Space,
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+        Self::I,
+        Self::J,
+        Self::K,
+        Self::L,
+        Self::M,
+        Self::N,
+        Self::O,
+        Self::P,
+        Self::Q,
+        Self::R,
+        Self::S,
+        Self::T,
+        Self::U,
+        Self::V,
+        Self::W,
+        Self::X,
+        Self::Y,
+        Self::Z,
+        Self::Num0,
+        Self::Num1,
+        Self::Num2,
+        Self::Num3,
+        Self::Num4,
+        Self::Num5,
+        Self::Num6,
+        Self::Num7,
+        Self::Num8,
+        Self::Num9,
+        Self::Escape,
+        Self::Enter,
+        Self::Tab,
+        Self::Backspace,
+        Self::Insert,
+        Self::Delete,
+        Self::Right,
+        Self::Left,
+        Self::Down,
+        Self::Up,
+        Self::F1,
+        Self::F2,
+        Self::F3,
+        Self::F4,
+        Self::F5,
+        Self::F6,
+        Self::F7,
+        Self::F8,
+        Self::F9,
+        Self::F10,
+        Self::F11,
+        Self::F12,
+        Self::LeftShift,
+        Self::LeftControl,
+        Self::LeftAlt,
+        Self::LeftSuper,
+        Self::RightShift,
+        Self::RightControl,
+        Self::RightAlt,
+        Self::RightSuper,
+        Self::MouseLeft,
+        Self::MouseRight,
+        Self::MouseMiddle,
+    ];
+
+    /// this function returns a number that stays the same for the key, so it can be saved
+    #[must_use]
+    pub const fn get_id(self) -> i32 {
+        self as i32
+    }
+
+    #[must_use]
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.get(usize::try_from(id).ok()?).copied()
+    }
+
+    /// this function returns the name of the key, like `A`, `Space` or `MouseLeft`
+    #[must_use]
+    pub fn get_name(self) -> String {
+        format!("{self:?}")
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|key| key.get_name() == name)
+    }
+}
+
 /// This function converts a sdl key to a graphics key
 const fn sdl_key_to_gfx_key(key: sdl2::keyboard::Keycode) -> Option<Key> {
     match key {
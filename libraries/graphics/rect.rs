@@ -33,7 +33,7 @@ impl Rect {
 
         // Safety: We are using a valid shader.
         unsafe {
-            gl::UniformMatrix3fv(graphics.passthrough_shader.transform_matrix, 1, gl::FALSE, &transform.matrix[0]);
+            gl::UniformMatrix3fv(graphics.passthrough_shader.transform_matrix, 1, gl::FALSE, transform.matrix.as_ptr());
             gl::Uniform4f(
                 graphics.passthrough_shader.global_color,
                 color.r as f32 / 255.0,
@@ -59,7 +59,7 @@ impl Rect {
 
         // Safety: We are using a valid shader.
         unsafe {
-            gl::UniformMatrix3fv(graphics.passthrough_shader.transform_matrix, 1, gl::FALSE, &transform.matrix[0]);
+            gl::UniformMatrix3fv(graphics.passthrough_shader.transform_matrix, 1, gl::FALSE, transform.matrix.as_ptr());
             gl::Uniform4f(
                 graphics.passthrough_shader.global_color,
                 color.r as f32 / 255.0,
@@ -51,7 +51,7 @@ impl ShadowContext {
     }
 
     /// Renders the shadow.
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, reason = "it draws every side and corner of the shadow")]
     pub fn render(&self, graphics: &gfx::GraphicsContext, rect: &gfx::Rect, shadow_intensity: f32) {
         let shadow_color = gfx::Color::new(0, 0, 0, (80.0 * shadow_intensity) as u8);
 
@@ -94,8 +94,10 @@ impl ShadowContext {
         ];
 
         if (shadow_edge_height - 350.0).abs() < f32::EPSILON {
-            let mut height_to_render = rect.size.1 - 300.0;
-            while height_to_render > 0.0 {
+            // the edge is drawn in pieces of 100 pixels, the last one can be shorter
+            let pieces = ((rect.size.1 - 300.0) / 100.0).ceil() as i32;
+            for piece in 0..pieces {
+                let height_to_render = rect.size.1 - 300.0 - piece as f32 * 100.0;
                 elements.push((
                     rect.pos + gfx::FloatPos(-200.0, rect.size.1 - 150.0 - height_to_render),
                     gfx::Rect::new(gfx::FloatPos(0.0, 300.0), gfx::FloatSize(200.0, f32::min(100.0, height_to_render))),
@@ -104,13 +106,14 @@ impl ShadowContext {
                     rect.pos + gfx::FloatPos(rect.size.0, rect.size.1 - 150.0 - height_to_render),
                     gfx::Rect::new(gfx::FloatPos(500.0, 300.0), gfx::FloatSize(200.0, f32::min(100.0, height_to_render))),
                 ));
-                height_to_render -= 100.0;
             }
         }
 
         if (shadow_edge_width - 350.0).abs() < f32::EPSILON {
-            let mut width_to_render = rect.size.0 - 300.0;
-            while width_to_render > 0.0 {
+            // the edge is drawn in pieces of 100 pixels, the last one can be shorter
+            let pieces = ((rect.size.0 - 300.0) / 100.0).ceil() as i32;
+            for piece in 0..pieces {
+                let width_to_render = rect.size.0 - 300.0 - piece as f32 * 100.0;
                 elements.push((
                     rect.pos + gfx::FloatPos(rect.size.0 - 150.0 - width_to_render, -200.0),
                     gfx::Rect::new(gfx::FloatPos(300.0, 0.0), gfx::FloatSize(f32::min(100.0, width_to_render), 200.0)),
@@ -119,7 +122,6 @@ impl ShadowContext {
                     rect.pos + gfx::FloatPos(rect.size.0 - 150.0 - width_to_render, rect.size.1),
                     gfx::Rect::new(gfx::FloatPos(300.0, 500.0), gfx::FloatSize(f32::min(100.0, width_to_render), 200.0)),
                 ));
-                width_to_render -= 100.0;
             }
         }
 
@@ -80,13 +80,13 @@ impl Surface {
 
     /// Returns a surface iterator.
     #[must_use]
-    pub const fn iter(&self) -> SurfaceIterator {
+    pub const fn iter(&self) -> SurfaceIterator<'_> {
         SurfaceIterator::new(self)
     }
 
     /// Returns a surface iterator.
     #[must_use]
-    pub fn iter_mut(&mut self) -> MutSurfaceIterator {
+    pub const fn iter_mut(&mut self) -> MutSurfaceIterator<'_> {
         MutSurfaceIterator::new(self)
     }
 }
@@ -133,7 +133,7 @@ pub struct MutSurfaceIterator<'surface_lifetime> {
 }
 
 impl<'surface_lifetime> MutSurfaceIterator<'surface_lifetime> {
-    pub fn new(surface: &'surface_lifetime mut Surface) -> Self {
+    pub const fn new(surface: &'surface_lifetime mut Surface) -> Self {
         Self { surface, pos: gfx::IntPos(0, 0) }
     }
 }
@@ -156,7 +156,7 @@ impl<'surface_lifetime> Iterator for MutSurfaceIterator<'surface_lifetime> {
         if let Some(result) = result {
             // Safety: We know that the result is a valid mutable reference and 'surface_lifetime
             // outlives the iterator. Apparently mutable iterators are not possible without unsafe code.
-            unsafe { Some((self.pos - gfx::IntPos(1, 0), &mut *(result as *mut Color))) }
+            unsafe { Some((self.pos - gfx::IntPos(1, 0), &mut *std::ptr::from_mut::<Color>(result))) }
         } else {
             None
         }
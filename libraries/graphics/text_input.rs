@@ -100,6 +100,11 @@ impl TextInput {
         self.cursor = std::cmp::min(self.cursor, (self.text.len(), self.text.len()));
     }
 
+    /// moves the cursor behind the last character
+    pub const fn move_cursor_to_end(&mut self) {
+        self.cursor = (self.text.len(), self.text.len());
+    }
+
     /// sets the hint text in the input box
     pub fn set_hint(&mut self, graphics: &gfx::GraphicsContext, hint: &str) {
         self.hint_texture = gfx::Texture::load_from_surface(&graphics.font.create_text_surface(hint, None));
@@ -142,7 +147,7 @@ impl TextInput {
     }
 
     /// renders the text input
-    #[allow(clippy::too_many_lines)] // TODO: split this function up
+    #[allow(clippy::too_many_lines, reason = "TODO: split this function up")]
     pub fn render(&mut self, graphics: &gfx::GraphicsContext, parent_container: Option<&gfx::Container>) {
         let container = self.get_container(graphics, parent_container);
         let rect = container.get_absolute_rect();
@@ -262,33 +267,31 @@ impl TextInput {
         self.text_changed = false;
     }
 
-    #[allow(clippy::too_many_lines)] // TODO: split this up
-    #[allow(clippy::cognitive_complexity)]
+    #[allow(clippy::too_many_lines, reason = "TODO: split this function up")]
+    #[allow(clippy::cognitive_complexity, reason = "TODO: split this function up")]
     pub fn on_event(&mut self, event: &gfx::Event, graphics: &mut gfx::GraphicsContext, parent_container: Option<&gfx::Container>) {
         match event {
-            gfx::Event::TextInput(text) => {
-                if self.selected {
-                    if self.cursor.0 != self.cursor.1 {
-                        self.text.replace_range(self.get_cursor().0..self.get_cursor().1, "");
-                        self.cursor.1 = self.cursor.0;
-                    }
-                    // run every character of text through text_processing closure if it exists and create new string
-                    let mut new_text = String::new();
-                    for c in text.chars() {
-                        if let Some(text_processing) = &self.text_processing {
-                            if let Some(c) = text_processing(c) {
-                                new_text.push(c);
-                            }
-                        } else {
+            gfx::Event::TextInput(text) if self.selected => {
+                if self.cursor.0 != self.cursor.1 {
+                    self.text.replace_range(self.get_cursor().0..self.get_cursor().1, "");
+                    self.cursor.1 = self.cursor.0;
+                }
+                // run every character of text through text_processing closure if it exists and create new string
+                let mut new_text = String::new();
+                for c in text.chars() {
+                    if let Some(text_processing) = &self.text_processing {
+                        if let Some(c) = text_processing(c) {
                             new_text.push(c);
                         }
+                    } else {
+                        new_text.push(c);
                     }
-
-                    self.text.insert_str(self.cursor.0, &new_text);
-                    self.cursor.0 += new_text.len();
-                    self.text_changed = true;
-                    self.cursor.1 = self.cursor.0;
                 }
+
+                self.text.insert_str(self.cursor.0, &new_text);
+                self.cursor.0 += new_text.len();
+                self.text_changed = true;
+                self.cursor.1 = self.cursor.0;
             }
 
             gfx::Event::KeyPress(key, ..) => {
@@ -349,46 +352,38 @@ impl TextInput {
                             self.cursor.1 = self.cursor.0;
                         }
                     }
-                    gfx::Key::C => {
-                        if graphics.get_key_state(gfx::Key::LeftControl) {
-                            graphics
-                                .clipboard_context
-                                .set_contents(self.text.get(self.get_cursor().0..self.get_cursor().1).unwrap_or("").to_owned())
-                                .unwrap_or_else(|e| {
-                                    println!("Error setting clipboard contents: {e}");
-                                });
-                        }
+                    gfx::Key::C if graphics.get_key_state(gfx::Key::LeftControl) => {
+                        graphics
+                            .clipboard_context
+                            .set_contents(self.text.get(self.get_cursor().0..self.get_cursor().1).unwrap_or("").to_owned())
+                            .unwrap_or_else(|e| {
+                                println!("Error setting clipboard contents: {e}");
+                            });
                     }
-                    gfx::Key::V => {
-                        if graphics.get_key_state(gfx::Key::LeftControl) {
-                            if let Ok(text) = graphics.clipboard_context.get_contents() {
-                                if self.cursor.0 != self.cursor.1 {
-                                    self.text.replace_range(self.get_cursor().0..self.get_cursor().1, ""); //add text filtering lol
-                                    self.cursor.0 = self.get_cursor().0;
-                                }
-                                self.text.insert_str(self.cursor.0, &text);
-                                self.cursor.0 += text.len();
-                                self.cursor.1 = self.cursor.0;
-                                self.text_changed = true;
-                            }
-                        }
-                    }
-                    gfx::Key::X => {
-                        if graphics.get_key_state(gfx::Key::LeftControl) && self.cursor.0 != self.cursor.1 {
-                            if graphics.get_key_state(gfx::Key::LeftControl) {
-                                graphics
-                                    .clipboard_context
-                                    .set_contents(self.text.get(self.get_cursor().0..self.get_cursor().1).unwrap_or("").to_owned())
-                                    .unwrap_or_else(|e| {
-                                        println!("Failed to copy to clipboard {e}");
-                                    });
+                    gfx::Key::V if graphics.get_key_state(gfx::Key::LeftControl) => {
+                        if let Ok(text) = graphics.clipboard_context.get_contents() {
+                            if self.cursor.0 != self.cursor.1 {
+                                self.text.replace_range(self.get_cursor().0..self.get_cursor().1, ""); //add text filtering lol
+                                self.cursor.0 = self.get_cursor().0;
                             }
-                            self.text.replace_range(self.get_cursor().0..self.get_cursor().1, ""); //add text filtering lol
-                            self.cursor.0 = self.get_cursor().0;
+                            self.text.insert_str(self.cursor.0, &text);
+                            self.cursor.0 += text.len();
                             self.cursor.1 = self.cursor.0;
                             self.text_changed = true;
                         }
                     }
+                    gfx::Key::X if graphics.get_key_state(gfx::Key::LeftControl) && self.cursor.0 != self.cursor.1 => {
+                        graphics
+                            .clipboard_context
+                            .set_contents(self.text.get(self.get_cursor().0..self.get_cursor().1).unwrap_or("").to_owned())
+                            .unwrap_or_else(|e| {
+                                println!("Failed to copy to clipboard {e}");
+                            });
+                        self.text.replace_range(self.get_cursor().0..self.get_cursor().1, ""); //add text filtering lol
+                        self.cursor.0 = self.get_cursor().0;
+                        self.cursor.1 = self.cursor.0;
+                        self.text_changed = true;
+                    }
                     _ => {}
                 }
             }
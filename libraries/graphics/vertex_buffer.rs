@@ -30,9 +30,9 @@ impl Drop for VertexBuffer {
     fn drop(&mut self) {
         // Safety: We are deleting the buffers and vertex array. They are either 0 or valid.
         unsafe {
-            gl::DeleteBuffers(1, &self.vertex_buffer);
-            gl::DeleteBuffers(1, &self.index_buffer);
-            gl::DeleteVertexArrays(1, &self.vertex_array);
+            gl::DeleteBuffers(1, &raw const self.vertex_buffer);
+            gl::DeleteBuffers(1, &raw const self.index_buffer);
+            gl::DeleteVertexArrays(1, &raw const self.vertex_array);
         }
     }
 }
@@ -49,9 +49,9 @@ impl VertexBuffer {
         };
         // Safety: We are generating the buffers and vertex array.
         unsafe {
-            gl::GenBuffers(1, &mut result.vertex_buffer);
-            gl::GenBuffers(1, &mut result.index_buffer);
-            gl::GenVertexArrays(1, &mut result.vertex_array);
+            gl::GenBuffers(1, &raw mut result.vertex_buffer);
+            gl::GenBuffers(1, &raw mut result.index_buffer);
+            gl::GenVertexArrays(1, &raw mut result.vertex_array);
         }
         result
     }
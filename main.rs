@@ -34,7 +34,6 @@
 #![warn(clippy::shadow_unrelated)]
 #![warn(clippy::single_char_lifetime_names)]
 #![warn(clippy::str_to_string)]
-#![warn(clippy::string_to_string)]
 #![warn(clippy::todo)]
 #![warn(clippy::try_err)]
 #![warn(clippy::undocumented_unsafe_blocks)]
@@ -45,10 +44,8 @@
 #![warn(clippy::unwrap_in_result)]
 #![warn(clippy::unwrap_used)]
 #![warn(clippy::verbose_file_reads)]
-#![warn(clippy::allow_attributes_without_reason)]
 #![warn(clippy::if_not_else)]
 #![warn(clippy::from_iter_instead_of_collect)]
-#![warn(clippy::if_then_some_else_none)]
 #![warn(clippy::ignored_unit_patterns)]
 #![warn(clippy::inefficient_to_string)]
 #![warn(clippy::impl_trait_in_params)]
@@ -68,11 +65,9 @@
 #![warn(clippy::manual_string_new)]
 #![warn(clippy::map_unwrap_or)]
 #![warn(clippy::match_bool)]
-#![warn(clippy::match_on_vec_items)]
 #![warn(clippy::match_same_arms)]
 #![warn(clippy::match_wild_err_arm)]
 #![warn(clippy::match_wildcard_for_single_variants)]
-#![warn(clippy::mem_forget)]
 #![warn(clippy::missing_const_for_fn)]
 #![warn(clippy::mixed_read_write_in_expression)]
 #![warn(clippy::mut_mut)]
@@ -88,13 +83,10 @@
 #![warn(clippy::needless_raw_string_hashes)]
 #![warn(clippy::needless_raw_strings)]
 #![warn(clippy::no_effect_underscore_binding)]
-#![warn(clippy::non_ascii_literal)]
 #![warn(clippy::option_option)]
 #![warn(clippy::pub_without_shorthand)]
 #![warn(clippy::range_minus_one)]
 #![warn(clippy::range_plus_one)]
-#![warn(clippy::rc_buffer)]
-#![warn(clippy::rc_mutex)]
 #![warn(clippy::redundant_clone)]
 #![warn(clippy::redundant_closure_for_method_calls)]
 #![warn(clippy::redundant_else)]
@@ -103,7 +95,6 @@
 #![warn(clippy::ref_binding_to_reference)]
 #![warn(clippy::ref_option_ref)]
 #![warn(clippy::ref_patterns)]
-#![warn(clippy::rest_pat_in_fully_bound_structs)]
 #![warn(clippy::return_self_not_must_use)]
 #![warn(clippy::same_functions_in_if_condition)]
 #![warn(clippy::semicolon_if_nothing_returned)]
@@ -113,51 +104,51 @@
 #![warn(clippy::unnecessary_join)]
 #![warn(clippy::unnecessary_safety_comment)]
 #![warn(clippy::unnecessary_safety_doc)]
-#![warn(clippy::unnecessary_self_imports)]
 #![warn(clippy::unnecessary_struct_initialization)]
 #![warn(clippy::unnecessary_wraps)]
-#![warn(clippy::unneeded_field_pattern)]
 #![warn(clippy::unnested_or_patterns)]
 #![warn(clippy::unreadable_literal)]
 #![warn(clippy::unused_async)]
 #![warn(clippy::unused_peekable)]
 #![warn(clippy::unused_rounding)]
 #![warn(clippy::unused_self)]
-#![warn(clippy::verbose_file_reads)]
 #![warn(clippy::wildcard_dependencies)]
 #![warn(clippy::zero_sized_map_values)]
 // disable some Clippy lints
-#![allow(clippy::cast_possible_truncation)]
-#![allow(clippy::cast_possible_wrap)]
-#![allow(clippy::cast_precision_loss)]
-#![allow(clippy::cast_sign_loss)]
-#![allow(clippy::suboptimal_flops)]
-#![allow(clippy::cast_lossless)]
-#![allow(clippy::module_name_repetitions)]
-#![allow(clippy::struct_excessive_bools)]
-#![allow(clippy::similar_names)]
-#![allow(clippy::ptr_as_ptr)]
-#![allow(clippy::shadow_unrelated)]
-#![allow(clippy::many_single_char_names)]
-#![allow(clippy::significant_drop_tightening)]
-#![allow(clippy::new_without_default)]
-#![allow(clippy::module_inception)]
-#![allow(clippy::missing_errors_doc)]
-#![allow(clippy::missing_panics_doc)]
-#![allow(clippy::todo)]
-#![allow(clippy::unimplemented)]
-#![allow(clippy::type_complexity)]
-#![allow(clippy::too_many_arguments)]
-#![allow(clippy::struct_field_names)]
-#![allow(clippy::iter_without_into_iter)]
+#![allow(clippy::cast_possible_truncation, reason = "the game converts between block, pixel and float coordinates everywhere")]
+#![allow(clippy::cast_possible_wrap, reason = "the game converts between block, pixel and float coordinates everywhere")]
+#![allow(clippy::cast_precision_loss, reason = "the game converts between block, pixel and float coordinates everywhere")]
+#![allow(clippy::cast_sign_loss, reason = "the game converts between block, pixel and float coordinates everywhere")]
+#![allow(clippy::suboptimal_flops, reason = "mul_add is harder to read and the precision doesn't matter")]
+#![allow(clippy::cast_lossless, reason = "the game converts between block, pixel and float coordinates everywhere")]
+#![allow(clippy::module_name_repetitions, reason = "modules are named after the structs they contain")]
+#![allow(clippy::struct_excessive_bools, reason = "settings and ui elements have many flags")]
+#![allow(clippy::similar_names, reason = "coordinates are named x, y, x1, y1 and so on")]
+#![allow(clippy::ptr_as_ptr, reason = "opengl takes untyped pointers")]
+#![allow(clippy::shadow_unrelated, reason = "values are often shadowed after being converted")]
+#![allow(clippy::many_single_char_names, reason = "coordinates and colors use single letter names")]
+#![allow(clippy::significant_drop_tightening, reason = "locks are held until the end of the scope on purpose")]
+#![allow(clippy::new_without_default, reason = "structs are created with new")]
+#![allow(clippy::module_inception, reason = "modules are named after the structs they contain")]
+#![allow(clippy::missing_errors_doc, reason = "most functions can fail and the errors explain themselves")]
+#![allow(clippy::missing_panics_doc, reason = "functions shouldn't panic, unwrap is not allowed")]
+#![allow(clippy::todo, reason = "unfinished parts are marked with todo")]
+#![allow(clippy::unimplemented, reason = "unfinished parts are marked with unimplemented")]
+#![allow(clippy::type_complexity, reason = "some callbacks have long types")]
+#![allow(clippy::too_many_arguments, reason = "render functions take all the parts of the game they draw")]
+#![allow(clippy::struct_field_names, reason = "field names may repeat the name of the struct")]
+#![allow(clippy::iter_without_into_iter, reason = "iterators are only used through iter")]
 #![windows_subsystem = "windows"]
 
+use std::path::PathBuf;
+use std::process::ExitCode;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::sync::Mutex;
 
 use directories::BaseDirs;
 
+use crate::build_project::mod_cli::run_mod_command;
 use crate::client::global_settings::GlobalSettings;
 use crate::client::menus::{run_main_menu, MenuBack};
 use crate::client::settings::Settings;
@@ -165,6 +156,13 @@ use crate::libraries::graphics as gfx;
 use crate::server::server_core::{Server, MULTIPLAYER_PORT};
 use crate::server::server_ui::UiManager;
 
+pub mod build_project {
+    pub mod compile_mod;
+    pub mod mod_cli;
+    pub mod png_to_opa;
+    mod tests;
+}
+
 pub mod libraries {
     pub mod events;
     pub mod graphics;
@@ -184,12 +182,13 @@ pub mod client {
     pub mod settings;
 }
 
-fn main() {
+fn main() -> ExitCode {
     let args: Vec<String> = std::env::args().collect();
 
     args.get(1).map_or_else(
         || {
             client_main();
+            ExitCode::SUCCESS
         },
         |arg| {
             if arg == "server" {
@@ -198,11 +197,18 @@ fn main() {
                 client_main();
             } else if arg == "version" {
                 println!("{}", shared::versions::VERSION);
+            } else if arg == "mod" {
+                // scripts that build mods need to know if it failed
+                if let Err(error) = run_mod_command(args.get(2..).unwrap_or_default()) {
+                    println!("{error}");
+                    return ExitCode::FAILURE;
+                }
             } else {
                 println!("Invalid argument: {arg}");
             }
+            ExitCode::SUCCESS
         },
-    );
+    )
 }
 
 fn server_main(args: &[String]) {
@@ -257,6 +263,9 @@ fn server_main(args: &[String]) {
     } else {
         Server::new(MULTIPLAYER_PORT, None, None)
     };
+    server.strict_mods = args.contains(&"strictmods".to_owned());
+    // the mod in the directory given with devmod=<dir> is reloaded whenever its files change
+    server.dev_mod_path = args.iter().find_map(|arg| arg.strip_prefix("devmod=")).map(PathBuf::from);
 
     if let Some(graphics) = server_graphics_context {
         let mut manager = UiManager::new(server, graphics, srv_to_ui_event_receiver, ui_to_srv_event_sender, path_clone);
@@ -9,21 +9,31 @@ use crate::libraries::events::{Event, EventManager};
 use crate::server::server_core::networking::SendTarget;
 use crate::server::server_core::players::ServerPlayers;
 use crate::shared::blocks::{
-    handle_event_for_blocks_interface, init_blocks_mod_interface, BlockBreakStartPacket, BlockBreakStopPacket, BlockChangeEvent, BlockChangePacket, BlockInventoryUpdateEvent,
-    BlockInventoryUpdatePacket, BlockRightClickPacket, BlockStartedBreakingEvent, BlockStoppedBreakingEvent, Blocks, BlocksWelcomePacket, ClientBlockBreakStartPacket,
+    handle_event_for_blocks_interface, init_blocks_mod_interface, BlockBreakEvent, BlockBreakStartPacket, BlockBreakStopPacket, BlockChangeEvent, BlockChangePacket, BlockDataUpdateEvent,
+    BlockDataUpdatePacket, BlockInventoryUpdateEvent, BlockInventoryUpdatePacket, BlockRightClickPacket, BlockStartedBreakingEvent, BlockStoppedBreakingEvent, Blocks, BlocksWelcomePacket,
+    ClientBlockBreakStartPacket,
 };
 use crate::shared::entities::Entities;
 use crate::shared::inventory::Inventory;
-use crate::shared::items::Items;
+use crate::shared::items::{ItemId, Items};
 use crate::shared::mod_manager::ModManager;
 use crate::shared::packet::Packet;
 
 use super::networking::{Connection, NewConnectionEvent, PacketFromClientEvent, ServerNetworking};
 
+/// Breaking state is the block a connection is breaking and the tool it started breaking it with.
+struct BreakingState {
+    pos: (i32, i32),
+    // the position of the main block, it is what a break event has
+    main_pos: (i32, i32),
+    // the slot of the tool and the item that was in it
+    tool: Option<(usize, ItemId)>,
+}
+
 /// A struct that handles all block related stuff on the server side.
 pub struct ServerBlocks {
     blocks: Arc<Mutex<Blocks>>,
-    conns_breaking: HashMap<Connection, (i32, i32)>,
+    conns_breaking: HashMap<Connection, BreakingState>,
     event_receiver: Option<Receiver<Event>>,
 }
 
@@ -42,11 +52,16 @@ impl ServerBlocks {
         Ok(())
     }
 
-    pub fn get_blocks(&self) -> MutexGuard<Blocks> {
+    /// this function returns the shared blocks, so other mod interfaces can look up block types
+    pub const fn get_shared_blocks(&self) -> &Arc<Mutex<Blocks>> {
+        &self.blocks
+    }
+
+    pub fn get_blocks(&self) -> MutexGuard<'_, Blocks> {
         self.blocks.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, reason = "it handles every packet and event of the blocks")]
     pub fn on_event(
         &mut self,
         event: &Event,
@@ -65,19 +80,26 @@ impl ServerBlocks {
             networking.send_packet(&welcome_packet, SendTarget::Connection(event.conn.clone()))?;
         } else if let Some(event) = event.downcast::<PacketFromClientEvent>() {
             if let Some(packet) = event.packet.try_deserialize::<ClientBlockBreakStartPacket>() {
-                if let Some(pos) = self.conns_breaking.get(&event.conn).copied() {
-                    self.get_blocks().stop_breaking_block(events, pos.0, pos.1)?;
+                if let Some(state) = self.conns_breaking.remove(&event.conn) {
+                    self.get_blocks().stop_breaking_block(events, state.pos.0, state.pos.1)?;
                 }
 
-                self.conns_breaking.insert(event.conn.clone(), (packet.x, packet.y));
-
                 let player_id = players.get_player_from_connection(&event.conn)?;
                 if let Some(player_id) = player_id {
-                    let held_item = entities.ecs.get::<&Inventory>(player_id)?.get_selected_item();
+                    let inventory = entities.ecs.get::<&Inventory>(player_id)?;
+                    let held_item = inventory.get_selected_item();
                     let tool = if let Some(item) = &held_item { items.get_item_type(item.item)?.tool } else { None };
 
                     let tool_power = if let Some(item) = &held_item { items.get_item_type(item.item)?.tool_power } else { 0 };
 
+                    let from_main = self.get_blocks().get_block_from_main(packet.x, packet.y)?;
+                    let state = BreakingState {
+                        pos: (packet.x, packet.y),
+                        main_pos: (packet.x - from_main.0, packet.y - from_main.1),
+                        tool: inventory.selected_slot.zip(held_item.map(|item| item.item)).filter(|_| tool.is_some()),
+                    };
+                    self.conns_breaking.insert(event.conn.clone(), state);
+
                     self.get_blocks().start_breaking_block(events, packet.x, packet.y, tool, tool_power)?;
                 }
             }
@@ -111,8 +133,20 @@ impl ServerBlocks {
                                 can_place
                             };
 
+                            let (x, y) = (packet.x, packet.y - block_height + 1);
+                            let name = networking.get_connection_name(&event.conn);
+                            let can_place = can_place
+                                && !mods.fire_event("block_place", |lua| {
+                                    let payload = lua.create_table()?;
+                                    payload.set("player", name.as_str())?;
+                                    payload.set("x", x)?;
+                                    payload.set("y", y)?;
+                                    payload.set("block", block)?;
+                                    Ok(payload)
+                                })?;
+
                             if can_place {
-                                self.get_blocks().set_block(events, packet.x, packet.y - block_height + 1, block)?;
+                                self.get_blocks().set_block(events, x, y, block)?;
                                 selected_item.count -= 1;
                                 let selected_slot = player_inventory.selected_slot.unwrap_or(0);
                                 player_inventory.set_item(selected_slot, Some(selected_item))?;
@@ -121,6 +155,27 @@ impl ServerBlocks {
                     }
                 }
             }
+        } else if let Some(event) = event.downcast::<BlockBreakEvent>() {
+            // players that broke the block with a tool use up the durability of the tool they started with
+            let breaking_conns: Vec<Connection> = self
+                .conns_breaking
+                .iter()
+                .filter(|(_, state)| state.main_pos == (event.x, event.y))
+                .map(|(conn, _)| conn.clone())
+                .collect();
+            for conn in breaking_conns {
+                let Some((slot, tool_item)) = self.conns_breaking.remove(&conn).and_then(|state| state.tool) else {
+                    continue;
+                };
+
+                if let Some(player) = players.get_player_from_connection(&conn)? {
+                    let mut inventory = entities.ecs.get::<&mut Inventory>(player)?;
+                    // the tool could have been moved while the block was breaking
+                    if inventory.get_item(slot)?.is_some_and(|item| item.item == tool_item) {
+                        inventory.damage_item(slot, items)?;
+                    }
+                }
+            }
         } else if let Some(event) = event.downcast::<BlockStartedBreakingEvent>() {
             let packet = Packet::new(BlockBreakStartPacket {
                 x: event.x,
@@ -154,6 +209,16 @@ impl ServerBlocks {
                     self.get_blocks().update_block(x, y, events)?;
                 }
             }
+
+            let block = self.get_blocks().get_block(event.x, event.y)?;
+            mods.fire_event("block_change", |lua| {
+                let payload = lua.create_table()?;
+                payload.set("x", event.x)?;
+                payload.set("y", event.y)?;
+                payload.set("block", block)?;
+                payload.set("previous_block", event.prev_block)?;
+                Ok(payload)
+            })?;
         } else if let Some(event) = event.downcast::<BlockInventoryUpdateEvent>() {
             let packet = Packet::new(BlockInventoryUpdatePacket {
                 x: event.x,
@@ -161,11 +226,18 @@ impl ServerBlocks {
                 inventory: self.get_blocks().get_block_inventory_data(event.x, event.y)?.unwrap_or(&vec![]).clone(),
             })?;
             networking.send_packet(&packet, SendTarget::All)?;
+        } else if let Some(event) = event.downcast::<BlockDataUpdateEvent>() {
+            let packet = Packet::new(BlockDataUpdatePacket {
+                x: event.x,
+                y: event.y,
+                data: self.get_blocks().get_block_data(event.x, event.y)?,
+            })?;
+            networking.send_packet(&packet, SendTarget::All)?;
         }
         Ok(())
     }
 
-    fn flush_mods_events(&mut self, events: &mut EventManager) {
+    fn flush_mods_events(&self, events: &mut EventManager) {
         if let Some(receiver) = &self.event_receiver {
             while let Ok(event) = receiver.try_recv() {
                 events.push_event(event);
@@ -173,7 +245,7 @@ impl ServerBlocks {
         }
     }
 
-    pub fn update(&mut self, events: &mut EventManager, frame_length: f32) -> Result<()> {
+    pub fn update(&self, events: &mut EventManager, frame_length: f32) -> Result<()> {
         self.flush_mods_events(events);
 
         self.get_blocks().update_breaking_blocks(events, frame_length)
@@ -1,29 +1,105 @@
-use anyhow::Result;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 
 use crate::libraries::events::Event;
 use crate::server::server_core::{
     networking::{PacketFromClientEvent, SendTarget, ServerNetworking},
     print_to_console,
 };
-use crate::shared::chat::ChatPacket;
+use crate::shared::chat::{ChatMessage, ChatMessagePacket, ChatPacket};
+use crate::shared::mod_manager::ModManager;
 use crate::shared::packet::Packet;
 
-pub fn server_chat_on_event(event: &Event, networking: &mut ServerNetworking) -> Result<()> {
-    if let Some(event) = event.downcast::<PacketFromClientEvent>() {
-        if let Some(packet) = event.packet.try_deserialize::<ChatPacket>() {
-            if packet.message.starts_with('/') {
-                return Ok(());
-            }
+/// Server chat sends the messages of the players to each other, remembers
+/// who whispered to whom last for the reply command and writes all messages
+/// into the chat log of the world.
+pub struct ServerChat {
+    // the player who last whispered to each player
+    last_whisperers: HashMap<String, String>,
+    log_file: Option<File>,
+}
+
+impl ServerChat {
+    pub fn new() -> Self {
+        Self {
+            last_whisperers: HashMap::new(),
+            log_file: None,
+        }
+    }
 
-            let mut name = networking.get_connection_name(&event.conn);
-            if name == "_" {
-                name = "Player".to_owned();
+    /// this function opens the chat log next to the world file, it is named like
+    /// the world with the extension chat.log and new messages are appended to it
+    pub fn init(&mut self, world_path: &Path) -> Result<()> {
+        if let Some(world_dir) = world_path.parent() {
+            std::fs::create_dir_all(world_dir)?;
+        }
+        self.log_file = Some(OpenOptions::new().create(true).append(true).open(world_path.with_extension("chat.log"))?);
+        Ok(())
+    }
+
+    fn log_message(&mut self, message: &ChatMessage) -> Result<()> {
+        let text = message.get_text();
+        print_to_console(&text, 0);
+        if let Some(log_file) = &mut self.log_file {
+            writeln!(log_file, "[{}] {text}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"))?;
+        }
+        Ok(())
+    }
+
+    /// this function sends the message to the target and writes it into the chat log
+    pub fn send_message(&mut self, networking: &mut ServerNetworking, message: ChatMessage, target: SendTarget) -> Result<()> {
+        self.log_message(&message)?;
+        networking.send_packet(&Packet::new(ChatMessagePacket { message })?, target)
+    }
+
+    /// this function sends a private message to the receiver, the sender
+    /// gets a copy of it if they are a player and not the server console
+    pub fn whisper(&mut self, networking: &mut ServerNetworking, sender: &str, receiver: &str, text: &str) -> Result<()> {
+        let receiver_conn = networking.get_connection_by_name(receiver).ok_or_else(|| anyhow!("player {receiver} is not online"))?;
+        let message = ChatMessage::whisper(sender, receiver, text);
+        if let Some(sender_conn) = networking.get_connection_by_name(sender) {
+            if sender_conn != receiver_conn {
+                networking.send_packet(&Packet::new(ChatMessagePacket { message: message.clone() })?, SendTarget::Connection(sender_conn))?;
             }
+        }
+        self.send_message(networking, message, SendTarget::Connection(receiver_conn))?;
+        self.last_whisperers.insert(receiver.to_owned(), sender.to_owned());
+        Ok(())
+    }
 
-            let message = format!("{}: {}", name, packet.message);
-            print_to_console(&message, 0);
-            networking.send_packet(&Packet::new(ChatPacket { message })?, SendTarget::All)?;
+    /// this function returns who the player should reply to
+    pub fn get_last_whisperer(&self, name: &str) -> Option<&str> {
+        self.last_whisperers.get(name).map(String::as_str)
+    }
+
+    /// this function sends the messages of the players to everyone, unless a mod cancels them
+    pub fn on_event(&mut self, event: &Event, networking: &mut ServerNetworking, mods: &mut ModManager) -> Result<()> {
+        if let Some(event) = event.downcast::<PacketFromClientEvent>() {
+            if let Some(packet) = event.packet.try_deserialize::<ChatPacket>() {
+                if packet.message.starts_with('/') || packet.message.is_empty() {
+                    return Ok(());
+                }
+
+                let mut name = networking.get_connection_name(&event.conn);
+                if name == "_" {
+                    "Player".clone_into(&mut name);
+                }
+
+                let cancelled = mods.fire_event("chat", |lua| {
+                    let payload = lua.create_table()?;
+                    payload.set("player", name.as_str())?;
+                    payload.set("message", packet.message.as_str())?;
+                    Ok(payload)
+                })?;
+                if !cancelled {
+                    self.send_message(networking, ChatMessage::player(&name, &packet.message), SendTarget::All)?;
+                }
+            }
         }
+        Ok(())
     }
-    Ok(())
 }
@@ -0,0 +1,55 @@
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+/// The clock tells the server how much time has passed, so the server
+/// knows how much to simulate on each update.
+pub trait Clock: Send {
+    /// returns the number of milliseconds since the clock was created
+    fn elapsed_ms(&self) -> f64;
+}
+
+/// System clock follows the real time. It is what servers use by default.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed_ms(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+}
+
+/// Manual clock only moves when it is advanced. All the clones
+/// of a manual clock share the same time, so one clone can be
+/// given to the server and the other one used to advance it.
+#[derive(Clone)]
+pub struct ManualClock {
+    elapsed_ms: Arc<Mutex<f64>>,
+}
+
+impl ManualClock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            elapsed_ms: Arc::new(Mutex::new(0.0)),
+        }
+    }
+
+    /// moves the clock forward by the given number of milliseconds
+    pub fn advance(&self, ms: f64) {
+        *self.elapsed_ms.lock().unwrap_or_else(PoisonError::into_inner) += ms;
+    }
+}
+
+impl Clock for ManualClock {
+    fn elapsed_ms(&self) -> f64 {
+        *self.elapsed_ms.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use hecs::Entity;
+
+use crate::libraries::events::{Event, EventManager};
+use crate::server::server_core::networking::{NewConnectionWelcomedEvent, PacketFromClientEvent, SendTarget, ServerNetworking};
+use crate::server::server_core::players::ServerPlayers;
+use crate::shared::combat::{attack, AttackPacket, EntityDamageEvent, EntityDamagePacket, ProjectileComponent, ProjectileSpawnEvent, ProjectileSpawnPacket};
+use crate::shared::entities::{Entities, PhysicsComponent, PositionComponent};
+use crate::shared::inventory::Inventory;
+use crate::shared::items::{Items, Weapon};
+use crate::shared::packet::Packet;
+
+/// this function handles the attacks of the players and sends projectiles and damage to the clients
+pub fn server_combat_on_event(event: &Event, entities: &mut Entities, players: &ServerPlayers, items: &Items, events: &mut EventManager, networking: &mut ServerNetworking) -> Result<()> {
+    if let Some(event) = event.downcast::<PacketFromClientEvent>() {
+        if let Some(packet) = event.packet.try_deserialize::<AttackPacket>() {
+            // dead players can't attack
+            if let Some(player) = players.get_player_from_connection(&event.conn)? {
+                let weapon = get_selected_weapon(entities, items, player)?;
+                // the client can't know if the attack hits, the server checks the reach and the cooldown
+                attack(entities, events, player, &weapon, packet.x, packet.y)?;
+            }
+        }
+    }
+
+    if let Some(event) = event.downcast::<ProjectileSpawnEvent>() {
+        networking.send_packet(&get_projectile_spawn_packet(entities, event.entity)?, SendTarget::All)?;
+    }
+
+    if let Some(event) = event.downcast::<NewConnectionWelcomedEvent>() {
+        let mut projectiles = Vec::new();
+        for (entity, _projectile) in &mut entities.ecs.query::<&ProjectileComponent>() {
+            projectiles.push(entity);
+        }
+        for entity in projectiles {
+            networking.send_packet(&get_projectile_spawn_packet(entities, entity)?, SendTarget::Connection(event.conn.clone()))?;
+        }
+    }
+
+    if let Some(event) = event.downcast::<EntityDamageEvent>() {
+        networking.send_packet(
+            &Packet::new(EntityDamagePacket {
+                id: event.entity,
+                damage: event.damage,
+            })?,
+            SendTarget::All,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// players attack with their fists, if the selected item is not a weapon
+fn get_selected_weapon(entities: &Entities, items: &Items, player: Entity) -> Result<Weapon> {
+    let selected_item = entities.ecs.get::<&Inventory>(player)?.get_selected_item();
+    Ok(match selected_item {
+        Some(stack) => items.get_item_type(stack.item)?.weapon.unwrap_or_else(Weapon::new),
+        None => Weapon::new(),
+    })
+}
+
+fn get_projectile_spawn_packet(entities: &Entities, entity: Entity) -> Result<Packet> {
+    let position = entities.ecs.get::<&PositionComponent>(entity)?;
+    let physics = entities.ecs.get::<&PhysicsComponent>(entity)?;
+
+    Packet::new(ProjectileSpawnPacket {
+        id: entities.get_id_from_entity(entity)?,
+        x: position.x(),
+        y: position.y(),
+        velocity_x: physics.velocity_x,
+        velocity_y: physics.velocity_y,
+    })
+}
@@ -1,39 +1,270 @@
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::sync::mpsc::Receiver;
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, bail, Error, Result};
+use hecs::Entity;
+use rlua::RegistryKey;
 
 use crate::libraries::events::{Event, EventManager};
+use crate::server::server_core::deaths::DeathMode;
 use crate::server::server_core::networking::{PacketFromClientEvent, SendTarget, ServerNetworking};
-use crate::server::server_core::{entities, players};
+use crate::server::server_core::{blocks, chat, entities, mod_manager, players, world_time};
 use crate::server::server_core::{items, print_to_console, send_to_ui};
 use crate::server::server_ui::{ConsoleMessageType, ServerState, UiMessageType};
-use crate::shared::chat::ChatPacket;
+use crate::shared::blocks::BlockId;
+use crate::shared::chat::{ChatMessage, ChatMessagePacket, ChatPacket, CommandCompletionPacket, CommandCompletionRequestPacket};
+use crate::shared::combat::LastDamageComponent;
+use crate::shared::entities::{HealthComponent, PhysicsComponent, PositionComponent};
 use crate::shared::inventory::Inventory;
-use crate::shared::items::ItemStack;
+use crate::shared::items::{ItemId, ItemStack};
 use crate::shared::packet::Packet;
 use crate::shared::players::PlayerComponent;
+use crate::shared::world_time::DAY_LENGTH_MS;
 
 /// This struct contains all parameters that are needed to execute any command
 /// It is used so that when a new argument is needed to be added to a command, it can be added here and all commands will be updated
-#[allow(clippy::single_char_lifetime_names)]
+#[allow(clippy::single_char_lifetime_names, reason = "all the references live as long as the command")]
 pub struct CommandParameters<'a> {
     pub command_manager: &'a CommandManager,
+    // the command that is executed
+    pub command: &'a Command,
     pub state: &'a mut ServerState,
     pub executor: Option<&'a str>,
     pub players: &'a mut players::ServerPlayers,
+    pub blocks: &'a mut blocks::ServerBlocks,
     pub items: &'a mut items::ServerItems,
     pub entities: &'a mut entities::ServerEntities,
+    pub world_time: &'a mut world_time::ServerWorldTime,
+    pub chat: &'a mut chat::ServerChat,
+    pub networking: &'a mut ServerNetworking,
     pub event_manager: &'a mut EventManager,
-    pub arguments: Vec<String>,
+    pub mods: &'a mut mod_manager::ServerModManager,
+    // the seed the world was generated with
+    pub seed: u64,
+    // the parsed arguments by their names, optional arguments that were not given are missing
+    pub arguments: HashMap<String, ArgumentValue>,
+}
+
+impl CommandParameters<'_> {
+    #[must_use]
+    pub fn has_argument(&self, name: &str) -> bool {
+        self.arguments.contains_key(name)
+    }
+
+    fn get_argument(&self, name: &str) -> Result<&ArgumentValue> {
+        self.arguments.get(name).ok_or_else(|| anyhow!("argument {name} is missing"))
+    }
+
+    /// this function returns the text of word, text and choice arguments and the name of player arguments
+    pub fn get_string(&self, name: &str) -> Result<&str> {
+        match self.get_argument(name)? {
+            ArgumentValue::Text(text) | ArgumentValue::Player(text, _) => Ok(text),
+            _ => Err(anyhow!("argument {name} is not text")),
+        }
+    }
+
+    pub fn get_integer(&self, name: &str) -> Result<i32> {
+        match self.get_argument(name)? {
+            ArgumentValue::Integer(value) => Ok(*value),
+            _ => Err(anyhow!("argument {name} is not an integer")),
+        }
+    }
+
+    pub fn get_coordinates(&self, name: &str) -> Result<(f32, f32)> {
+        match self.get_argument(name)? {
+            ArgumentValue::Coordinates(x, y) => Ok((*x, *y)),
+            _ => Err(anyhow!("argument {name} is not a position")),
+        }
+    }
+
+    pub fn get_item(&self, name: &str) -> Result<ItemId> {
+        match self.get_argument(name)? {
+            ArgumentValue::Item(item) => Ok(*item),
+            _ => Err(anyhow!("argument {name} is not an item")),
+        }
+    }
+
+    pub fn get_block(&self, name: &str) -> Result<BlockId> {
+        match self.get_argument(name)? {
+            ArgumentValue::Block(block) => Ok(*block),
+            _ => Err(anyhow!("argument {name} is not a block")),
+        }
+    }
+
+    /// this function returns the position of a destination argument or where the player in it is
+    pub fn get_destination(&self, name: &str) -> Result<(f32, f32)> {
+        match self.get_argument(name)? {
+            ArgumentValue::Coordinates(x, y) => Ok((*x, *y)),
+            ArgumentValue::Player(_, player) => {
+                let position = self.entities.entities.ecs.get::<&PositionComponent>(*player)?;
+                Ok((position.x(), position.y()))
+            }
+            _ => Err(anyhow!("argument {name} is not a position or a player")),
+        }
+    }
+
+    pub fn get_player(&self, name: &str) -> Result<Entity> {
+        match self.get_argument(name)? {
+            ArgumentValue::Player(_, player) => Ok(*player),
+            _ => Err(anyhow!("argument {name} is not a player")),
+        }
+    }
+
+    fn get_player_name(&self, player: Entity) -> Result<String> {
+        Ok(self.entities.entities.ecs.get::<&PlayerComponent>(player)?.get_name().to_owned())
+    }
+
+    /// this function returns the player given in the argument or the player
+    /// who executed the command if the argument was left out
+    pub fn get_player_or_executor(&self, name: &str) -> Result<Entity> {
+        if self.has_argument(name) {
+            self.get_player(name)
+        } else if let Some(executor) = self.executor {
+            Ok(*self.players.get_player_entity_from_name(executor, &self.entities.entities)?)
+        } else {
+            Err(anyhow!("No {name} specified when the executor is a server console"))
+        }
+    }
+}
+
+/// Argument type tells how an argument is parsed and completed.
+#[derive(Clone, Copy)]
+pub enum ArgumentType {
+    // the name of a player who is online
+    Player,
+    // the name of an item type
+    Item,
+    // the name of a block type
+    Block,
+    // the name of a command
+    Command,
+    Integer,
+    // a position made of two numbers
+    Coordinates,
+    // a single word, or a quoted string with spaces
+    Word,
+    // the rest of the line
+    Text,
+    // one of the given words
+    Choice(&'static [&'static str]),
+    // a position made of two numbers or the name of a player who is online
+    Destination,
+}
+
+/// Argument is a declaration of a value a command takes.
+#[derive(Clone)]
+pub struct Argument {
+    pub name: String,
+    pub argument_type: ArgumentType,
+    // optional arguments can only be followed by other optional arguments
+    pub optional: bool,
+}
+
+impl Argument {
+    #[must_use]
+    pub fn required(name: &str, argument_type: ArgumentType) -> Self {
+        Self {
+            name: name.to_owned(),
+            argument_type,
+            optional: false,
+        }
+    }
+
+    #[must_use]
+    pub fn optional(name: &str, argument_type: ArgumentType) -> Self {
+        Self {
+            name: name.to_owned(),
+            argument_type,
+            optional: true,
+        }
+    }
+
+    /// this function returns how the argument is shown in the usage of the command
+    #[must_use]
+    pub fn get_usage(&self) -> String {
+        let usage = match self.argument_type {
+            ArgumentType::Coordinates => "x y".to_owned(),
+            ArgumentType::Destination => format!("x y|{}", self.name),
+            ArgumentType::Choice(choices) => choices.join("|"),
+            _ => self.name.clone(),
+        };
+        if self.optional {
+            format!("[{usage}]")
+        } else {
+            format!("<{usage}>")
+        }
+    }
+}
+
+/// Argument value is a parsed argument.
+#[derive(Clone)]
+pub enum ArgumentValue {
+    Text(String),
+    Integer(i32),
+    Coordinates(f32, f32),
+    Item(ItemId),
+    Block(BlockId),
+    Player(String, Entity),
+}
+
+/// this function splits the command into words and quoted strings, each with the byte it starts at,
+/// the bool is true if the last quote was not closed
+fn split_words(text: &str) -> (Vec<(usize, String)>, bool) {
+    let mut words = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    let mut in_quotes = false;
+    for (index, character) in text.char_indices() {
+        if character == '"' {
+            in_quotes = !in_quotes;
+            current.get_or_insert_with(|| (index, String::new()));
+        } else if character.is_whitespace() && !in_quotes {
+            words.extend(current.take());
+        } else {
+            current.get_or_insert_with(|| (index, String::new())).1.push(character);
+        }
+    }
+    words.extend(current);
+    (words, in_quotes)
+}
+
+/// this function quotes the word if it has to be quoted to stay a single argument
+fn quote_word(word: &str) -> String {
+    if word.contains(char::is_whitespace) {
+        format!("\"{word}\"")
+    } else {
+        word.to_owned()
+    }
+}
+
+/// Mod function is the lua function a command registered by a mod calls,
+/// it is kept in the lua registry of the mod.
+pub struct ModFunction {
+    pub mod_id: i32,
+    pub function: RegistryKey,
 }
 
 /// struct that contains the command name and the function that will be executed when the command is called
 pub struct Command {
     pub call_name: String,
-    pub name: String,
     pub description: String,
+    pub arguments: Vec<Argument>,
     pub function: fn(&mut CommandParameters) -> anyhow::Result<String>,
+    // commands registered by mods run this lua function through mod_command
+    pub mod_function: Option<ModFunction>,
+}
+
+impl Command {
+    /// this function returns how the command is used, for example give <item> [player]
+    #[must_use]
+    pub fn get_usage(&self) -> String {
+        let mut usage = self.call_name.clone();
+        for argument in &self.arguments {
+            usage.push(' ');
+            usage.push_str(&argument.get_usage());
+        }
+        usage
+    }
 }
 
 /// contains all the commands
@@ -51,18 +282,151 @@ impl CommandManager {
         self.commands.push(command);
     }
 
+    #[must_use]
+    pub fn get_command(&self, call_name: &str) -> Option<&Command> {
+        self.commands.iter().find(|command| command.call_name == call_name)
+    }
+
+    fn parse_player(name: &str, players: &players::ServerPlayers, entities: &entities::ServerEntities) -> Result<ArgumentValue> {
+        let player = *players
+            .get_player_entity_from_name(name, &entities.entities)
+            .ok()
+            .ok_or_else(|| anyhow!("player {name} is not online"))?;
+        Ok(ArgumentValue::Player(name.to_owned(), player))
+    }
+
+    /// this function parses the words after the command name into the arguments of the command
+    fn parse_arguments(
+        command: &Command,
+        text: &str,
+        words: &[(usize, String)],
+        players: &players::ServerPlayers,
+        blocks: &blocks::ServerBlocks,
+        items: &items::ServerItems,
+        entities: &entities::ServerEntities,
+    ) -> Result<HashMap<String, ArgumentValue>> {
+        let mut values = HashMap::new();
+        let mut words = words.iter();
+        for argument in &command.arguments {
+            let Some((start, word)) = words.next() else {
+                if argument.optional {
+                    break;
+                }
+                bail!("missing {}, usage: {}", argument.name, command.get_usage());
+            };
+
+            let value = match argument.argument_type {
+                ArgumentType::Player => Self::parse_player(word, players, entities)?,
+                ArgumentType::Destination => {
+                    if let Ok(x) = word.parse() {
+                        let (_, y) = words.next().ok_or_else(|| anyhow!("missing y of {}, usage: {}", argument.name, command.get_usage()))?;
+                        ArgumentValue::Coordinates(x, y.parse().ok().ok_or_else(|| anyhow!("y must be a number, got {y}"))?)
+                    } else {
+                        Self::parse_player(word, players, entities)?
+                    }
+                }
+                ArgumentType::Item => ArgumentValue::Item(items.get_items().get_item_type_by_name(word).ok().ok_or_else(|| anyhow!("unknown item {word}"))?.get_id()),
+                ArgumentType::Block => ArgumentValue::Block(blocks.get_blocks().get_block_id_by_name(word).ok().ok_or_else(|| anyhow!("unknown block {word}"))?),
+                ArgumentType::Integer => ArgumentValue::Integer(word.parse().ok().ok_or_else(|| anyhow!("{} must be a whole number, got {word}", argument.name))?),
+                ArgumentType::Coordinates => {
+                    let (_, y) = words.next().ok_or_else(|| anyhow!("missing y of {}, usage: {}", argument.name, command.get_usage()))?;
+                    let x = word.parse().ok().ok_or_else(|| anyhow!("x must be a number, got {word}"))?;
+                    let y = y.parse().ok().ok_or_else(|| anyhow!("y must be a number, got {y}"))?;
+                    ArgumentValue::Coordinates(x, y)
+                }
+                ArgumentType::Command | ArgumentType::Word => ArgumentValue::Text(word.clone()),
+                ArgumentType::Text => {
+                    words.by_ref().for_each(drop);
+                    ArgumentValue::Text(text.get(*start..).unwrap_or("").trim_end().to_owned())
+                }
+                ArgumentType::Choice(choices) => {
+                    if !choices.contains(&word.as_str()) {
+                        bail!("invalid {} {word}, use {}", argument.name, choices.join(", "));
+                    }
+                    ArgumentValue::Text(word.clone())
+                }
+            };
+            values.insert(argument.name.clone(), value);
+        }
+
+        if words.next().is_some() {
+            bail!("too many arguments, usage: {}", command.get_usage());
+        }
+        Ok(values)
+    }
+
+    /// this function returns what the last word of the command could be completed to,
+    /// the first word is completed to command names and the others depending on the argument type
+    pub fn get_completions(&self, text: &str, blocks: &blocks::ServerBlocks, items: &items::ServerItems, entities: &entities::ServerEntities) -> Vec<String> {
+        let (words, in_quotes) = split_words(text);
+        let ends_word = !in_quotes && (text.is_empty() || text.ends_with(char::is_whitespace));
+        let word_index = if ends_word { words.len() } else { words.len().saturating_sub(1) };
+        let prefix = if ends_word { "" } else { words.last().map_or("", |(_, word)| word.as_str()) };
+
+        let mut argument_type = Some(ArgumentType::Command);
+        if word_index > 0 {
+            argument_type = None;
+            let mut index = 1;
+            let command = words.first().and_then(|(_, call_name)| self.get_command(call_name));
+            for argument in command.map(|command| command.arguments.as_slice()).unwrap_or_default() {
+                let size = if matches!(argument.argument_type, ArgumentType::Coordinates) { 2 } else { 1 };
+                // text takes all the words that are left
+                if word_index < index + size || matches!(argument.argument_type, ArgumentType::Text) {
+                    argument_type = Some(argument.argument_type);
+                    break;
+                }
+                index += size;
+            }
+        }
+
+        let candidates: Vec<String> = match argument_type {
+            Some(ArgumentType::Command) => self.commands.iter().map(|command| command.call_name.clone()).collect(),
+            Some(ArgumentType::Player | ArgumentType::Destination) => {
+                let mut query = entities.entities.ecs.query::<&PlayerComponent>();
+                query.iter().map(|(_, player)| player.get_name().to_owned()).collect()
+            }
+            Some(ArgumentType::Item) => {
+                let items = items.get_items();
+                items.get_all_item_type_ids().into_iter().filter_map(|id| items.get_item_type(id).ok()).map(|item| item.name).collect()
+            }
+            Some(ArgumentType::Block) => {
+                let blocks = blocks.get_blocks();
+                blocks.get_all_block_ids().into_iter().filter_map(|id| blocks.get_block_type(id).ok()).map(|block| block.name).collect()
+            }
+            Some(ArgumentType::Choice(choices)) => choices.iter().map(|choice| (*choice).to_owned()).collect(),
+            _ => Vec::new(),
+        };
+
+        let mut completions: Vec<String> = candidates.iter().filter(|candidate| candidate.starts_with(prefix)).map(|candidate| quote_word(candidate)).collect();
+        completions.sort();
+        completions.dedup();
+        completions
+    }
+
     /// receives an event and executes the command
     pub fn on_event(
         &self,
         event: &Event,
         state: &mut ServerState,
         players: &mut players::ServerPlayers,
+        blocks: &mut blocks::ServerBlocks,
         items: &mut items::ServerItems,
         entities: &mut entities::ServerEntities,
+        world_time: &mut world_time::ServerWorldTime,
+        chat: &mut chat::ServerChat,
         event_manager: &mut EventManager,
         networking: &mut ServerNetworking,
+        mods: &mut mod_manager::ServerModManager,
+        seed: u64,
     ) -> Result<()> {
         if let Some(event) = event.downcast::<PacketFromClientEvent>() {
+            if let Some(packet) = event.packet.try_deserialize::<CommandCompletionRequestPacket>() {
+                let text = packet.text.strip_prefix('/').unwrap_or(&packet.text);
+                let completions = self.get_completions(text, blocks, items, entities);
+                let packet = Packet::new(CommandCompletionPacket { text: packet.text, completions })?;
+                networking.send_packet(&packet, SendTarget::Connection(event.conn.clone()))?;
+            }
+
             if let Some(packet) = event.packet.try_deserialize::<ChatPacket>() {
                 let mut command = packet.message;
 
@@ -78,21 +442,23 @@ impl CommandManager {
                     let name = entities.entities.ecs.get::<&mut PlayerComponent>(player_entity)?.get_name().to_owned();
 
                     let mut output = String::new();
-                    let result = self.execute_command(&command, state, Some(&name), players, items, entities, event_manager);
+                    let result = self.execute_command(&command, state, Some(&name), players, blocks, items, entities, world_time, chat, networking, event_manager, mods, seed);
 
                     writeln!(output, "Player \"{name}\" executed a command: {command}")?;
-                    let result = match result {
-                        Ok(result) => result,
-                        Err(e) => format!("Error: {e}"),
+                    let message = match result {
+                        Ok(result) => ChatMessage::system(&result),
+                        Err(e) => ChatMessage::error(&format!("Error: {e}")),
                     };
 
-                    writeln!(output, "Command result: {result:?}")?;
+                    writeln!(output, "Command result: {:?}", message.get_text())?;
 
                     send_to_ui(UiMessageType::SrvToUiConsoleMessage(ConsoleMessageType::Info(output)), None);
 
-                    let packet = Packet::new(ChatPacket { message: result })?;
-
-                    networking.send_packet(&packet, SendTarget::Connection(event.conn.clone()))?;
+                    // commands like msg show their result in another way
+                    if !message.get_text().is_empty() {
+                        let packet = Packet::new(ChatMessagePacket { message })?;
+                        networking.send_packet(&packet, SendTarget::Connection(event.conn.clone()))?;
+                    }
                 }
             }
         }
@@ -107,28 +473,42 @@ impl CommandManager {
         state: &mut ServerState,
         executor: Option<&str>,
         players: &mut players::ServerPlayers,
+        blocks: &mut blocks::ServerBlocks,
         items: &mut items::ServerItems,
         entities: &mut entities::ServerEntities,
+        world_time: &mut world_time::ServerWorldTime,
+        chat: &mut chat::ServerChat,
+        networking: &mut ServerNetworking,
         event_manager: &mut EventManager,
+        mods: &mut mod_manager::ServerModManager,
+        seed: u64,
     ) -> Result<String> {
-        let arguments: Vec<String> = command.split(' ').map(std::borrow::ToOwned::to_owned).collect();
-
-        for c in &self.commands {
-            if c.call_name == *arguments.first().unwrap_or(&String::new()) {
-                return (c.function)(&mut CommandParameters {
-                    //returns the feedback message from the command
-                    command_manager: self,
-                    state,
-                    executor,
-                    players,
-                    items,
-                    entities,
-                    event_manager,
-                    arguments: arguments.get(1..).unwrap_or(&[]).to_vec(),
-                });
-            }
+        let (words, in_quotes) = split_words(command);
+        if in_quotes {
+            bail!("missing closing quote");
         }
-        Err(Error::msg("Invalid command"))
+        let call_name = words.first().map_or("", |(_, word)| word.as_str());
+        let command_to_execute = self.get_command(call_name).ok_or_else(|| Error::msg("Invalid command"))?;
+        let arguments = Self::parse_arguments(command_to_execute, command, words.get(1..).unwrap_or(&[]), players, blocks, items, entities)?;
+
+        //returns the feedback message from the command
+        (command_to_execute.function)(&mut CommandParameters {
+            command_manager: self,
+            command: command_to_execute,
+            state,
+            executor,
+            players,
+            blocks,
+            items,
+            entities,
+            world_time,
+            chat,
+            networking,
+            event_manager,
+            mods,
+            seed,
+            arguments,
+        })
     }
 
     /// executes all commands that are typed into the console
@@ -137,64 +517,324 @@ impl CommandManager {
         receiver: &Receiver<UiMessageType>,
         state: &mut ServerState,
         players: &mut players::ServerPlayers,
+        blocks: &mut blocks::ServerBlocks,
         items: &mut items::ServerItems,
         entities: &mut entities::ServerEntities,
+        world_time: &mut world_time::ServerWorldTime,
+        chat: &mut chat::ServerChat,
+        networking: &mut ServerNetworking,
         event_manager: &mut EventManager,
+        mods: &mut mod_manager::ServerModManager,
+        seed: u64,
     ) {
         //goes through the messages received from the server
-        while let Ok(UiMessageType::UiToSrvConsoleMessage(message)) = receiver.try_recv() {
-            let feedback = self.execute_command(&message, state, None, players, items, entities, event_manager);
-            match feedback {
-                Ok(feedback) => print_to_console(&feedback, 0),
-                Err(val) => print_to_console(&val.to_string(), 1),
-            };
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                UiMessageType::UiToSrvConsoleMessage(message) => {
+                    let feedback = self.execute_command(&message, state, None, players, blocks, items, entities, world_time, chat, networking, event_manager, mods, seed);
+                    match feedback {
+                        Ok(feedback) => print_to_console(&feedback, 0),
+                        Err(val) => print_to_console(&val.to_string(), 1),
+                    }
+                }
+                UiMessageType::UiToSrvCompletionRequest(text) => {
+                    let completions = self.get_completions(&text, blocks, items, entities);
+                    send_to_ui(UiMessageType::SrvToUiCompletion((text, completions)), None);
+                }
+                _ => {}
+            }
         }
     }
 }
 
-//help command
-#[allow(clippy::unnecessary_wraps)] //all command functions must return the same type
+//help command, shows all commands or how to use one of them
 pub fn help_command(parameters: &mut CommandParameters) -> Result<String> {
+    if parameters.has_argument("command") {
+        let call_name = parameters.get_string("command")?;
+        let command = parameters.command_manager.get_command(call_name).ok_or_else(|| anyhow!("unknown command {call_name}"))?;
+        return Ok(format!("{} - {}\nUsage: {}", command.call_name, command.description, command.get_usage()));
+    }
+
     let mut string = String::new();
     string.push_str("Commands:\n");
     for c in &parameters.command_manager.commands {
-        writeln!(string, "{} - {}", c.call_name, c.description)?;
+        writeln!(string, "{} - {}", c.get_usage(), c.description)?;
     }
     anyhow::Ok(string)
 }
 
-#[allow(clippy::unnecessary_wraps)] //all command functions must return the same type
+#[allow(clippy::unnecessary_wraps, reason = "all command functions must return the same type")]
 pub fn stop_command(parameters: &mut CommandParameters) -> Result<String> {
     *parameters.state = ServerState::Stopping;
     anyhow::Ok(String::from("Stopping server..."))
 }
 
-//this command gives an item to the player
+// give refuses more items so a typo doesn't flood the world with dropped items
+const MAX_GIVE_COUNT: i32 = 10_000;
+
+//this command gives items to the player, what doesn't fit into the inventory is dropped where the player is
 pub fn give_command(parameters: &mut CommandParameters) -> Result<String> {
-    let item_name = parameters.arguments.first().ok_or_else(|| anyhow!("no item name specified"))?;
-    let player_name = parameters.arguments.get(1);
-    let item = parameters.items.get_items().get_item_type_by_name(item_name)?;
-    let player;
+    let item = parameters.get_item("item")?;
+    let player = parameters.get_player_or_executor("player")?;
+    let count = if parameters.has_argument("count") { parameters.get_integer("count")? } else { 1 };
+    if !(1..=MAX_GIVE_COUNT).contains(&count) {
+        bail!("count must be between 1 and {MAX_GIVE_COUNT}");
+    }
 
-    if let Some(player_name) = player_name {
-        player = parameters.players.get_player_entity_from_name(player_name, &parameters.entities.entities)?;
-    } else if let Some(executor) = parameters.executor {
-        player = parameters.players.get_player_entity_from_name(executor, &parameters.entities.entities)?;
-    } else {
-        return Err(anyhow!("No player specified when the executor is a server console"));
+    let position = {
+        let position = parameters.entities.entities.ecs.get::<&PositionComponent>(player)?;
+        (position.x(), position.y())
     };
-
-    let mut inventory = parameters.entities.entities.ecs.get::<&mut Inventory>(*player)?.clone();
+    let mut inventory = parameters.entities.entities.ecs.get::<&mut Inventory>(player)?.clone();
 
     inventory.give_item(
-        ItemStack::new(item.get_id(), 1),
-        (0.0, 0.0),
+        ItemStack::new(item, count),
+        position,
         &mut parameters.items.get_items(),
         &mut parameters.entities.entities,
         parameters.event_manager,
     )?;
 
-    *parameters.entities.entities.ecs.get::<&mut Inventory>(*player)? = inventory;
+    *parameters.entities.entities.ecs.get::<&mut Inventory>(player)? = inventory;
+
+    let item_name = parameters.items.get_items().get_item_type(item)?.name;
+    Ok(format!("Gave {count} {item_name} to {}", parameters.get_player_name(player)?))
+}
+
+//this command moves a player to a position or to another player
+pub fn tp_command(parameters: &mut CommandParameters) -> Result<String> {
+    let player = parameters.get_player("player")?;
+    let (x, y) = parameters.get_destination("target")?;
+    {
+        let blocks = parameters.blocks.get_blocks();
+        if x < 0.0 || y < 0.0 || x >= blocks.get_width() as f32 || y >= blocks.get_height() as f32 {
+            bail!("position {x} {y} is outside the world");
+        }
+    }
+
+    let mut position = parameters.entities.entities.ecs.get::<&mut PositionComponent>(player)?;
+    position.set_x(x);
+    position.set_y(y);
+    drop(position);
+    if let Ok(mut physics) = parameters.entities.entities.ecs.get::<&mut PhysicsComponent>(player) {
+        physics.velocity_x = 0.0;
+        physics.velocity_y = 0.0;
+    }
+    Ok(format!("Teleported {} to {x} {y}", parameters.get_player_name(player)?))
+}
+
+//this command changes a single block
+pub fn setblock_command(parameters: &mut CommandParameters) -> Result<String> {
+    let (x, y) = (parameters.get_integer("x")?, parameters.get_integer("y")?);
+    let block = parameters.get_block("block")?;
+    let mut blocks = parameters.blocks.get_blocks();
+    blocks.set_block(parameters.event_manager, x, y, block)?;
+    Ok(format!("Set the block at {x} {y} to {}", blocks.get_block_type(block)?.name))
+}
+
+// fill refuses bigger areas so a typo doesn't replace the whole world
+const MAX_FILL_BLOCKS: i64 = 100_000;
+
+//this command sets all blocks in a rectangle, both corners included
+pub fn fill_command(parameters: &mut CommandParameters) -> Result<String> {
+    let (x1, y1) = (parameters.get_integer("x1")?, parameters.get_integer("y1")?);
+    let (x2, y2) = (parameters.get_integer("x2")?, parameters.get_integer("y2")?);
+    let block = parameters.get_block("block")?;
+    let (min_x, max_x) = (i32::min(x1, x2), i32::max(x1, x2));
+    let (min_y, max_y) = (i32::min(y1, y2), i32::max(y1, y2));
+
+    let count = (i64::from(max_x) - i64::from(min_x) + 1) * (i64::from(max_y) - i64::from(min_y) + 1);
+    if count > MAX_FILL_BLOCKS {
+        bail!("cannot fill {count} blocks, the limit is {MAX_FILL_BLOCKS}");
+    }
+
+    let mut blocks = parameters.blocks.get_blocks();
+    // the corners are checked first so a fill outside the world doesn't change anything
+    blocks.get_block(min_x, min_y)?;
+    blocks.get_block(max_x, max_y)?;
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            blocks.set_block(parameters.event_manager, x, y, block)?;
+        }
+    }
+    Ok(format!("Filled {count} blocks with {}", blocks.get_block_type(block)?.name))
+}
+
+//this command sets the health of a player to the maximum
+pub fn heal_command(parameters: &mut CommandParameters) -> Result<String> {
+    let player = parameters.get_player_or_executor("player")?;
+    let name = parameters.get_player_name(player)?;
+    let id = parameters.entities.entities.get_id_from_entity(player)?;
+    let mut health = parameters.entities.entities.ecs.get::<&mut HealthComponent>(player)?;
+    if health.health() == 0 {
+        bail!("{name} is dead");
+    }
+    let max_health = health.max_health();
+    health.set_health(max_health, parameters.event_manager, id);
+    Ok(format!("Healed {name}"))
+}
+
+//this command kills a player, they die as if they were killed in any other way
+pub fn kill_command(parameters: &mut CommandParameters) -> Result<String> {
+    let player = parameters.get_player_or_executor("player")?;
+    let name = parameters.get_player_name(player)?;
+    let id = parameters.entities.entities.get_id_from_entity(player)?;
+    let mut health = parameters.entities.entities.ecs.get::<&mut HealthComponent>(player)?;
+    if health.health() == 0 {
+        bail!("{name} is already dead");
+    }
+    health.set_health(0, parameters.event_manager, id);
+    drop(health);
+    // whoever hurt the player before is not the reason they died
+    parameters.entities.entities.ecs.remove_one::<LastDamageComponent>(player).ok();
+    Ok(format!("Killed {name}"))
+}
+
+//this command removes all items from the inventory of a player
+pub fn clear_command(parameters: &mut CommandParameters) -> Result<String> {
+    let player = parameters.get_player_or_executor("player")?;
+    let name = parameters.get_player_name(player)?;
+    let mut inventory = parameters.entities.entities.ecs.get::<&mut Inventory>(player)?;
+    let count: i32 = inventory.iter().flatten().map(|item| item.count).sum();
+    for slot in 0..inventory.get_size() {
+        inventory.set_item(slot, None)?;
+    }
+    Ok(format!("Removed {count} items from the inventory of {name}"))
+}
+
+//this command shows who is online
+#[allow(clippy::unnecessary_wraps, reason = "all command functions must return the same type")]
+pub fn list_command(parameters: &mut CommandParameters) -> Result<String> {
+    let mut query = parameters.entities.entities.ecs.query::<&PlayerComponent>();
+    let mut names: Vec<&str> = query.iter().map(|(_, player)| player.get_name()).collect();
+    if names.is_empty() {
+        return Ok("No players are online".to_owned());
+    }
+    names.sort_unstable();
+    Ok(format!("{} players online: {}", names.len(), names.join(", ")))
+}
+
+//this command shows the seed the world was generated with
+#[allow(clippy::unnecessary_wraps, reason = "all command functions must return the same type")]
+pub fn seed_command(parameters: &mut CommandParameters) -> Result<String> {
+    Ok(format!("Seed: {}", parameters.seed))
+}
+
+//this command sends a message to everyone in the name of the executor
+pub fn say_command(parameters: &mut CommandParameters) -> Result<String> {
+    let text = parameters.get_string("message")?.to_owned();
+    let sender = parameters.executor.unwrap_or("Server");
+    parameters
+        .chat
+        .send_message(parameters.networking, ChatMessage::system(&format!("[{sender}] {text}")), SendTarget::All)?;
+    Ok(String::new())
+}
 
-    Ok(("Gave item to player").into())
+//this command sets, changes or shows the world time
+pub fn time_command(parameters: &mut CommandParameters) -> Result<String> {
+    let time = *parameters.world_time.get_time();
+
+    match parameters.get_string("action")? {
+        "set" => {
+            let value = parameters.get_string("time").ok().ok_or_else(|| anyhow!("no time specified"))?;
+            // named times are at the current day, numbers are the total time
+            let time_ms = match value {
+                "day" => time.get_day() * DAY_LENGTH_MS + DAY_LENGTH_MS / 10,
+                "noon" => time.get_day() * DAY_LENGTH_MS + DAY_LENGTH_MS / 4,
+                "night" => time.get_day() * DAY_LENGTH_MS + DAY_LENGTH_MS * 6 / 10,
+                "midnight" => time.get_day() * DAY_LENGTH_MS + DAY_LENGTH_MS * 3 / 4,
+                _ => value.parse::<i64>().ok().ok_or_else(|| anyhow!("invalid time {value}, use a number, day, noon, night or midnight"))?,
+            };
+            parameters.world_time.set_time_ms(time_ms);
+            Ok(format!("Set the time to {}", parameters.world_time.get_time().get_time_ms()))
+        }
+        "add" => {
+            let value = parameters.get_string("time").ok().ok_or_else(|| anyhow!("no time specified"))?;
+            let time_ms = value.parse::<i64>().ok().ok_or_else(|| anyhow!("time must be a whole number, got {value}"))?;
+            parameters.world_time.set_time_ms(time.get_time_ms() + time_ms);
+            Ok(format!("Set the time to {}", parameters.world_time.get_time().get_time_ms()))
+        }
+        _ => Ok(format!(
+            "The time is {} (day {}, {} ms into the day, {})",
+            time.get_time_ms(),
+            time.get_day(),
+            time.get_time_of_day_ms(),
+            if time.is_night() { "night" } else { "day" }
+        )),
+    }
+}
+
+//this command sets the point where new players and players without a bed spawn
+pub fn setworldspawn_command(parameters: &mut CommandParameters) -> Result<String> {
+    let (x, y) = if parameters.has_argument("position") {
+        parameters.get_coordinates("position")?
+    } else if let Some(executor) = parameters.executor {
+        let player = *parameters.players.get_player_entity_from_name(executor, &parameters.entities.entities)?;
+        let position = parameters.entities.entities.ecs.get::<&PositionComponent>(player)?;
+        (position.x(), position.y())
+    } else {
+        return Err(anyhow!("No position specified when the executor is a server console"));
+    };
+
+    parameters.players.spawn_points.set_world_spawn(x, y);
+    Ok(format!("Set the world spawn to {x} {y}"))
+}
+
+//this command sets or shows what happens to the items of players who die
+pub fn deathmode_command(parameters: &mut CommandParameters) -> Result<String> {
+    if parameters.has_argument("mode") {
+        let mode = parameters.get_string("mode")?.to_owned();
+        parameters.players.death_mode = DeathMode::from_name(&mode)?;
+        Ok(format!("Set the death mode to {mode}"))
+    } else {
+        Ok(format!("The death mode is {}", parameters.players.death_mode.get_name()))
+    }
+}
+
+//this command sends a private message to a player
+pub fn msg_command(parameters: &mut CommandParameters) -> Result<String> {
+    let receiver = parameters.get_string("player")?.to_owned();
+    let text = parameters.get_string("message")?.to_owned();
+
+    let sender = parameters.executor.unwrap_or("Server");
+    parameters.chat.whisper(parameters.networking, sender, &receiver, &text)?;
+    Ok(String::new())
+}
+
+//this command answers the last private message the player received
+pub fn reply_command(parameters: &mut CommandParameters) -> Result<String> {
+    let text = parameters.get_string("message")?.to_owned();
+
+    let sender = parameters.executor.unwrap_or("Server");
+    let receiver = parameters.chat.get_last_whisperer(sender).ok_or_else(|| anyhow!("nobody has sent you a message"))?.to_owned();
+    parameters.chat.whisper(parameters.networking, sender, &receiver, &text)?;
+    Ok(String::new())
+}
+
+//this function runs a command registered by a mod, the lua function gets the name of the executor,
+//which is nil for the server console, and the words after the command and returns the feedback
+pub fn mod_command(parameters: &mut CommandParameters) -> Result<String> {
+    let call_name = &parameters.command.call_name;
+    let mod_function = parameters.command.mod_function.as_ref().ok_or_else(|| anyhow!("command {call_name} was not registered by a mod"))?;
+    let arguments: Vec<String> = if parameters.has_argument("arguments") {
+        split_words(parameters.get_string("arguments")?).0.into_iter().map(|(_, word)| word).collect()
+    } else {
+        Vec::new()
+    };
+
+    let mod_manager = &mut parameters.mods.mod_manager;
+    let game_mod = mod_manager.get_mod(mod_function.mod_id).ok_or_else(|| anyhow!("the mod of command {call_name} is not loaded"))?;
+    if game_mod.is_disabled() {
+        bail!("command {call_name} can't be used, mod {} is disabled", game_mod.get_name());
+    }
+    // the mod manager counts and reports the errors of commands like all other errors of the mod
+    match mod_manager.call_registry_function::<_, Option<String>>(mod_function.mod_id, &mod_function.function, (parameters.executor.map(str::to_owned), arguments)) {
+        Ok(Some(feedback)) => Ok(feedback.unwrap_or_default()),
+        Ok(None) => Err(anyhow!("command {call_name} failed")),
+        // a strict mod manager stops the server on the first error of a mod
+        Err(error) => {
+            *parameters.state = ServerState::Stopping;
+            Err(anyhow!("command {call_name} failed: {error}"))
+        }
+    }
 }
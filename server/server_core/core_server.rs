@@ -1,137 +1,181 @@
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
-use std::sync::{Mutex, PoisonError};
+use std::sync::{Mutex, OnceLock, PoisonError};
 use std::thread::sleep;
 use std::time::Duration;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 
+use crate::build_project::compile_mod::{compile_mod, compress_mod, decompress_mod};
 use crate::libraries::events::EventManager;
-use crate::server::server_core::chat::server_chat_on_event;
+use crate::server::server_core::chat::ServerChat;
+use crate::server::server_core::combat::server_combat_on_event;
 use crate::server::server_core::entities::ServerEntities;
 use crate::server::server_core::items::ServerItems;
-use crate::server::server_core::networking::{DisconnectEvent, NewConnectionEvent};
+use crate::server::server_core::mobs::ServerMobs;
+use crate::server::server_core::networking::{DisconnectEvent, NewConnectionEvent, SendTarget};
 use crate::server::server_core::players::ServerPlayers;
 use crate::server::server_ui::{ConsoleMessageType, PlayerEventType, ServerState, UiMessageType};
+use crate::shared::chat::{ChatMessage, ChatMessagePacket};
+use crate::shared::combat::update_combat_ms;
+use crate::shared::mod_manager::{GameMod, ModsReloadPacket};
+use crate::shared::packet::Packet;
 
 use super::blocks::ServerBlocks;
-use super::commands::{Command, CommandManager};
+use super::clock::{Clock, SystemClock};
+use super::commands::{Argument, ArgumentType, Command, CommandManager};
+use super::dev_mod::DevModWatcher;
 use super::mod_manager::ServerModManager;
 use super::networking::ServerNetworking;
+use super::stats::ServerStats;
 use super::walls::ServerWalls;
 use super::world_generator::WorldGenerator;
+use super::world_time::ServerWorldTime;
 
 pub const SINGLEPLAYER_PORT: u16 = 49152;
 pub const MULTIPLAYER_PORT: u16 = 49153;
+/// version of the world file format, it changes when saved data can't be read the old way anymore
+pub const WORLD_VERSION: u32 = 1;
 
 pub struct Server {
     pub tps_limit: f32,
     pub state: ServerState,
-    events: EventManager,
-    networking: ServerNetworking,
-    mods: ServerModManager,
-    blocks: ServerBlocks,
-    walls: ServerWalls,
-    entities: ServerEntities,
-    items: ServerItems,
-    players: ServerPlayers,
+    // size of the world that is generated if there is no world to load
+    pub world_width: i32,
+    pub world_height: i32,
+    // seed of the generated world, loaded worlds keep the seed they were generated with
+    pub seed: u64,
+    // mod errors stop the server instead of being reported, so mod developers notice them right away
+    pub strict_mods: bool,
+    // directory of a mod that is being developed, it is compiled when the server
+    // starts and reloaded whenever one of its files changes
+    pub dev_mod_path: Option<PathBuf>,
+    pub(super) events: EventManager,
+    pub(super) networking: ServerNetworking,
+    pub(super) mods: ServerModManager,
+    pub(super) blocks: ServerBlocks,
+    pub(super) walls: ServerWalls,
+    pub(super) entities: ServerEntities,
+    pub(super) items: ServerItems,
+    pub(super) players: ServerPlayers,
+    pub(super) mobs: ServerMobs,
+    pub(super) world_time: ServerWorldTime,
+    pub(super) stats: ServerStats,
+    pub(super) chat: ServerChat,
     ui_event_receiver: Option<Receiver<UiMessageType>>,
     commands: CommandManager,
+    // the compressed mods the server was started with, the dev mod is the last one.
+    // Reloading the dev mod creates all mods from them again, so they all get fresh lua states
+    mods_serialized: Vec<Vec<u8>>,
+    dev_mod: Option<DevModWatcher>,
+    clock: Box<dyn Clock>,
+    // clock time of the last update, None before the first update
+    last_update_ms: Option<f64>,
+    // how much time was simulated in total
+    simulated_ms: f64,
+    // how much of the simulated time the 5 ms updates have covered
+    ms_counter: i64,
+    seconds_counter: i64,
 }
 
 impl Server {
     #[must_use]
     pub fn new(port: u16, ui_event_receiver: Option<Receiver<UiMessageType>>, ui_event_sender: Option<Sender<UiMessageType>>) -> Self {
         send_to_ui(UiMessageType::ServerState(ServerState::Nothing), ui_event_sender); //this is useless but sets the ui event sender
-        let blocks = ServerBlocks::new();
-        let walls = ServerWalls::new(&mut blocks.get_blocks());
-        let mut commands = CommandManager::new();
-        commands.add_command(Command {
-            call_name: "help".to_owned(),
-            name: "Help".to_owned(),
-            description: "Shows all commands".to_owned(),
-            function: super::commands::help_command,
-        });
-        commands.add_command(Command {
-            call_name: "stop".to_owned(),
-            name: "Stop".to_owned(),
-            description: "Stops the server".to_owned(),
-            function: super::commands::stop_command,
-        });
-        commands.add_command(Command {
-            call_name: "give".to_owned(),
-            name: "Give".to_owned(),
-            description: "Gives an item to a player".to_owned(),
-            function: super::commands::give_command,
-        });
+        let modules = ModModules::new(Vec::new());
         Self {
             tps_limit: 20.0,
             state: ServerState::Nothing,
+            world_width: 4400,
+            world_height: 1200,
+            seed: 423_657,
+            strict_mods: false,
+            dev_mod_path: None,
             events: EventManager::new(),
             networking: ServerNetworking::new(port),
-            mods: ServerModManager::new(Vec::new()),
-            blocks,
-            walls,
+            mods: modules.mods,
+            blocks: modules.blocks,
+            walls: modules.walls,
             entities: ServerEntities::new(),
-            items: ServerItems::new(),
+            items: modules.items,
             players: ServerPlayers::new(),
+            mobs: modules.mobs,
+            world_time: modules.world_time,
+            stats: modules.stats,
+            chat: ServerChat::new(),
             ui_event_receiver,
-            commands,
+            commands: modules.commands,
+            mods_serialized: Vec::new(),
+            dev_mod: None,
+            clock: Box::new(SystemClock::new()),
+            last_update_ms: None,
+            simulated_ms: 0.0,
+            ms_counter: 0,
+            seconds_counter: 0,
         }
     }
 
+    /// Returns the port the server listens on. It is only known after
+    /// the server was started, if the server was created with port 0.
+    #[must_use]
+    pub const fn get_port(&self) -> u16 {
+        self.networking.get_port()
+    }
+
     /// Starts the server - manual way. It only inits the server but doesn't run a loop
-    #[allow(clippy::too_many_lines)]
-    pub fn start(&mut self, status_text: &Mutex<String>, mods_serialized: Vec<Vec<u8>>, world_path: &Path) -> Result<()> {
+    #[allow(clippy::too_many_lines, reason = "it loads every part of the server in order")]
+    pub fn start(&mut self, status_text: &Mutex<String>, mut mods_serialized: Vec<Vec<u8>>, world_path: &Path) -> Result<()> {
         print_to_console("Starting server...", 0);
         let timer = std::time::Instant::now();
-        *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Starting server".to_owned();
+        "Starting server".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
         self.state = ServerState::Starting;
         send_to_ui(UiMessageType::ServerState(self.state), None);
 
-        let mut mods = Vec::new();
-        for game_mod in mods_serialized {
-            // decompress mod with snap
-            let game_mod = snap::raw::Decoder::new().decompress_vec(&game_mod)?;
-            mods.push(bincode::deserialize(&game_mod)?);
+        if let Some(dev_mod_path) = &self.dev_mod_path {
+            print_to_console(&format!("compiling dev mod {}", dev_mod_path.display()), 0);
+            // the watcher starts before compiling, so changes made meanwhile aren't missed
+            self.dev_mod = Some(DevModWatcher::new(dev_mod_path.clone())?);
+            mods_serialized.push(compress_mod(&compile_mod(dev_mod_path)?)?);
         }
-        self.mods = ServerModManager::new(mods);
+        self.mods = ServerModManager::new(mods_serialized.iter().map(Vec::as_slice).map(decompress_mod).collect::<Result<_>>()?);
+        self.mods.mod_manager.strict = self.strict_mods;
+        self.mods_serialized = mods_serialized;
 
         // init modules
-        self.networking.init();
-        self.blocks.init(&mut self.mods.mod_manager)?;
-        self.walls.init(&mut self.mods.mod_manager)?;
-        self.items.init(&mut self.mods.mod_manager)?;
-
-        let mut generator = WorldGenerator::new();
-        generator.init(&mut self.mods.mod_manager)?;
+        self.networking.init()?;
+        self.chat.init(world_path)?;
+        let generator = self.init_mod_modules()?;
 
         self.state = ServerState::InitMods;
         send_to_ui(UiMessageType::ServerState(self.state), None);
         print_to_console("initializing mods", 0);
-        *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Initializing mods".to_owned();
+        "Initializing mods".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
         self.mods.init()?;
+        self.mods.register_commands(&mut self.commands);
+        self.mods.report_errors(&mut self.networking)?;
 
         if world_path.exists() {
             self.state = ServerState::LoadingWorld;
             send_to_ui(UiMessageType::ServerState(self.state), None);
             print_to_console("loading world", 0);
-            *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Loading world".to_owned();
+            "Loading world".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
             self.load_world(world_path)?;
         } else {
             self.state = ServerState::GeneratingWorld;
             send_to_ui(UiMessageType::ServerState(self.state), None);
-            generator.generate(
+            self.mobs.biomes = generator.generate(
                 (&mut *self.blocks.get_blocks(), &mut self.walls.get_walls()),
                 &mut self.mods.mod_manager,
-                4400,
-                1200,
-                423_657,
+                self.world_width,
+                self.world_height,
+                self.seed,
                 status_text,
             )?;
         }
+        self.mobs.init_world(&self.blocks.get_blocks())?;
+        self.players.spawn_points.init_world(&self.blocks.get_blocks());
 
         self.state = ServerState::Running;
         send_to_ui(UiMessageType::ServerState(self.state), None);
@@ -169,34 +213,56 @@ impl Server {
         Ok(())
     }
 
-    ///Updates the server - manual way. It updates the server once and returns
+    ///Updates the server - manual way. It simulates the time that passed on the clock since the last update and returns
     pub fn update(&mut self) -> Result<()> {
-        //there's no point in outside functions knowing about the counters. Letting outside functions manage these variables could lead to bugs
-        static mut MS_COUNTER: i32 = 0;
-        static mut SECONDS_COUNTER: i32 = 0;
-        static mut MS_TIMER: Option<std::time::Instant> = None;
-        static mut LAST_TIME: Option<std::time::Instant> = None;
-
-        let Some((ms_timer, last_time)) =
-            //Safety: safe, we're only updating and using the count without any multithreading
-            (unsafe { get_timers_from_static(&mut MS_TIMER, &mut LAST_TIME) })
-        else {
-            return Ok(()); //we return early this time
+        let now = self.clock.elapsed_ms();
+        let Some(last_update_ms) = self.last_update_ms.replace(now) else {
+            return Ok(()); //the first update only starts measuring time
         };
 
-        let delta_time = last_time.elapsed().as_secs_f32() * 1000.0;
+        self.simulate((now - last_update_ms) as f32)
+    }
+
+    ///Steps the server - manual way. It simulates n ticks as long as they would be at the tps limit, no matter what the clock says
+    pub fn step(&mut self, n_ticks: u32) -> Result<()> {
+        for _ in 0..n_ticks {
+            self.simulate(1000.0 / self.tps_limit)?;
+        }
+        Ok(())
+    }
+
+    ///Sets the clock the server follows in updates
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.clock = Box::new(clock);
+        self.last_update_ms = None;
+    }
+
+    fn simulate(&mut self, delta_time: f32) -> Result<()> {
+        self.simulated_ms += delta_time as f64;
 
         // update modules
         self.networking.update(&mut self.events)?;
-        self.mods.update()?;
+        self.mods.update(delta_time)?;
         self.blocks.update(&mut self.events, delta_time)?;
         self.walls.update(delta_time, &mut self.events)?;
+        self.items.update(&mut self.blocks.get_blocks(), &mut self.entities.entities, &mut self.events, delta_time)?;
+        self.world_time.update(delta_time, &mut self.networking)?;
+        self.stats.update(&mut self.entities.entities, &mut self.events, delta_time)?;
 
         // handle events
         self.handle_events()?;
 
-        //Safety: safe, we're only updating and using the count without any multithreading
-        while unsafe { MS_COUNTER < ms_timer.elapsed().as_millis() as i32 } {
+        let time = *self.world_time.get_time();
+        self.mobs.update(
+            &mut self.entities.entities,
+            &self.blocks.get_blocks(),
+            &mut self.events,
+            &mut self.mods.mod_manager,
+            &mut self.networking,
+            time,
+        )?;
+
+        while self.ms_counter < self.simulated_ms as i64 {
             self.players.update(
                 &mut self.entities.entities,
                 &self.blocks.get_blocks(),
@@ -204,21 +270,22 @@ impl Server {
                 &mut self.items.get_items(),
                 &mut self.networking,
             )?;
+            ServerMobs::update_ms(&mut self.entities.entities, &self.blocks.get_blocks());
             self.entities.entities.update_entities_ms(&self.blocks.get_blocks(), &mut self.events)?;
-            //Safety: safe, we're only updating and using the count without any multithreading
-            unsafe {
-                MS_COUNTER += 5;
-            }
+            update_combat_ms(&mut self.entities.entities, &self.blocks.get_blocks(), &mut self.events)?;
+            self.ms_counter += 5;
         }
 
-        //Safety: safe, we're only updating and using the count without any multithreading
-        unsafe {
-            if SECONDS_COUNTER < MS_COUNTER / 1000 {
-                self.entities.sync_entities(&mut self.networking)?;
-                SECONDS_COUNTER = MS_COUNTER / 1000;
+        if self.seconds_counter < self.ms_counter / 1000 {
+            self.entities.sync_entities(&mut self.networking)?;
+            self.seconds_counter = self.ms_counter / 1000;
+            if self.dev_mod.as_mut().is_some_and(DevModWatcher::has_changed) {
+                self.reload_dev_mod()?;
             }
         }
 
+        self.mods.report_errors(&mut self.networking)?;
+
         Ok(())
     }
 
@@ -232,16 +299,17 @@ impl Server {
         self.networking.stop(&mut self.events)?;
         self.mods.stop()?;
         self.handle_events()?;
+        self.mods.report_errors(&mut self.networking)?;
 
         self.state = ServerState::Stopping;
         send_to_ui(UiMessageType::ServerState(self.state), None);
         print_to_console("saving world", 0);
-        *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Saving world".to_owned();
+        "Saving world".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
 
         self.save_world(world_path)?;
 
         print_to_console("stopping server", 0);
-        *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Stopping server".to_owned();
+        "Stopping server".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
 
         self.state = ServerState::Stopped;
         send_to_ui(UiMessageType::ServerState(self.state), None);
@@ -251,10 +319,41 @@ impl Server {
         Ok(())
     }
 
+    /// this function executes a command as if it was typed by the executor, None is the server console
+    pub fn execute_command(&mut self, command: &str, executor: Option<&str>) -> Result<String> {
+        self.commands.execute_command(
+            command,
+            &mut self.state,
+            executor,
+            &mut self.players,
+            &mut self.blocks,
+            &mut self.items,
+            &mut self.entities,
+            &mut self.world_time,
+            &mut self.chat,
+            &mut self.networking,
+            &mut self.events,
+            &mut self.mods,
+            self.seed,
+        )
+    }
+
     fn handle_events(&mut self) -> Result<()> {
         if let Some(receiver) = &self.ui_event_receiver {
-            self.commands
-                .execute_commands(receiver, &mut self.state, &mut self.players, &mut self.items, &mut self.entities, &mut self.events);
+            self.commands.execute_commands(
+                receiver,
+                &mut self.state,
+                &mut self.players,
+                &mut self.blocks,
+                &mut self.items,
+                &mut self.entities,
+                &mut self.world_time,
+                &mut self.chat,
+                &mut self.networking,
+                &mut self.events,
+                &mut self.mods,
+                self.seed,
+            );
         }
 
         while let Some(event) = self.events.pop_event() {
@@ -268,10 +367,22 @@ impl Server {
                 send_to_ui(event.clone(), None);
             }
 
-            self.commands
-                .on_event(&event, &mut self.state, &mut self.players, &mut self.items, &mut self.entities, &mut self.events, &mut self.networking)?;
+            self.commands.on_event(
+                &event,
+                &mut self.state,
+                &mut self.players,
+                &mut self.blocks,
+                &mut self.items,
+                &mut self.entities,
+                &mut self.world_time,
+                &mut self.chat,
+                &mut self.events,
+                &mut self.networking,
+                &mut self.mods,
+                self.seed,
+            )?;
 
-            self.mods.on_event(&event, &mut self.networking)?;
+            self.mods.on_event(&event, &mut self.networking, &self.entities.entities)?;
             self.blocks.on_event(
                 &event,
                 &mut self.events,
@@ -290,32 +401,202 @@ impl Server {
                 &mut self.networking,
                 &mut self.events,
                 &mut self.items.get_items(),
+                &self.mobs.get_mobs(),
             )?;
+            self.mobs.on_event(
+                &event,
+                &mut self.entities.entities,
+                &self.blocks.get_blocks(),
+                &mut self.items.get_items(),
+                &mut self.events,
+                &mut self.networking,
+            )?;
+            server_combat_on_event(&event, &mut self.entities.entities, &self.players, &self.items.get_items(), &mut self.events, &mut self.networking)?;
+            self.world_time.on_event(&event, &mut self.networking)?;
             ServerEntities::on_event(&event, &mut self.networking)?;
             self.networking.on_event(&event, &mut self.events)?;
-            server_chat_on_event(&event, &mut self.networking)?;
+            self.chat.on_event(&event, &mut self.networking, &mut self.mods.mod_manager)?;
+        }
+
+        Ok(())
+    }
+
+    /// this function initializes the modules mods add content to, the world generator is
+    /// returned because it is only needed when there is no world to load
+    fn init_mod_modules(&mut self) -> Result<WorldGenerator> {
+        self.blocks.init(&mut self.mods.mod_manager)?;
+        self.walls.init(&mut self.mods.mod_manager)?;
+        self.items.init(self.blocks.get_shared_blocks(), &mut self.mods.mod_manager)?;
+        self.mobs.init(&mut self.mods.mod_manager)?;
+        self.world_time.init(&mut self.mods.mod_manager)?;
+        self.stats.init(&mut self.mods.mod_manager)?;
+
+        let generator = WorldGenerator::new();
+        generator.init(&mut self.mods.mod_manager)?;
+        Ok(generator)
+    }
+
+    /// this function compiles the dev mod again and restarts all mods in fresh lua states.
+    /// If the mod doesn't compile, fails to initialize or would change the ids of anything
+    /// the world already uses, the reload is rejected and the server keeps the old mods.
+    /// Clients are told to join again, so they get the new mods and resources.
+    pub(super) fn reload_dev_mod(&mut self) -> Result<()> {
+        let Some(dev_mod_path) = self.dev_mod.as_ref().map(|dev_mod| dev_mod.get_path().to_owned()) else {
+            bail!("the server has no dev mod");
+        };
+        print_to_console("reloading dev mod", 0);
+
+        let result = compile_mod(&dev_mod_path).and_then(|game_mod| self.reload_mods(compress_mod(&game_mod)?));
+        if let Err(error) = result {
+            let message = format!("dev mod reload was rejected: {error}");
+            print_to_console(&message, 2);
+            self.networking.send_packet(
+                &Packet::new(ChatMessagePacket {
+                    message: ChatMessage::error(&message),
+                })?,
+                SendTarget::All,
+            )?;
+            return Ok(());
+        }
+
+        self.networking.send_packet(&Packet::new(ModsReloadPacket)?, SendTarget::All)?;
+        print_to_console("dev mod was reloaded", 0);
+        Ok(())
+    }
+
+    /// this function replaces the dev mod and restarts all mods with new mod modules,
+    /// the old ones are put back if the new mods can't be used with the world
+    fn reload_mods(&mut self, dev_mod: Vec<u8>) -> Result<()> {
+        let mut mods_serialized = self.mods_serialized.clone();
+        mods_serialized.pop();
+        mods_serialized.push(dev_mod);
+
+        let old_names = self.get_registered_names()?;
+        let mut modules = ModModules::new(mods_serialized.iter().map(Vec::as_slice).map(decompress_mod).collect::<Result<_>>()?);
+        modules.mods.mod_manager.strict = self.strict_mods;
+        let mut old_modules = self.swap_mod_modules(modules);
+
+        if let Err(error) = self.init_reloaded_mods(&old_modules, &old_names) {
+            self.swap_mod_modules(old_modules);
+            return Err(error);
         }
 
+        self.mods_serialized = mods_serialized;
+        // the new mods are already running, so the old ones failing to stop doesn't matter anymore
+        if let Err(error) = old_modules.mods.stop() {
+            print_to_console(&format!("old mods failed to stop: {error}"), 2);
+        }
         Ok(())
     }
 
+    /// this function initializes the reloaded mods, checks that everything that was registered
+    /// before still has the same id and moves the world over from the old modules
+    fn init_reloaded_mods(&mut self, old_modules: &ModModules, old_names: &[(&str, Vec<String>)]) -> Result<()> {
+        self.init_mod_modules()?;
+        self.mods.init()?;
+        self.mods.register_commands(&mut self.commands);
+        // a mod that failed to initialize would be missing things the world needs
+        if let Some(error) = self.mods.mod_manager.take_errors().first() {
+            bail!("{}", error.message);
+        }
+
+        let new_names = self.get_registered_names()?;
+        for ((kind, old_names), (_, new_names)) in old_names.iter().zip(&new_names) {
+            for (index, old_name) in old_names.iter().enumerate() {
+                if new_names.get(index) != Some(old_name) {
+                    bail!("{kind} {old_name} was removed or got a different id, reloading would break the world");
+                }
+            }
+        }
+
+        self.blocks.get_blocks().deserialize(&old_modules.blocks.get_blocks().serialize()?)?;
+        self.walls.get_walls().deserialize(&old_modules.walls.get_walls().serialize()?)?;
+        self.world_time.deserialize(&old_modules.world_time.serialize()?)?;
+        self.mobs.biomes.clone_from(&old_modules.mobs.biomes);
+        self.mobs.init_world(&self.blocks.get_blocks())
+    }
+
+    /// this function returns the names of the things mods have registered in the order of their ids
+    fn get_registered_names(&self) -> Result<Vec<(&'static str, Vec<String>)>> {
+        let blocks = self.blocks.get_blocks();
+        let block_names = blocks.get_all_block_ids().into_iter().map(|id| Ok(blocks.get_block_type(id)?.name)).collect::<Result<_>>()?;
+        let mut walls = self.walls.get_walls();
+        let wall_names = walls.get_all_wall_ids().into_iter().map(|id| Ok(walls.get_wall_type(id)?.name)).collect::<Result<_>>()?;
+        let items = self.items.get_items();
+        let item_names = items.get_all_item_type_ids().into_iter().map(|id| Ok(items.get_item_type(id)?.name)).collect::<Result<_>>()?;
+        let mobs = self.mobs.get_mobs();
+        let mob_names = mobs.get_all_mob_type_ids().into_iter().map(|id| Ok(mobs.get_mob_type(id)?.name.clone())).collect::<Result<_>>()?;
+        let stats = self.stats.get_stats();
+        let stat_names = stats.get_all_stat_ids().into_iter().map(|id| Ok(stats.get_stat_type(id)?.name.clone())).collect::<Result<_>>()?;
+        Ok(vec![("block", block_names), ("wall", wall_names), ("item", item_names), ("mob", mob_names), ("stat", stat_names)])
+    }
+
+    /// this function puts the mod modules into the server and returns the ones it had before
+    const fn swap_mod_modules(&mut self, mut modules: ModModules) -> ModModules {
+        std::mem::swap(&mut self.mods, &mut modules.mods);
+        std::mem::swap(&mut self.blocks, &mut modules.blocks);
+        std::mem::swap(&mut self.walls, &mut modules.walls);
+        std::mem::swap(&mut self.items, &mut modules.items);
+        std::mem::swap(&mut self.mobs, &mut modules.mobs);
+        std::mem::swap(&mut self.world_time, &mut modules.world_time);
+        std::mem::swap(&mut self.stats, &mut modules.stats);
+        std::mem::swap(&mut self.commands, &mut modules.commands);
+        modules
+    }
+
     fn load_world(&mut self, world_path: &Path) -> Result<()> {
         // load world file into Vec<u8>
         let world_file = std::fs::read(world_path)?;
         // decode world file as HashMap<String, Vec<u8>>
         let world: HashMap<String, Vec<u8>> = bincode::deserialize(&world_file)?;
 
-        self.blocks.get_blocks().deserialize(world.get("blocks").unwrap_or(&Vec::new()))?;
+        // worlds saved before the version was saved have item stacks without metadata and players without stats
+        let version: u32 = world.get("version").map(|version| bincode::deserialize(version)).transpose()?.unwrap_or(0);
+        if version > WORLD_VERSION {
+            bail!("world was saved by a newer version of the game");
+        }
+        if version == 0 {
+            self.blocks.get_blocks().deserialize_legacy(world.get("blocks").unwrap_or(&Vec::new()))?;
+            self.players.deserialize_legacy(world.get("players").unwrap_or(&Vec::new()))?;
+        } else {
+            self.blocks.get_blocks().deserialize(world.get("blocks").unwrap_or(&Vec::new()))?;
+            self.players.deserialize(world.get("players").unwrap_or(&Vec::new()), &self.stats.get_stats())?;
+        }
         self.walls.get_walls().deserialize(world.get("walls").unwrap_or(&Vec::new()))?;
-        self.players.deserialize(world.get("players").unwrap_or(&Vec::new()))?;
+        // worlds saved before mobs existed don't have biomes, mobs there only spawn with rules for all biomes
+        if let Some(biomes) = world.get("biomes") {
+            self.mobs.biomes = bincode::deserialize(biomes)?;
+        }
+        // worlds saved before the day and night cycle existed start in the morning
+        if let Some(time) = world.get("time") {
+            self.world_time.deserialize(time)?;
+        }
+        // worlds saved before the seed was saved were all generated with the same seed
+        if let Some(seed) = world.get("seed") {
+            self.seed = bincode::deserialize(seed)?;
+        }
+        // worlds saved before death modes existed drop the items of dead players
+        if let Some(death_mode) = world.get("death_mode") {
+            self.players.death_mode = bincode::deserialize(death_mode)?;
+        }
+        // worlds saved before spawn points existed choose their world spawn when they are loaded
+        if let Some(spawn_points) = world.get("spawn_points") {
+            self.players.spawn_points.deserialize(spawn_points)?;
+        }
         Ok(())
     }
 
     fn save_world(&self, world_path: &Path) -> Result<()> {
         let mut world = HashMap::new();
+        world.insert("version".to_owned(), bincode::serialize(&WORLD_VERSION)?);
         world.insert("blocks".to_owned(), self.blocks.get_blocks().serialize()?);
         world.insert("walls".to_owned(), self.walls.get_walls().serialize()?);
-        world.insert("players".to_owned(), self.players.serialize()?);
+        world.insert("players".to_owned(), self.players.serialize(&self.stats.get_stats())?);
+        world.insert("biomes".to_owned(), bincode::serialize(&self.mobs.biomes)?);
+        world.insert("time".to_owned(), self.world_time.serialize()?);
+        world.insert("spawn_points".to_owned(), self.players.spawn_points.serialize()?);
+        world.insert("death_mode".to_owned(), bincode::serialize(&self.players.death_mode)?);
+        world.insert("seed".to_owned(), bincode::serialize(&self.seed)?);
 
         let world_file = bincode::serialize(&world)?;
         if !world_path.exists() {
@@ -326,43 +607,193 @@ impl Server {
     }
 }
 
-unsafe fn get_timers_from_static(ms_timer_static: &mut Option<std::time::Instant>, last_time_static: &mut Option<std::time::Instant>) -> Option<(std::time::Instant, std::time::Instant)> {
-    let ms_timer = if let Some(timer) = ms_timer_static {
-        *timer
-    } else {
-        *ms_timer_static = Some(std::time::Instant::now());
-        *last_time_static = *ms_timer_static;
-        return None; //we skip this time
-    };
-
-    let last_time = if let Some(instant) = last_time_static {
-        *instant
-    } else {
-        *last_time_static = Some(std::time::Instant::now());
-        return None; //we skip this time
-    };
+/// Mod modules are the modules mods add content to, they
+/// are created again when the dev mod is reloaded.
+struct ModModules {
+    mods: ServerModManager,
+    blocks: ServerBlocks,
+    walls: ServerWalls,
+    items: ServerItems,
+    mobs: ServerMobs,
+    world_time: ServerWorldTime,
+    stats: ServerStats,
+    commands: CommandManager,
+}
 
-    *last_time_static = Some(std::time::Instant::now());
+impl ModModules {
+    fn new(mods: Vec<GameMod>) -> Self {
+        let blocks = ServerBlocks::new();
+        let walls = ServerWalls::new(&mut blocks.get_blocks());
+        let mut commands = CommandManager::new();
+        register_commands(&mut commands);
+        Self {
+            mods: ServerModManager::new(mods),
+            blocks,
+            walls,
+            items: ServerItems::new(),
+            mobs: ServerMobs::new(),
+            world_time: ServerWorldTime::new(),
+            stats: ServerStats::new(),
+            commands,
+        }
+    }
+}
 
-    Some((ms_timer, last_time))
+/// this function adds the commands that are built into the server
+#[allow(clippy::too_many_lines, reason = "every command takes a few lines to declare")]
+fn register_commands(commands: &mut CommandManager) {
+    commands.add_command(Command {
+        call_name: "help".to_owned(),
+        description: "Shows all commands or how to use one of them".to_owned(),
+        arguments: vec![Argument::optional("command", ArgumentType::Command)],
+        function: super::commands::help_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "stop".to_owned(),
+        description: "Stops the server".to_owned(),
+        arguments: Vec::new(),
+        function: super::commands::stop_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "give".to_owned(),
+        description: "Gives items to a player".to_owned(),
+        arguments: vec![
+            Argument::required("item", ArgumentType::Item),
+            Argument::optional("player", ArgumentType::Player),
+            Argument::optional("count", ArgumentType::Integer),
+        ],
+        function: super::commands::give_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "time".to_owned(),
+        description: "Sets, adds to or shows the world time, the time is in ms or day, noon, night or midnight".to_owned(),
+        arguments: vec![
+            Argument::required("action", ArgumentType::Choice(&["set", "add", "query"])),
+            Argument::optional("time", ArgumentType::Word),
+        ],
+        function: super::commands::time_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "setworldspawn".to_owned(),
+        description: "Sets the world spawn to the given position or to where the player is".to_owned(),
+        arguments: vec![Argument::optional("position", ArgumentType::Coordinates)],
+        function: super::commands::setworldspawn_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "deathmode".to_owned(),
+        description: "Sets or shows what happens to the items of players who die".to_owned(),
+        arguments: vec![Argument::optional("mode", ArgumentType::Choice(&["keep", "drop", "grave"]))],
+        function: super::commands::deathmode_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "msg".to_owned(),
+        description: "Sends a private message to a player".to_owned(),
+        arguments: vec![Argument::required("player", ArgumentType::Player), Argument::required("message", ArgumentType::Text)],
+        function: super::commands::msg_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "reply".to_owned(),
+        description: "Answers the last private message".to_owned(),
+        arguments: vec![Argument::required("message", ArgumentType::Text)],
+        function: super::commands::reply_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "tp".to_owned(),
+        description: "Moves a player to a position or to another player".to_owned(),
+        arguments: vec![Argument::required("player", ArgumentType::Player), Argument::required("target", ArgumentType::Destination)],
+        function: super::commands::tp_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "setblock".to_owned(),
+        description: "Changes the block at the position".to_owned(),
+        arguments: vec![
+            Argument::required("x", ArgumentType::Integer),
+            Argument::required("y", ArgumentType::Integer),
+            Argument::required("block", ArgumentType::Block),
+        ],
+        function: super::commands::setblock_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "fill".to_owned(),
+        description: "Changes all blocks between the two corners".to_owned(),
+        arguments: vec![
+            Argument::required("x1", ArgumentType::Integer),
+            Argument::required("y1", ArgumentType::Integer),
+            Argument::required("x2", ArgumentType::Integer),
+            Argument::required("y2", ArgumentType::Integer),
+            Argument::required("block", ArgumentType::Block),
+        ],
+        function: super::commands::fill_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "heal".to_owned(),
+        description: "Gives a player full health".to_owned(),
+        arguments: vec![Argument::optional("player", ArgumentType::Player)],
+        function: super::commands::heal_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "kill".to_owned(),
+        description: "Kills a player".to_owned(),
+        arguments: vec![Argument::optional("player", ArgumentType::Player)],
+        function: super::commands::kill_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "clear".to_owned(),
+        description: "Removes all items from the inventory of a player".to_owned(),
+        arguments: vec![Argument::optional("player", ArgumentType::Player)],
+        function: super::commands::clear_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "list".to_owned(),
+        description: "Shows the players who are online".to_owned(),
+        arguments: Vec::new(),
+        function: super::commands::list_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "seed".to_owned(),
+        description: "Shows the seed of the world".to_owned(),
+        arguments: Vec::new(),
+        function: super::commands::seed_command,
+        mod_function: None,
+    });
+    commands.add_command(Command {
+        call_name: "say".to_owned(),
+        description: "Sends a message to everyone".to_owned(),
+        arguments: vec![Argument::required("message", ArgumentType::Text)],
+        function: super::commands::say_command,
+        mod_function: None,
+    });
 }
 
 //sends any data to the ui if the server was started without nogui flag
 pub fn send_to_ui(data: UiMessageType, ui_event_sender: Option<Sender<UiMessageType>>) {
-    static mut UI_EVENT_SENDER: Option<Sender<UiMessageType>> = None;
+    static UI_EVENT_SENDER: OnceLock<Sender<UiMessageType>> = OnceLock::new();
 
-    //Safety: safe becuase the server is single threaded
-    unsafe {
-        if UI_EVENT_SENDER.is_none() {
-            UI_EVENT_SENDER = ui_event_sender;
-        }
+    // the first sender is kept, later ones are ignored
+    if let Some(ui_event_sender) = ui_event_sender {
+        UI_EVENT_SENDER.set(ui_event_sender).ok();
+    }
 
-        if let Some(sender) = &UI_EVENT_SENDER {
-            let result = sender.send(data);
+    if let Some(sender) = UI_EVENT_SENDER.get() {
+        let result = sender.send(data);
 
-            if let Err(_e) = result {
-                println!("error sending data to ui");
-            }
+        if let Err(_e) = result {
+            println!("error sending data to ui");
         }
     }
 }
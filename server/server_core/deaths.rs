@@ -0,0 +1,125 @@
+use anyhow::{anyhow, Result};
+use hecs::Entity;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::EventManager;
+use crate::shared::blocks::Blocks;
+use crate::shared::combat::{DamageCause, LastDamageComponent};
+use crate::shared::entities::Entities;
+use crate::shared::items::{ItemStack, Items};
+use crate::shared::mobs::{MobComponent, Mobs};
+use crate::shared::players::{PlayerComponent, PLAYER_HEIGHT, PLAYER_WIDTH};
+
+// how many blocks away from the place of death a grave can be placed
+const GRAVE_SEARCH_RADIUS: i32 = 5;
+
+/// Death mode decides what happens to the inventory of a player who died.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathMode {
+    // the player keeps all items
+    KeepInventory,
+    // every stack is dropped as an item entity
+    DropItems,
+    // the items are stored in a grave block, which is the block named "grave" registered by a mod
+    Grave,
+}
+
+impl DeathMode {
+    /// this function parses the name used by the deathmode command
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "keep" => Ok(Self::KeepInventory),
+            "drop" => Ok(Self::DropItems),
+            "grave" => Ok(Self::Grave),
+            _ => Err(anyhow!("invalid death mode {name}, use keep, drop or grave")),
+        }
+    }
+
+    #[must_use]
+    pub const fn get_name(self) -> &'static str {
+        match self {
+            Self::KeepInventory => "keep",
+            Self::DropItems => "drop",
+            Self::Grave => "grave",
+        }
+    }
+}
+
+/// this function returns the name of a player or the mob type name of a mob
+fn get_entity_name(entities: &Entities, mobs: &Mobs, entity: Entity) -> Option<String> {
+    if let Ok(player) = entities.ecs.get::<&PlayerComponent>(entity) {
+        return Some(player.get_name().to_owned());
+    }
+    let mob = entities.ecs.get::<&MobComponent>(entity).ok()?;
+    Some(mobs.get_mob_type(mob.get_mob_type()).ok()?.name.clone())
+}
+
+/// this function describes how the player died, using what hurt them the last time
+pub fn get_death_message(entities: &Entities, mobs: &Mobs, player: Entity, name: &str) -> String {
+    let cause = entities.ecs.get::<&LastDamageComponent>(player).ok().map(|last_damage| last_damage.cause);
+    match cause {
+        Some(DamageCause::Entity(killer)) => get_entity_name(entities, mobs, killer).map_or_else(|| format!("{name} was slain"), |killer| format!("{name} was slain by {killer}")),
+        Some(DamageCause::Projectile(owner)) => owner
+            .and_then(|owner| get_entity_name(entities, mobs, owner))
+            .map_or_else(|| format!("{name} was shot"), |owner| format!("{name} was shot by {owner}")),
+        None => format!("{name} died"),
+    }
+}
+
+/// this function drops every stack as a single item entity
+pub fn drop_stacks(stacks: &[ItemStack], items: &mut Items, entities: &mut Entities, events: &mut EventManager, x: f32, y: f32) -> Result<()> {
+    for stack in stacks {
+        items.drop_item_stack(events, entities, stack, x, y)?;
+    }
+    Ok(())
+}
+
+/// this function checks if the grave can be placed with its bottom left corner at the coordinates
+fn can_place_grave(blocks: &Blocks, x: i32, y: i32, width: i32, height: i32) -> bool {
+    for block_x in x..x + width {
+        for block_y in y - height + 1..=y {
+            if blocks.get_block(block_x, block_y).ok() != Some(blocks.air()) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// this function places a grave near the player's position and stores the stacks in it
+///
+/// It returns the stacks that didn't fit into the grave, which are all of them
+/// if no mod registered a grave block or there is no room for it.
+pub fn place_grave(stacks: Vec<ItemStack>, blocks: &mut Blocks, events: &mut EventManager, x: f32, y: f32) -> Result<Vec<ItemStack>> {
+    let Ok(grave) = blocks.get_block_id_by_name("grave") else {
+        return Ok(stacks);
+    };
+    let grave_type = blocks.get_block_type(grave)?;
+
+    // the grave stands where the feet of the player were, or as close to them as possible
+    let feet_x = (x + PLAYER_WIDTH / 2.0).floor() as i32;
+    let feet_y = (y + PLAYER_HEIGHT).ceil() as i32 - 1;
+    let mut spot = None;
+    'search: for distance_y in 0..=GRAVE_SEARCH_RADIUS {
+        for distance_x in 0..=GRAVE_SEARCH_RADIUS {
+            for grave_x in [feet_x + distance_x, feet_x - distance_x] {
+                if can_place_grave(blocks, grave_x, feet_y - distance_y, grave_type.width, grave_type.height) {
+                    spot = Some((grave_x, feet_y - distance_y - grave_type.height + 1));
+                    break 'search;
+                }
+            }
+        }
+    }
+    let Some((grave_x, grave_y)) = spot else {
+        return Ok(stacks);
+    };
+
+    blocks.set_block(events, grave_x, grave_y, grave)?;
+    let size = blocks.get_block_inventory_size(grave_x, grave_y)? as usize;
+    let mut stacks = stacks.into_iter();
+    let inventory: Vec<Option<ItemStack>> = (0..size).map(|_| stacks.next()).collect();
+    if size > 0 {
+        blocks.set_block_inventory_data(grave_x, grave_y, inventory, events)?;
+    }
+    Ok(stacks.collect())
+}
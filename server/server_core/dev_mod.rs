@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+/// Dev mod watcher keeps an eye on the source directory of a mod that is being
+/// developed, so the server can compile and reload it when it is changed.
+pub struct DevModWatcher {
+    path: PathBuf,
+    last_modified: SystemTime,
+}
+
+impl DevModWatcher {
+    pub fn new(path: PathBuf) -> Result<Self> {
+        let last_modified = get_last_modified(&path)?;
+        Ok(Self { path, last_modified })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// this function checks if any file in the directory was changed since the last check
+    pub fn has_changed(&mut self) -> bool {
+        // files can be missing for a moment while an editor saves them, they are checked again later
+        let Ok(last_modified) = get_last_modified(&self.path) else {
+            return false;
+        };
+        if last_modified == self.last_modified {
+            return false;
+        }
+        self.last_modified = last_modified;
+        true
+    }
+}
+
+/// this function returns when the directory or anything in it was last modified
+fn get_last_modified(path: &Path) -> Result<SystemTime> {
+    let mut last_modified = std::fs::metadata(path)?.modified()?;
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            last_modified = last_modified.max(get_last_modified(&entry?.path())?);
+        }
+    }
+    Ok(last_modified)
+}
@@ -14,7 +14,7 @@ impl ServerEntities {
         Self { entities: Entities::new() }
     }
 
-    pub fn sync_entities(&mut self, networking: &mut ServerNetworking) -> Result<()> {
+    pub fn sync_entities(&self, networking: &mut ServerNetworking) -> Result<()> {
         for (entity, (position, physics)) in &mut self.entities.ecs.query::<(&PositionComponent, &PhysicsComponent)>() {
             let id = self.entities.get_id_from_entity(entity)?;
             networking.send_packet(
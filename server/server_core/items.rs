@@ -1,63 +1,119 @@
 use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use anyhow::Result;
+use hecs::Entity;
 
 use crate::libraries::events::{Event, EventManager};
-use crate::server::server_core::networking::{SendTarget, ServerNetworking};
-use crate::shared::blocks::BlockBreakEvent;
+use crate::server::server_core::networking::{NewConnectionWelcomedEvent, SendTarget, ServerNetworking};
+use crate::shared::blocks::{BlockBreakEvent, Blocks};
 use crate::shared::entities::{Entities, PhysicsComponent, PositionComponent};
-use crate::shared::items::{init_items_mod_interface, ItemComponent, ItemSpawnEvent, ItemSpawnPacket, Items};
+use crate::shared::items::{
+    init_item_metadata_mod_interface, init_items_mod_interface, update_item_timers, ItemComponent, ItemCountChangeEvent, ItemCountChangePacket, ItemSpawnEvent, ItemSpawnPacket, ItemStack, Items,
+    PlayerInventories,
+};
 use crate::shared::mod_manager::ModManager;
 use crate::shared::packet::Packet;
+use crate::shared::walls::WallBreakEvent;
 
 pub struct ServerItems {
     items: Arc<Mutex<Items>>,
+    player_inventories: Arc<Mutex<PlayerInventories>>,
 }
 
 impl ServerItems {
     pub fn new() -> Self {
         Self {
             items: Arc::new(Mutex::new(Items::new())),
+            player_inventories: Arc::new(Mutex::new(PlayerInventories::new())),
         }
     }
 
-    pub fn init(&mut self, mods: &mut ModManager) -> Result<()> {
-        init_items_mod_interface(&self.items, mods)
+    pub fn init(&self, blocks: &Arc<Mutex<Blocks>>, mods: &mut ModManager) -> Result<()> {
+        init_items_mod_interface(&self.items, blocks, mods)?;
+        init_item_metadata_mod_interface(&self.player_inventories, mods)
     }
 
-    pub fn on_event(&mut self, event: &Event, entities: &mut Entities, events: &mut EventManager, networking: &mut ServerNetworking) -> Result<()> {
+    pub fn on_event(&self, event: &Event, entities: &mut Entities, events: &mut EventManager, networking: &mut ServerNetworking) -> Result<()> {
         if let Some(event) = event.downcast::<BlockBreakEvent>() {
-            let broken_block = event.prev_block_id;
-            let drop = self.get_items().get_block_drop(broken_block);
-            if let Ok(drop) = drop {
-                // spawn item at random chance. drop.chance is a float between 0 and 1
-                if rand::random::<f32>() <= drop.chance {
-                    self.get_items().drop_item(events, entities, drop.item, event.x as f32, event.y as f32)?;
-                }
+            let drops = self
+                .get_items()
+                .get_block_drop(event.prev_block_id)
+                .map(|loot_table| loot_table.roll(&mut rand::thread_rng(), event.tool, event.tool_power));
+            self.drop_items(drops.unwrap_or_default(), event.x, event.y, entities, events)?;
+            // items stored in the block, like in a chest or a grave, drop as whole stacks
+            for stack in event.inventory.iter().flatten() {
+                self.get_items().drop_item_stack(events, entities, stack, event.x as f32, event.y as f32)?;
             }
         }
+        if let Some(event) = event.downcast::<WallBreakEvent>() {
+            let drops = self
+                .get_items()
+                .get_wall_drop(event.prev_wall_id)
+                .map(|loot_table| loot_table.roll(&mut rand::thread_rng(), event.tool, event.tool_power));
+            self.drop_items(drops.unwrap_or_default(), event.x, event.y, entities, events)?;
+        }
         if let Some(event) = event.downcast::<ItemSpawnEvent>() {
-            let entity = event.entity;
-            let physics = entities.ecs.get::<&mut PhysicsComponent>(entity)?;
-            let item = entities.ecs.get::<&ItemComponent>(entity)?;
-            let id = entities.get_id_from_entity(entity)?;
-            let position = entities.ecs.get::<&PositionComponent>(entity)?;
-
-            let packet = ItemSpawnPacket {
-                item_type: item.get_item_type(),
-                id,
-                x: position.x(),
-                y: position.y(),
-                velocity_x: physics.velocity_x,
-                velocity_y: physics.velocity_y,
-            };
-
-            networking.send_packet(&Packet::new(packet)?, SendTarget::All)?;
+            // the item could have already merged with another one
+            if entities.ecs.contains(event.entity) {
+                networking.send_packet(&Self::get_spawn_packet(entities, event.entity)?, SendTarget::All)?;
+            }
+        }
+        if let Some(event) = event.downcast::<ItemCountChangeEvent>() {
+            if let Ok(item) = entities.ecs.get::<&ItemComponent>(event.entity) {
+                let packet = Packet::new(ItemCountChangePacket {
+                    id: entities.get_id_from_entity(event.entity)?,
+                    count: item.get_count(),
+                })?;
+                networking.send_packet(&packet, SendTarget::All)?;
+            }
+        }
+        if let Some(event) = event.downcast::<NewConnectionWelcomedEvent>() {
+            let mut item_entities = Vec::new();
+            for (entity, _item) in &mut entities.ecs.query::<&ItemComponent>() {
+                item_entities.push(entity);
+            }
+            for entity in item_entities {
+                networking.send_packet(&Self::get_spawn_packet(entities, entity)?, SendTarget::Connection(event.conn.clone()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_spawn_packet(entities: &Entities, entity: Entity) -> Result<Packet> {
+        let physics = entities.ecs.get::<&PhysicsComponent>(entity)?;
+        let item = entities.ecs.get::<&ItemComponent>(entity)?;
+        let position = entities.ecs.get::<&PositionComponent>(entity)?;
+
+        Packet::new(ItemSpawnPacket {
+            item_type: item.get_item_type(),
+            count: item.get_count(),
+            pickup_delay_ms: item.get_pickup_delay_ms(),
+            id: entities.get_id_from_entity(entity)?,
+            x: position.x(),
+            y: position.y(),
+            velocity_x: physics.velocity_x,
+            velocity_y: physics.velocity_y,
+        })
+    }
+
+    /// spawns the dropped items of a broken tile at its position
+    fn drop_items(&self, drops: Vec<ItemStack>, x: i32, y: i32, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        for drop in drops {
+            self.get_items().drop_item_stack(events, entities, &drop, x as f32, y as f32)?;
         }
         Ok(())
     }
 
-    pub fn get_items(&self) -> MutexGuard<Items> {
+    /// ticks all processing blocks, such as furnaces, and the item entities,
+    /// and lets mods see the inventories of the players
+    pub fn update(&self, blocks: &mut Blocks, entities: &mut Entities, events: &mut EventManager, frame_length: f32) -> Result<()> {
+        self.player_inventories.lock().unwrap_or_else(PoisonError::into_inner).update(entities)?;
+        update_item_timers(entities, frame_length as i32);
+        self.get_items().update_item_entities(entities, events)?;
+        self.get_items().update_processing_blocks(blocks, events, frame_length)
+    }
+
+    pub fn get_items(&self) -> MutexGuard<'_, Items> {
         self.items.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
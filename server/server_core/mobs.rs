@@ -0,0 +1,280 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+use hecs::Entity;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::libraries::events::{Event, EventManager};
+use crate::server::server_core::networking::{NewConnectionWelcomedEvent, SendTarget, ServerNetworking};
+use crate::shared::blocks::Blocks;
+use crate::shared::combat::{damage_entity, overlaps, DamageCause};
+use crate::shared::entities::{collides_with_blocks, Entities, EntityPositionVelocityPacket, HealthChangeEvent, HealthComponent, PhysicsComponent, PositionComponent};
+use crate::shared::items::Items;
+use crate::shared::lights::Lights;
+use crate::shared::mobs::{get_nearest_player, init_mobs_mod_interface, update_ai, update_ai_ms, AiComponent, MobComponent, MobId, MobSpawnEvent, MobSpawnPacket, MobType, Mobs};
+use crate::shared::mod_manager::ModManager;
+use crate::shared::packet::Packet;
+use crate::shared::players::PlayerComponent;
+use crate::shared::world_time::WorldTime;
+
+// the chance that the server tries to spawn a mob in a tick
+const SPAWN_ATTEMPT_CHANCE: f32 = 0.05;
+// mobs spawn between these horizontal distances from a player
+const MIN_SPAWN_DISTANCE: f32 = 10.0;
+const MAX_SPAWN_DISTANCE: f32 = 30.0;
+// how far above and below the player the ground for a mob is searched
+const SPAWN_SEARCH_HEIGHT: i32 = 20;
+// how far below the mob the ground has to be for it to spawn
+const GROUND_CHECK_DISTANCE: f32 = 0.1;
+// how far around the spawn position the lights are computed
+const LIGHT_UPDATE_RADIUS: i32 = 32;
+// the speed players are pushed away with, when a mob hurts them
+const MOB_KNOCKBACK: f32 = 15.0;
+
+pub struct ServerMobs {
+    mobs: Arc<Mutex<Mobs>>,
+    // lights are only used to decide where mobs can spawn
+    lights: Lights,
+    // biome id of every column of the world
+    pub(super) biomes: Vec<i32>,
+}
+
+impl ServerMobs {
+    pub fn new() -> Self {
+        Self {
+            mobs: Arc::new(Mutex::new(Mobs::new())),
+            lights: Lights::new(),
+            biomes: Vec::new(),
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_mobs_mod_interface(&self.mobs, mods)
+    }
+
+    /// this function has to be called after the world was loaded or generated
+    pub fn init_world(&mut self, blocks: &Blocks) -> Result<()> {
+        self.lights.create(blocks.get_width(), blocks.get_height());
+        self.lights.init_sky_heights(blocks)
+    }
+
+    /// decides what the mobs do, spawns new mobs and sends the mob positions to the clients
+    pub fn update(&mut self, entities: &mut Entities, blocks: &Blocks, events: &mut EventManager, mods: &mut ModManager, networking: &mut ServerNetworking, time: WorldTime) -> Result<()> {
+        // the light levels mobs spawn at follow the day and night
+        self.lights.set_sky_color(time.get_sky_color());
+
+        update_ai(entities, &mut rand::thread_rng());
+        self.call_ai_functions(entities, mods)?;
+        self.attack_touching_players(entities, events)?;
+        self.try_spawn_mob(entities, blocks, events, time.is_night())?;
+        Self::sync_mobs(entities, networking)
+    }
+
+    pub fn update_ms(entities: &mut Entities, blocks: &Blocks) {
+        update_ai_ms(entities, blocks);
+    }
+
+    /// lets the mods override the built-in behaviour of their mobs. The ai function gets the
+    /// center of the mob and of the nearest player in sight (or nil) and returns the
+    /// direction the mob should walk in (or nil to keep it) and whether it should jump
+    fn call_ai_functions(&self, entities: &Entities, mods: &mut ModManager) -> Result<()> {
+        let mut calls = Vec::new();
+        for (entity, (position, physics, mob, ai)) in &mut entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &MobComponent, &AiComponent)>() {
+            let mobs = self.get_mobs();
+            let mob_type = mobs.get_mob_type(mob.get_mob_type())?;
+            if let Some(ai_function) = &mob_type.ai_function {
+                let center_x = position.x() + physics.collision_width() / 2.0;
+                let center_y = position.y() + physics.collision_height() / 2.0;
+                calls.push((entity, mob_type.mod_id, ai_function.clone(), center_x, center_y, ai.sight_range));
+            }
+        }
+
+        for (entity, mod_id, ai_function, center_x, center_y, sight_range) in calls {
+            let nearest_player = get_nearest_player(entities, center_x, center_y, sight_range);
+            // mobs of a broken mod keep their built-in behaviour
+            let Some((direction, jump)) =
+                mods.call_function::<_, (Option<f32>, Option<bool>)>(mod_id, &ai_function, (center_x, center_y, nearest_player.map(|player| player.0), nearest_player.map(|player| player.1)))?
+            else {
+                continue;
+            };
+
+            let mut ai = entities.ecs.get::<&mut AiComponent>(entity)?;
+            if let Some(direction) = direction {
+                ai.direction = direction.clamp(-1.0, 1.0);
+            }
+            ai.jumping |= jump.unwrap_or(false);
+        }
+        Ok(())
+    }
+
+    /// mobs that deal damage hurt every player they touch
+    fn attack_touching_players(&self, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        let mut hits = Vec::new();
+        for (entity, (position, physics, mob)) in &mut entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &MobComponent)>() {
+            let damage = self.get_mobs().get_mob_type(mob.get_mob_type())?.damage;
+            if damage <= 0 {
+                continue;
+            }
+            for (player, (player_position, player_physics, _player)) in &mut entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &PlayerComponent)>() {
+                if overlaps(position, physics, player_position, player_physics) {
+                    hits.push((entity, player, damage, position.x() + physics.collision_width() / 2.0));
+                }
+            }
+        }
+
+        for (mob, player, damage, source_x) in hits {
+            damage_entity(entities, events, player, damage, MOB_KNOCKBACK, source_x, DamageCause::Entity(mob))?;
+        }
+        Ok(())
+    }
+
+    /// tries to spawn a random mob type at a random place near a random player,
+    /// the mob only spawns if one of its spawn rules matches that place
+    fn try_spawn_mob(&mut self, entities: &mut Entities, blocks: &Blocks, events: &mut EventManager, is_night: bool) -> Result<()> {
+        let mut rng = rand::thread_rng();
+        if rng.gen::<f32>() >= SPAWN_ATTEMPT_CHANCE {
+            return Ok(());
+        }
+
+        let mut players = Vec::new();
+        for (_, (position, _player)) in &mut entities.ecs.query::<(&PositionComponent, &PlayerComponent)>() {
+            players.push((position.x(), position.y()));
+        }
+        let Some(&(player_x, player_y)) = players.choose(&mut rng) else {
+            return Ok(());
+        };
+
+        let mob_type = {
+            let mobs = self.get_mobs();
+            let spawnable: Vec<&MobType> = mobs.mob_types.iter().filter(|mob_type| !mob_type.spawn_rules.is_empty()).collect();
+            let Some(mob_type) = spawnable.choose(&mut rng) else {
+                return Ok(());
+            };
+            (*mob_type).clone()
+        };
+
+        let side = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+        let x = (player_x + side * rng.gen_range(MIN_SPAWN_DISTANCE..MAX_SPAWN_DISTANCE)).floor();
+        let Some(y) = Self::find_ground(x, player_y as i32, &mob_type, blocks) else {
+            return Ok(());
+        };
+
+        let light = self.lights.get_light_level(x as i32, y.floor() as i32, LIGHT_UPDATE_RADIUS, blocks)?;
+        let biome = self.biomes.get(x as usize).copied();
+        let count = Mobs::count_mobs(entities, mob_type.get_id());
+        let can_spawn = mob_type
+            .spawn_rules
+            .iter()
+            .any(|rule| count < rule.max_count && rule.matches(biome, light, is_night) && rng.gen::<f32>() < rule.chance);
+
+        if can_spawn {
+            self.spawn_mob(events, entities, mob_type.get_id(), x, y)?;
+        }
+        Ok(())
+    }
+
+    /// finds the highest place in the column around the given height, where the mob stands on the ground
+    fn find_ground(x: f32, around_y: i32, mob_type: &MobType, blocks: &Blocks) -> Option<f32> {
+        if x < 0.0 || x + mob_type.width > blocks.get_width() as f32 {
+            return None;
+        }
+        let physics = PhysicsComponent::new(mob_type.width, mob_type.height);
+
+        let start_y = i32::max(1, around_y - SPAWN_SEARCH_HEIGHT);
+        let end_y = i32::min(blocks.get_height() as i32, around_y + SPAWN_SEARCH_HEIGHT);
+        (start_y..end_y).map(|ground_y| ground_y as f32 - mob_type.height).find(|y| {
+            let below = PositionComponent::new(x, *y + GROUND_CHECK_DISTANCE);
+            !collides_with_blocks(&PositionComponent::new(x, *y), &physics, blocks) && collides_with_blocks(&below, &physics, blocks)
+        })
+    }
+
+    pub fn spawn_mob(&self, events: &mut EventManager, entities: &mut Entities, mob: MobId, x: f32, y: f32) -> Result<Entity> {
+        let id = entities.new_id();
+        self.get_mobs().spawn_mob(events, entities, mob, x, y, id)
+    }
+
+    fn sync_mobs(entities: &Entities, networking: &mut ServerNetworking) -> Result<()> {
+        for (entity, (position, physics, _mob)) in &mut entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &MobComponent)>() {
+            let packet = EntityPositionVelocityPacket {
+                id: entities.get_id_from_entity(entity)?,
+                x: position.x(),
+                y: position.y(),
+                velocity_x: physics.velocity_x,
+                velocity_y: physics.velocity_y,
+            };
+            networking.send_packet(&Packet::new(packet)?, SendTarget::All)?;
+        }
+        Ok(())
+    }
+
+    fn get_spawn_packet(entities: &Entities, entity: Entity) -> Result<Packet> {
+        let position = entities.ecs.get::<&PositionComponent>(entity)?;
+        let physics = entities.ecs.get::<&PhysicsComponent>(entity)?;
+        let mob = entities.ecs.get::<&MobComponent>(entity)?;
+
+        Packet::new(MobSpawnPacket {
+            mob_type: mob.get_mob_type(),
+            x: position.x(),
+            y: position.y(),
+            velocity_x: physics.velocity_x,
+            velocity_y: physics.velocity_y,
+            id: entities.get_id_from_entity(entity)?,
+        })
+    }
+
+    pub fn on_event(&mut self, event: &Event, entities: &mut Entities, blocks: &Blocks, items: &mut Items, events: &mut EventManager, networking: &mut ServerNetworking) -> Result<()> {
+        self.lights.on_event(event, blocks)?;
+
+        if let Some(event) = event.downcast::<MobSpawnEvent>() {
+            networking.send_packet(&Self::get_spawn_packet(entities, event.entity)?, SendTarget::All)?;
+        }
+
+        if let Some(event) = event.downcast::<NewConnectionWelcomedEvent>() {
+            let mut mob_entities = Vec::new();
+            for (entity, _mob) in &mut entities.ecs.query::<&MobComponent>() {
+                mob_entities.push(entity);
+            }
+            for entity in mob_entities {
+                networking.send_packet(&Self::get_spawn_packet(entities, entity)?, SendTarget::Connection(event.conn.clone()))?;
+            }
+        }
+
+        if let Some(event) = event.downcast::<HealthChangeEvent>() {
+            // the entity could have already died from an earlier health change
+            if let Ok(entity) = entities.get_entity_from_id(event.entity) {
+                self.kill_mob_if_dead(entity, entities, items, events)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// despawns the mob if it has no health left and drops its loot
+    fn kill_mob_if_dead(&self, entity: Entity, entities: &mut Entities, items: &mut Items, events: &mut EventManager) -> Result<()> {
+        let (center_x, center_y, mob) = {
+            let Ok(mut query) = entities.ecs.query_one::<(&PositionComponent, &PhysicsComponent, &HealthComponent, &MobComponent)>(entity) else {
+                return Ok(());
+            };
+            let Some((position, physics, health, mob)) = query.get() else {
+                return Ok(());
+            };
+            if health.health() > 0 {
+                return Ok(());
+            }
+            (position.x() + physics.collision_width() / 2.0, position.y() + physics.collision_height() / 2.0, mob.get_mob_type())
+        };
+
+        let drops = self.get_mobs().get_mob_type(mob)?.drops.roll(&mut rand::thread_rng(), None, 0);
+        entities.despawn_entity(entities.get_id_from_entity(entity)?, events)?;
+
+        for drop in drops {
+            items.drop_item_stack(events, entities, &drop, center_x - 0.5, center_y - 0.5)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_mobs(&self) -> MutexGuard<'_, Mobs> {
+        self.mobs.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
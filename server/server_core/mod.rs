@@ -1,13 +1,24 @@
+pub use clock::{Clock, ManualClock, SystemClock};
 pub use core_server::{print_to_console, send_to_ui, Server, MULTIPLAYER_PORT, SINGLEPLAYER_PORT};
 
 mod blocks;
 mod chat;
+mod clock;
+mod combat;
 mod commands;
 mod core_server;
+mod deaths;
+mod dev_mod;
 mod entities;
 mod items;
+mod mobs;
 mod mod_manager;
 mod networking;
 mod players;
+mod spawn_points;
+mod stats;
+mod test_harness;
+mod tests;
 mod walls;
 mod world_generator;
+mod world_time;
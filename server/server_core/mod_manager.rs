@@ -1,12 +1,50 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
 use anyhow::Result;
+use rlua::FromLuaMulti;
 
 use crate::libraries::events::Event;
 use crate::server::server_core::networking::SendTarget;
 use crate::server::server_core::print_to_console;
-use crate::shared::mod_manager::{GameMod, ModManager, ModsWelcomePacket};
+use crate::shared::chat::{ChatMessage, ChatMessagePacket};
+use crate::shared::entities::{Entities, EntityId, HealthChangeEvent, HealthComponent};
+use crate::shared::mobs::{MobComponent, MobId};
+use crate::shared::mod_manager::{get_mod_id, GameMod, ModManager, ModsWelcomePacket};
 use crate::shared::packet::Packet;
+use crate::shared::players::{ItemPickupEvent, PlayerComponent};
+
+use super::commands::{mod_command, Argument, ArgumentType, Command, CommandManager, ModFunction};
+use super::networking::{DisconnectEvent, NewConnectionEvent, ServerNetworking};
 
-use super::networking::{NewConnectionEvent, ServerNetworking};
+// the events mods can subscribe to with terralistic_on
+const MOD_EVENTS: [&str; 9] = [
+    "player_join",
+    "player_leave",
+    "chat",
+    "block_place",
+    "block_change",
+    "item_pickup",
+    "entity_death",
+    "health_change",
+    "tick",
+];
+
+/// this function creates the payload of events about an entity,
+/// players are named and mobs have their type in it
+fn create_entity_payload<'lua>(lua: rlua::Context<'lua>, entity: EntityId, player: Option<&str>, mob: Option<MobId>) -> rlua::Result<rlua::Table<'lua>> {
+    let payload = lua.create_table()?;
+    payload.set("entity", entity)?;
+    payload.set("player", player)?;
+    payload.set("mob", mob)?;
+    Ok(payload)
+}
+
+/// Mod command is a command a mod registered, it is added to the command manager after the mods are initialized.
+struct ModCommand {
+    name: String,
+    description: String,
+    function: ModFunction,
+}
 
 /// server mod manager that manages all the mods for the server.
 /// It is used to initialize, update and stop all the mods.
@@ -15,12 +53,16 @@ use super::networking::{NewConnectionEvent, ServerNetworking};
 /// and always loads the base game mod.
 pub struct ServerModManager {
     pub mod_manager: ModManager,
+    new_commands: Arc<Mutex<Vec<ModCommand>>>,
 }
 
 impl ServerModManager {
     /// Creates a new server mod manager.
     pub fn new(mods: Vec<GameMod>) -> Self {
-        Self { mod_manager: ModManager::new(mods) }
+        Self {
+            mod_manager: ModManager::new(mods),
+            new_commands: Arc::new(Mutex::new(Vec::new())),
+        }
     }
 
     /// This function initializes the server mod manager.
@@ -32,14 +74,51 @@ impl ServerModManager {
             Ok(())
         })?;
 
-        self.mod_manager.init()?;
-        for game_mod in self.mod_manager.mods_iter_mut() {
-            game_mod.call_function::<(), ()>("init_server", ())?;
+        // a method to add a command to the chat and the console, the function
+        // is called with the executor and the arguments and returns the feedback
+        let new_commands = self.new_commands.clone();
+        self.mod_manager.add_global_function_with_values("register_command", move |context, arguments| {
+            let (name, description, function) = <(String, String, rlua::Function)>::from_lua_multi(arguments, context)?;
+            new_commands.lock().unwrap_or_else(PoisonError::into_inner).push(ModCommand {
+                name,
+                description,
+                function: ModFunction {
+                    mod_id: get_mod_id(context)?,
+                    function: context.create_registry_value(function)?,
+                },
+            });
+            Ok(())
+        })?;
+
+        for event_name in MOD_EVENTS {
+            self.mod_manager.add_event(event_name);
         }
+
+        self.mod_manager.init()?;
+        self.mod_manager.call_function_in_mods("init_server", ())?;
         Ok(())
     }
 
-    pub fn on_event(&mut self, event: &Event, networking: &mut ServerNetworking) -> Result<()> {
+    /// This function adds the commands registered by mods to the command manager,
+    /// commands with the name of an existing command are skipped.
+    pub fn register_commands(&self, commands: &mut CommandManager) {
+        let new_commands = std::mem::take(&mut *self.new_commands.lock().unwrap_or_else(PoisonError::into_inner));
+        for command in new_commands {
+            if commands.get_command(&command.name).is_some() {
+                print_to_console(&format!("Error registering command: command {} already exists", command.name), 1);
+                continue;
+            }
+            commands.add_command(Command {
+                call_name: command.name,
+                description: command.description,
+                arguments: vec![Argument::optional("arguments", ArgumentType::Text)],
+                function: mod_command,
+                mod_function: Some(command.function),
+            });
+        }
+    }
+
+    pub fn on_event(&mut self, event: &Event, networking: &mut ServerNetworking, entities: &Entities) -> Result<()> {
         if let Some(event) = event.downcast::<NewConnectionEvent>() {
             let mut mods = Vec::new();
             for game_mod in self.mod_manager.mods_iter_mut() {
@@ -47,14 +126,86 @@ impl ServerModManager {
             }
             let welcome_packet = Packet::new(ModsWelcomePacket { mods })?;
             networking.send_packet(&welcome_packet, SendTarget::Connection(event.conn.clone()))?;
+
+            self.mod_manager.fire_event("player_join", |lua| {
+                let payload = lua.create_table()?;
+                payload.set("player", event.name.as_str())?;
+                Ok(payload)
+            })?;
+        } else if let Some(event) = event.downcast::<DisconnectEvent>() {
+            let name = networking.get_connection_name(&event.conn);
+            self.mod_manager.fire_event("player_leave", |lua| {
+                let payload = lua.create_table()?;
+                payload.set("player", name.as_str())?;
+                Ok(payload)
+            })?;
+        } else if let Some(event) = event.downcast::<ItemPickupEvent>() {
+            let name = entities.ecs.get::<&PlayerComponent>(event.player)?.get_name().to_owned();
+            self.mod_manager.fire_event("item_pickup", |lua| {
+                let payload = lua.create_table()?;
+                payload.set("player", name.as_str())?;
+                payload.set("item", event.item)?;
+                payload.set("count", event.count)?;
+                Ok(payload)
+            })?;
+        } else if let Some(event) = event.downcast::<HealthChangeEvent>() {
+            // the entity could have already died from an earlier health change
+            let Ok(entity) = entities.get_entity_from_id(event.entity) else {
+                return Ok(());
+            };
+            let Ok(health) = entities.ecs.get::<&HealthComponent>(entity).map(|health| (health.health(), health.max_health())) else {
+                return Ok(());
+            };
+            let player = entities.ecs.get::<&PlayerComponent>(entity).ok().map(|player| player.get_name().to_owned());
+            let mob = entities.ecs.get::<&MobComponent>(entity).ok().map(|mob| mob.get_mob_type());
+
+            self.mod_manager.fire_event("health_change", |lua| {
+                let payload = create_entity_payload(lua, event.entity, player.as_deref(), mob)?;
+                payload.set("health", health.0)?;
+                payload.set("max_health", health.1)?;
+                Ok(payload)
+            })?;
+            if health.0 == 0 {
+                self.mod_manager.fire_event("entity_death", |lua| create_entity_payload(lua, event.entity, player.as_deref(), mob))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// This function shows the errors of the mods in the console and to the players,
+    /// the server keeps running without the calls that failed.
+    pub fn report_errors(&mut self, networking: &mut ServerNetworking) -> Result<()> {
+        for error in self.mod_manager.take_errors() {
+            print_to_console(&error.message, 2);
+            // players only get the first line, the console has the whole traceback
+            let mut messages = vec![error.message.lines().next().unwrap_or_default().to_owned()];
+            if error.disabled {
+                let message = format!("mod {} was disabled because it failed too many times", error.mod_name);
+                print_to_console(&message, 2);
+                messages.push(message);
+            }
+            for message in messages {
+                networking.send_packet(
+                    &Packet::new(ChatMessagePacket {
+                        message: ChatMessage::error(&message),
+                    })?,
+                    SendTarget::All,
+                )?;
+            }
         }
         Ok(())
     }
 
     /// This function updates the client mod manager.
-    /// It updates the shared mod manager.
-    pub fn update(&mut self) -> Result<()> {
-        self.mod_manager.update()
+    /// It updates the shared mod manager and lets the mods know that a tick has passed.
+    pub fn update(&mut self, delta_time: f32) -> Result<()> {
+        self.mod_manager.update()?;
+        self.mod_manager.fire_event("tick", |lua| {
+            let payload = lua.create_table()?;
+            payload.set("delta_time", delta_time)?;
+            Ok(payload)
+        })?;
+        Ok(())
     }
 
     /// This function stops the client mod manager.
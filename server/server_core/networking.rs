@@ -9,7 +9,7 @@ use std::sync::Arc;
 use anyhow::{anyhow, bail, Result};
 use message_io::network::{Endpoint, NetEvent, SendStatus, Transport};
 use message_io::node;
-use message_io::node::{NodeEvent, NodeHandler};
+use message_io::node::{NodeEvent, NodeHandler, NodeListener};
 
 use crate::libraries::events::{Event, EventManager};
 use crate::server::server_core::print_to_console;
@@ -50,7 +50,7 @@ pub struct ServerNetworking {
     event_receiver: Option<Receiver<Event>>,
     packet_sender: Option<Sender<(Vec<u8>, Connection)>>,
     is_running: Arc<AtomicBool>,
-    net_loop_thread: Option<std::thread::JoinHandle<Result<()>>>,
+    net_loop_thread: Option<std::thread::JoinHandle<()>>,
 }
 
 impl ServerNetworking {
@@ -71,26 +71,42 @@ impl ServerNetworking {
         self.connection_names.get(conn).unwrap_or(&unknown).clone()
     }
 
-    pub fn init(&mut self) {
+    /// this function finds the connection of the player with the given name
+    pub fn get_connection_by_name(&self, name: &str) -> Option<Connection> {
+        self.connections
+            .iter()
+            .find(|conn| self.connection_names.get(*conn).is_some_and(|connection_name| connection_name == name))
+            .cloned()
+    }
+
+    /// Returns the port the server listens on. If the server was
+    /// created with port 0, this is the port the system assigned
+    /// after the networking was initialized.
+    pub const fn get_port(&self) -> u16 {
+        self.server_port
+    }
+
+    pub fn init(&mut self) -> Result<()> {
         // start listening for connections
         let (event_sender, event_receiver) = mpsc::channel();
         let (packet_sender, packet_receiver) = mpsc::channel();
         self.event_receiver = Some(event_receiver);
         self.packet_sender = Some(packet_sender);
 
+        // listen before the loop is started, so that clients can connect as soon as this function returns
+        let (handler, listener) = node::split::<()>();
+        let listen_addr = format!("127.0.0.1:{}", self.server_port);
+        let (_, listen_addr) = handler.network().listen(Transport::FramedTcp, listen_addr)?;
+        self.server_port = listen_addr.port();
+
         let is_running = self.is_running.clone();
-        let server_port = self.server_port;
 
-        self.net_loop_thread = Some(std::thread::spawn(move || Self::net_receive_loop(&event_sender, &packet_receiver, &is_running, server_port)));
+        self.net_loop_thread = Some(std::thread::spawn(move || Self::net_receive_loop(&handler, listener, &event_sender, &packet_receiver, &is_running)));
+        Ok(())
     }
 
-    #[allow(clippy::expect_used)]
-    fn net_receive_loop(event_sender: &Sender<Event>, packet_receiver: &Receiver<(Vec<u8>, Connection)>, is_running: &Arc<AtomicBool>, server_port: u16) -> Result<()> {
-        let (handler, listener) = node::split::<()>();
-
-        let listen_addr = format!("127.0.0.1:{server_port}");
-        handler.network().listen(Transport::FramedTcp, listen_addr)?;
-
+    #[allow(clippy::expect_used, reason = "clients only send packets the server can deserialize")]
+    fn net_receive_loop(handler: &NodeHandler<()>, listener: NodeListener<()>, event_sender: &Sender<Event>, packet_receiver: &Receiver<(Vec<u8>, Connection)>, is_running: &Arc<AtomicBool>) {
         handler.signals().send(());
 
         listener.for_each(|event| match event {
@@ -140,14 +156,15 @@ impl ServerNetworking {
                 }
 
                 while let Ok((packet_data, conn)) = packet_receiver.try_recv() {
-                    Self::send_packet_internal(&handler, &packet_data, &conn).expect("Failed to send Packet");
+                    // the client could have disconnected after the packet was queued for it
+                    if let Err(e) = Self::send_packet_internal(handler, &packet_data, &conn) {
+                        print_to_console(&format!("Failed to send packet: {e}"), 2);
+                    }
                 }
 
                 handler.signals().send_with_timer((), std::time::Duration::from_millis(1));
             }
         });
-
-        Ok(())
     }
 
     pub fn on_event(&mut self, event: &Event, events: &mut EventManager) -> Result<()> {
@@ -183,13 +200,9 @@ impl ServerNetworking {
 
         if net_loop_finished {
             if let Some(thread_handle) = self.net_loop_thread.take() {
-                let result = match thread_handle.join() {
-                    Ok(result) => result,
-                    Err(_e) => {
-                        bail!("Failed to join net loop thread");
-                    }
-                };
-                return result;
+                if thread_handle.join().is_err() {
+                    bail!("Failed to join net loop thread");
+                }
             }
         }
 
@@ -207,13 +220,17 @@ impl ServerNetworking {
                     // wait a bit and try again
                     std::thread::sleep(std::time::Duration::from_millis(1));
                 }
-            };
+            }
         }
 
         Ok(())
     }
 
     pub fn send_packet(&mut self, packet: &Packet, target: SendTarget) -> Result<()> {
+        // events left over when the server stops can't reach the closed connections anymore
+        if !self.is_running.load(Ordering::Relaxed) {
+            return Ok(());
+        }
         let packet_data = bincode::serialize(&packet)?;
 
         match target {
@@ -248,15 +265,16 @@ impl ServerNetworking {
         self.is_running.store(false, Ordering::Relaxed);
         if let Some(thread_handle) = self.net_loop_thread.take() {
             match thread_handle.join() {
-                Ok(_) => {}
+                Ok(()) => {}
                 Err(_) => {
                     bail!("Failed to join net loop thread");
                 }
             }
         }
 
-        for conn in &self.connections {
-            events.push_event(Event::new(DisconnectEvent { conn: conn.clone() }));
+        // the connections are removed right away, since packets can't be sent anymore
+        for conn in self.connections.drain(..) {
+            events.push_event(Event::new(DisconnectEvent { conn }));
         }
 
         Ok(())
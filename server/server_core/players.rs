@@ -6,30 +6,57 @@ use hecs::Entity;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::libraries::events::{Event, EventManager};
+use crate::server::server_core::deaths::{drop_stacks, get_death_message, place_grave, DeathMode};
 use crate::server::server_core::networking::{Connection, DisconnectEvent, NewConnectionWelcomedEvent, PacketFromClientEvent, SendTarget, ServerNetworking};
 use crate::server::server_core::print_to_console;
-use crate::shared::blocks::Blocks;
+use crate::server::server_core::spawn_points::SpawnPoints;
+use crate::shared::blocks::{BlockRightClickPacket, Blocks};
+use crate::shared::chat::{ChatMessage, ChatMessagePacket};
+use crate::shared::entities::HealthComponent;
 use crate::shared::entities::{Entities, HealthChangeEvent, PhysicsComponent, PositionComponent};
-use crate::shared::entities::{HealthChangePacket, HealthComponent};
-use crate::shared::inventory::{Inventory, InventoryCraftPacket, InventoryPacket, InventorySelectPacket, InventorySwapPacket, Slot};
-use crate::shared::items::Items;
+use crate::shared::inventory::{
+    is_block_inventory_in_range, Inventory, InventoryCraftPacket, InventoryDropPacket, InventoryMergePacket, InventoryPacket, InventoryQuickMovePacket, InventorySelectPacket, InventorySortPacket,
+    InventorySplitPacket, InventorySwapPacket, InventoryTakeOnePacket, LegacyInventory, QuickMoveTarget, Slot,
+};
+use crate::shared::items::{ItemStack, Items};
+use crate::shared::mobs::Mobs;
 use crate::shared::packet::Packet;
 use crate::shared::players::{
-    remove_all_picked_items, spawn_player, update_players_ms, PlayerComponent, PlayerMovingPacketToClient, PlayerMovingPacketToServer, PlayerSpawnPacket, RespawnPacket, PLAYER_HEIGHT,
-    PLAYER_INVENTORY_SIZE, PLAYER_MAX_HEALTH, PLAYER_WIDTH,
+    remove_all_picked_items, spawn_player, throw_item_from_player, update_players_ms, PlayerComponent, PlayerMovingPacketToClient, PlayerMovingPacketToServer, PlayerSpawnPacket, RespawnPacket,
+    PLAYER_HEIGHT, PLAYER_HOTBAR_SIZE, PLAYER_INVENTORY_SIZE, PLAYER_MAX_HEALTH, PLAYER_WIDTH,
 };
+use crate::shared::stats::{SavedStats, StatChangeEvent, StatChangePacket, StatId, Stats, StatsComponent, HEALTH_STAT};
 
-#[derive(Serialize, Deserialize)]
 pub struct SavedPlayerData {
     pub inventory: Inventory,
     pub position: PositionComponent,
     pub health: HealthComponent,
+    pub stats: StatsComponent,
+}
+
+/// Player save is how saved player data is stored in the world file.
+#[derive(Serialize, Deserialize)]
+struct PlayerSave {
+    inventory: Inventory,
+    position: PositionComponent,
+    health: HealthComponent,
+    stats: SavedStats,
+}
+
+/// Legacy saved player data is how players were saved before they had stats.
+#[derive(Deserialize)]
+struct LegacySavedPlayerData {
+    inventory: LegacyInventory,
+    position: PositionComponent,
+    health: HealthComponent,
 }
 
 pub struct ServerPlayers {
     conns_to_players: HashMap<Connection, Option<Entity>>,
     players_to_conns: HashMap<Entity, Connection>,
     saved_players: HashMap<String, SavedPlayerData>,
+    pub(super) spawn_points: SpawnPoints,
+    pub(super) death_mode: DeathMode,
 }
 
 impl ServerPlayers {
@@ -38,33 +65,11 @@ impl ServerPlayers {
             conns_to_players: HashMap::new(),
             players_to_conns: HashMap::new(),
             saved_players: HashMap::new(),
+            spawn_points: SpawnPoints::new(),
+            death_mode: DeathMode::DropItems,
         }
     }
 
-    fn get_spawn_coords(blocks: &Blocks) -> (f32, f32) {
-        let spawn_x = blocks.get_width() as f32 / 2.0;
-        let mut spawn_y = 0.0;
-        // find a spawn point
-        // iterate from the top of the map to the bottom
-        for y in (0..blocks.get_height()).rev() {
-            for x in 0..(PLAYER_WIDTH.ceil() as i32) {
-                let block_type = blocks.get_block_type(blocks.get_block(spawn_x as i32 + x, y as i32).unwrap_or_else(|_| blocks.air()));
-
-                let is_ghost = match block_type.ok() {
-                    Some(block_type) => block_type.ghost,
-                    None => false,
-                };
-
-                if !is_ghost {
-                    spawn_y = y as f32 - PLAYER_HEIGHT;
-                    break;
-                }
-            }
-        }
-
-        (spawn_x, spawn_y)
-    }
-
     pub fn handle_client_packet(
         &mut self,
         packet_event: &PacketFromClientEvent,
@@ -91,8 +96,14 @@ impl ServerPlayers {
                 networking.send_packet(&packet, SendTarget::AllExcept(packet_event.conn.clone()))?;
             } else if let Some(packet) = packet_event.packet.try_deserialize::<InventorySelectPacket>() {
                 let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
-                inventory.selected_slot = packet.slot;
+                // a slot the inventory doesn't have is ignored, so the selected slot always exists
+                if packet.slot.is_none_or(|slot| slot < inventory.get_size()) {
+                    inventory.selected_slot = packet.slot;
+                }
             } else if let Some(packet) = packet_event.packet.try_deserialize::<InventorySwapPacket>() {
+                if !Self::can_use_slot(entities, player_entity, &packet.slot, blocks)? {
+                    return Ok(());
+                }
                 let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
 
                 if let Slot::Inventory(slot) = packet.slot {
@@ -120,23 +131,132 @@ impl ServerPlayers {
                 let position_y = entities.ecs.get::<&PositionComponent>(player_entity)?.y();
                 let recipe = items.get_recipe(packet.recipe)?.clone();
 
-                inventory.craft(&recipe, (position_x, position_y), items, entities, events)?;
+                // the player could have walked away from the station before the packet arrived, so the packet is ignored
+                if recipe.is_station_in_range(blocks, position_x + PLAYER_WIDTH / 2.0, position_y + PLAYER_HEIGHT / 2.0)? {
+                    inventory.craft(&recipe, (position_x, position_y), items, entities, events)?;
+
+                    *entities.ecs.get::<&mut Inventory>(player_entity)? = inventory;
+                }
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<InventorySplitPacket>() {
+                if Self::can_use_slot(entities, player_entity, &packet.slot, blocks)? {
+                    let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
+                    inventory.split(&packet.slot, blocks, events)?;
+                }
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<InventoryTakeOnePacket>() {
+                if Self::can_use_slot(entities, player_entity, &packet.slot, blocks)? {
+                    let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
+                    inventory.take_one(&packet.slot, items, blocks, events)?;
+                }
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<InventoryQuickMovePacket>() {
+                Self::quick_move(entities, player_entity, &packet, items, blocks, events)?;
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<InventoryMergePacket>() {
+                let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
+                if packet.slot < inventory.get_size() {
+                    inventory.merge(packet.slot, items)?;
+                }
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<InventoryDropPacket>() {
+                // the packet is ignored if the selected slot is empty
+                let stack = entities.ecs.get::<&mut Inventory>(player_entity)?.take_selected_item(packet.whole_stack)?;
+                if let Some(stack) = stack {
+                    throw_item_from_player(entities, events, items, player_entity, &stack)?;
+                }
+            } else if packet_event.packet.try_deserialize::<InventorySortPacket>().is_some() {
+                let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
+                let size = inventory.get_size();
+                inventory.sort(PLAYER_HOTBAR_SIZE..size, items)?;
+            } else if let Some(packet) = packet_event.packet.try_deserialize::<BlockRightClickPacket>() {
+                // clicks on blocks outside the world or out of reach of the player are ignored
+                if !Self::can_reach_block(entities, player_entity, blocks, packet.x, packet.y)? {
+                    return Ok(());
+                }
+                let name = networking.get_connection_name(&packet_event.conn);
+                if self.spawn_points.set_player_spawn(&name, blocks, packet.x, packet.y)? {
+                    let packet = Packet::new(ChatMessagePacket {
+                        message: ChatMessage::system("Spawn point set"),
+                    })?;
+                    networking.send_packet(&packet, SendTarget::Connection(packet_event.conn.clone()))?;
+                }
+            }
+        } else if packet_event.packet.try_deserialize::<RespawnPacket>().is_some() {
+            let name = networking.get_connection_name(&packet_event.conn);
 
-                *entities.ecs.get::<&mut Inventory>(player_entity)? = inventory;
+            // the bed could have been broken or blocked while the player was dead
+            let ((spawn_x, spawn_y), bed_lost) = self.spawn_points.get_respawn_coords(&name, blocks);
+            if let Some(saved_player) = self.saved_players.get_mut(&name) {
+                saved_player.position = PositionComponent::new(spawn_x, spawn_y);
+            }
+            if bed_lost {
+                self.spawn_points.remove_player_spawn(&name);
+                let packet = Packet::new(ChatMessagePacket {
+                    message: ChatMessage::system("Your bed was missing or obstructed"),
+                })?;
+                networking.send_packet(&packet, SendTarget::Connection(packet_event.conn.clone()))?;
             }
-        }
 
-        if packet_event.packet.try_deserialize::<RespawnPacket>().is_some() {
-            self.spawn_player(&networking.get_connection_name(&packet_event.conn), blocks, entities, networking, &packet_event.conn)?;
+            self.spawn_player(&name, blocks, entities, networking, &packet_event.conn)?;
         }
 
         Ok(())
     }
 
+    /// This function moves the stack in the slot of the packet. Items from a block go to the player,
+    /// items from the player go to the opened block or if there is none, between the hotbar and the rest of the inventory.
+    fn quick_move(entities: &Entities, player_entity: Entity, packet: &InventoryQuickMovePacket, items: &Items, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+        // the packet is ignored if the player can't use the slot or the block it goes to
+        if !Self::can_use_slot(entities, player_entity, &packet.slot, blocks)? {
+            return Ok(());
+        }
+        if let Some((x, y)) = packet.block {
+            if !Self::can_use_block_inventory(entities, player_entity, blocks, x, y)? {
+                return Ok(());
+            }
+        }
+        let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
+
+        let target = match (&packet.slot, packet.block) {
+            (Slot::Block(..), _) => QuickMoveTarget::Inventory(0..inventory.get_size()),
+            (Slot::Inventory(_), Some((x, y))) => QuickMoveTarget::Block(x, y),
+            (Slot::Inventory(slot), None) if *slot < PLAYER_HOTBAR_SIZE => QuickMoveTarget::Inventory(PLAYER_HOTBAR_SIZE..inventory.get_size()),
+            (Slot::Inventory(_), None) => QuickMoveTarget::Inventory(0..PLAYER_HOTBAR_SIZE),
+        };
+
+        inventory.quick_move(&packet.slot, &target, items, blocks, events)
+    }
+
+    /// This function checks if the player can use the slot. Slots that don't exist and
+    /// slots of blocks that are too far away can come from outdated or broken clients.
+    fn can_use_slot(entities: &Entities, player_entity: Entity, slot: &Slot, blocks: &Blocks) -> Result<bool> {
+        if !entities.ecs.get::<&Inventory>(player_entity)?.has_slot(slot, blocks) {
+            return Ok(false);
+        }
+        match *slot {
+            Slot::Inventory(_) => Ok(true),
+            Slot::Block(x, y, _) => Self::can_use_block_inventory(entities, player_entity, blocks, x, y),
+        }
+    }
+
+    /// this function checks if the block has an inventory and is in range of the player
+    fn can_use_block_inventory(entities: &Entities, player_entity: Entity, blocks: &Blocks, x: i32, y: i32) -> Result<bool> {
+        if blocks.get_block_inventory_data(x, y).ok().flatten().is_none() {
+            return Ok(false);
+        }
+        Self::can_reach_block(entities, player_entity, blocks, x, y)
+    }
+
+    /// this function checks if the block is in the world and the player can reach it,
+    /// which is as far as the player can use block inventories
+    fn can_reach_block(entities: &Entities, player_entity: Entity, blocks: &Blocks, x: i32, y: i32) -> Result<bool> {
+        if blocks.get_block(x, y).is_err() {
+            return Ok(false);
+        }
+        let position = entities.ecs.get::<&PositionComponent>(player_entity)?;
+        Ok(is_block_inventory_in_range(x, y, position.x() + PLAYER_WIDTH / 2.0, position.y() + PLAYER_HEIGHT / 2.0))
+    }
+
     fn spawn_player(&mut self, name: &String, blocks: &Blocks, entities: &mut Entities, networking: &mut ServerNetworking, connection: &Connection) -> Result<()> {
         let player_data = self.saved_players.get(name);
 
-        let (spawn_x, spawn_y) = player_data.map_or_else(|| Self::get_spawn_coords(blocks), |player_data| (player_data.position.x(), player_data.position.y()));
+        let (spawn_x, spawn_y) = player_data.map_or_else(|| self.spawn_points.get_world_spawn(blocks), |player_data| (player_data.position.x(), player_data.position.y()));
 
         for (entity, (player, position)) in &mut entities.ecs.query::<(&PlayerComponent, &PositionComponent)>() {
             let id = entities.get_id_from_entity(entity)?;
@@ -163,12 +283,10 @@ impl ServerPlayers {
             name: name.clone(),
         })?;
 
-        let health_component = entities.ecs.get::<&mut HealthComponent>(player_entity)?;
-        let health_packet = Packet::new(HealthChangePacket {
-            health: health_component.health(),
-            max_health: health_component.max_health(),
-        })?;
-        networking.send_packet(&health_packet, SendTarget::Connection(connection.clone()))?;
+        // the stats are sent to the client once they are updated for the first time
+        let mut stats = player_data.map_or_else(StatsComponent::new, |player_data| player_data.stats.clone());
+        stats.mark_unsynced();
+        entities.ecs.insert_one(player_entity, stats)?;
 
         if let Some(player_data) = player_data {
             let mut inventory = entities.ecs.get::<&mut Inventory>(player_entity)?;
@@ -186,19 +304,30 @@ impl ServerPlayers {
         let position = entities.ecs.get::<&PositionComponent>(*player_entity)?.clone();
         let inventory = entities.ecs.get::<&Inventory>(*player_entity)?.clone();
         let health = entities.ecs.get::<&HealthComponent>(*player_entity)?.clone();
+        let stats = entities.ecs.get::<&StatsComponent>(*player_entity)?.clone();
         self.saved_players.insert(
             name.to_owned(),
             SavedPlayerData {
                 position: position.deref().clone(),
                 inventory: inventory.deref().clone(),
                 health: health.deref().clone(),
+                stats: stats.deref().clone(),
             },
         );
         Ok(())
     }
 
-    #[allow(clippy::too_many_lines)]
-    pub fn on_event(&mut self, event: &Event, entities: &mut Entities, blocks: &mut Blocks, networking: &mut ServerNetworking, events: &mut EventManager, items: &mut Items) -> Result<()> {
+    #[allow(clippy::too_many_lines, reason = "it handles every packet and event of the players")]
+    pub fn on_event(
+        &mut self,
+        event: &Event,
+        entities: &mut Entities,
+        blocks: &mut Blocks,
+        networking: &mut ServerNetworking,
+        events: &mut EventManager,
+        items: &mut Items,
+        mobs: &Mobs,
+    ) -> Result<()> {
         if let Some(packet_event) = event.downcast::<PacketFromClientEvent>() {
             self.handle_client_packet(packet_event, entities, networking, blocks, events, items)?;
         }
@@ -225,38 +354,57 @@ impl ServerPlayers {
             }
         }
 
+        if let Some(stat_change_event) = event.downcast::<StatChangeEvent>() {
+            if let Some(player_conn) = self.players_to_conns.get(&stat_change_event.entity) {
+                let packet = Self::get_stat_packet(entities, stat_change_event.entity, stat_change_event.stat)?;
+                networking.send_packet(&packet, SendTarget::Connection(player_conn.clone()))?;
+            }
+        }
+
         if let Some(health_change_event) = event.downcast::<HealthChangeEvent>() {
             let entity = entities.get_entity_from_id(health_change_event.entity)?;
             let player_conn = self.players_to_conns.get(&entity).cloned();
             if let Some(player_conn) = player_conn {
-                let health_component = entities.ecs.query_one_mut::<&HealthComponent>(entity)?;
-                networking.send_packet(
-                    &Packet::new(HealthChangePacket {
-                        health: health_component.health(),
-                        max_health: health_component.max_health(),
-                    })?,
-                    SendTarget::Connection(player_conn.clone()),
-                )?;
-
-                if health_component.health() == 0 {
-                    let inventory = entities.ecs.get::<&Inventory>(entity)?.deref().clone();
-                    for item in inventory.iter().flatten() {
-                        for _ in 0..item.count {
-                            let x = entities.ecs.get::<&PositionComponent>(entity)?.x();
-                            let y = entities.ecs.get::<&PositionComponent>(entity)?.y();
-
-                            items.drop_item(events, entities, item.item, x, y)?;
+                let packet = Self::get_stat_packet(entities, entity, HEALTH_STAT)?;
+                networking.send_packet(&packet, SendTarget::Connection(player_conn.clone()))?;
+
+                if entities.ecs.get::<&HealthComponent>(entity)?.health() == 0 {
+                    let name = networking.get_connection_name(&player_conn);
+                    let message = get_death_message(entities, mobs, entity, &name);
+                    print_to_console(&message, 0);
+                    networking.send_packet(
+                        &Packet::new(ChatMessagePacket {
+                            message: ChatMessage::system(&message),
+                        })?,
+                        SendTarget::All,
+                    )?;
+
+                    let (x, y) = {
+                        let position = entities.ecs.get::<&PositionComponent>(entity)?;
+                        (position.x(), position.y())
+                    };
+                    let stacks: Vec<ItemStack> = entities.ecs.get::<&Inventory>(entity)?.iter().flatten().cloned().collect();
+                    match self.death_mode {
+                        DeathMode::KeepInventory => {}
+                        DeathMode::DropItems => drop_stacks(&stacks, items, entities, events, x, y)?,
+                        DeathMode::Grave => {
+                            // if there is no grave block or no room for it, the items are dropped
+                            let leftover = place_grave(stacks, blocks, events, x, y)?;
+                            drop_stacks(&leftover, items, entities, events, x, y)?;
                         }
                     }
 
-                    let name = networking.get_connection_name(&player_conn);
                     self.save_player(&name, entities)?;
 
-                    let spawn_coord = Self::get_spawn_coords(blocks);
+                    // if the player leaves before respawning, they join at their spawn point
+                    let (spawn_coord, _) = self.spawn_points.get_respawn_coords(&name, blocks);
                     let saved_player = self.saved_players.get_mut(&name).ok_or_else(|| anyhow!("Player not found"))?;
                     saved_player.position = PositionComponent::new(spawn_coord.0, spawn_coord.1);
-                    saved_player.inventory = Inventory::new(PLAYER_INVENTORY_SIZE);
+                    if self.death_mode != DeathMode::KeepInventory {
+                        saved_player.inventory = Inventory::new(PLAYER_INVENTORY_SIZE);
+                    }
                     saved_player.health = HealthComponent::new(PLAYER_MAX_HEALTH, PLAYER_MAX_HEALTH);
+                    saved_player.stats = StatsComponent::new();
 
                     entities.despawn_entity(health_change_event.entity, events)?;
                     self.conns_to_players.insert(player_conn, None);
@@ -268,7 +416,7 @@ impl ServerPlayers {
         Ok(())
     }
 
-    pub fn update(&mut self, entities: &mut Entities, blocks: &Blocks, events: &mut EventManager, items: &mut Items, networking: &mut ServerNetworking) -> Result<()> {
+    pub fn update(&self, entities: &mut Entities, blocks: &Blocks, events: &mut EventManager, items: &mut Items, networking: &mut ServerNetworking) -> Result<()> {
         update_players_ms(entities, blocks);
         remove_all_picked_items(entities, events, items)?;
 
@@ -284,11 +432,24 @@ impl ServerPlayers {
         Ok(())
     }
 
+    /// this function creates the packet with the value of the stat, health is kept in the health component
+    fn get_stat_packet(entities: &Entities, entity: Entity, stat: StatId) -> Result<Packet> {
+        let (value, max_value) = if stat == HEALTH_STAT {
+            let health = entities.ecs.get::<&HealthComponent>(entity)?;
+            (health.health(), health.max_health())
+        } else {
+            let stats = entities.ecs.get::<&StatsComponent>(entity)?;
+            let stat = stats.get_stat(stat).ok_or_else(|| anyhow!("player doesn't have this stat"))?;
+            (stat.get_value(), stat.get_max_value())
+        };
+        Packet::new(StatChangePacket { stat, value, max_value })
+    }
+
     pub fn get_player_from_connection(&self, conn: &Connection) -> Result<Option<Entity>> {
         self.conns_to_players.get(conn).ok_or_else(|| anyhow!("Received PlayerMovingPacket from unknown connection")).cloned()
     }
 
-    pub fn get_player_entity_from_name(&mut self, name: &str, entities: &Entities) -> Result<&Entity> {
+    pub fn get_player_entity_from_name(&self, name: &str, entities: &Entities) -> Result<&Entity> {
         for entity in &mut self.conns_to_players.values().flatten() {
             let e_component = entities.ecs.get::<&mut PlayerComponent>(*entity)?;
             if e_component.get_name() == name {
@@ -298,12 +459,53 @@ impl ServerPlayers {
         Err(anyhow!("Player not found"))
     }
 
-    pub fn serialize(&self) -> Result<Vec<u8>> {
-        Ok(bincode::serialize(&self.saved_players)?)
+    /// Serializes the saved players, their stats are saved by the name of the stat type.
+    pub fn serialize(&self, stats: &Stats) -> Result<Vec<u8>> {
+        let mut player_saves = HashMap::new();
+        for (name, player) in &self.saved_players {
+            let player_save = PlayerSave {
+                inventory: player.inventory.clone(),
+                position: player.position.clone(),
+                health: player.health.clone(),
+                stats: player.stats.save(stats)?,
+            };
+            player_saves.insert(name.clone(), player_save);
+        }
+        Ok(bincode::serialize(&player_saves)?)
+    }
+
+    pub fn deserialize(&mut self, data: &[u8], stats: &Stats) -> Result<()> {
+        let player_saves: HashMap<String, PlayerSave> = bincode::deserialize(data)?;
+        self.saved_players = player_saves
+            .into_iter()
+            .map(|(name, player)| {
+                let saved_player = SavedPlayerData {
+                    inventory: player.inventory,
+                    position: player.position,
+                    health: player.health,
+                    stats: StatsComponent::load(player.stats, stats),
+                };
+                (name, saved_player)
+            })
+            .collect();
+        Ok(())
     }
 
-    pub fn deserialize(&mut self, data: &[u8]) -> Result<()> {
-        self.saved_players = bincode::deserialize(data)?;
+    /// Deserializes players of a world saved before item stacks had metadata and players had stats.
+    pub fn deserialize_legacy(&mut self, data: &[u8]) -> Result<()> {
+        let legacy_players: HashMap<String, LegacySavedPlayerData> = bincode::deserialize(data)?;
+        self.saved_players = legacy_players
+            .into_iter()
+            .map(|(name, player)| {
+                let saved_player = SavedPlayerData {
+                    inventory: player.inventory.into(),
+                    position: player.position,
+                    health: player.health,
+                    stats: StatsComponent::new(),
+                };
+                (name, saved_player)
+            })
+            .collect();
         Ok(())
     }
 }
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::shared::blocks::Blocks;
+use crate::shared::players::{PLAYER_HEIGHT, PLAYER_WIDTH};
+
+/// Spawn points store where players appear when they join for the first time
+/// or respawn. Every world has one world spawn which is chosen once when the
+/// world is created, and players can have their own spawn point at a bed.
+pub struct SpawnPoints {
+    world_spawn: Option<(f32, f32)>,
+    // the main block of the bed for each player name
+    player_spawns: HashMap<String, (i32, i32)>,
+}
+
+impl SpawnPoints {
+    pub fn new() -> Self {
+        Self {
+            world_spawn: None,
+            player_spawns: HashMap::new(),
+        }
+    }
+
+    /// this function chooses the world spawn if the world doesn't have one yet,
+    /// so it has to be called after the world is generated or loaded
+    pub fn init_world(&mut self, blocks: &Blocks) {
+        if self.world_spawn.is_none() {
+            self.world_spawn = Some(Self::find_surface(blocks, blocks.get_width() as f32 / 2.0));
+        }
+    }
+
+    /// this function finds the highest spot where a player can stand at the given x
+    fn find_surface(blocks: &Blocks, spawn_x: f32) -> (f32, f32) {
+        // iterate from the top of the map to the bottom
+        for y in 0..blocks.get_height() as i32 {
+            let spawn_y = y as f32 - PLAYER_HEIGHT;
+            if Self::is_solid_below(blocks, spawn_x, spawn_y + PLAYER_HEIGHT) {
+                return (spawn_x, spawn_y);
+            }
+        }
+        (spawn_x, 0.0)
+    }
+
+    fn is_solid(blocks: &Blocks, x: i32, y: i32) -> bool {
+        let Ok(block) = blocks.get_block(x, y) else {
+            // outside of the world counts as solid, so nobody spawns there
+            return true;
+        };
+        blocks.get_block_type(block).map_or(true, |block_type| !block_type.ghost)
+    }
+
+    fn is_solid_below(blocks: &Blocks, x: f32, feet_y: f32) -> bool {
+        let first_x = x.floor() as i32;
+        let last_x = (x + PLAYER_WIDTH).ceil() as i32;
+        (first_x..last_x).any(|block_x| Self::is_solid(blocks, block_x, feet_y.ceil() as i32))
+    }
+
+    /// this function checks that a player at the given position would not be inside
+    /// any solid blocks and would be standing on the ground
+    pub fn is_safe(blocks: &Blocks, x: f32, y: f32) -> bool {
+        if x < 0.0 || y < 0.0 || x + PLAYER_WIDTH > blocks.get_width() as f32 || y + PLAYER_HEIGHT > blocks.get_height() as f32 {
+            return false;
+        }
+
+        for block_x in x.floor() as i32..(x + PLAYER_WIDTH).ceil() as i32 {
+            for block_y in y.floor() as i32..(y + PLAYER_HEIGHT).ceil() as i32 {
+                if Self::is_solid(blocks, block_x, block_y) {
+                    return false;
+                }
+            }
+        }
+
+        Self::is_solid_below(blocks, x, y + PLAYER_HEIGHT)
+    }
+
+    /// this function returns the world spawn, if something was built over it,
+    /// the player spawns on the surface above it instead
+    pub fn get_world_spawn(&self, blocks: &Blocks) -> (f32, f32) {
+        let (x, y) = self.world_spawn.unwrap_or_else(|| Self::find_surface(blocks, blocks.get_width() as f32 / 2.0));
+        if Self::is_safe(blocks, x, y) {
+            (x, y)
+        } else {
+            Self::find_surface(blocks, x)
+        }
+    }
+
+    pub const fn set_world_spawn(&mut self, x: f32, y: f32) {
+        self.world_spawn = Some((x, y));
+    }
+
+    /// this function sets the spawn point of a player to the bed at the given
+    /// position, it returns false if the block there is not a bed
+    pub fn set_player_spawn(&mut self, name: &str, blocks: &Blocks, x: i32, y: i32) -> Result<bool> {
+        if !blocks.get_block_type_at(x, y)?.spawn_point {
+            return Ok(false);
+        }
+
+        let from_main = blocks.get_block_from_main(x, y)?;
+        self.player_spawns.insert(name.to_owned(), (x - from_main.0, y - from_main.1));
+        Ok(true)
+    }
+
+    /// this function finds where the player should stand next to their bed,
+    /// or None if the bed was broken or there is no room around it
+    fn get_bed_spawn(blocks: &Blocks, bed_x: i32, bed_y: i32) -> Option<(f32, f32)> {
+        let block_type = blocks.get_block_type_at(bed_x, bed_y).ok()?;
+        if !block_type.spawn_point || blocks.get_block_from_main(bed_x, bed_y).ok()? != (0, 0) {
+            return None;
+        }
+
+        let x = bed_x as f32 + (block_type.width as f32 - PLAYER_WIDTH) / 2.0;
+        // try standing on the floor under the bed first and then on top of it
+        let floor_y = (bed_y + block_type.height) as f32 - PLAYER_HEIGHT;
+        let top_y = bed_y as f32 - PLAYER_HEIGHT;
+        [floor_y, top_y].into_iter().find(|y| Self::is_safe(blocks, x, *y)).map(|y| (x, y))
+    }
+
+    /// this function returns where the player respawns. It is their bed if it is
+    /// still there and safe, otherwise the world spawn. The bool tells if the
+    /// player has a bed spawn that can't be used anymore.
+    pub fn get_respawn_coords(&self, name: &str, blocks: &Blocks) -> ((f32, f32), bool) {
+        if let Some((bed_x, bed_y)) = self.player_spawns.get(name).copied() {
+            if let Some(coords) = Self::get_bed_spawn(blocks, bed_x, bed_y) {
+                return (coords, false);
+            }
+            return (self.get_world_spawn(blocks), true);
+        }
+        (self.get_world_spawn(blocks), false)
+    }
+
+    pub fn remove_player_spawn(&mut self, name: &str) {
+        self.player_spawns.remove(name);
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(&(self.world_spawn, &self.player_spawns))?)
+    }
+
+    pub fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        (self.world_spawn, self.player_spawns) = bincode::deserialize(data)?;
+        Ok(())
+    }
+}
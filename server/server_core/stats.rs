@@ -0,0 +1,37 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+
+use crate::libraries::events::EventManager;
+use crate::shared::entities::Entities;
+use crate::shared::mod_manager::ModManager;
+use crate::shared::stats::{init_player_stats_mod_interface, init_stats_mod_interface, update_stats, PlayerStats, Stats};
+
+pub struct ServerStats {
+    stats: Arc<Mutex<Stats>>,
+    player_stats: Arc<Mutex<PlayerStats>>,
+}
+
+impl ServerStats {
+    pub fn new() -> Self {
+        Self {
+            stats: Arc::new(Mutex::new(Stats::new())),
+            player_stats: Arc::new(Mutex::new(PlayerStats::new())),
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_stats_mod_interface(&self.stats, mods)?;
+        init_player_stats_mod_interface(&self.player_stats, mods)
+    }
+
+    /// regenerates and drains the stats of all players and lets mods see them
+    pub fn update(&self, entities: &mut Entities, events: &mut EventManager, delta_time: f32) -> Result<()> {
+        update_stats(entities, events, &self.get_stats(), delta_time)?;
+        self.player_stats.lock().unwrap_or_else(PoisonError::into_inner).update(entities, events)
+    }
+
+    pub fn get_stats(&self) -> MutexGuard<'_, Stats> {
+        self.stats.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
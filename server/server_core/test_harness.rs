@@ -0,0 +1,323 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+use hecs::Entity;
+use message_io::network::{Endpoint, NetEvent, Transport};
+use message_io::node::{self, NodeEvent, NodeHandler};
+use serde::de::DeserializeOwned;
+
+use crate::shared::blocks::BlockId;
+use crate::shared::entities::{HealthComponent, PositionComponent};
+use crate::shared::inventory::Inventory;
+use crate::shared::items::{ItemId, ItemStack};
+use crate::shared::mod_manager::GameMod;
+use crate::shared::packet::{Packet, WelcomeCompletePacket};
+use crate::shared::players::{NamePacket, PlayerSpawnPacket};
+use crate::shared::stats::{StatId, StatsComponent};
+
+use super::Server;
+
+pub const TEST_WORLD_WIDTH: i32 = 64;
+pub const TEST_WORLD_HEIGHT: i32 = 64;
+// the test mod fills the bottom half of the world with dirt
+pub const TEST_WORLD_GROUND_Y: i32 = TEST_WORLD_HEIGHT / 2;
+// how many ticks are run while waiting for something before giving up
+const MAX_WAIT_TICKS: usize = 2000;
+
+static NEXT_WORLD_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Test server runs a real server with the test mod on an ephemeral loopback port.
+/// It is not updated on its own, tests drive it tick by tick.
+pub struct TestServer {
+    pub server: Server,
+    world_path: PathBuf,
+    status_text: Mutex<String>,
+}
+
+impl TestServer {
+    pub fn new() -> Result<Self> {
+        Self::start(None, None)
+    }
+
+    /// Starts a test server that develops a mod with the lua code next to the test mod.
+    pub fn with_dev_mod(lua_code: &str) -> Result<Self> {
+        Self::start(Some(lua_code), None)
+    }
+
+    /// Starts a test server that loads the world file instead of generating a new world.
+    pub fn with_world_file(world_file: &[u8]) -> Result<Self> {
+        Self::start(None, Some(world_file))
+    }
+
+    fn start(dev_mod: Option<&str>, world_file: Option<&[u8]>) -> Result<Self> {
+        let test_mod = GameMod::new(include_str!("test_mod.lua").to_owned(), HashMap::new());
+        let test_mod = snap::raw::Encoder::new().compress_vec(&bincode::serialize(&test_mod)?)?;
+
+        let world_id = NEXT_WORLD_ID.fetch_add(1, Ordering::Relaxed);
+        let world_path = std::env::temp_dir().join(format!("terralistic_test_{}_{world_id}", std::process::id())).join("server.world");
+
+        let mut server = Server::new(0, None, None);
+        server.world_width = TEST_WORLD_WIDTH;
+        server.world_height = TEST_WORLD_HEIGHT;
+        if let Some(lua_code) = dev_mod {
+            server.dev_mod_path = Some(write_dev_mod(&world_path, lua_code)?);
+        }
+        if let Some(world_file) = world_file {
+            std::fs::create_dir_all(world_path.parent().ok_or_else(|| anyhow!("world path has no parent"))?)?;
+            std::fs::write(&world_path, world_file)?;
+        }
+
+        let status_text = Mutex::new(String::new());
+        server.start(&status_text, vec![test_mod], &world_path)?;
+
+        Ok(Self { server, world_path, status_text })
+    }
+
+    /// Replaces the code of the dev mod, the server reloads it once it notices the change.
+    pub fn write_dev_mod(&self, lua_code: &str) -> Result<()> {
+        write_dev_mod(&self.world_path, lua_code)?;
+        Ok(())
+    }
+
+    /// Simulates one server tick, no matter how much time has actually passed.
+    pub fn tick(&mut self) -> Result<()> {
+        self.server.step(1)
+    }
+
+    /// Ticks the server until the condition is met. Between ticks it waits
+    /// a bit, so that packets have time to travel over the network.
+    pub fn tick_until<F: FnMut(&mut Self) -> Result<bool>>(&mut self, mut condition: F) -> Result<()> {
+        for _ in 0..MAX_WAIT_TICKS {
+            self.tick()?;
+            if condition(self)? {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        bail!("condition was not met in {MAX_WAIT_TICKS} ticks")
+    }
+
+    /// Connects a new fake client and ticks the server until the client is welcomed and its player has spawned.
+    pub fn connect(&mut self, name: &str) -> Result<FakeClient> {
+        let mut client = FakeClient::connect(self.server.get_port(), name)?;
+        client.wait_for::<WelcomeCompletePacket>(self)?;
+        let name = name.to_owned();
+        client.wait_until(self, |client| client.received::<PlayerSpawnPacket>().iter().any(|packet| packet.name == name))?;
+        Ok(client)
+    }
+
+    pub fn get_block(&self, x: i32, y: i32) -> Result<BlockId> {
+        self.server.blocks.get_blocks().get_block(x, y)
+    }
+
+    pub fn set_block(&mut self, x: i32, y: i32, block: BlockId) -> Result<()> {
+        let server = &mut self.server;
+        server.blocks.get_blocks().set_block(&mut server.events, x, y, block)
+    }
+
+    pub fn get_block_id_by_name(&self, name: &str) -> Result<BlockId> {
+        self.server.blocks.get_blocks().get_block_id_by_name(name)
+    }
+
+    pub fn get_item_id_by_name(&self, name: &str) -> Result<ItemId> {
+        Ok(self.server.items.get_items().get_item_type_by_name(name)?.get_id())
+    }
+
+    /// Spawns an item entity without any velocity.
+    pub fn spawn_item(&mut self, item: ItemId, x: f32, y: f32) -> Result<Entity> {
+        let server = &mut self.server;
+        let id = server.entities.entities.new_id();
+        server.items.get_items().spawn_item(&mut server.events, &mut server.entities.entities, item, x, y, id)
+    }
+
+    /// Spawns an item entity holding the whole stack without any velocity.
+    pub fn spawn_item_stack(&mut self, item: ItemId, count: i32, x: f32, y: f32) -> Result<Entity> {
+        let server = &mut self.server;
+        let id = server.entities.entities.new_id();
+        server
+            .items
+            .get_items()
+            .spawn_item_stack(&mut server.events, &mut server.entities.entities, &ItemStack::new(item, count), x, y, id)
+    }
+
+    pub fn spawn_mob(&mut self, name: &str, x: f32, y: f32) -> Result<Entity> {
+        let server = &mut self.server;
+        let mob = server.mobs.get_mobs().get_mob_type_by_name(name)?.get_id();
+        server.mobs.spawn_mob(&mut server.events, &mut server.entities.entities, mob, x, y)
+    }
+
+    pub fn set_health(&mut self, entity: Entity, health: i32) -> Result<()> {
+        let server = &mut self.server;
+        let id = server.entities.entities.get_id_from_entity(entity)?;
+        server.entities.entities.ecs.get::<&mut HealthComponent>(entity)?.set_health(health, &mut server.events, id);
+        Ok(())
+    }
+
+    pub fn get_position(&self, entity: Entity) -> Result<(f32, f32)> {
+        let position = self.server.entities.entities.ecs.get::<&PositionComponent>(entity)?;
+        Ok((position.x(), position.y()))
+    }
+
+    pub fn get_player(&self, player_name: &str) -> Result<Entity> {
+        Ok(*self.server.players.get_player_entity_from_name(player_name, &self.server.entities.entities)?)
+    }
+
+    pub fn get_health(&self, entity: Entity) -> Result<i32> {
+        Ok(self.server.entities.entities.ecs.get::<&HealthComponent>(entity)?.health())
+    }
+
+    /// Puts one of the item into the first slot of the player's inventory and selects it.
+    pub fn select_item(&self, player_name: &str, item: ItemId) -> Result<()> {
+        let player = self.get_player(player_name)?;
+        let mut inventory = self.server.entities.entities.ecs.get::<&mut Inventory>(player)?;
+        inventory.set_item(0, Some(ItemStack::new(item, 1)))?;
+        inventory.selected_slot = Some(0);
+        Ok(())
+    }
+
+    /// Puts the item stack into the given slot of the player's inventory.
+    pub fn set_inventory_item(&self, player_name: &str, slot: usize, item: ItemId, count: i32) -> Result<()> {
+        let player = self.get_player(player_name)?;
+        self.server.entities.entities.ecs.get::<&mut Inventory>(player)?.set_item(slot, Some(ItemStack::new(item, count)))
+    }
+
+    pub fn get_stat_id(&self, name: &str) -> Result<StatId> {
+        Ok(self.server.stats.get_stats().get_stat_type_by_name(name)?.get_id())
+    }
+
+    pub fn set_stat(&mut self, player_name: &str, stat: StatId, value: f32) -> Result<()> {
+        let player = self.get_player(player_name)?;
+        let server = &mut self.server;
+        server.entities.entities.ecs.get::<&mut StatsComponent>(player)?.set_value(stat, value, &mut server.events, player)
+    }
+
+    pub fn read_chat_log(&self) -> Result<String> {
+        Ok(std::fs::read_to_string(self.world_path.with_extension("chat.log"))?)
+    }
+
+    pub fn get_inventory(&self, player_name: &str) -> Result<Inventory> {
+        let player = *self.server.players.get_player_entity_from_name(player_name, &self.server.entities.entities)?;
+        Ok(self.server.entities.entities.ecs.get::<&Inventory>(player)?.deref().clone())
+    }
+}
+
+/// this function writes the dev mod into a directory next to the world and returns the directory
+fn write_dev_mod(world_path: &Path, lua_code: &str) -> Result<PathBuf> {
+    let dev_mod_path = world_path.with_file_name("dev_mod");
+    std::fs::create_dir_all(&dev_mod_path)?;
+    std::fs::write(dev_mod_path.join("dev_mod.lua"), lua_code)?;
+    Ok(dev_mod_path)
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let result = self.server.stop(&self.status_text, &self.world_path);
+        if let Some(world_dir) = self.world_path.parent() {
+            std::fs::remove_dir_all(world_dir).ok();
+        }
+        // panicking again while the test is already failing would abort all tests
+        if std::thread::panicking() {
+            if let Err(error) = result {
+                eprintln!("failed to stop the test server: {error}");
+            }
+        } else {
+            result.unwrap();
+        }
+    }
+}
+
+/// Fake client connects to a test server like a real client would, but instead
+/// of running the game, it records all the packets it receives, so that tests
+/// can assert on them. It can also send any packet to the server.
+pub struct FakeClient {
+    handler: NodeHandler<()>,
+    server_endpoint: Endpoint,
+    packet_receiver: Receiver<Packet>,
+    received_packets: Vec<Packet>,
+    net_loop_thread: Option<JoinHandle<()>>,
+}
+
+impl FakeClient {
+    pub fn connect(server_port: u16, name: &str) -> Result<Self> {
+        let (handler, listener) = node::split::<()>();
+        let (packet_sender, packet_receiver) = mpsc::channel();
+
+        let net_loop_thread = std::thread::spawn(move || {
+            listener.for_each(move |event| {
+                if let NodeEvent::Network(NetEvent::Message(_, data)) = event {
+                    if let Ok(packet) = bincode::deserialize::<Packet>(data) {
+                        packet_sender.send(packet).ok();
+                    }
+                }
+            });
+        });
+
+        let (server_endpoint, _) = handler.network().connect_sync(Transport::FramedTcp, format!("127.0.0.1:{server_port}"))?;
+
+        let client = Self {
+            handler,
+            server_endpoint,
+            packet_receiver,
+            received_packets: Vec::new(),
+            net_loop_thread: Some(net_loop_thread),
+        };
+        client.send(NamePacket { name: name.to_owned() })?;
+        Ok(client)
+    }
+
+    pub fn send<T: serde::Serialize + 'static>(&self, packet: T) -> Result<()> {
+        let data = bincode::serialize(&Packet::new(packet)?)?;
+        self.handler.network().send(self.server_endpoint, &data);
+        Ok(())
+    }
+
+    fn receive(&mut self) {
+        while let Ok(packet) = self.packet_receiver.try_recv() {
+            self.received_packets.push(packet);
+        }
+    }
+
+    /// Returns all the received packets of the given type, that were not taken yet.
+    pub fn received<T: DeserializeOwned + 'static>(&mut self) -> Vec<T> {
+        self.receive();
+        self.received_packets.iter().filter_map(Packet::try_deserialize::<T>).collect()
+    }
+
+    /// Returns all the received packets of the given type and forgets them,
+    /// so that the next wait only sees packets that arrive after this call.
+    pub fn take<T: DeserializeOwned + 'static>(&mut self) -> Vec<T> {
+        let packets = self.received::<T>();
+        self.received_packets.retain(|packet| packet.try_deserialize::<T>().is_none());
+        packets
+    }
+
+    /// Ticks the server until the condition on the client is met.
+    pub fn wait_until<F: FnMut(&mut Self) -> bool>(&mut self, server: &mut TestServer, mut condition: F) -> Result<()> {
+        server.tick_until(|_| Ok(condition(self)))
+    }
+
+    /// Ticks the server until a packet of the given type arrives and returns the first one.
+    pub fn wait_for<T: DeserializeOwned + 'static>(&mut self, server: &mut TestServer) -> Result<T> {
+        self.wait_until(server, |client| !client.received::<T>().is_empty())?;
+        self.received::<T>().into_iter().next().ok_or_else(|| anyhow!("packet was not received"))
+    }
+}
+
+impl Drop for FakeClient {
+    fn drop(&mut self) {
+        self.handler.stop();
+        if let Some(thread) = self.net_loop_thread.take() {
+            thread.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,300 @@
+--[[
+
+This is a tiny mod that is used instead of base_game
+by the server integration tests. It generates a small
+flat world of dirt, which breaks instantly and drops
+a dirt item. It also has a blob mob, which always
+walks right, drops dirt and spawns anywhere, a spike
+mob, which hurts players touching it, a sling,
+which shoots projectiles, a bed, which sets the
+spawn point of players that right click it, a
+grave, which stores the items of dead players, mana,
+which fills up quickly, hunger, which stays full,
+a greet command and a broken command that always fails.
+It logs the events it subscribes to, cancels chat messages
+containing "forbidden" and block placement in the leftmost
+column of the world and fails on the chat message "crash". It also has functions that break the
+rules of the sandbox mods run in.
+
+]]--
+
+MOD_NAME = "test_mod"
+VERSION = "1.0"
+
+blocks = {}
+walls = {}
+tools = {}
+items = {}
+mobs = {}
+stats = {}
+event_log = {}
+ticks = 0
+
+function init()
+    blocks.air = terralistic_get_block_id_by_name("air")
+    blocks.dirt = terralistic_register_block_type(
+            -- effective_tool, required_tool_power
+            nil, 0,
+            -- ghost, transparent
+            false, false,
+            -- name, connects_to
+            "dirt", {},
+            -- break_time
+            1,
+            -- light_emission
+            0, 0, 0,
+            -- size
+            1, 1,
+            -- can_update_states, feet_collidable, clickable
+            false, false, false,
+            -- inventory_slots
+            {}
+    )
+
+    blocks.bed = terralistic_register_block_type(
+            -- effective_tool, required_tool_power
+            nil, 0,
+            -- ghost, transparent
+            true, true,
+            -- name, connects_to
+            "bed", {},
+            -- break_time
+            1,
+            -- light_emission
+            0, 0, 0,
+            -- size
+            2, 1,
+            -- can_update_states, feet_collidable, clickable
+            false, false, true,
+            -- inventory_slots
+            {}
+    )
+    terralistic_set_block_spawn_point(blocks.bed)
+
+    blocks.grave = terralistic_register_block_type(
+            -- effective_tool, required_tool_power
+            nil, 0,
+            -- ghost, transparent
+            true, true,
+            -- name, connects_to
+            "grave", {},
+            -- break_time
+            1,
+            -- light_emission
+            0, 0, 0,
+            -- size
+            1, 1,
+            -- can_update_states, feet_collidable, clickable
+            false, false, false,
+            -- inventory_slots
+            {
+                {0, 0}, {1, 0}, {2, 0}, {3, 0}, {4, 0}, {5, 0}, {6, 0}, {7, 0}, {8, 0}, {9, 0},
+                {0, 1}, {1, 1}, {2, 1}, {3, 1}, {4, 1}, {5, 1}, {6, 1}, {7, 1}, {8, 1}, {9, 1},
+            }
+    )
+
+    walls.dirt = terralistic_register_wall_type("dirt", 1)
+
+    items.dirt = terralistic_register_item_type("dirt", "Dirt Block", 99, blocks.dirt, nil, nil, 0)
+    terralistic_set_block_drop(blocks.dirt, items.dirt, 1)
+
+    items.sling = terralistic_register_item_type("sling", "Sling", 1, nil, nil, nil, 0)
+    terralistic_set_item_weapon(items.sling, { damage = 3, cooldown = 100, projectile_speed = 30 })
+    -- slings can only be crafted next to a bed, which is a crafting station
+    terralistic_register_recipe(items.sling, 1, { items.dirt }, { 3 }, blocks.bed)
+
+    tools.shovel = terralistic_register_tool("shovel")
+    -- the shovel breaks after breaking 10 blocks
+    items.shovel = terralistic_register_item_type("shovel", "Shovel", 1, nil, nil, tools.shovel, 1, 10)
+
+    mobs.blob = terralistic_register_mob_type({
+        name = "blob",
+        behaviour = "idle",
+        ai_function = "blob_ai",
+    })
+    terralistic_set_mob_drop(mobs.blob, items.dirt, 1)
+    terralistic_add_mob_loot(mobs.blob, 1, { { item = items.dirt, weight = 1, min_count = 3, max_count = 3 } })
+
+    mobs.spike = terralistic_register_mob_type({
+        name = "spike",
+        behaviour = "idle",
+        damage = 5,
+    })
+
+    stats.mana = terralistic_register_stat({ name = "mana", max = 10, initial = 0, regen = 5 })
+    stats.hunger = terralistic_register_stat({ name = "hunger", max = 20 })
+end
+
+function init_server()
+    local biome = terralistic_new_biome()
+    biome["min_width"] = 16
+    biome["max_width"] = 17
+    biome["min_terrain_height"] = 0
+    biome["max_terrain_height"] = 0
+    biome["base_block"] = blocks.dirt
+    biome["base_wall"] = walls.dirt
+    biome["generator_function"] = "generate_flat"
+    local flat = terralistic_register_biome(biome)
+    terralistic_connect_biomes(flat, flat, 1)
+
+    terralistic_add_mob_spawn_rule(mobs.blob, { biomes = { flat }, max_count = 1 })
+
+    terralistic_register_command("greet", "Greets the executor", greet_command)
+    terralistic_register_command("broken", "Always fails", broken_command)
+    -- the name of a built in command can't be taken
+    terralistic_register_command("help", "Doesn't help", broken_command)
+
+    terralistic_on("player_join", function(event)
+        log_event("join " .. event.player)
+    end)
+    terralistic_on("player_leave", function(event)
+        log_event("leave " .. event.player)
+    end)
+    terralistic_on("chat", function(event)
+        if string.find(event.message, "forbidden") then
+            event.cancelled = true
+        end
+        if event.message == "crash" then
+            error("crashed on purpose")
+        end
+        log_event("chat " .. event.player .. ": " .. event.message)
+    end)
+    terralistic_on("block_place", function(event)
+        if event.x == 0 then
+            event.cancelled = true
+        end
+        log_event("place " .. event.player .. " " .. event.x .. " " .. event.y .. " dirt " .. tostring(event.block == blocks.dirt))
+    end)
+    terralistic_on("block_change", function(event)
+        log_event("change " .. event.x .. " " .. event.y .. " was air " .. tostring(event.previous_block == blocks.air))
+    end)
+    terralistic_on("item_pickup", function(event)
+        log_event("pickup " .. event.player .. " " .. event.count .. " dirt " .. tostring(event.item == items.dirt))
+    end)
+    terralistic_on("health_change", function(event)
+        if event.player then
+            log_event("health " .. event.player .. " " .. event.health .. "/" .. event.max_health)
+        end
+    end)
+    terralistic_on("entity_death", function(event)
+        if event.player then
+            log_event("death " .. event.player)
+        elseif event.mob == mobs.blob then
+            log_event("death blob")
+        end
+    end)
+    terralistic_on("tick", function(event)
+        ticks = ticks + 1
+    end)
+end
+
+function log_event(text)
+    table.insert(event_log, text)
+end
+
+-- lets the tests check which events the mod has seen
+function get_event_log()
+    return event_log, ticks
+end
+
+-- lets the tests check that only known events can be subscribed to
+function subscribe_to_unknown_event()
+    local success, error = pcall(terralistic_on, "explode", function(event) end)
+    return success, tostring(error)
+end
+
+-- lets the tests check that processing blocks can only use slots their block has
+function register_processing_grave(input_slot, output_slot)
+    local success, error = pcall(terralistic_register_processing_block, blocks.grave, input_slot, output_slot, nil)
+    return success, tostring(error)
+end
+
+function greet_command(executor, arguments)
+    return "Hello " .. (executor or "console") .. ", you said " .. table.concat(arguments, ", ")
+end
+
+function broken_command(executor, arguments)
+    error("this command is broken")
+end
+
+-- lets the tests check what mods see of the world time
+function get_time()
+    return terralistic_get_world_time(), terralistic_is_night()
+end
+
+-- lets the tests check that wall loot depends on the tool the wall is broken with
+function add_shovel_wall_loot(weight)
+    local success, error = pcall(terralistic_add_wall_loot, walls.dirt, 1, { { item = items.sling, weight = weight, tool = tools.shovel } })
+    return success, tostring(error)
+end
+
+-- lets the tests check that the light mobs spawn at changes with the time of day
+function add_bright_spike_spawn_rule()
+    terralistic_add_mob_spawn_rule(mobs.spike, { min_light = 200, max_count = 1 })
+end
+
+-- lets the tests check that mods can change the metadata of the items players have
+function rename_item(player, slot, name)
+    local metadata = terralistic_get_item_metadata(player, slot)
+    metadata.display_name = name
+    metadata.data.renamed_by = "test mod"
+    terralistic_set_item_metadata(player, slot, metadata)
+end
+
+function get_item_name(player, slot)
+    local metadata = terralistic_get_item_metadata(player, slot)
+    return metadata.display_name, metadata.data.renamed_by
+end
+
+-- lets the tests check that mods can change the stats of players
+function feed(player, amount)
+    terralistic_set_player_stat(player, stats.hunger, terralistic_get_player_stat(player, stats.hunger) + amount)
+    return terralistic_get_player_stat(player, stats.hunger)
+end
+
+function blob_ai(x, y, player_x, player_y)
+    return 1, false
+end
+
+-- the bottom half of the world is dirt, the top half is air
+function generate_flat(terrain, heights, width, height)
+    for x = 1, width do
+        for y = 1, height do
+            if y > height / 2 then
+                terrain[x][y] = blocks.dirt
+            else
+                terrain[x][y] = blocks.air
+            end
+        end
+    end
+    return terrain
+end
+
+-- lets the tests check which parts of the standard library mods can reach
+function get_unsafe_functions()
+    return {
+        io = io ~= nil,
+        execute = os.execute ~= nil,
+        remove = os.remove ~= nil,
+        loadfile = loadfile ~= nil,
+        dofile = dofile ~= nil,
+        load = load ~= nil,
+        require = require ~= nil,
+        time = os.time ~= nil,
+    }
+end
+
+function run_forever()
+    while true do end
+end
+
+function use_all_memory()
+    local strings = {}
+    while true do
+        table.insert(strings, string.rep("x", 1000000) .. #strings)
+    end
+end
+
+function stop()
+
+end
@@ -0,0 +1,1690 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fmt::Write;
+
+    use serde_derive::{Deserialize, Serialize};
+
+    use crate::server::server_core::deaths::DeathMode;
+    use crate::server::server_core::test_harness::{FakeClient, TestServer, TEST_WORLD_GROUND_Y, TEST_WORLD_WIDTH};
+    use crate::server::server_core::ManualClock;
+    use crate::server::server_ui::ServerState;
+    use crate::shared::blocks::{BlockChangePacket, BlockId, BlockRightClickPacket, BlocksWelcomePacket, ClientBlockBreakStartPacket};
+    use crate::shared::chat::{complete_last_word, ChatMessageKind, ChatMessagePacket, ChatPacket, CommandCompletionPacket, CommandCompletionRequestPacket};
+    use crate::shared::combat::{get_entity_center, AttackPacket, EntityDamagePacket, ProjectileSpawnPacket};
+    use crate::shared::entities::{EntityDespawnPacket, EntityPositionVelocityPacket, HealthComponent, PhysicsComponent, PositionComponent};
+    use crate::shared::inventory::{
+        Inventory, InventoryCraftPacket, InventoryDropPacket, InventoryMergePacket, InventoryPacket, InventoryQuickMovePacket, InventorySelectPacket, InventorySplitPacket, InventoryTakeOnePacket,
+        Slot,
+    };
+    use crate::shared::items::{ItemComponent, ItemCountChangePacket, ItemId, ItemSpawnPacket, ItemStack, ITEM_PICKUP_DELAY_MS};
+    use crate::shared::mobs::{MobComponent, MobSpawnPacket, Mobs};
+    use crate::shared::mod_manager::{ModsReloadPacket, ModsWelcomePacket};
+    use crate::shared::players::{PlayerSpawnPacket, RespawnPacket, PLAYER_HEIGHT, PLAYER_INVENTORY_SIZE, PLAYER_MAX_HEALTH};
+    use crate::shared::stats::{StatChangePacket, StatId, HEALTH_STAT};
+    use crate::shared::world_map::WorldMap;
+    use crate::shared::world_time::{WorldTimePacket, DAY_LENGTH_MS};
+
+    // the block right below the spawned players
+    const BLOCK_X: i32 = TEST_WORLD_WIDTH / 2;
+    const BLOCK_Y: i32 = TEST_WORLD_GROUND_Y;
+
+    #[test]
+    fn test_client_is_welcomed_and_spawned() {
+        let mut server = TestServer::new().unwrap();
+        let mut client = server.connect("alice").unwrap();
+
+        assert_eq!(client.received::<BlocksWelcomePacket>().len(), 1);
+        let spawn = client.take::<PlayerSpawnPacket>().pop().unwrap();
+        assert_eq!(spawn.name, "alice");
+        assert_eq!(spawn.x as i32, BLOCK_X);
+        assert!(spawn.y < BLOCK_Y as f32);
+    }
+
+    #[test]
+    fn test_other_clients_see_new_player() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        alice.take::<PlayerSpawnPacket>();
+        let mut bob = server.connect("bob").unwrap();
+
+        let spawn = alice.wait_for::<PlayerSpawnPacket>(&mut server).unwrap();
+        assert_eq!(spawn.name, "bob");
+        // bob is told about alice, who was there before him
+        assert!(bob.received::<PlayerSpawnPacket>().iter().any(|packet| packet.name == "alice"));
+    }
+
+    #[test]
+    fn test_broken_block_is_sent_to_all_clients_and_drops_item() {
+        let mut server = TestServer::new().unwrap();
+        let dirt = server.get_block_id_by_name("dirt").unwrap();
+        let air = server.get_block_id_by_name("air").unwrap();
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        assert!(server.get_block(BLOCK_X, BLOCK_Y).unwrap() == dirt);
+
+        let mut alice = server.connect("alice").unwrap();
+        let mut bob = server.connect("bob").unwrap();
+
+        alice.send(ClientBlockBreakStartPacket { x: BLOCK_X, y: BLOCK_Y }).unwrap();
+
+        for client in [&mut alice, &mut bob] {
+            let packet = client.wait_for::<BlockChangePacket>(&mut server).unwrap();
+            assert_eq!((packet.x, packet.y), (BLOCK_X, BLOCK_Y));
+            assert!(packet.block == air);
+
+            let packet = client.wait_for::<ItemSpawnPacket>(&mut server).unwrap();
+            assert_eq!(packet.item_type, dirt_item);
+        }
+        assert!(server.get_block(BLOCK_X, BLOCK_Y).unwrap() == air);
+    }
+
+    #[test]
+    fn test_breaking_a_block_uses_up_the_tool_it_was_started_with() {
+        let mut server = TestServer::new().unwrap();
+        let shovel = server.get_item_id_by_name("shovel").unwrap();
+        let air = server.get_block_id_by_name("air").unwrap();
+        let alice = server.connect("alice").unwrap();
+        server.select_item("alice", shovel).unwrap();
+        server.set_inventory_item("alice", 1, shovel, 1).unwrap();
+
+        alice.send(ClientBlockBreakStartPacket { x: BLOCK_X, y: BLOCK_Y }).unwrap();
+        server.tick_until(|server| Ok(!server.server.blocks.get_blocks().get_breaking_blocks().is_empty())).unwrap();
+        // switching to another tool while breaking doesn't use up the other tool
+        let player = server.get_player("alice").unwrap();
+        server.server.entities.entities.ecs.get::<&mut Inventory>(player).unwrap().selected_slot = Some(1);
+        server.tick_until(|server| Ok(server.get_block(BLOCK_X, BLOCK_Y)? == air)).unwrap();
+
+        let inventory = server.get_inventory("alice").unwrap();
+        assert_eq!(inventory.get_item(0).unwrap().unwrap().metadata.durability, Some(9));
+        assert_eq!(inventory.get_item(1).unwrap().unwrap().metadata.durability, None);
+    }
+
+    #[test]
+    fn test_dropped_item_is_picked_up() {
+        let mut server = TestServer::new().unwrap();
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        let alice = server.connect("alice").unwrap();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt_item), 0);
+
+        alice.send(ClientBlockBreakStartPacket { x: BLOCK_X, y: BLOCK_Y }).unwrap();
+
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(dirt_item) == 1)).unwrap();
+    }
+
+    #[test]
+    fn test_update_follows_clock() {
+        let mut server = TestServer::new().unwrap();
+        let clock = ManualClock::new();
+        server.server.set_clock(clock.clone());
+        // the first update only starts measuring time
+        server.server.update().unwrap();
+
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        let item = server.spawn_item(dirt_item, BLOCK_X as f32, 5.0).unwrap();
+        for _ in 0..3 {
+            server.server.update().unwrap();
+        }
+        assert_eq!(server.get_position(item).unwrap(), (BLOCK_X as f32, 5.0));
+
+        clock.advance(100.0);
+        server.server.update().unwrap();
+        assert!(server.get_position(item).unwrap().1 > 5.0);
+    }
+
+    #[test]
+    fn test_step_ignores_clock() {
+        let mut server = TestServer::new().unwrap();
+        server.server.set_clock(ManualClock::new());
+
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        let item = server.spawn_item(dirt_item, BLOCK_X as f32, 5.0).unwrap();
+        server.server.step(2).unwrap();
+        assert!(server.get_position(item).unwrap().1 > 5.0);
+    }
+
+    #[test]
+    fn test_step_is_deterministic() {
+        // two servers run side by side in one process and don't affect each other
+        let mut server1 = TestServer::new().unwrap();
+        let mut server2 = TestServer::new().unwrap();
+        let dirt_item = server1.get_item_id_by_name("dirt").unwrap();
+        let item1 = server1.spawn_item(dirt_item, BLOCK_X as f32, 5.0).unwrap();
+        let item2 = server2.spawn_item(dirt_item, BLOCK_X as f32, 5.0).unwrap();
+
+        server1.server.step(10).unwrap();
+        for _ in 0..10 {
+            server2.server.step(1).unwrap();
+        }
+
+        let position = server1.get_position(item1).unwrap();
+        assert!(position.1 > 5.0);
+        assert_eq!(position, server2.get_position(item2).unwrap());
+    }
+
+    #[test]
+    fn test_mob_is_synced_to_clients() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mob = server.spawn_mob("blob", 10.0, 20.0).unwrap();
+        let id = server.server.entities.entities.get_id_from_entity(mob).unwrap();
+
+        alice
+            .wait_until(&mut server, |client| client.received::<MobSpawnPacket>().iter().any(|packet| packet.id == id))
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| client.received::<EntityPositionVelocityPacket>().iter().any(|packet| packet.id == id))
+            .unwrap();
+
+        // clients that join later are told about the mobs that are already there
+        let mut bob = server.connect("bob").unwrap();
+        bob.wait_until(&mut server, |client| client.received::<MobSpawnPacket>().iter().any(|packet| packet.id == id)).unwrap();
+    }
+
+    #[test]
+    fn test_mob_ai_function_moves_mob() {
+        let mut server = TestServer::new().unwrap();
+        let mob = server.spawn_mob("blob", 10.0, (BLOCK_Y - 1) as f32).unwrap();
+        server.server.step(20).unwrap();
+        // the blob is idle, but its ai function makes it walk right
+        assert!(server.get_position(mob).unwrap().0 > 11.0);
+    }
+
+    #[test]
+    fn test_dead_mob_drops_loot() {
+        let mut server = TestServer::new().unwrap();
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mob = server.spawn_mob("blob", 10.0, (BLOCK_Y - 1) as f32).unwrap();
+        let id = server.server.entities.entities.get_id_from_entity(mob).unwrap();
+        server.tick().unwrap();
+
+        server.set_health(mob, 0).unwrap();
+        alice
+            .wait_until(&mut server, |client| client.received::<EntityDespawnPacket>().iter().any(|packet| packet.id == id))
+            .unwrap();
+        let packet = alice.wait_for::<ItemSpawnPacket>(&mut server).unwrap();
+        assert_eq!(packet.item_type, dirt_item);
+        assert!(!server.server.entities.entities.ecs.contains(mob));
+        // each rolled stack is dropped as one entity
+        alice
+            .wait_until(&mut server, |client| client.received::<ItemSpawnPacket>().iter().any(|packet| packet.count == 3))
+            .unwrap();
+        let mut counts: Vec<i32> = alice.received::<ItemSpawnPacket>().iter().map(|packet| packet.count).collect();
+        counts.sort_unstable();
+        assert_eq!(counts, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_mobs_spawn_near_players() {
+        let mut server = TestServer::new().unwrap();
+        server.server.step(100).unwrap();
+        // no mobs spawn without players
+        assert_eq!(server.server.entities.entities.ecs.query_mut::<&MobComponent>().into_iter().count(), 0);
+
+        let mut alice = server.connect("alice").unwrap();
+        let packet = alice.wait_for::<MobSpawnPacket>(&mut server).unwrap();
+        assert!((packet.x - BLOCK_X as f32).abs() >= 9.0);
+        // the mob stands on the ground
+        assert!((packet.y - (BLOCK_Y - 1) as f32).abs() < 0.1);
+
+        // the spawn rule allows only one blob
+        server.server.step(500).unwrap();
+        assert_eq!(server.server.entities.entities.ecs.query_mut::<&MobComponent>().into_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_mob_spawn_light_follows_time_of_day() {
+        let mut server = TestServer::new().unwrap();
+        // the sky is too dark at midnight
+        server.server.world_time.set_time_ms(DAY_LENGTH_MS * 3 / 4);
+        server.server.mods.mod_manager.get_mod(0).unwrap().call_function::<_, ()>("add_bright_spike_spawn_rule", ()).unwrap();
+        let spike = server.server.mobs.get_mobs().get_mob_type_by_name("spike").unwrap().get_id();
+        let _alice = server.connect("alice").unwrap();
+
+        server.server.step(1000).unwrap();
+        assert_eq!(Mobs::count_mobs(&server.server.entities.entities, spike), 0);
+
+        server.server.world_time.set_time_ms(DAY_LENGTH_MS / 4);
+        server.tick_until(|server| Ok(Mobs::count_mobs(&server.server.entities.entities, spike) == 1)).unwrap();
+    }
+
+    #[test]
+    fn test_player_attacks_player_in_reach() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mut bob = server.connect("bob").unwrap();
+        let bob_player = server.get_player("bob").unwrap();
+        let bob_id = server.server.entities.entities.get_id_from_entity(bob_player).unwrap();
+        server.server.step(10).unwrap();
+
+        let (x, y) = get_entity_center(&server.server.entities.entities, bob_player).unwrap();
+        alice.send(AttackPacket { x, y }).unwrap();
+
+        let packet = alice.wait_for::<EntityDamagePacket>(&mut server).unwrap();
+        assert_eq!((packet.id, packet.damage), (bob_id, 1));
+        bob.wait_until(&mut server, |client| {
+            client
+                .received::<StatChangePacket>()
+                .iter()
+                .any(|packet| packet.stat == HEALTH_STAT && packet.value == PLAYER_MAX_HEALTH - 1)
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_attack_out_of_reach_is_ignored() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let mob = server.spawn_mob("spike", (BLOCK_X + 10) as f32, (BLOCK_Y - 1) as f32).unwrap();
+        server.server.step(10).unwrap();
+
+        let (x, y) = get_entity_center(&server.server.entities.entities, mob).unwrap();
+        alice.send(AttackPacket { x, y }).unwrap();
+        for _ in 0..50 {
+            server.tick().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(server.get_health(mob).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_projectile_hurts_mob() {
+        let mut server = TestServer::new().unwrap();
+        let sling = server.get_item_id_by_name("sling").unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        server.select_item("alice", sling).unwrap();
+        let mob = server.spawn_mob("spike", (BLOCK_X + 8) as f32, (BLOCK_Y - 1) as f32).unwrap();
+        let mob_id = server.server.entities.entities.get_id_from_entity(mob).unwrap();
+        server.server.step(10).unwrap();
+
+        // the projectile falls a bit, so it is aimed at the top of the mob
+        let (x, y) = get_entity_center(&server.server.entities.entities, mob).unwrap();
+        alice.send(AttackPacket { x, y: y - 0.4 }).unwrap();
+
+        alice.wait_for::<ProjectileSpawnPacket>(&mut server).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<EntityDamagePacket>().iter().any(|packet| packet.id == mob_id && packet.damage == 3)
+            })
+            .unwrap();
+        assert_eq!(server.get_health(mob).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_mob_hurts_touching_player() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        server.spawn_mob("spike", x, y).unwrap();
+
+        alice
+            .wait_until(&mut server, |client| {
+                client
+                    .received::<StatChangePacket>()
+                    .iter()
+                    .any(|packet| packet.stat == HEALTH_STAT && packet.value == PLAYER_MAX_HEALTH - 5)
+            })
+            .unwrap();
+        // the player is knocked away from the mob
+        assert!(server.get_position(player).unwrap().0 > x);
+    }
+
+    #[test]
+    fn test_world_time_is_synced_and_advances() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let packet = alice.wait_for::<WorldTimePacket>(&mut server).unwrap();
+
+        let before = server.server.world_time.get_time().get_time_ms();
+        assert!(before >= packet.time.get_time_ms());
+        server.server.step(20).unwrap();
+        assert_eq!(server.server.world_time.get_time().get_time_ms(), before + 1000);
+    }
+
+    #[test]
+    fn test_time_command() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/time set night".to_owned(),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| client.received::<WorldTimePacket>().iter().any(|packet| packet.time.is_night()))
+            .unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: format!("/time add {DAY_LENGTH_MS}"),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| client.received::<WorldTimePacket>().iter().any(|packet| packet.time.get_day() == 1))
+            .unwrap();
+
+        alice.send(ChatPacket { message: "/time query".to_owned() }).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client
+                    .received::<ChatMessagePacket>()
+                    .iter()
+                    .any(|packet| packet.message.get_text().contains("day 1") && packet.message.get_text().ends_with("night)"))
+            })
+            .unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/time set yesterday".to_owned(),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text().starts_with("Error"))
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mods_can_read_world_time() {
+        let mut server = TestServer::new().unwrap();
+        server.server.world_time.set_time_ms(DAY_LENGTH_MS * 3 / 4);
+        let (time, is_night) = server.server.mods.mod_manager.get_mod(0).unwrap().call_function::<_, (i64, bool)>("get_time", ()).unwrap();
+        assert_eq!(time, DAY_LENGTH_MS * 3 / 4);
+        assert!(is_night);
+    }
+
+    #[test]
+    fn test_mods_can_change_item_metadata() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 3, dirt, 2).unwrap();
+        server.tick().unwrap();
+
+        let test_mod = server.server.mods.mod_manager.get_mod(0).unwrap();
+        test_mod.call_function::<_, ()>("rename_item", ("alice", 3, "Fancy Dirt")).unwrap();
+        // empty slots have no metadata to change
+        assert!(test_mod.call_function::<_, ()>("rename_item", ("alice", 4, "Nothing")).is_err());
+        // mods see their change right away
+        let (name, renamed_by) = test_mod.call_function::<_, (String, String)>("get_item_name", ("alice", 3)).unwrap();
+        assert_eq!(name, "Fancy Dirt");
+        assert_eq!(renamed_by, "test mod");
+
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<InventoryPacket>().iter().any(|packet| {
+                    packet
+                        .inventory
+                        .get_item(3)
+                        .unwrap()
+                        .is_some_and(|stack| stack.count == 2 && stack.metadata.display_name.as_deref() == Some("Fancy Dirt"))
+                })
+            })
+            .unwrap();
+    }
+
+    // kills the player and waits until they respawn, returning where they respawned
+    fn kill_and_respawn(server: &mut TestServer, client: &mut FakeClient, name: &str) -> PlayerSpawnPacket {
+        let player = server.get_player(name).unwrap();
+        server.set_health(player, 0).unwrap();
+        server.tick().unwrap();
+        client.take::<PlayerSpawnPacket>();
+
+        client.send(RespawnPacket).unwrap();
+        client.wait_for::<PlayerSpawnPacket>(server).unwrap()
+    }
+
+    #[test]
+    fn test_setworldspawn_command() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: format!("/setworldspawn 10 {}", BLOCK_Y as f32 - PLAYER_HEIGHT),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text().starts_with("Set the world spawn"))
+            })
+            .unwrap();
+
+        let mut bob = server.connect("bob").unwrap();
+        let spawn = bob.take::<PlayerSpawnPacket>().into_iter().find(|packet| packet.name == "bob").unwrap();
+        assert_eq!(spawn.x as i32, 10);
+
+        // the world spawn is saved with the world
+        let saved = server.server.players.spawn_points.serialize().unwrap();
+        server.server.players.spawn_points.set_world_spawn(0.0, 0.0);
+        server.server.players.spawn_points.deserialize(&saved).unwrap();
+        let spawn = kill_and_respawn(&mut server, &mut alice, "alice");
+        assert_eq!(spawn.x as i32, 10);
+    }
+
+    #[test]
+    fn test_recipe_needs_station_in_range() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let sling = server.get_item_id_by_name("sling").unwrap();
+        let bed = server.get_block_id_by_name("bed").unwrap();
+        let recipe = server.server.items.get_items().get_recipes().iter().find(|recipe| recipe.result.item == sling).unwrap().get_id();
+        server.set_inventory_item("alice", 0, dirt, 6).unwrap();
+
+        // the packet is ignored without a bed nearby, selecting a slot afterwards shows it arrived
+        alice.send(InventoryCraftPacket { recipe }).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(5) }).unwrap();
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.selected_slot == Some(5))).unwrap();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 6);
+
+        server.set_block(BLOCK_X + 2, BLOCK_Y - 1, bed).unwrap();
+        alice.send(InventoryCraftPacket { recipe }).unwrap();
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(sling) == 1)).unwrap();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 3);
+    }
+
+    #[test]
+    fn test_player_respawns_at_bed() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let bed = server.get_block_id_by_name("bed").unwrap();
+        server.set_block(BLOCK_X - 4, BLOCK_Y - 1, bed).unwrap();
+        server.tick().unwrap();
+
+        // clicking any part of the bed sets the spawn point
+        alice.send(BlockRightClickPacket { x: BLOCK_X - 3, y: BLOCK_Y - 1 }).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "Spawn point set")
+            })
+            .unwrap();
+
+        let spawn = kill_and_respawn(&mut server, &mut alice, "alice");
+        assert_eq!(spawn.name, "alice");
+        assert!(((BLOCK_X - 5) as f32..(BLOCK_X - 2) as f32).contains(&spawn.x));
+        assert!((spawn.y + PLAYER_HEIGHT - BLOCK_Y as f32).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_broken_bed_falls_back_to_world_spawn() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let bed = server.get_block_id_by_name("bed").unwrap();
+        server.set_block(BLOCK_X - 4, BLOCK_Y - 1, bed).unwrap();
+        server.tick().unwrap();
+
+        alice.send(BlockRightClickPacket { x: BLOCK_X - 4, y: BLOCK_Y - 1 }).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "Spawn point set")
+            })
+            .unwrap();
+
+        let air = server.get_block_id_by_name("air").unwrap();
+        server.set_block(BLOCK_X - 4, BLOCK_Y - 1, air).unwrap();
+        server.set_block(BLOCK_X - 3, BLOCK_Y - 1, air).unwrap();
+
+        let spawn = kill_and_respawn(&mut server, &mut alice, "alice");
+        assert_eq!(spawn.x as i32, BLOCK_X);
+        assert!(alice
+            .received::<ChatMessagePacket>()
+            .iter()
+            .any(|packet| packet.message.get_text() == "Your bed was missing or obstructed"));
+
+        // the lost bed is forgotten, so the player is only told once
+        alice.take::<ChatMessagePacket>();
+        let spawn = kill_and_respawn(&mut server, &mut alice, "alice");
+        assert_eq!(spawn.x as i32, BLOCK_X);
+        assert!(!alice
+            .received::<ChatMessagePacket>()
+            .iter()
+            .any(|packet| packet.message.get_text() == "Your bed was missing or obstructed"));
+    }
+
+    #[test]
+    fn test_non_bed_blocks_do_not_set_spawn_point() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice.send(BlockRightClickPacket { x: BLOCK_X, y: BLOCK_Y }).unwrap();
+        for _ in 0..10 {
+            server.tick().unwrap();
+        }
+        assert!(alice.received::<ChatMessagePacket>().is_empty());
+    }
+
+    #[test]
+    fn test_unreachable_beds_do_not_set_spawn_point() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let bed = server.get_block_id_by_name("bed").unwrap();
+        server.set_block(BLOCK_X + 20, BLOCK_Y - 1, bed).unwrap();
+
+        // the bed is too far away and the other block is outside the world
+        alice.send(BlockRightClickPacket { x: BLOCK_X + 20, y: BLOCK_Y - 1 }).unwrap();
+        alice.send(BlockRightClickPacket { x: -5, y: BLOCK_Y }).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(5) }).unwrap();
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.selected_slot == Some(5))).unwrap();
+        assert!(alice.received::<ChatMessagePacket>().is_empty());
+
+        let spawn = kill_and_respawn(&mut server, &mut alice, "alice");
+        assert_eq!(spawn.x as i32, BLOCK_X);
+    }
+
+    fn count_item_entities(server: &TestServer) -> Vec<(ItemId, i32)> {
+        let mut query = server.server.entities.entities.ecs.query::<&ItemComponent>();
+        query.iter().map(|(_, item)| (item.get_item_type(), item.get_count())).collect()
+    }
+
+    #[test]
+    fn test_wall_loot_uses_the_tool_the_wall_is_broken_with() {
+        let mut server = TestServer::new().unwrap();
+        let test_mod = server.server.mods.mod_manager.get_mod(0).unwrap();
+        let (success, error) = test_mod.call_function::<_, (bool, String)>("add_shovel_wall_loot", -1.0).unwrap();
+        assert!(!success);
+        assert!(error.contains("weight must be a non-negative number"));
+        let (success, _) = test_mod.call_function::<_, (bool, String)>("add_shovel_wall_loot", 1.0).unwrap();
+        assert!(success);
+
+        let sling = server.get_item_id_by_name("sling").unwrap();
+        let shovel = server.server.blocks.get_blocks().get_tool_id_by_name(&"shovel".to_owned()).unwrap();
+        {
+            let mut walls = server.server.walls.get_walls();
+            walls.start_breaking_wall(BLOCK_X - 5, BLOCK_Y + 2, None, 0).unwrap();
+            walls.start_breaking_wall(BLOCK_X + 5, BLOCK_Y + 2, Some(shovel), 1).unwrap();
+        }
+        server.server.step(5).unwrap();
+        assert!(count_item_entities(&server) == vec![(sling, 1)]);
+    }
+
+    #[test]
+    fn test_death_drops_stacks_and_is_announced() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mut bob = server.connect("bob").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 30).unwrap();
+        server.set_inventory_item("alice", 5, dirt, 2).unwrap();
+
+        let player = server.get_player("alice").unwrap();
+        server.set_health(player, 0).unwrap();
+        server.tick().unwrap();
+
+        let mut drops = count_item_entities(&server);
+        drops.sort_by_key(|(_, count)| *count);
+        assert!(drops == vec![(dirt, 2), (dirt, 30)]);
+        bob.wait_until(&mut server, |client| {
+            client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "alice died")
+        })
+        .unwrap();
+
+        alice.take::<PlayerSpawnPacket>();
+        alice.send(RespawnPacket).unwrap();
+        alice.wait_for::<PlayerSpawnPacket>(&mut server).unwrap();
+        assert!(server.get_inventory("alice").unwrap().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_keep_inventory_death_mode() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 30).unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/deathmode keep".to_owned(),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "Set the death mode to keep")
+            })
+            .unwrap();
+
+        kill_and_respawn(&mut server, &mut alice, "alice");
+        assert!(count_item_entities(&server).is_empty());
+        let inventory = server.get_inventory("alice").unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(dirt, 30)));
+    }
+
+    #[test]
+    fn test_grave_death_mode_stores_items() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 30).unwrap();
+        server.server.players.death_mode = DeathMode::Grave;
+
+        kill_and_respawn(&mut server, &mut alice, "alice");
+        assert!(count_item_entities(&server).is_empty());
+        assert!(server.get_inventory("alice").unwrap().iter().all(Option::is_none));
+
+        // the grave stands on the ground where the player died
+        let grave = server.get_block_id_by_name("grave").unwrap();
+        assert!(server.get_block(BLOCK_X + 1, BLOCK_Y - 1).unwrap() == grave);
+        let blocks = server.server.blocks.get_blocks();
+        let inventory = blocks.get_block_inventory_data(BLOCK_X + 1, BLOCK_Y - 1).unwrap().unwrap();
+        assert!(inventory.first().unwrap() == &Some(ItemStack::new(dirt, 30)));
+    }
+
+    #[test]
+    fn test_breaking_grave_drops_its_items() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 30).unwrap();
+        server.server.players.death_mode = DeathMode::Grave;
+        kill_and_respawn(&mut server, &mut alice, "alice");
+
+        // the grave is right below the player, who would pick the items up
+        let player = server.get_player("alice").unwrap();
+        server.server.entities.entities.ecs.get::<&mut PositionComponent>(player).unwrap().set_x(5.0);
+        server.server.blocks.get_blocks().break_block(&mut server.server.events, BLOCK_X + 1, BLOCK_Y - 1, None, 0).unwrap();
+        server.tick().unwrap();
+        assert!(count_item_entities(&server) == vec![(dirt, 30)]);
+    }
+
+    #[test]
+    fn test_death_message_names_the_killer() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let player = server.get_player("alice").unwrap();
+        server.set_health(player, 1).unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        server.spawn_mob("spike", x, y).unwrap();
+
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "alice was slain by spike")
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_close_item_stacks_merge() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let item = server.spawn_item_stack(dirt, 3, 5.0, 5.0).unwrap();
+        server.spawn_item_stack(dirt, 4, 5.5, 5.0).unwrap();
+        server.tick().unwrap();
+
+        assert!(count_item_entities(&server) == vec![(dirt, 7)]);
+        assert_eq!(server.server.entities.entities.ecs.get::<&ItemComponent>(item).unwrap().get_count(), 7);
+        alice
+            .wait_until(&mut server, |client| client.received::<ItemCountChangePacket>().iter().any(|packet| packet.count == 7))
+            .unwrap();
+        alice.wait_for::<EntityDespawnPacket>(&mut server).unwrap();
+    }
+
+    #[test]
+    fn test_items_do_not_merge_over_max_stack() {
+        let mut server = TestServer::new().unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let sling = server.get_item_id_by_name("sling").unwrap();
+        server.spawn_item_stack(dirt, 60, 5.0, 5.0).unwrap();
+        server.spawn_item_stack(dirt, 60, 5.0, 5.0).unwrap();
+        server.spawn_item_stack(sling, 1, 5.0, 5.0).unwrap();
+        server.spawn_item_stack(sling, 1, 5.0, 5.0).unwrap();
+        server.spawn_item_stack(dirt, 10, 20.0, 5.0).unwrap();
+        server.tick().unwrap();
+
+        let mut items = count_item_entities(&server);
+        items.sort_by_key(|(_, count)| *count);
+        assert!(items == vec![(sling, 1), (sling, 1), (dirt, 10), (dirt, 60), (dirt, 60)]);
+    }
+
+    #[test]
+    fn test_item_despawns_after_lifetime() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.server.items.get_items().set_item_lifetime(200);
+        let item = server.spawn_item_stack(dirt, 5, 5.0, 5.0).unwrap();
+
+        server.server.step(2).unwrap();
+        assert!(server.server.entities.entities.ecs.contains(item));
+        server.server.step(3).unwrap();
+        assert!(!server.server.entities.entities.ecs.contains(item));
+        alice.wait_for::<EntityDespawnPacket>(&mut server).unwrap();
+    }
+
+    #[test]
+    fn test_dropped_item_has_pickup_delay() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        let server_core = &mut server.server;
+        server_core
+            .items
+            .get_items()
+            .drop_item_stack(&mut server_core.events, &mut server_core.entities.entities, &ItemStack::new(dirt, 3), x, y)
+            .unwrap();
+
+        let packet = alice.wait_for::<ItemSpawnPacket>(&mut server).unwrap();
+        // the delay could have already been counting down for a tick
+        assert_eq!(packet.count, 3);
+        assert!(packet.pickup_delay_ms > 0 && packet.pickup_delay_ms <= ITEM_PICKUP_DELAY_MS);
+        server.server.step(5).unwrap();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 0);
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(dirt) == 3)).unwrap();
+    }
+
+    #[test]
+    fn test_full_inventory_picks_up_part_of_stack() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let sling = server.get_item_id_by_name("sling").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 95).unwrap();
+        for slot in 1..PLAYER_INVENTORY_SIZE {
+            server.set_inventory_item("alice", slot, sling, 1).unwrap();
+        }
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        let item = server.spawn_item_stack(dirt, 10, x, y).unwrap();
+
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(dirt) == 99)).unwrap();
+        server.server.step(5).unwrap();
+        assert!(count_item_entities(&server) == vec![(dirt, 6)]);
+        assert!(server.server.entities.entities.ecs.contains(item));
+        alice
+            .wait_until(&mut server, |client| client.received::<ItemCountChangePacket>().iter().any(|packet| packet.count == 6))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_new_client_receives_existing_items() {
+        let mut server = TestServer::new().unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.spawn_item_stack(dirt, 5, 5.0, 5.0).unwrap();
+        server.tick().unwrap();
+
+        let mut alice = server.connect("alice").unwrap();
+        let packet = alice.wait_for::<ItemSpawnPacket>(&mut server).unwrap();
+        assert_eq!((packet.item_type, packet.count), (dirt, 5));
+    }
+
+    #[test]
+    fn test_player_drops_selected_item() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 5).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(0) }).unwrap();
+
+        alice.send(InventoryDropPacket { whole_stack: false }).unwrap();
+        let packet = alice.wait_for::<ItemSpawnPacket>(&mut server).unwrap();
+        assert_eq!((packet.item_type, packet.count), (dirt, 1));
+        // players face right when they spawn
+        assert!(packet.velocity_x > 0.0 && packet.velocity_y < 0.0);
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 4);
+
+        alice.send(InventoryDropPacket { whole_stack: true }).unwrap();
+        alice
+            .wait_until(&mut server, |client| client.received::<ItemSpawnPacket>().iter().any(|packet| packet.count == 4))
+            .unwrap();
+        // the first item could have been picked up again in the meantime, but no items are lost
+        let on_ground: i32 = count_item_entities(&server).iter().map(|(_, count)| count).sum();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt) + on_ground, 5);
+    }
+
+    #[test]
+    fn test_dropping_empty_slot_is_ignored() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        alice.send(InventorySelectPacket { slot: Some(3) }).unwrap();
+        alice.send(InventoryDropPacket { whole_stack: true }).unwrap();
+
+        server.server.step(5).unwrap();
+        assert!(count_item_entities(&server).is_empty());
+    }
+
+    #[test]
+    fn test_unusable_slots_are_ignored() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let grave = server.get_block_id_by_name("grave").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        let far_x = BLOCK_X + 20;
+        for x in [BLOCK_X, far_x] {
+            server.set_block(x, BLOCK_Y, grave).unwrap();
+            let server = &mut server.server;
+            let mut slots = vec![None; server.blocks.get_blocks().get_block_inventory_size(x, BLOCK_Y).unwrap() as usize];
+            *slots.first_mut().unwrap() = Some(ItemStack::new(dirt, 4));
+            server.blocks.get_blocks().set_block_inventory_data(x, BLOCK_Y, slots, &mut server.events).unwrap();
+        }
+        server.set_inventory_item("alice", 1, dirt, 2).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(0) }).unwrap();
+
+        // the grave is too far away, the slots don't exist or aren't blocks with inventories
+        alice.send(InventoryTakeOnePacket { slot: Slot::Block(far_x, BLOCK_Y, 0) }).unwrap();
+        alice
+            .send(InventorySplitPacket {
+                slot: Slot::Block(BLOCK_X, BLOCK_Y, 99),
+            })
+            .unwrap();
+        alice
+            .send(InventorySplitPacket {
+                slot: Slot::Block(BLOCK_X, BLOCK_Y - 1, 0),
+            })
+            .unwrap();
+        alice
+            .send(InventoryQuickMovePacket {
+                slot: Slot::Inventory(999),
+                block: None,
+            })
+            .unwrap();
+        alice
+            .send(InventoryQuickMovePacket {
+                slot: Slot::Inventory(1),
+                block: Some((far_x, BLOCK_Y)),
+            })
+            .unwrap();
+        // the grave below the player is in range
+        alice
+            .send(InventoryTakeOnePacket {
+                slot: Slot::Block(BLOCK_X, BLOCK_Y, 0),
+            })
+            .unwrap();
+
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item(0)?.is_some())).unwrap();
+        let inventory = server.get_inventory("alice").unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(dirt, 1)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(dirt, 2)));
+        let blocks = server.server.blocks.get_blocks();
+        assert!(blocks.get_block_inventory_data(far_x, BLOCK_Y).unwrap().unwrap().first().unwrap() == &Some(ItemStack::new(dirt, 4)));
+        assert!(blocks.get_block_inventory_data(BLOCK_X, BLOCK_Y).unwrap().unwrap().first().unwrap() == &Some(ItemStack::new(dirt, 3)));
+    }
+
+    #[test]
+    fn test_out_of_range_inventory_slots_are_ignored() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 2).unwrap();
+        server.set_inventory_item("alice", 1, dirt, 3).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(0) }).unwrap();
+
+        alice.send(InventoryMergePacket { slot: 999 }).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(999) }).unwrap();
+        // the selected slot is still the first one, so a single item of it is dropped
+        alice.send(InventoryDropPacket { whole_stack: false }).unwrap();
+
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(dirt) == 4)).unwrap();
+        let inventory = server.get_inventory("alice").unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(dirt, 1)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(dirt, 3)));
+    }
+
+    #[test]
+    fn test_thrown_item_is_not_picked_up_right_away() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 5).unwrap();
+        alice.send(InventorySelectPacket { slot: Some(0) }).unwrap();
+        alice.send(InventoryDropPacket { whole_stack: true }).unwrap();
+
+        server.tick_until(|server| Ok(server.get_inventory("alice")?.get_item_count(dirt) == 0)).unwrap();
+        server.server.step(10).unwrap();
+        assert!(count_item_entities(&server) == vec![(dirt, 5)]);
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 0);
+    }
+
+    fn received_stat(client: &mut FakeClient, stat: StatId, value: i32) -> bool {
+        client.received::<StatChangePacket>().iter().any(|packet| packet.stat == stat && packet.value == value)
+    }
+
+    #[test]
+    fn test_player_receives_stats() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let hunger = server.get_stat_id("hunger").unwrap();
+
+        alice
+            .wait_until(&mut server, |client| {
+                let packets = client.received::<StatChangePacket>();
+                let has_stat = |stat, value, max_value| packets.iter().any(|packet| packet.stat == stat && packet.value == value && packet.max_value == max_value);
+                has_stat(HEALTH_STAT, PLAYER_MAX_HEALTH, PLAYER_MAX_HEALTH) && has_stat(hunger, 20, 20)
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_stats_regenerate() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mana = server.get_stat_id("mana").unwrap();
+
+        alice.wait_until(&mut server, |client| received_stat(client, mana, 0)).unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, mana, 10)).unwrap();
+    }
+
+    #[test]
+    fn test_health_regenerates_when_enabled() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let player = server.get_player("alice").unwrap();
+        server.set_health(player, 50).unwrap();
+        server.server.step(20).unwrap();
+        assert_eq!(server.get_health(player).unwrap(), 50);
+
+        server.server.stats.get_stats().get_stat_type_mut(HEALTH_STAT).unwrap().regen = 10.0;
+        alice.wait_until(&mut server, |client| received_stat(client, HEALTH_STAT, 55)).unwrap();
+    }
+
+    #[test]
+    fn test_earlier_hits_are_not_the_cause_of_falls_and_kills() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        let spike = server.spawn_mob("spike", x, y).unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, HEALTH_STAT, PLAYER_MAX_HEALTH - 5)).unwrap();
+        server.server.entities.entities.ecs.despawn(spike).unwrap();
+
+        // the player lands on the ground fast enough to die
+        server.set_health(player, 1).unwrap();
+        server.server.entities.entities.ecs.get::<&mut PhysicsComponent>(player).unwrap().velocity_y = 200.0;
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "alice died")
+            })
+            .unwrap();
+
+        alice.take::<PlayerSpawnPacket>();
+        alice.send(RespawnPacket).unwrap();
+        alice.wait_for::<PlayerSpawnPacket>(&mut server).unwrap();
+        alice.take::<StatChangePacket>();
+        alice.take::<ChatMessagePacket>();
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        let spike = server.spawn_mob("spike", x, y).unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, HEALTH_STAT, PLAYER_MAX_HEALTH - 5)).unwrap();
+        server.server.entities.entities.ecs.despawn(spike).unwrap();
+        server.server.execute_command("kill alice", None).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "alice died")
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_empty_stat_starves_player() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let hunger = server.get_stat_id("hunger").unwrap();
+        // the spike hurts the player first, but they die of hunger
+        let player = server.get_player("alice").unwrap();
+        let (x, y) = server.get_position(player).unwrap();
+        let spike = server.spawn_mob("spike", x, y).unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, HEALTH_STAT, PLAYER_MAX_HEALTH - 5)).unwrap();
+        server.server.entities.entities.ecs.despawn(spike).unwrap();
+
+        server.server.stats.get_stats().get_stat_type_mut(hunger).unwrap().empty_damage = 100.0;
+        server.set_stat("alice", hunger, 0.0).unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, hunger, 0)).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == "alice died")
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mods_can_change_player_stats() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let hunger = server.get_stat_id("hunger").unwrap();
+        server.tick().unwrap();
+
+        let test_mod = server.server.mods.mod_manager.get_mod(0).unwrap();
+        // mods see their change right away and the value stays between 0 and the max value
+        assert_eq!(test_mod.call_function::<_, i32>("feed", ("alice", -5)).unwrap(), 15);
+        assert_eq!(test_mod.call_function::<_, i32>("feed", ("alice", 100)).unwrap(), 20);
+        assert_eq!(test_mod.call_function::<_, i32>("feed", ("alice", -30)).unwrap(), 0);
+        test_mod.call_function::<_, i32>("feed", ("bob", 1)).unwrap_err();
+
+        alice.wait_until(&mut server, |client| received_stat(client, hunger, 0)).unwrap();
+    }
+
+    #[test]
+    fn test_stats_are_saved() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let hunger = server.get_stat_id("hunger").unwrap();
+        server.set_stat("alice", hunger, 7.0).unwrap();
+        drop(alice);
+        server.tick_until(|server| Ok(server.get_player("alice").is_err())).unwrap();
+
+        let mut alice = server.connect("alice").unwrap();
+        alice.wait_until(&mut server, |client| received_stat(client, hunger, 7)).unwrap();
+    }
+
+    // this is how worlds were saved before item stacks had metadata and players had stats
+    #[derive(Clone, Serialize, Deserialize)]
+    struct OldItemStack {
+        item: ItemId,
+        count: i32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct OldBlocksData {
+        map: WorldMap,
+        blocks: Vec<BlockId>,
+        block_from_main: HashMap<usize, (i32, i32)>,
+        block_data: HashMap<usize, Vec<u8>>,
+        block_inventory_data: HashMap<usize, Vec<Option<OldItemStack>>>,
+    }
+
+    #[derive(Serialize)]
+    struct OldInventory {
+        items: Vec<Option<OldItemStack>>,
+        has_changed: bool,
+        selected_slot: Option<usize>,
+    }
+
+    #[derive(Serialize)]
+    struct OldSavedPlayerData {
+        inventory: OldInventory,
+        position: PositionComponent,
+        health: HealthComponent,
+    }
+
+    /// this function returns the slots with the stack in the first slot
+    fn old_slots(stack: OldItemStack, size: usize) -> Vec<Option<OldItemStack>> {
+        std::iter::once(Some(stack)).chain(std::iter::repeat(None)).take(size).collect()
+    }
+
+    #[test]
+    fn test_world_saved_before_item_metadata_and_stats_is_loaded() {
+        let mut server = TestServer::new().unwrap();
+        let grave = server.get_block_id_by_name("grave").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_block(BLOCK_X, BLOCK_Y, grave).unwrap();
+        let grave_size = server.server.blocks.get_blocks().get_block_inventory_size(BLOCK_X, BLOCK_Y).unwrap() as usize;
+
+        let blocks = server.server.blocks.get_blocks().serialize().unwrap();
+        let mut blocks: OldBlocksData = bincode::deserialize(&snap::raw::Decoder::new().decompress_vec(&blocks).unwrap()).unwrap();
+        let grave_index = blocks.map.translate_coords(BLOCK_X, BLOCK_Y).unwrap();
+        blocks.block_inventory_data.insert(grave_index, old_slots(OldItemStack { item: dirt, count: 5 }, grave_size));
+
+        let alice = OldSavedPlayerData {
+            inventory: OldInventory {
+                items: old_slots(OldItemStack { item: dirt, count: 3 }, PLAYER_INVENTORY_SIZE),
+                has_changed: false,
+                selected_slot: None,
+            },
+            position: PositionComponent::new(BLOCK_X as f32, 0.0),
+            health: HealthComponent::new(PLAYER_MAX_HEALTH - 10, PLAYER_MAX_HEALTH),
+        };
+        let mut world = HashMap::new();
+        world.insert("blocks".to_owned(), snap::raw::Encoder::new().compress_vec(&bincode::serialize(&blocks).unwrap()).unwrap());
+        world.insert("walls".to_owned(), server.server.walls.get_walls().serialize().unwrap());
+        world.insert("players".to_owned(), bincode::serialize(&HashMap::from([("alice".to_owned(), alice)])).unwrap());
+        let world_file = bincode::serialize(&world).unwrap();
+        drop(server);
+
+        let mut server = TestServer::with_world_file(&world_file).unwrap();
+        let grave_items = server.server.blocks.get_blocks().get_block_inventory_data(BLOCK_X, BLOCK_Y).unwrap().unwrap().clone();
+        assert!(grave_items.first().unwrap().as_ref() == Some(&ItemStack::new(dirt, 5)));
+
+        let _alice = server.connect("alice").unwrap();
+        let inventory = server.get_inventory("alice").unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(dirt, 3)));
+        let player = server.get_player("alice").unwrap();
+        assert_eq!(server.get_health(player).unwrap(), PLAYER_MAX_HEALTH - 10);
+    }
+
+    fn received_chat(client: &mut FakeClient, text: &str) -> bool {
+        client.received::<ChatMessagePacket>().iter().any(|packet| packet.message.get_text() == text)
+    }
+
+    #[test]
+    fn test_chat_message_is_sent_to_everyone() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mut bob = server.connect("bob").unwrap();
+
+        alice.send(ChatPacket { message: "hello".to_owned() }).unwrap();
+        bob.wait_until(&mut server, |client| received_chat(client, "alice: hello")).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "alice: hello")).unwrap();
+
+        let packet = bob.take::<ChatMessagePacket>().into_iter().find(|packet| packet.message.get_text() == "alice: hello").unwrap();
+        assert_eq!(packet.message.kind, ChatMessageKind::Player);
+        assert_eq!(packet.message.sender.as_deref(), Some("alice"));
+        assert_eq!(packet.message.spans.len(), 2);
+        assert!(server.read_chat_log().unwrap().contains("alice: hello"));
+    }
+
+    #[test]
+    fn test_msg_and_reply() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let mut bob = server.connect("bob").unwrap();
+        let mut carol = server.connect("carol").unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/msg bob are you there".to_owned(),
+            })
+            .unwrap();
+        bob.wait_until(&mut server, |client| received_chat(client, "[alice -> bob] are you there")).unwrap();
+        // the sender sees what they whispered
+        alice.wait_until(&mut server, |client| received_chat(client, "[alice -> bob] are you there")).unwrap();
+        assert!(bob.received::<ChatMessagePacket>().iter().all(|packet| packet.message.kind == ChatMessageKind::Whisper));
+
+        bob.send(ChatPacket { message: "/reply yes".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "[bob -> alice] yes")).unwrap();
+        for _ in 0..10 {
+            server.tick().unwrap();
+        }
+        assert!(carol.received::<ChatMessagePacket>().is_empty());
+        assert!(server.read_chat_log().unwrap().contains("[bob -> alice] yes"));
+    }
+
+    #[test]
+    fn test_msg_to_offline_player_fails() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice.send(ChatPacket { message: "/msg bob hello".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Error: player bob is not online")).unwrap();
+
+        alice.send(ChatPacket { message: "/reply hello".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Error: nobody has sent you a message")).unwrap();
+    }
+
+    #[test]
+    fn test_command_argument_errors_show_usage() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice.send(ChatPacket { message: "/give".to_owned() }).unwrap();
+        alice
+            .wait_until(&mut server, |client| received_chat(client, "Error: missing item, usage: give <item> [player] [count]"))
+            .unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/give dirt alice 1 2".to_owned(),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| received_chat(client, "Error: too many arguments, usage: give <item> [player] [count]"))
+            .unwrap();
+
+        alice.send(ChatPacket { message: "/give gold_bar".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Error: unknown item gold_bar")).unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/deathmode never".to_owned(),
+            })
+            .unwrap();
+        alice
+            .wait_until(&mut server, |client| received_chat(client, "Error: invalid mode never, use keep, drop, grave"))
+            .unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "/setworldspawn 10 up".to_owned(),
+            })
+            .unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Error: y must be a number, got up")).unwrap();
+    }
+
+    #[test]
+    fn test_quoted_command_arguments() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice.send(ChatPacket { message: "/help \"give\"".to_owned() }).unwrap();
+        alice
+            .wait_until(&mut server, |client| received_chat(client, "give - Gives items to a player\nUsage: give <item> [player] [count]"))
+            .unwrap();
+
+        alice.send(ChatPacket { message: "/help \"give".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Error: missing closing quote")).unwrap();
+
+        // the text argument keeps the message as it was written
+        alice
+            .send(ChatPacket {
+                message: "/msg alice \"hi  there\" !".to_owned(),
+            })
+            .unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "[alice -> alice] \"hi  there\" !")).unwrap();
+    }
+
+    fn request_completions(server: &mut TestServer, client: &mut FakeClient, text: &str) -> Vec<String> {
+        client.take::<CommandCompletionPacket>();
+        client.send(CommandCompletionRequestPacket { text: text.to_owned() }).unwrap();
+        let packet = client.wait_for::<CommandCompletionPacket>(server).unwrap();
+        assert_eq!(packet.text, text);
+        packet.completions
+    }
+
+    #[test]
+    fn test_command_completion() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        let _bob = server.connect("bob").unwrap();
+
+        assert_eq!(request_completions(&mut server, &mut alice, "/hel"), vec!["help"]);
+        assert_eq!(request_completions(&mut server, &mut alice, "/msg "), vec!["alice", "bob"]);
+        assert_eq!(request_completions(&mut server, &mut alice, "/msg b"), vec!["bob"]);
+        assert_eq!(request_completions(&mut server, &mut alice, "/deathmode "), vec!["drop", "grave", "keep"]);
+        assert!(request_completions(&mut server, &mut alice, "/give di").contains(&"dirt".to_owned()));
+        assert!(request_completions(&mut server, &mut alice, "/give dirt alice 1 ").is_empty());
+        assert!(request_completions(&mut server, &mut alice, "/msg bob hel").is_empty());
+
+        assert_eq!(complete_last_word("msg b", &["bob".to_owned()]).unwrap(), "msg bob ");
+        assert_eq!(complete_last_word("time ", &["set".to_owned(), "setup".to_owned()]).unwrap(), "time set");
+        assert!(complete_last_word("time se", &["set".to_owned(), "sell".to_owned()]).is_none());
+    }
+
+    #[test]
+    fn test_give_command() {
+        let mut server = TestServer::new().unwrap();
+        let _alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+
+        assert_eq!(server.server.execute_command("give dirt alice 5", None).unwrap(), "Gave 5 dirt to alice");
+        assert_eq!(server.server.execute_command("give dirt", Some("alice")).unwrap(), "Gave 1 dirt to alice");
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt), 6);
+
+        server.server.execute_command("give dirt alice 0", None).unwrap_err();
+        server.server.execute_command("give dirt alice 1000000000", None).unwrap_err();
+        server.server.execute_command("give dirt", None).unwrap_err();
+        server.server.execute_command("give dirt bob", None).unwrap_err();
+    }
+
+    #[test]
+    fn test_given_items_that_dont_fit_are_dropped_in_stacks() {
+        let mut server = TestServer::new().unwrap();
+        let _alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+
+        server.server.execute_command("give dirt alice 10000", None).unwrap();
+        let in_inventory = server.get_inventory("alice").unwrap().get_item_count(dirt);
+        assert_eq!(in_inventory, PLAYER_INVENTORY_SIZE as i32 * 99);
+        let drops = count_item_entities(&server);
+        assert!(drops.iter().all(|(item, count)| *item == dirt && *count <= 99));
+        assert_eq!(drops.iter().map(|(_, count)| count).sum::<i32>(), 10000 - in_inventory);
+    }
+
+    #[test]
+    fn test_tp_command() {
+        let mut server = TestServer::new().unwrap();
+        let _alice = server.connect("alice").unwrap();
+        let _bob = server.connect("bob").unwrap();
+        let alice = server.get_player("alice").unwrap();
+        let bob = server.get_player("bob").unwrap();
+
+        assert_eq!(server.server.execute_command("tp alice 10 20", None).unwrap(), "Teleported alice to 10 20");
+        assert_eq!(server.get_position(alice).unwrap(), (10.0, 20.0));
+
+        server.server.execute_command("tp bob alice", None).unwrap();
+        assert_eq!(server.get_position(bob).unwrap(), (10.0, 20.0));
+
+        server.server.execute_command("tp alice -5 3", None).unwrap_err();
+        server.server.execute_command("tp alice 5", None).unwrap_err();
+        assert_eq!(server.get_position(alice).unwrap(), (10.0, 20.0));
+    }
+
+    #[test]
+    fn test_setblock_and_fill_commands() {
+        let mut server = TestServer::new().unwrap();
+        let dirt = server.get_block_id_by_name("dirt").unwrap();
+        let air = server.get_block_id_by_name("air").unwrap();
+
+        assert_eq!(server.server.execute_command("setblock 5 5 dirt", None).unwrap(), "Set the block at 5 5 to dirt");
+        assert!(server.get_block(5, 5).unwrap() == dirt);
+
+        assert_eq!(server.server.execute_command("fill 3 2 1 1 dirt", None).unwrap(), "Filled 6 blocks with dirt");
+        for (x, y) in [(1, 1), (3, 2), (2, 1)] {
+            assert!(server.get_block(x, y).unwrap() == dirt);
+        }
+        assert!(server.get_block(4, 1).unwrap() == air);
+
+        // fills that are too big or reach outside the world don't change anything
+        server.server.execute_command("fill 0 0 1000 1000 dirt", None).unwrap_err();
+        server.server.execute_command("fill 60 2 70 3 dirt", None).unwrap_err();
+        assert!(server.get_block(60, 2).unwrap() == air);
+        server.server.execute_command("setblock 5 5 cheese", None).unwrap_err();
+    }
+
+    #[test]
+    fn test_heal_and_kill_commands() {
+        let mut server = TestServer::new().unwrap();
+        let _alice = server.connect("alice").unwrap();
+        let player = server.get_player("alice").unwrap();
+
+        server.set_health(player, 10).unwrap();
+        assert_eq!(server.server.execute_command("heal", Some("alice")).unwrap(), "Healed alice");
+        assert_eq!(server.get_health(player).unwrap(), PLAYER_MAX_HEALTH);
+
+        assert_eq!(server.server.execute_command("kill alice", None).unwrap(), "Killed alice");
+        assert_eq!(server.get_health(player).unwrap(), 0);
+        // the player dies like they would in any other way
+        server.tick().unwrap();
+        server.server.execute_command("kill alice", None).unwrap_err();
+        server.server.execute_command("heal alice", None).unwrap_err();
+    }
+
+    #[test]
+    fn test_clear_command() {
+        let mut server = TestServer::new().unwrap();
+        let _alice = server.connect("alice").unwrap();
+        let dirt = server.get_item_id_by_name("dirt").unwrap();
+        server.set_inventory_item("alice", 0, dirt, 30).unwrap();
+        server.set_inventory_item("alice", 5, dirt, 2).unwrap();
+
+        assert_eq!(server.server.execute_command("clear alice", None).unwrap(), "Removed 32 items from the inventory of alice");
+        assert!(server.get_inventory("alice").unwrap().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_list_seed_and_say_commands() {
+        let mut server = TestServer::new().unwrap();
+        assert_eq!(server.server.execute_command("list", None).unwrap(), "No players are online");
+
+        let _bob = server.connect("bob").unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        assert_eq!(server.server.execute_command("list", None).unwrap(), "2 players online: alice, bob");
+        assert_eq!(server.server.execute_command("seed", None).unwrap(), format!("Seed: {}", server.server.seed));
+
+        server.server.execute_command("say the server restarts soon", None).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "[Server] the server restarts soon")).unwrap();
+    }
+
+    #[test]
+    fn test_mod_commands() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        assert_eq!(server.server.execute_command("greet", None).unwrap(), "Hello console, you said ");
+        assert_eq!(server.server.execute_command("greet a \"b c\"", Some("alice")).unwrap(), "Hello alice, you said a, b c");
+        assert!(server.server.execute_command("help", None).unwrap().contains("greet [arguments] - Greets the executor"));
+        // the mod can't replace the built in help command
+        assert!(server.server.execute_command("help help", None).unwrap().starts_with("help - Shows all commands"));
+
+        assert_eq!(server.server.execute_command("broken", None).unwrap_err().to_string(), "command broken failed");
+
+        // the error of the mod is reported to the players and the server keeps running
+        alice.send(ChatPacket { message: "/broken".to_owned() }).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                let messages = client.received::<ChatMessagePacket>();
+                messages.iter().any(|packet| packet.message.get_text().starts_with("Error: command broken failed"))
+                    && messages.iter().any(|packet| packet.message.get_text().contains("this command is broken"))
+            })
+            .unwrap();
+        alice.send(ChatPacket { message: "/greet again".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "Hello alice, you said again")).unwrap();
+    }
+
+    fn get_mod_events(server: &mut TestServer) -> (Vec<String>, i32) {
+        server.server.mods.mod_manager.get_mod(0).unwrap().call_function::<_, (Vec<String>, i32)>("get_event_log", ()).unwrap()
+    }
+
+    fn has_mod_event(server: &mut TestServer, text: &str) -> bool {
+        get_mod_events(server).0.iter().any(|event| event == text)
+    }
+
+    #[test]
+    fn test_mod_events() {
+        let mut server = TestServer::new().unwrap();
+        let (_, ticks) = get_mod_events(&mut server);
+        server.server.step(5).unwrap();
+        assert_eq!(get_mod_events(&mut server).1, ticks + 5);
+
+        let mut alice = server.connect("alice").unwrap();
+        let bob = server.connect("bob").unwrap();
+        assert!(has_mod_event(&mut server, "join alice"));
+
+        // the block is picked up after it is broken
+        alice.send(ClientBlockBreakStartPacket { x: BLOCK_X, y: BLOCK_Y }).unwrap();
+        server.tick_until(|server| Ok(has_mod_event(server, "pickup alice 1 dirt true"))).unwrap();
+        assert!(has_mod_event(&mut server, &format!("change {BLOCK_X} {BLOCK_Y} was air false")));
+
+        let player = server.get_player("alice").unwrap();
+        server.set_health(player, 40).unwrap();
+        server.tick().unwrap();
+        assert!(has_mod_event(&mut server, &format!("health alice 40/{PLAYER_MAX_HEALTH}")));
+        kill_and_respawn(&mut server, &mut alice, "alice");
+        assert!(has_mod_event(&mut server, "death alice"));
+
+        let blob = server.spawn_mob("blob", 10.0, BLOCK_Y as f32 - 2.0).unwrap();
+        server.set_health(blob, 0).unwrap();
+        server.tick().unwrap();
+        assert!(has_mod_event(&mut server, "death blob"));
+
+        drop(bob);
+        server.tick_until(|server| Ok(has_mod_event(server, "leave bob"))).unwrap();
+
+        let (success, error) = server
+            .server
+            .mods
+            .mod_manager
+            .get_mod(0)
+            .unwrap()
+            .call_function::<_, (bool, String)>("subscribe_to_unknown_event", ())
+            .unwrap();
+        assert!(!success);
+        assert!(error.contains("unknown event explode"));
+    }
+
+    #[test]
+    fn test_processing_block_slots_must_exist() {
+        let mut server = TestServer::new().unwrap();
+        let test_mod = server.server.mods.mod_manager.get_mod(0).unwrap();
+
+        let (success, error) = test_mod.call_function::<_, (bool, String)>("register_processing_grave", (0, 20)).unwrap();
+        assert!(!success);
+        assert!(error.contains("slot 20 is out of range"));
+
+        let (success, _) = test_mod.call_function::<_, (bool, String)>("register_processing_grave", (0, 19)).unwrap();
+        assert!(success);
+    }
+
+    #[test]
+    fn test_mods_can_cancel_chat() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice
+            .send(ChatPacket {
+                message: "this is forbidden".to_owned(),
+            })
+            .unwrap();
+        alice.send(ChatPacket { message: "hello".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "alice: hello")).unwrap();
+        assert!(!received_chat(&mut alice, "alice: this is forbidden"));
+        assert!(has_mod_event(&mut server, "chat alice: this is forbidden"));
+        assert!(!server.read_chat_log().unwrap().contains("forbidden"));
+    }
+
+    #[test]
+    fn test_mods_can_cancel_block_placement() {
+        let mut server = TestServer::new().unwrap();
+        let alice = server.connect("alice").unwrap();
+        let dirt = server.get_block_id_by_name("dirt").unwrap();
+        let air = server.get_block_id_by_name("air").unwrap();
+        let dirt_item = server.get_item_id_by_name("dirt").unwrap();
+        server.select_item("alice", dirt_item).unwrap();
+        server.set_inventory_item("alice", 0, dirt_item, 2).unwrap();
+
+        // the test mod doesn't let anyone build in the leftmost column
+        alice.send(BlockRightClickPacket { x: 0, y: BLOCK_Y - 1 }).unwrap();
+        server.tick_until(|server| Ok(has_mod_event(server, &format!("place alice 0 {} dirt true", BLOCK_Y - 1)))).unwrap();
+        assert!(server.get_block(0, BLOCK_Y - 1).unwrap() == air);
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt_item), 2);
+
+        alice.send(BlockRightClickPacket { x: 1, y: BLOCK_Y - 1 }).unwrap();
+        server.tick_until(|server| Ok(server.get_block(1, BLOCK_Y - 1)? == dirt)).unwrap();
+        assert_eq!(server.get_inventory("alice").unwrap().get_item_count(dirt_item), 1);
+        assert!(has_mod_event(&mut server, &format!("change 1 {} was air true", BLOCK_Y - 1)));
+    }
+
+    #[test]
+    fn test_mods_are_sandboxed() {
+        let mut server = TestServer::new().unwrap();
+        let functions = server
+            .server
+            .mods
+            .mod_manager
+            .get_mod(0)
+            .unwrap()
+            .call_function::<_, HashMap<String, bool>>("get_unsafe_functions", ())
+            .unwrap();
+        for (name, available) in functions {
+            assert_eq!(available, name == "time", "{name}");
+        }
+    }
+
+    #[test]
+    fn test_mods_are_stopped_when_breaking_limits() {
+        let mut server = TestServer::new().unwrap();
+        let game_mod = server.server.mods.mod_manager.get_mod(0).unwrap();
+        assert_eq!(game_mod.get_name(), "test_mod");
+
+        let error = game_mod.call_function::<_, ()>("run_forever", ()).unwrap_err().to_string();
+        assert!(error.starts_with("mod test_mod: ") && error.contains("instructions without returning"), "{error}");
+        let error = game_mod.call_function::<_, ()>("use_all_memory", ()).unwrap_err().to_string();
+        assert!(error.starts_with("mod test_mod: used more than"), "{error}");
+
+        // the limits apply to each call on its own, so the mod keeps working
+        assert_eq!(server.server.execute_command("greet", None).unwrap(), "Hello console, you said ");
+        server.server.step(5).unwrap();
+    }
+
+    #[test]
+    fn test_mod_errors_are_reported_and_disable_the_mod() {
+        let mut server = TestServer::new().unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        alice.send(ChatPacket { message: "crash".to_owned() }).unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client.received::<ChatMessagePacket>().iter().any(|packet| {
+                    let text = packet.message.get_text();
+                    text.starts_with("mod test_mod: ") && text.contains("crashed on purpose")
+                })
+            })
+            .unwrap();
+        // the failed handler doesn't cancel the message
+        assert!(received_chat(&mut alice, "alice: crash"));
+
+        for _ in 1..10 {
+            alice.send(ChatPacket { message: "crash".to_owned() }).unwrap();
+        }
+        alice
+            .wait_until(&mut server, |client| received_chat(client, "mod test_mod was disabled because it failed too many times"))
+            .unwrap();
+        assert!(server.server.mods.mod_manager.get_mod(0).unwrap().is_disabled());
+
+        // disabled mods don't cancel events and their commands can't be used
+        alice.send(ChatPacket { message: "forbidden".to_owned() }).unwrap();
+        alice.wait_until(&mut server, |client| received_chat(client, "alice: forbidden")).unwrap();
+        let error = server.server.execute_command("greet", None).unwrap_err().to_string();
+        assert_eq!(error, "command greet can't be used, mod test_mod is disabled");
+    }
+
+    #[test]
+    fn test_strict_mods_stop_the_server() {
+        let mut server = TestServer::new().unwrap();
+        server.server.mods.mod_manager.strict = true;
+        let alice = server.connect("alice").unwrap();
+
+        alice.send(ChatPacket { message: "crash".to_owned() }).unwrap();
+        let error = (0..1000).find_map(|_| server.tick().err()).unwrap().to_string();
+        assert!(error.starts_with("mod test_mod: ") && error.contains("crashed on purpose"), "{error}");
+    }
+
+    #[test]
+    fn test_failing_mod_commands_are_mod_errors() {
+        let mut server = TestServer::new().unwrap();
+        // failing commands disable the mod like failures anywhere else in the mod
+        for _ in 0..10 {
+            server.server.execute_command("broken", None).unwrap_err();
+        }
+        assert!(server.server.mods.mod_manager.get_mod(0).unwrap().is_disabled());
+        let error = server.server.execute_command("greet", None).unwrap_err().to_string();
+        assert!(error.starts_with("command greet can't be used"), "{error}");
+
+        let mut server = TestServer::new().unwrap();
+        server.server.mods.mod_manager.strict = true;
+        let error = server.server.execute_command("broken", None).unwrap_err().to_string();
+        assert!(error.contains("this command is broken"), "{error}");
+        assert!(server.server.state == ServerState::Stopping);
+    }
+
+    // a dev mod that registers blocks with the names in the order they are given
+    fn create_dev_mod(block_names: &[&str]) -> String {
+        let mut lua_code = "MOD_NAME = \"dev_mod\"\nfunction init()\n".to_owned();
+        for name in block_names {
+            writeln!(
+                lua_code,
+                "terralistic_register_block_type(nil, 0, false, false, \"{name}\", {{}}, 1, 0, 0, 0, 1, 1, false, false, false, {{}})"
+            )
+            .unwrap();
+        }
+        lua_code.push_str("end\n");
+        lua_code
+    }
+
+    #[test]
+    fn test_dev_mod_is_reloaded_when_it_changes() {
+        let mut server = TestServer::with_dev_mod(&create_dev_mod(&["crystal"])).unwrap();
+        let crystal = server.get_block_id_by_name("crystal").unwrap();
+        server.set_block(BLOCK_X, BLOCK_Y, crystal).unwrap();
+        let mut alice = server.connect("alice").unwrap();
+        assert_eq!(alice.received::<ModsWelcomePacket>().pop().unwrap().mods.len(), 2);
+
+        server.write_dev_mod(&create_dev_mod(&["crystal", "gem"])).unwrap();
+        alice.wait_for::<ModsReloadPacket>(&mut server).unwrap();
+
+        // the world and everything registered before keep their ids
+        assert!(server.get_block_id_by_name("crystal").unwrap() == crystal);
+        assert!(server.get_block(BLOCK_X, BLOCK_Y).unwrap() == crystal);
+        assert!(server.get_block_id_by_name("gem").is_ok_and(|gem| gem != crystal));
+    }
+
+    #[test]
+    fn test_dev_mod_reload_is_rejected_if_it_breaks_the_world() {
+        let mut server = TestServer::with_dev_mod(&create_dev_mod(&["crystal", "gem"])).unwrap();
+        let gem = server.get_block_id_by_name("gem").unwrap();
+        let mut alice = server.connect("alice").unwrap();
+
+        // without crystal, gem would get its id
+        server.write_dev_mod(&create_dev_mod(&["gem"])).unwrap();
+        server.server.reload_dev_mod().unwrap();
+        let rejection = "dev mod reload was rejected: block crystal was removed or got a different id, reloading would break the world";
+        alice.wait_until(&mut server, |client| received_chat(client, rejection)).unwrap();
+        assert!(server.get_block_id_by_name("gem").unwrap() == gem);
+        server.get_block_id_by_name("crystal").unwrap();
+
+        server.write_dev_mod("function init(").unwrap();
+        server.server.reload_dev_mod().unwrap();
+        alice
+            .wait_until(&mut server, |client| {
+                client
+                    .received::<ChatMessagePacket>()
+                    .iter()
+                    .any(|packet| packet.message.get_text().starts_with("dev mod reload was rejected: could not parse lua code"))
+            })
+            .unwrap();
+        assert!(alice.received::<ModsReloadPacket>().is_empty());
+    }
+}
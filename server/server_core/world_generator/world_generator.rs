@@ -35,13 +35,13 @@ impl WorldGenerator {
         }
     }
 
-    fn get_biomes(&self) -> MutexGuard<Vec<Biome>> {
+    fn get_biomes(&self) -> MutexGuard<'_, Vec<Biome>> {
         self.biomes.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// This function creates a array of biome ids and returns it along with the width of the world.
     /// Each biome id is for each column of the world.
-    fn generate_biome_ids(&mut self, min_width: i32) -> Result<(Vec<i32>, i32)> {
+    fn generate_biome_ids(&self, min_width: i32) -> Result<(Vec<i32>, i32)> {
         let mut biome_ids = Vec::new();
         let mut width = 0;
 
@@ -261,8 +261,9 @@ impl WorldGenerator {
         Ok(curr_terrain)
     }
 
-    #[allow(clippy::too_many_lines)] // TODO: split this function up
-    pub fn generate(&mut self, world: (&mut Blocks, &mut Walls), mods: &mut ModManager, min_width: i32, height: i32, seed: u64, status_text: &Mutex<String>) -> Result<()> {
+    /// Generates the world and returns the biome id of every column.
+    #[allow(clippy::too_many_lines, reason = "TODO: split this function up")]
+    pub fn generate(&self, world: (&mut Blocks, &mut Walls), mods: &mut ModManager, min_width: i32, height: i32, seed: u64, status_text: &Mutex<String>) -> Result<Vec<i32>> {
         let start_time = std::time::Instant::now();
 
         let blocks = world.0;
@@ -298,7 +299,7 @@ impl WorldGenerator {
             *status_text.lock().unwrap_or_else(PoisonError::into_inner) = format!("Generating world {}%", (current_task as f32 / total_tasks as f32 * 100.0) as i32);
         };
 
-        *status_text.lock().unwrap_or_else(PoisonError::into_inner) = "Generating world".to_owned();
+        "Generating world".clone_into(&mut status_text.lock().unwrap_or_else(PoisonError::into_inner));
         blocks.create(width as u32, height as u32);
 
         let mut block_terrain = vec![vec![BlockId::undefined(); height as usize]; width as usize];
@@ -390,6 +391,6 @@ impl WorldGenerator {
             println!("Not all tasks were completed! {current_task} != {total_tasks}");
         }
 
-        Ok(())
+        Ok(biome_ids)
     }
 }
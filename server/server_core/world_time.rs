@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use anyhow::Result;
+
+use crate::libraries::events::Event;
+use crate::server::server_core::networking::{NewConnectionWelcomedEvent, SendTarget, ServerNetworking};
+use crate::shared::mod_manager::ModManager;
+use crate::shared::packet::Packet;
+use crate::shared::world_time::{init_world_time_mod_interface, WorldTime, WorldTimePacket};
+
+// clients advance the time on their own, it is only sent to them once in a while to correct it
+const SYNC_INTERVAL_MS: f32 = 5000.0;
+
+pub struct ServerWorldTime {
+    time: Arc<Mutex<WorldTime>>,
+    // the part of a millisecond that wasn't added to the time yet
+    remainder_ms: f32,
+    ms_since_sync: f32,
+    // set when the time was changed by a command, so that the clients get it right away
+    changed: bool,
+}
+
+impl ServerWorldTime {
+    pub fn new() -> Self {
+        Self {
+            time: Arc::new(Mutex::new(WorldTime::new(0))),
+            remainder_ms: 0.0,
+            ms_since_sync: 0.0,
+            changed: false,
+        }
+    }
+
+    pub fn init(&self, mods: &mut ModManager) -> Result<()> {
+        init_world_time_mod_interface(&self.time, mods)
+    }
+
+    /// advances the time and sends it to the clients when needed
+    pub fn update(&mut self, delta_time: f32, networking: &mut ServerNetworking) -> Result<()> {
+        self.remainder_ms += delta_time;
+        let whole_ms = self.remainder_ms.floor();
+        self.remainder_ms -= whole_ms;
+        self.get_time().add_time_ms(whole_ms as i64);
+
+        self.ms_since_sync += delta_time;
+        if self.changed || self.ms_since_sync >= SYNC_INTERVAL_MS {
+            self.changed = false;
+            self.ms_since_sync = 0.0;
+            networking.send_packet(&self.get_packet()?, SendTarget::All)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_time_ms(&mut self, time_ms: i64) {
+        self.get_time().set_time_ms(time_ms);
+        self.changed = true;
+    }
+
+    fn get_packet(&self) -> Result<Packet> {
+        Packet::new(WorldTimePacket { time: *self.get_time() })
+    }
+
+    pub fn on_event(&self, event: &Event, networking: &mut ServerNetworking) -> Result<()> {
+        if let Some(event) = event.downcast::<NewConnectionWelcomedEvent>() {
+            networking.send_packet(&self.get_packet()?, SendTarget::Connection(event.conn.clone()))?;
+        }
+        Ok(())
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(&*self.get_time())?)
+    }
+
+    pub fn deserialize(&self, data: &[u8]) -> Result<()> {
+        *self.get_time() = bincode::deserialize(data)?;
+        Ok(())
+    }
+
+    pub fn get_time(&self) -> MutexGuard<'_, WorldTime> {
+        self.time.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
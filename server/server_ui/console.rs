@@ -2,6 +2,7 @@ use std::sync::mpsc::Sender;
 
 use crate::libraries::graphics as gfx;
 use crate::server::server_ui::{ConsoleMessageType, UiMessageType, EDGE_SPACING};
+use crate::shared::chat::complete_last_word;
 
 use super::ui_manager;
 
@@ -32,7 +33,7 @@ impl ConsoleLine {
 
     pub fn render(
         //TODO fix rendering, multiline sprites have less spacing than normal lines
-        &mut self,
+        &self,
         graphics_context: &gfx::GraphicsContext,
         container: &mut gfx::Container,
         max_y: f32,
@@ -121,6 +122,19 @@ impl ui_manager::ModuleTrait for Console {
     }
 
     fn on_server_message(&mut self, message: &UiMessageType, graphics_context: &mut gfx::GraphicsContext) {
+        //completions for text that was changed in the meantime are ignored
+        if let UiMessageType::SrvToUiCompletion((text, completions)) = message {
+            if text == self.input.get_text() {
+                if let Some(completed) = complete_last_word(text, completions) {
+                    self.input.set_text(completed);
+                    self.input.move_cursor_to_end();
+                }
+                if completions.len() > 1 {
+                    self.add_line(completions.join(" "), graphics_context, gfx::Color::new(200, 200, 200, 255));
+                }
+            }
+        }
+
         if let UiMessageType::SrvToUiConsoleMessage(message) = message {
             //extract the string from the message enum and color the sprite
             let mut color = gfx::Color::new(200, 200, 200, 255);
@@ -136,7 +150,7 @@ impl ui_manager::ModuleTrait for Console {
                 }
             };
 
-            self.add_line(message.to_string(), graphics_context, color);
+            self.add_line(message.clone(), graphics_context, color);
         }
     }
 
@@ -144,7 +158,7 @@ impl ui_manager::ModuleTrait for Console {
         &mut self.container
     }
 
-    fn get_name(&self) -> &str {
+    fn get_name(&self) -> &'static str {
         "console"
     }
 
@@ -172,6 +186,10 @@ impl ui_manager::ModuleTrait for Console {
                     println!("{message}");
                     self.input.set_text(String::new());
                 }
+                //if tab is pressed, ask the server to complete the last word
+                if matches!(key, gfx::Key::Tab) && self.input.selected {
+                    send_to_srv(UiMessageType::UiToSrvCompletionRequest(self.input.get_text().clone()), self.sender.as_ref());
+                }
             }
             _ => {}
         }
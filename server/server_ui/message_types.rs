@@ -3,6 +3,9 @@ pub enum UiMessageType {
     ServerState(ServerState),
     SrvToUiConsoleMessage(ConsoleMessageType),
     UiToSrvConsoleMessage(String),
+    // the text typed into the console so far and what its last word can be completed to
+    UiToSrvCompletionRequest(String),
+    SrvToUiCompletion((String, Vec<String>)),
     MsptUpdate((Option<f64>, f64)),
     PlayerEvent(PlayerEventType),
 }
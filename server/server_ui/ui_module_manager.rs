@@ -164,7 +164,7 @@ impl ModuleManager {
         }))
     }
 
-    pub fn get_root_mut(&mut self) -> &mut ModuleTreeNodeType {
+    pub const fn get_root_mut(&mut self) -> &mut ModuleTreeNodeType {
         &mut self.root
     }
 
@@ -218,19 +218,15 @@ impl ModuleManager {
                 self.depth += 1;
                 self.recalculate_selection_rect();
             }
-            gfx::Key::Up => {
-                if self.depth > 0 {
-                    self.depth -= 1;
-                    self.recalculate_selection_rect();
-                }
+            gfx::Key::Up if self.depth > 0 => {
+                self.depth -= 1;
+                self.recalculate_selection_rect();
             }
-            gfx::Key::Space => {
-                if self.depth > 0 {
-                    let path_at_depth = self.path.get_mut(self.depth - 1);
-                    if let Some(path_at_depth) = path_at_depth {
-                        *path_at_depth = !*path_at_depth;
-                        self.recalculate_selection_rect();
-                    }
+            gfx::Key::Space if self.depth > 0 => {
+                let path_at_depth = self.path.get_mut(self.depth - 1);
+                if let Some(path_at_depth) = path_at_depth {
+                    *path_at_depth = !*path_at_depth;
+                    self.recalculate_selection_rect();
                 }
             }
             gfx::Key::V => {
@@ -281,7 +277,7 @@ impl ModuleManager {
         }
     }
 
-    fn handle_resize_mode_key_events(&mut self, key: gfx::Key) {
+    const fn handle_resize_mode_key_events(&mut self, key: gfx::Key) {
         match key {
             gfx::Key::Enter | gfx::Key::Escape => {
                 self.mode = EditMode::Select;
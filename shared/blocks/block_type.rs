@@ -36,6 +36,8 @@ pub struct Block {
     // if right clicking the block sends an event
     // this is used for example: doors
     pub clickable: bool,
+    // if right clicking the block sets the player's spawn point, for example: beds
+    pub spawn_point: bool,
     // where will those inventory slots be located on screen, when the inventory is opened (0, 0) is top middle
     // the size also tells how many inventory slots it has, like chest or furnace
     // if it's empty, then it cannot be opened, else it can be
@@ -63,6 +65,7 @@ impl Block {
             can_update_states: false,
             feet_collidable: false,
             clickable: false,
+            spawn_point: false,
             inventory_slots: vec![],
         }
     }
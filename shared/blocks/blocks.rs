@@ -6,7 +6,7 @@ use snap;
 
 use crate::libraries::events::{Event, EventManager};
 use crate::shared::blocks::{Block, BreakingBlock, Tool};
-use crate::shared::items::ItemStack;
+use crate::shared::items::{ItemStack, LegacyItemStack};
 use crate::shared::world_map::WorldMap;
 
 pub const BLOCK_WIDTH: f32 = 8.0;
@@ -26,6 +26,16 @@ pub(super) struct BlocksData {
     pub block_inventory_data: HashMap<usize, Vec<Option<ItemStack>>>,
 }
 
+/// Legacy blocks data is how blocks were saved before item stacks had metadata.
+#[derive(Deserialize)]
+struct LegacyBlocksData {
+    map: WorldMap,
+    blocks: Vec<BlockId>,
+    block_from_main: HashMap<usize, (i32, i32)>,
+    block_data: HashMap<usize, Vec<u8>>,
+    block_inventory_data: HashMap<usize, Vec<Option<LegacyItemStack>>>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BlockId {
     pub(super) id: i8,
@@ -65,7 +75,7 @@ impl Blocks {
         };
 
         let mut air = Block::new();
-        air.name = "air".to_owned();
+        "air".clone_into(&mut air.name);
         air.ghost = true;
         air.transparent = true;
         result.air = result.register_new_block_type(air);
@@ -133,7 +143,7 @@ impl Blocks {
         if block_id != self.get_block(x, y)? || from_main != self.get_block_from_main(x, y)? {
             let prev_block = self.get_block(x, y)?;
 
-            self.set_block_data(x, y, vec![])?;
+            self.set_block_data(x, y, vec![], events)?;
             *self
                 .block_data
                 .blocks
@@ -172,13 +182,18 @@ impl Blocks {
     }
 
     /// This function sets the block data for a block. If it is empty the value is removed from the hashmap.
-    pub(super) fn set_block_data(&mut self, x: i32, y: i32, data: Vec<u8>) -> Result<()> {
+    pub fn set_block_data(&mut self, x: i32, y: i32, data: Vec<u8>, events: &mut EventManager) -> Result<()> {
         let index = self.block_data.map.translate_coords(x, y)?;
+        if self.block_data.block_data.get(&index).map_or(data.is_empty(), |prev_data| *prev_data == data) {
+            return Ok(());
+        }
+
         if data.is_empty() {
             self.block_data.block_data.remove(&index);
         } else {
             self.block_data.block_data.insert(index, data);
         }
+        events.push_event(Event::new(BlockDataUpdateEvent { x, y }));
         Ok(())
     }
 
@@ -197,7 +212,7 @@ impl Blocks {
         if size == 0 {
             self.set_block_inventory_data(x, y, vec![], events)?;
         } else {
-            if self.block_data.block_inventory_data.get(&index).is_some() {
+            if self.block_data.block_inventory_data.contains_key(&index) {
                 return Ok(());
             }
 
@@ -207,6 +222,15 @@ impl Blocks {
         Ok(())
     }
 
+    /// This function returns the coordinates of all blocks that have inventory slots.
+    pub fn get_block_inventory_coords(&self) -> Result<Vec<(i32, i32)>> {
+        let mut result = Vec::new();
+        for index in self.block_data.block_inventory_data.keys() {
+            result.push(self.block_data.map.translate_index(*index)?);
+        }
+        Ok(result)
+    }
+
     /// This function gets inventory slots for a block. If the value is not found, it returns an empty vector.
     pub fn get_block_inventory_data(&self, x: i32, y: i32) -> Result<Option<&Vec<Option<ItemStack>>>> {
         Ok(self.block_data.block_inventory_data.get(&self.block_data.map.translate_coords(x, y)?))
@@ -251,6 +275,23 @@ impl Blocks {
         Ok(())
     }
 
+    /// Deserializes blocks of a world saved before item stacks had metadata.
+    pub fn deserialize_legacy(&mut self, serial: &[u8]) -> Result<()> {
+        let legacy_data: LegacyBlocksData = bincode::deserialize(&snap::raw::Decoder::new().decompress_vec(serial)?)?;
+        self.block_data = BlocksData {
+            map: legacy_data.map,
+            blocks: legacy_data.blocks,
+            block_from_main: legacy_data.block_from_main,
+            block_data: legacy_data.block_data,
+            block_inventory_data: legacy_data
+                .block_inventory_data
+                .into_iter()
+                .map(|(index, items)| (index, items.into_iter().map(|item| item.map(ItemStack::from)).collect()))
+                .collect(),
+        };
+        Ok(())
+    }
+
     /// This function adds a new block type
     pub fn register_new_block_type(&mut self, mut block_type: Block) -> BlockId {
         let id = self.block_types.len() as i8;
@@ -315,6 +356,24 @@ impl Blocks {
     pub fn get_block_type_at(&self, x: i32, y: i32) -> Result<Block> {
         self.get_block_type(self.get_block(x, y)?)
     }
+
+    /// Checks if there is a block of given type within range of the coordinates.
+    /// The part of the range that is outside of the world is ignored.
+    pub fn is_block_in_range(&self, block_id: BlockId, x: i32, y: i32, range: i32) -> Result<bool> {
+        let min_x = (x - range).max(0);
+        let max_x = (x + range).min(self.get_width() as i32 - 1);
+        let min_y = (y - range).max(0);
+        let max_y = (y + range).min(self.get_height() as i32 - 1);
+
+        for block_x in min_x..=max_x {
+            for block_y in min_y..=max_y {
+                if self.get_block(block_x, block_y)? == block_id {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
 }
 
 /// Event that is fired when a block is changed
@@ -342,6 +401,12 @@ pub struct BlockInventoryUpdateEvent {
     pub y: i32,
 }
 
+/// Event that is fired when block data is updated
+pub struct BlockDataUpdateEvent {
+    pub x: i32,
+    pub y: i32,
+}
+
 /// A welcome packet that carries all the information about the world blocks
 #[derive(Serialize, Deserialize)]
 pub struct BlocksWelcomePacket {
@@ -373,3 +438,11 @@ pub struct BlockInventoryUpdatePacket {
     pub y: i32,
     pub inventory: Vec<Option<ItemStack>>,
 }
+
+/// A packet that is sent to the client, when block data is updated.
+#[derive(Serialize, Deserialize)]
+pub struct BlockDataUpdatePacket {
+    pub x: i32,
+    pub y: i32,
+    pub data: Vec<u8>,
+}
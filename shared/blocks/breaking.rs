@@ -3,6 +3,7 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::libraries::events::{Event, EventManager};
 use crate::shared::blocks::{BlockId, ToolId};
+use crate::shared::items::ItemStack;
 
 use super::Blocks;
 
@@ -12,6 +13,9 @@ pub struct BreakingBlock {
     pub break_progress: i32,
     pub is_breaking: bool,
     pub coord: (i32, i32),
+    // the tool the block is being broken with
+    pub tool: Option<ToolId>,
+    pub tool_power: i32,
 }
 
 impl BreakingBlock {
@@ -21,6 +25,8 @@ impl BreakingBlock {
             break_progress: 0,
             is_breaking: true,
             coord,
+            tool: None,
+            tool_power: 0,
         }
     }
 
@@ -97,6 +103,8 @@ impl Blocks {
         };
 
         breaking_block.is_breaking = true;
+        breaking_block.tool = tool;
+        breaking_block.tool_power = tool_power;
 
         let event = BlockStartedBreakingEvent { x, y, tool, tool_power };
         events.push_event(Event::new(event));
@@ -130,30 +138,35 @@ impl Blocks {
         let mut broken_blocks = Vec::new();
         for breaking_block in &self.breaking_blocks {
             if breaking_block.break_progress > self.get_block_type_at(breaking_block.get_coord().0, breaking_block.get_coord().1)?.break_time.unwrap_or(1) {
-                broken_blocks.push(breaking_block.get_coord());
+                broken_blocks.push(breaking_block.clone());
             }
         }
 
         for broken_block in &broken_blocks {
-            self.break_block(events, broken_block.0, broken_block.1)?;
+            let (x, y) = broken_block.get_coord();
+            self.break_block(events, x, y, broken_block.tool, broken_block.tool_power)?;
 
-            self.breaking_blocks.retain(|breaking_block| breaking_block.get_coord() != *broken_block);
+            self.breaking_blocks.retain(|breaking_block| breaking_block.get_coord() != (x, y));
         }
 
         Ok(())
     }
 
-    /// breaks a block at the given coordinates
-    pub fn break_block(&mut self, events: &mut EventManager, x: i32, y: i32) -> Result<()> {
+    /// breaks a block at the given coordinates with the given tool, the tool decides what the block drops
+    pub fn break_block(&mut self, events: &mut EventManager, x: i32, y: i32, tool: Option<ToolId>, tool_power: i32) -> Result<()> {
         let transformed_x = x - self.get_block_from_main(x, y)?.0;
         let transformed_y = y - self.get_block_from_main(x, y)?.1;
 
         let prev_block_id = self.get_block_type_at(transformed_x, transformed_y)?.id;
+        let inventory = self.get_block_inventory_data(transformed_x, transformed_y)?.cloned().unwrap_or_default();
 
         let event = BlockBreakEvent {
             x: transformed_x,
             y: transformed_y,
             prev_block_id,
+            tool,
+            tool_power,
+            inventory,
         };
         events.push_event(Event::new(event));
 
@@ -203,6 +216,10 @@ pub struct BlockBreakEvent {
     pub prev_block_id: BlockId,
     pub x: i32,
     pub y: i32,
+    pub tool: Option<ToolId>,
+    pub tool_power: i32,
+    // the items that were stored in the block, they are dropped
+    pub inventory: Vec<Option<ItemStack>>,
 }
 
 /// Event that is fired when a block has started breaking
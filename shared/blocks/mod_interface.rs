@@ -17,7 +17,7 @@ impl rlua::UserData for BlockId {
 }
 
 /// initialize the mod interface for the blocks module
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, reason = "it registers every function of the blocks mod interface")]
 pub fn init_blocks_mod_interface(blocks: &Arc<Mutex<Blocks>>, mods: &mut ModManager) -> Result<Receiver<Event>> {
     let (sender, receiver) = std::sync::mpsc::channel();
 
@@ -127,6 +127,17 @@ pub fn init_blocks_mod_interface(blocks: &Arc<Mutex<Blocks>>, mods: &mut ModMana
         Ok(())
     })?;
 
+    // a method to make a block set the spawn point of players that right click it
+    blocks2 = blocks.clone();
+    mods.add_global_function("set_block_spawn_point", move |_lua, block_id: BlockId| {
+        let block_types = &mut blocks2.lock().unwrap_or_else(PoisonError::into_inner).block_types;
+        block_types
+            .get_mut(block_id.id as usize)
+            .ok_or(rlua::Error::RuntimeError("block type id is invalid".to_owned()))?
+            .spawn_point = true;
+        Ok(())
+    })?;
+
     // a method to break a block
     blocks2 = blocks.clone();
     let sender2 = sender.clone();
@@ -134,7 +145,7 @@ pub fn init_blocks_mod_interface(blocks: &Arc<Mutex<Blocks>>, mods: &mut ModMana
         let mut block_types = blocks2.lock().unwrap_or_else(PoisonError::into_inner);
         let mut events = EventManager::new();
         block_types
-            .break_block(&mut events, x, y)
+            .break_block(&mut events, x, y, None, 0)
             .ok()
             .ok_or(rlua::Error::RuntimeError("block type id is invalid".to_owned()))?;
 
@@ -186,11 +197,7 @@ pub fn init_blocks_mod_interface(blocks: &Arc<Mutex<Blocks>>, mods: &mut ModMana
 
 pub fn handle_event_for_blocks_interface(mods: &mut ModManager, event: &Event) -> Result<()> {
     if let Some(event) = event.downcast::<BlockBreakEvent>() {
-        for game_mod in mods.mods_iter_mut() {
-            if game_mod.is_symbol_defined("on_block_break")? {
-                game_mod.call_function("on_block_break", (event.x, event.y, event.prev_block_id))?;
-            }
-        }
+        mods.call_function_in_mods("on_block_break", (event.x, event.y, event.prev_block_id))?;
     }
     Ok(())
 }
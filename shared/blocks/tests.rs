@@ -1,4 +1,4 @@
-#![allow(clippy::unwrap_used)]
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #![cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -155,4 +155,23 @@ mod tests {
         let event = events.pop_event();
         assert!(event.is_none());
     }
+
+    #[test]
+    fn test_is_block_in_range() {
+        let mut blocks = Blocks::new();
+        blocks.create(20, 20);
+        let block_id = blocks.register_new_block_type(Block::new());
+        let other_block_id = blocks.register_new_block_type(Block::new());
+
+        let mut events = EventManager::new();
+        blocks.set_block(&mut events, 5, 5, block_id).unwrap();
+
+        assert_ok_and_eq(blocks.is_block_in_range(block_id, 5, 5, 0), &true);
+        assert_ok_and_eq(blocks.is_block_in_range(block_id, 8, 3, 3), &true);
+        assert_ok_and_eq(blocks.is_block_in_range(block_id, 9, 5, 3), &false);
+        assert_ok_and_eq(blocks.is_block_in_range(other_block_id, 5, 5, 3), &false);
+        // the range is clipped to the world borders
+        assert_ok_and_eq(blocks.is_block_in_range(block_id, 0, 0, 6), &true);
+        assert_ok_and_eq(blocks.is_block_in_range(block_id, 19, 19, 100), &true);
+    }
 }
@@ -1,6 +1,153 @@
 use serde_derive::{Deserialize, Serialize};
 
+// colors of the parts of chat messages, the client shows spans without a color in white
+pub const CHAT_NAME_COLOR: [u8; 3] = [255, 220, 100];
+pub const CHAT_SYSTEM_COLOR: [u8; 3] = [200, 200, 200];
+pub const CHAT_WHISPER_COLOR: [u8; 3] = [200, 130, 255];
+pub const CHAT_ERROR_COLOR: [u8; 3] = [255, 90, 90];
+
+/// Chat message kind tells where the message came from.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatMessageKind {
+    // messages from the server, like command results and death messages
+    System,
+    // messages a player sent to everyone
+    Player,
+    // private messages between two players
+    Whisper,
+}
+
+/// Chat span is a part of a chat message with its own color.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChatSpan {
+    pub text: String,
+    pub color: Option<[u8; 3]>,
+}
+
+impl ChatSpan {
+    #[must_use]
+    pub const fn new(text: String, color: Option<[u8; 3]>) -> Self {
+        Self { text, color }
+    }
+}
+
+/// Chat message is a single line in the chat, made of spans.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub kind: ChatMessageKind,
+    // the name of the player who sent the message, system messages have no sender
+    pub sender: Option<String>,
+    pub spans: Vec<ChatSpan>,
+}
+
+impl ChatMessage {
+    #[must_use]
+    pub fn system(text: &str) -> Self {
+        Self {
+            kind: ChatMessageKind::System,
+            sender: None,
+            spans: vec![ChatSpan::new(text.to_owned(), Some(CHAT_SYSTEM_COLOR))],
+        }
+    }
+
+    /// this function creates a system message shown in the error color
+    #[must_use]
+    pub fn error(text: &str) -> Self {
+        Self {
+            kind: ChatMessageKind::System,
+            sender: None,
+            spans: vec![ChatSpan::new(text.to_owned(), Some(CHAT_ERROR_COLOR))],
+        }
+    }
+
+    #[must_use]
+    pub fn player(sender: &str, text: &str) -> Self {
+        Self {
+            kind: ChatMessageKind::Player,
+            sender: Some(sender.to_owned()),
+            spans: vec![ChatSpan::new(sender.to_owned(), Some(CHAT_NAME_COLOR)), ChatSpan::new(format!(": {text}"), None)],
+        }
+    }
+
+    /// this function creates a private message, the receiver is only used in the text
+    #[must_use]
+    pub fn whisper(sender: &str, receiver: &str, text: &str) -> Self {
+        Self {
+            kind: ChatMessageKind::Whisper,
+            sender: Some(sender.to_owned()),
+            spans: vec![ChatSpan::new(format!("[{sender} -> {receiver}]"), Some(CHAT_WHISPER_COLOR)), ChatSpan::new(format!(" {text}"), None)],
+        }
+    }
+
+    /// this function returns the text of the message without colors
+    #[must_use]
+    pub fn get_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+}
+
+/// this packet is sent by the client when the player writes something into the chat,
+/// messages starting with a slash are commands
 #[derive(Serialize, Deserialize)]
 pub struct ChatPacket {
     pub message: String,
 }
+
+/// this packet is sent by the server to show a message in the chat
+#[derive(Serialize, Deserialize)]
+pub struct ChatMessagePacket {
+    pub message: ChatMessage,
+}
+
+/// this packet asks the server what the last word of a command can be completed to
+#[derive(Serialize, Deserialize)]
+pub struct CommandCompletionRequestPacket {
+    pub text: String,
+}
+
+/// this packet is the answer to the completion request, the text is
+/// the one from the request so outdated answers can be ignored
+#[derive(Serialize, Deserialize)]
+pub struct CommandCompletionPacket {
+    pub text: String,
+    pub completions: Vec<String>,
+}
+
+/// this function returns where the last word of the command starts,
+/// an unclosed quote starts the last word
+fn get_last_word_start(text: &str) -> usize {
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (index, character) in text.char_indices() {
+        if character == '"' {
+            if !in_quotes {
+                start = index;
+            }
+            in_quotes = !in_quotes;
+        } else if character.is_whitespace() && !in_quotes {
+            start = index + character.len_utf8();
+        }
+    }
+    start
+}
+
+/// this function completes the last word of the text with the part all completions share,
+/// a single completion is finished with a space, None means nothing could be added
+#[must_use]
+pub fn complete_last_word(text: &str, completions: &[String]) -> Option<String> {
+    let first = completions.first()?;
+    let mut common_len = first.len();
+    for completion in completions {
+        common_len = first.bytes().zip(completion.bytes()).take(common_len).take_while(|(a, b)| a == b).count();
+    }
+    while !first.is_char_boundary(common_len) {
+        common_len -= 1;
+    }
+
+    let start = get_last_word_start(text);
+    let mut completed = format!("{}{}", text.get(..start)?, first.get(..common_len)?);
+    if completions.len() == 1 {
+        completed.push(' ');
+    }
+    (completed != text).then_some(completed)
+}
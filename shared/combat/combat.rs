@@ -0,0 +1,291 @@
+use anyhow::Result;
+use hecs::Entity;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::{Event, EventManager};
+use crate::shared::blocks::Blocks;
+use crate::shared::entities::{collides_with_blocks, Entities, EntityId, HealthComponent, PhysicsComponent, PositionComponent, DEFAULT_GRAVITY};
+use crate::shared::items::Weapon;
+
+// how long an entity can't be hurt after it was hit
+pub const INVULNERABILITY_MS: i32 = 500;
+pub const PROJECTILE_SIZE: f32 = 0.25;
+// projectiles fly further than other entities fall
+const PROJECTILE_GRAVITY: f32 = DEFAULT_GRAVITY / 4.0;
+// projectiles that didn't hit anything are despawned after this time
+const PROJECTILE_LIFETIME_MS: i32 = 5000;
+// how close to a block the projectile has to be to hit it
+const PROJECTILE_BLOCK_MARGIN: f32 = 0.05;
+
+/// Invulnerability component is added to an entity when it is hit
+/// and removed when it can be hurt again.
+pub struct InvulnerabilityComponent {
+    remaining_ms: i32,
+}
+
+impl InvulnerabilityComponent {
+    #[must_use]
+    pub const fn new(remaining_ms: i32) -> Self {
+        Self { remaining_ms }
+    }
+}
+
+/// Damage cause tells what hurt an entity, it is used for death messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageCause {
+    // hit directly by an entity, like a player attacking or a mob touching
+    Entity(Entity),
+    // hit by a projectile shot by the owner
+    Projectile(Option<Entity>),
+}
+
+/// Last damage component remembers what hurt the entity the last time.
+pub struct LastDamageComponent {
+    pub cause: DamageCause,
+}
+
+/// Attack cooldown component is added to an entity when it attacks
+/// and removed when it can attack again.
+pub struct AttackCooldownComponent {
+    remaining_ms: i32,
+}
+
+impl AttackCooldownComponent {
+    #[must_use]
+    pub const fn new(remaining_ms: i32) -> Self {
+        Self { remaining_ms }
+    }
+}
+
+/// Projectile component marks an entity as a projectile, that hurts
+/// the first entity with health it touches and disappears.
+pub struct ProjectileComponent {
+    pub damage: i32,
+    pub knockback: f32,
+    // the owner can't be hit by its own projectile, clients don't know the owner
+    pub owner: Option<Entity>,
+    lifetime_ms: i32,
+}
+
+impl ProjectileComponent {
+    #[must_use]
+    pub const fn new(damage: i32, knockback: f32, owner: Option<Entity>) -> Self {
+        Self {
+            damage,
+            knockback,
+            owner,
+            lifetime_ms: PROJECTILE_LIFETIME_MS,
+        }
+    }
+}
+
+/// this function checks if the collision boxes of two entities overlap
+#[must_use]
+pub fn overlaps(position_a: &PositionComponent, physics_a: &PhysicsComponent, position_b: &PositionComponent, physics_b: &PhysicsComponent) -> bool {
+    position_a.x() < position_b.x() + physics_b.collision_width()
+        && position_b.x() < position_a.x() + physics_a.collision_width()
+        && position_a.y() < position_b.y() + physics_b.collision_height()
+        && position_b.y() < position_a.y() + physics_a.collision_height()
+}
+
+/// this function returns the center of the entity
+pub fn get_entity_center(entities: &Entities, entity: Entity) -> Result<(f32, f32)> {
+    let position = entities.ecs.get::<&PositionComponent>(entity)?;
+    let physics = entities.ecs.get::<&PhysicsComponent>(entity)?;
+    Ok((position.x() + physics.collision_width() / 2.0, position.y() + physics.collision_height() / 2.0))
+}
+
+/// this function finds an entity with health, whose collision box contains the given point
+#[must_use]
+pub fn find_entity_at(entities: &Entities, x: f32, y: f32, except: Entity) -> Option<Entity> {
+    let mut query = entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &HealthComponent)>();
+    let found = query.iter().find(|(entity, (position, physics, _health))| {
+        *entity != except && (position.x()..position.x() + physics.collision_width()).contains(&x) && (position.y()..position.y() + physics.collision_height()).contains(&y)
+    });
+    found.map(|(entity, _)| entity)
+}
+
+/// this function hurts the entity and pushes it away from the source, unless it is invulnerable
+///
+/// Afterwards the entity is invulnerable for a short time. It returns whether the entity was hurt.
+pub fn damage_entity(entities: &mut Entities, events: &mut EventManager, entity: Entity, damage: i32, knockback: f32, source_x: f32, cause: DamageCause) -> Result<bool> {
+    if entities.ecs.get::<&InvulnerabilityComponent>(entity).is_ok() {
+        return Ok(false);
+    }
+    let id = entities.get_id_from_entity(entity)?;
+    let (center_x, _center_y) = get_entity_center(entities, entity)?;
+
+    {
+        let mut physics = entities.ecs.get::<&mut PhysicsComponent>(entity)?;
+        let direction = if center_x < source_x { -1.0 } else { 1.0 };
+        physics.velocity_x += direction * knockback;
+        // the entity is lifted a bit, so the ground friction doesn't stop it right away
+        physics.velocity_y -= knockback / 2.0;
+    }
+
+    // the cause has to be known before the health changes, because the entity may die
+    entities.ecs.insert_one(entity, LastDamageComponent { cause })?;
+    entities.ecs.get::<&mut HealthComponent>(entity)?.increase_health(-damage, events, id);
+    entities.ecs.insert_one(entity, InvulnerabilityComponent::new(INVULNERABILITY_MS))?;
+    events.push_event(Event::new(EntityDamageEvent { entity: id, damage }));
+    Ok(true)
+}
+
+/// this function spawns a projectile with the given velocity
+pub fn spawn_projectile(entities: &mut Entities, events: &mut EventManager, x: f32, y: f32, velocity_x: f32, velocity_y: f32, projectile: ProjectileComponent, id: EntityId) -> Result<Entity> {
+    let mut physics = PhysicsComponent::new(PROJECTILE_SIZE, PROJECTILE_SIZE);
+    physics.velocity_x = velocity_x;
+    physics.velocity_y = velocity_y;
+    physics.acceleration_y = PROJECTILE_GRAVITY;
+
+    let entity = entities.ecs.spawn((PositionComponent::new(x, y), physics, projectile));
+    entities.assign_id(entity, id)?;
+    events.push_event(Event::new(ProjectileSpawnEvent { entity }));
+    Ok(entity)
+}
+
+/// this function lets the attacker attack the given point with the weapon
+///
+/// Melee weapons hit the entity at the point, if it is within reach, and ranged weapons shoot a projectile
+/// towards the point. It returns false if the attacker has to wait for the cooldown or missed.
+pub fn attack(entities: &mut Entities, events: &mut EventManager, attacker: Entity, weapon: &Weapon, target_x: f32, target_y: f32) -> Result<bool> {
+    if entities.ecs.get::<&AttackCooldownComponent>(attacker).is_ok() {
+        return Ok(false);
+    }
+    entities.ecs.insert_one(attacker, AttackCooldownComponent::new(weapon.cooldown_ms))?;
+
+    let (center_x, center_y) = get_entity_center(entities, attacker)?;
+    let distance_x = target_x - center_x;
+    let distance_y = target_y - center_y;
+    let distance = f32::hypot(distance_x, distance_y);
+
+    if let Some(speed) = weapon.projectile_speed {
+        if distance <= 0.0 {
+            return Ok(false);
+        }
+        let id = entities.new_id();
+        spawn_projectile(
+            entities,
+            events,
+            center_x - PROJECTILE_SIZE / 2.0,
+            center_y - PROJECTILE_SIZE / 2.0,
+            distance_x / distance * speed,
+            distance_y / distance * speed,
+            ProjectileComponent::new(weapon.damage, weapon.knockback, Some(attacker)),
+            id,
+        )?;
+        return Ok(true);
+    }
+
+    if distance > weapon.reach {
+        return Ok(false);
+    }
+    let Some(target) = find_entity_at(entities, target_x, target_y, attacker) else {
+        return Ok(false);
+    };
+    damage_entity(entities, events, target, weapon.damage, weapon.knockback, center_x, DamageCause::Entity(attacker))
+}
+
+/// this function counts down the cooldowns and lets projectiles hit entities and blocks
+///
+/// It is called every 5 milliseconds, like the entity physics.
+pub fn update_combat_ms(entities: &mut Entities, blocks: &Blocks, events: &mut EventManager) -> Result<()> {
+    let mut expired = Vec::new();
+    for (entity, invulnerability) in entities.ecs.query_mut::<&mut InvulnerabilityComponent>() {
+        invulnerability.remaining_ms -= 5;
+        if invulnerability.remaining_ms <= 0 {
+            expired.push(entity);
+        }
+    }
+    for entity in expired {
+        entities.ecs.remove_one::<InvulnerabilityComponent>(entity)?;
+    }
+
+    let mut expired = Vec::new();
+    for (entity, cooldown) in entities.ecs.query_mut::<&mut AttackCooldownComponent>() {
+        cooldown.remaining_ms -= 5;
+        if cooldown.remaining_ms <= 0 {
+            expired.push(entity);
+        }
+    }
+    for entity in expired {
+        entities.ecs.remove_one::<AttackCooldownComponent>(entity)?;
+    }
+
+    update_projectiles_ms(entities, blocks, events)
+}
+
+fn update_projectiles_ms(entities: &mut Entities, blocks: &Blocks, events: &mut EventManager) -> Result<()> {
+    let mut projectiles = Vec::new();
+    for (entity, (position, physics, projectile)) in entities.ecs.query_mut::<(&PositionComponent, &PhysicsComponent, &mut ProjectileComponent)>() {
+        projectile.lifetime_ms -= 5;
+        projectiles.push((
+            entity,
+            position.clone(),
+            physics.clone(),
+            projectile.owner,
+            projectile.damage,
+            projectile.knockback,
+            projectile.lifetime_ms,
+        ));
+    }
+
+    for (projectile, position, physics, owner, damage, knockback, lifetime_ms) in projectiles {
+        let target = {
+            let mut query = entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &HealthComponent)>();
+            let found = query
+                .iter()
+                .find(|(entity, (target_position, target_physics, _health))| Some(*entity) != owner && overlaps(&position, &physics, target_position, target_physics));
+            found.map(|(entity, _)| entity)
+        };
+
+        let source_x = position.x() + physics.collision_width() / 2.0;
+        let hit_entity = match target {
+            Some(target) => damage_entity(entities, events, target, damage, knockback, source_x, DamageCause::Projectile(owner))?,
+            None => false,
+        };
+
+        let around = PositionComponent::new(position.x() - PROJECTILE_BLOCK_MARGIN, position.y() - PROJECTILE_BLOCK_MARGIN);
+        let around_physics = PhysicsComponent::new(physics.collision_width() + 2.0 * PROJECTILE_BLOCK_MARGIN, physics.collision_height() + 2.0 * PROJECTILE_BLOCK_MARGIN);
+        let hit_block = collides_with_blocks(&around, &around_physics, blocks);
+
+        if hit_entity || hit_block || lifetime_ms <= 0 {
+            entities.despawn_entity(entities.get_id_from_entity(projectile)?, events)?;
+        }
+    }
+    Ok(())
+}
+
+/// this event is sent when an entity is hurt by an attack
+pub struct EntityDamageEvent {
+    pub entity: EntityId,
+    pub damage: i32,
+}
+
+/// this event is sent when a projectile is spawned
+pub struct ProjectileSpawnEvent {
+    pub entity: Entity,
+}
+
+/// the client sends this packet, when the player attacks the given point in the world
+#[derive(Serialize, Deserialize)]
+pub struct AttackPacket {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProjectileSpawnPacket {
+    pub id: EntityId,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+}
+
+/// the server sends this packet to show the damage an entity took
+#[derive(Serialize, Deserialize)]
+pub struct EntityDamagePacket {
+    pub id: EntityId,
+    pub damage: i32,
+}
@@ -0,0 +1,4 @@
+pub use combat::*;
+
+mod combat;
+mod tests;
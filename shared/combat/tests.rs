@@ -0,0 +1,138 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use hecs::Entity;
+
+    use crate::shared::combat::{attack, damage_entity, update_combat_ms, DamageCause, EntityDamageEvent, InvulnerabilityComponent, LastDamageComponent, ProjectileComponent, INVULNERABILITY_MS};
+    use crate::shared::entities::{HealthComponent, PhysicsComponent, PositionComponent};
+    use crate::shared::items::Weapon;
+    use crate::shared::test_world::{TestWorld, GROUND_Y};
+
+    /// spawns a 1x1 entity with 20 health standing on the ground
+    fn spawn_target(world: &mut TestWorld, x: f32) -> Entity {
+        world.spawn_entity((PositionComponent::new(x, GROUND_Y as f32 - 1.0), PhysicsComponent::new(1.0, 1.0), HealthComponent::new(20, 20)))
+    }
+
+    fn simulate_ms(world: &mut TestWorld, ms: i32) {
+        for _ in 0..ms / 5 {
+            world.entities.update_entities_ms(&world.blocks, &mut world.events).unwrap();
+            update_combat_ms(&mut world.entities, &world.blocks, &mut world.events).unwrap();
+        }
+    }
+
+    fn get_health(world: &TestWorld, entity: Entity) -> i32 {
+        world.entities.ecs.get::<&HealthComponent>(entity).unwrap().health()
+    }
+
+    fn count_projectiles(world: &TestWorld) -> usize {
+        world.entities.ecs.query::<&ProjectileComponent>().iter().count()
+    }
+
+    fn sword() -> Weapon {
+        let mut weapon = Weapon::new();
+        weapon.damage = 5;
+        weapon.reach = 3.0;
+        weapon.cooldown_ms = 200;
+        weapon
+    }
+
+    #[test]
+    fn test_damage_applies_knockback_and_invulnerability() {
+        let mut world = TestWorld::new();
+        let target = spawn_target(&mut world, 10.0);
+        let source = spawn_target(&mut world, 8.0);
+        let cause = DamageCause::Entity(source);
+
+        assert!(damage_entity(&mut world.entities, &mut world.events, target, 5, 10.0, 9.0, cause).unwrap());
+        assert_eq!(get_health(&world, target), 15);
+        assert_eq!(world.entities.ecs.get::<&LastDamageComponent>(target).unwrap().cause, cause);
+        assert!(world.entities.ecs.satisfies::<&InvulnerabilityComponent>(target).unwrap());
+        {
+            let physics = world.entities.ecs.get::<&PhysicsComponent>(target).unwrap();
+            assert!(physics.velocity_x > 0.0);
+            assert!(physics.velocity_y < 0.0);
+        }
+
+        let mut damage_events = 0;
+        while let Some(event) = world.events.pop_event() {
+            if event.downcast::<EntityDamageEvent>().is_some() {
+                damage_events += 1;
+            }
+        }
+        assert_eq!(damage_events, 1);
+
+        // the entity can't be hurt again right away
+        assert!(!damage_entity(&mut world.entities, &mut world.events, target, 5, 10.0, 9.0, cause).unwrap());
+        assert_eq!(get_health(&world, target), 15);
+
+        simulate_ms(&mut world, INVULNERABILITY_MS);
+        assert!(damage_entity(&mut world.entities, &mut world.events, target, 5, 10.0, 11.0, cause).unwrap());
+        assert_eq!(get_health(&world, target), 10);
+    }
+
+    #[test]
+    fn test_melee_attack_reach() {
+        let mut world = TestWorld::new();
+        let attacker = spawn_target(&mut world, 5.0);
+        let near = spawn_target(&mut world, 7.0);
+        let far = spawn_target(&mut world, 12.0);
+        let target_y = GROUND_Y as f32 - 0.5;
+
+        assert!(!attack(&mut world.entities, &mut world.events, attacker, &sword(), 12.5, target_y).unwrap());
+        assert_eq!(get_health(&world, far), 20);
+
+        simulate_ms(&mut world, 200);
+        assert!(attack(&mut world.entities, &mut world.events, attacker, &sword(), 7.5, target_y).unwrap());
+        assert_eq!(get_health(&world, near), 15);
+
+        // attacking an empty place misses
+        simulate_ms(&mut world, INVULNERABILITY_MS);
+        assert!(!attack(&mut world.entities, &mut world.events, attacker, &sword(), 6.5, target_y - 2.0).unwrap());
+    }
+
+    #[test]
+    fn test_attack_cooldown() {
+        let mut world = TestWorld::new();
+        let attacker = spawn_target(&mut world, 5.0);
+        let target = spawn_target(&mut world, 7.0);
+        let target_y = GROUND_Y as f32 - 0.5;
+        let mut weapon = sword();
+        weapon.cooldown_ms = 1000;
+
+        assert!(attack(&mut world.entities, &mut world.events, attacker, &weapon, 7.5, target_y).unwrap());
+        simulate_ms(&mut world, INVULNERABILITY_MS);
+        let x = world.entities.ecs.get::<&PositionComponent>(target).unwrap().x();
+        assert!(!attack(&mut world.entities, &mut world.events, attacker, &weapon, x + 0.5, target_y).unwrap());
+        assert_eq!(get_health(&world, target), 15);
+    }
+
+    #[test]
+    fn test_projectile_hits_entity() {
+        let mut world = TestWorld::new();
+        let attacker = spawn_target(&mut world, 5.0);
+        let target = spawn_target(&mut world, 15.0);
+        let mut bow = sword();
+        bow.projectile_speed = Some(40.0);
+
+        assert!(attack(&mut world.entities, &mut world.events, attacker, &bow, 15.5, GROUND_Y as f32 - 1.0).unwrap());
+        assert_eq!(count_projectiles(&world), 1);
+
+        simulate_ms(&mut world, 1000);
+        assert_eq!(get_health(&world, target), 15);
+        assert_eq!(count_projectiles(&world), 0);
+        // the projectile doesn't hurt its owner
+        assert_eq!(get_health(&world, attacker), 20);
+    }
+
+    #[test]
+    fn test_projectile_stops_at_block() {
+        let mut world = TestWorld::new();
+        let attacker = spawn_target(&mut world, 5.0);
+        let mut bow = sword();
+        bow.projectile_speed = Some(40.0);
+
+        assert!(attack(&mut world.entities, &mut world.events, attacker, &bow, 5.5, GROUND_Y as f32 + 5.0).unwrap());
+        simulate_ms(&mut world, 1000);
+        assert_eq!(count_projectiles(&world), 0);
+    }
+}
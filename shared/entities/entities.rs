@@ -6,6 +6,7 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::libraries::events::{Event, EventManager};
 use crate::shared::blocks::Blocks;
+use crate::shared::combat::LastDamageComponent;
 
 pub const DEFAULT_GRAVITY: f32 = 80.0;
 pub const FRICTION_COEFFICIENT: f32 = 0.2;
@@ -142,6 +143,8 @@ impl Entities {
             if let Ok(health_component) = self.ecs.query_one_mut::<&mut HealthComponent>(entity) {
                 if velocity_change > 30.0 {
                     health_component.increase_health((-velocity_change / 5.0) as i32, events, id);
+                    // whoever hurt the entity before is not the reason it fell
+                    self.ecs.remove_one::<LastDamageComponent>(entity).ok();
                 }
             }
         }
@@ -171,7 +174,7 @@ impl Entities {
         self.entity_to_id.get(&entity).ok_or_else(|| anyhow!("invalid entity")).copied()
     }
 
-    pub fn new_id(&mut self) -> EntityId {
+    pub const fn new_id(&mut self) -> EntityId {
         self.current_id += 1;
         EntityId::new(self.current_id)
     }
@@ -222,6 +225,13 @@ impl EntityId {
     }
 }
 
+// make EntityId lua compatible, so mods can tell entities in event payloads apart
+impl rlua::UserData for EntityId {
+    fn add_methods<'lua, M: rlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_meta_method(rlua::MetaMethod::Eq, |_, this, other: Self| Ok(this.id == other.id));
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PositionComponent {
     x: f32,
@@ -244,11 +254,11 @@ impl PositionComponent {
         self.y
     }
 
-    pub fn set_x(&mut self, x: f32) {
+    pub const fn set_x(&mut self, x: f32) {
         self.x = x;
     }
 
-    pub fn set_y(&mut self, y: f32) {
+    pub const fn set_y(&mut self, y: f32) {
         self.y = y;
     }
 }
@@ -275,12 +285,16 @@ impl PhysicsComponent {
             collision_height,
         }
     }
-}
 
-#[derive(Serialize, Deserialize)]
-pub struct HealthChangePacket {
-    pub health: i32,
-    pub max_health: i32,
+    #[must_use]
+    pub const fn collision_width(&self) -> f32 {
+        self.collision_width
+    }
+
+    #[must_use]
+    pub const fn collision_height(&self) -> f32 {
+        self.collision_height
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
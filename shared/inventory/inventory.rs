@@ -1,17 +1,30 @@
+use std::ops::Range;
+
 use anyhow::{anyhow, bail, Result};
 use serde_derive::{Deserialize, Serialize};
 
 use crate::libraries::events::EventManager;
+use crate::shared::blocks::Blocks;
 use crate::shared::entities::Entities;
-use crate::shared::items::{ItemId, ItemStack, Items, Recipe, RecipeId};
+use crate::shared::items::{ItemId, ItemStack, Items, LegacyItemStack, Recipe, RecipeId};
 
 /// Slot can be either an inventory slot or a block and its slot
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Slot {
     Inventory(usize),
     Block(i32, i32, usize),
 }
 
+/// how far away from the player, in blocks, the inventory of a block can be used
+pub const BLOCK_INVENTORY_RANGE: f32 = 8.0;
+
+/// Checks if the block is close enough to the center of
+/// the player, which is in blocks, to use its inventory.
+#[must_use]
+pub fn is_block_inventory_in_range(x: i32, y: i32, player_x: f32, player_y: f32) -> bool {
+    (x as f32 + 0.5 - player_x).abs() <= BLOCK_INVENTORY_RANGE && (y as f32 + 0.5 - player_y).abs() <= BLOCK_INVENTORY_RANGE
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Inventory {
     items: Vec<Option<ItemStack>>,
@@ -19,6 +32,24 @@ pub struct Inventory {
     pub selected_slot: Option<usize>,
 }
 
+/// Legacy inventory is how inventories were saved before item stacks had metadata.
+#[derive(Deserialize)]
+pub struct LegacyInventory {
+    items: Vec<Option<LegacyItemStack>>,
+    has_changed: bool,
+    selected_slot: Option<usize>,
+}
+
+impl From<LegacyInventory> for Inventory {
+    fn from(inventory: LegacyInventory) -> Self {
+        Self {
+            items: inventory.items.into_iter().map(|item| item.map(ItemStack::from)).collect(),
+            has_changed: inventory.has_changed,
+            selected_slot: inventory.selected_slot,
+        }
+    }
+}
+
 impl Inventory {
     #[must_use]
     pub fn new(size: usize) -> Self {
@@ -102,9 +133,26 @@ impl Inventory {
     /// This function adds an item to the
     /// inventory. If the item can't be added
     /// it is dropped in the world.
-    pub fn give_item(&mut self, mut item: ItemStack, drop_pos: (f32, f32), items: &mut Items, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+    pub fn give_item(&mut self, item: ItemStack, drop_pos: (f32, f32), items: &mut Items, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        // what doesn't fit into the inventory is dropped in stacks no bigger than the item can stack
+        if let Some(mut leftover) = self.insert_item(item, items)? {
+            let max_stack = items.get_item_type(leftover.item)?.max_stack.max(1);
+            while leftover.count > 0 {
+                let stack = leftover.with_count(leftover.count.min(max_stack));
+                leftover.count -= stack.count;
+                let id = entities.new_id();
+                items.spawn_item_stack(events, entities, &stack, drop_pos.0, drop_pos.1, id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function puts the item into the inventory, first onto stacks of the same item
+    /// and then into empty slots. It returns the part of the stack that didn't fit.
+    pub fn insert_item(&mut self, mut item: ItemStack, items: &Items) -> Result<Option<ItemStack>> {
         for slot in self.items.iter_mut().flatten() {
-            if slot.item == item.item {
+            if slot.can_stack_with(&item) {
                 let max = items.get_item_type(slot.item)?.max_stack;
                 if slot.count < max {
                     let count = std::cmp::min(max - slot.count, item.count);
@@ -112,7 +160,7 @@ impl Inventory {
                     item.count -= count;
                     self.has_changed = true;
                     if item.count == 0 {
-                        return Ok(());
+                        return Ok(None);
                     }
                 }
             }
@@ -126,7 +174,7 @@ impl Inventory {
             if slot.is_none() {
                 let max = items.get_item_type(item.item)?.max_stack;
                 if item.count > max {
-                    *slot = Some(ItemStack { item: item.item, count: max });
+                    *slot = Some(item.with_count(max));
                     item.count -= max;
                 } else {
                     *slot = Some(item.clone());
@@ -136,13 +184,7 @@ impl Inventory {
             }
         }
 
-        // drop item
-        for _ in 0..item.count {
-            let id = entities.new_id();
-            items.spawn_item(events, entities, item.item, drop_pos.0, drop_pos.1, id)?;
-        }
-
-        Ok(())
+        Ok((item.count > 0).then_some(item))
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Option<ItemStack>> {
@@ -169,11 +211,259 @@ impl Inventory {
         Ok(())
     }
 
+    /// This function uses up one durability of the selected item, if it has durability.
+    pub fn damage_selected_item(&mut self, items: &Items) -> Result<()> {
+        let Some(selected_slot) = self.selected_slot else {
+            return Ok(());
+        };
+        self.damage_item(selected_slot, items)
+    }
+
+    /// This function uses up one durability of the item in the slot, if it has durability.
+    /// When the durability runs out, one item of the stack breaks.
+    pub fn damage_item(&mut self, slot: usize, items: &Items) -> Result<()> {
+        let Some(mut item) = self.get_item(slot)? else {
+            return Ok(());
+        };
+        let Some(max_durability) = items.get_item_type(item.item)?.max_durability else {
+            return Ok(());
+        };
+
+        let durability = item.metadata.durability.unwrap_or(max_durability) - 1;
+        if durability > 0 {
+            item.metadata.durability = Some(durability);
+        } else {
+            item.count -= 1;
+            item.metadata.durability = None;
+        }
+        self.set_item(slot, Some(item))
+    }
+
+    /// This function removes one item or the whole stack from the selected
+    /// slot and returns what was removed, so it can be dropped into the world.
+    /// Nothing is taken if the selected slot is out of range.
+    pub fn take_selected_item(&mut self, whole_stack: bool) -> Result<Option<ItemStack>> {
+        let Some(selected_slot) = self.selected_slot.filter(|slot| *slot < self.items.len()) else {
+            return Ok(None);
+        };
+        let Some(item) = self.get_item(selected_slot)? else {
+            return Ok(None);
+        };
+
+        let count = if whole_stack { item.count } else { 1 };
+        self.set_item(selected_slot, Some(item.with_count(item.count - count)))?;
+        Ok(Some(item.with_count(count)))
+    }
+
     /// This function returns inventory size
     #[must_use]
-    pub fn get_size(&self) -> usize {
+    pub const fn get_size(&self) -> usize {
         self.items.len()
     }
+
+    /// This function checks if the slot exists, which for a slot of a block
+    /// means that the block is in the world and its inventory has the slot.
+    #[must_use]
+    pub fn has_slot(&self, slot: &Slot, blocks: &Blocks) -> bool {
+        match *slot {
+            Slot::Inventory(index) => index < self.items.len(),
+            Slot::Block(x, y, index) => blocks.get_block_inventory_data(x, y).ok().flatten().is_some_and(|inventory_data| index < inventory_data.len()),
+        }
+    }
+
+    /// This function returns the item in the slot, which can also be a slot of a block.
+    pub fn get_slot_item(&self, slot: &Slot, blocks: &Blocks) -> Result<Option<ItemStack>> {
+        match *slot {
+            Slot::Inventory(index) => self.get_item(index),
+            Slot::Block(x, y, index) => Ok(blocks
+                .get_block_inventory_data(x, y)?
+                .ok_or_else(|| anyhow!("Block at ({}, {}) doesn't have inventory", x, y))?
+                .get(index)
+                .ok_or_else(|| anyhow!("Block at ({}, {}) doesn't have slot {}", x, y, index))?
+                .clone()),
+        }
+    }
+
+    /// This function sets the item in the slot, which can also be a slot of a block.
+    pub fn set_slot_item(&mut self, slot: &Slot, item: Option<ItemStack>, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+        match *slot {
+            Slot::Inventory(index) => self.set_item(index, item),
+            Slot::Block(x, y, index) => {
+                let mut inventory_data = blocks.get_block_inventory_data(x, y)?.ok_or_else(|| anyhow!("Block at ({}, {}) doesn't have inventory", x, y))?.clone();
+                *inventory_data.get_mut(index).ok_or_else(|| anyhow!("Block at ({}, {}) doesn't have slot {}", x, y, index))? = item.filter(|item| item.count > 0);
+                blocks.set_block_inventory_data(x, y, inventory_data, events)?;
+                blocks.update_block(x, y, events)
+            }
+        }
+    }
+
+    /// This function moves half of the stack in the slot (rounded up)
+    /// into the selected slot. It does nothing if the selected slot
+    /// is not empty or is the slot itself.
+    pub fn split(&mut self, slot: &Slot, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+        let Some(selected_slot) = self.selected_slot else {
+            return Ok(());
+        };
+        if *slot == Slot::Inventory(selected_slot) || self.get_item(selected_slot)?.is_some() {
+            return Ok(());
+        }
+
+        if let Some(item) = self.get_slot_item(slot, blocks)? {
+            let half = (item.count + 1) / 2;
+            self.set_slot_item(slot, Some(item.with_count(item.count - half)), blocks, events)?;
+            self.set_item(selected_slot, Some(item.with_count(half)))?;
+        }
+        Ok(())
+    }
+
+    /// This function moves one item from the slot into the selected slot.
+    /// It does nothing if the selected slot holds a different item or is full.
+    pub fn take_one(&mut self, slot: &Slot, items: &Items, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+        let Some(selected_slot) = self.selected_slot else {
+            return Ok(());
+        };
+        if *slot == Slot::Inventory(selected_slot) {
+            return Ok(());
+        }
+
+        if let Some(item) = self.get_slot_item(slot, blocks)? {
+            let selected_count = match self.get_item(selected_slot)? {
+                None => 0,
+                Some(selected_item) if selected_item.can_stack_with(&item) && selected_item.count < items.get_item_type(item.item)?.max_stack => selected_item.count,
+                Some(_) => return Ok(()),
+            };
+
+            self.set_slot_item(slot, Some(item.with_count(item.count - 1)), blocks, events)?;
+            self.set_item(selected_slot, Some(item.with_count(selected_count + 1)))?;
+        }
+        Ok(())
+    }
+
+    /// This function moves as much as possible of the stack in the slot to
+    /// the target. Whatever doesn't fit stays in the slot.
+    pub fn quick_move(&mut self, slot: &Slot, target: &QuickMoveTarget, items: &Items, blocks: &mut Blocks, events: &mut EventManager) -> Result<()> {
+        let mut item = self.get_slot_item(slot, blocks)?;
+        if item.is_none() {
+            return Ok(());
+        }
+        // the slot is emptied first, so the item doesn't get merged with itself
+        self.set_slot_item(slot, None, blocks, events)?;
+
+        match target {
+            QuickMoveTarget::Inventory(range) => {
+                let slots = self.items.get_mut(range.clone()).ok_or_else(|| anyhow!("Out of bounds"))?;
+                if insert_into_slots(slots, &mut item, items)? {
+                    self.has_changed = true;
+                }
+            }
+            QuickMoveTarget::Block(x, y) => {
+                let mut inventory_data = blocks
+                    .get_block_inventory_data(*x, *y)?
+                    .ok_or_else(|| anyhow!("Block at ({}, {}) doesn't have inventory", x, y))?
+                    .clone();
+                insert_into_slots(&mut inventory_data, &mut item, items)?;
+                blocks.set_block_inventory_data(*x, *y, inventory_data, events)?;
+                blocks.update_block(*x, *y, events)?;
+            }
+        }
+
+        self.set_slot_item(slot, item, blocks, events)
+    }
+
+    /// This function fills the stack in the slot with the
+    /// same item from the other slots of the inventory.
+    pub fn merge(&mut self, slot: usize, items: &Items) -> Result<()> {
+        let Some(mut merged_item) = self.get_item(slot)? else {
+            return Ok(());
+        };
+        let max_stack = items.get_item_type(merged_item.item)?.max_stack;
+
+        for index in 0..self.items.len() {
+            if index == slot || merged_item.count >= max_stack {
+                continue;
+            }
+
+            if let Some(item) = self.get_item(index)? {
+                if item.can_stack_with(&merged_item) {
+                    let count = std::cmp::min(max_stack - merged_item.count, item.count);
+                    merged_item.count += count;
+                    self.set_item(index, Some(item.with_count(item.count - count)))?;
+                }
+            }
+        }
+
+        self.set_item(slot, Some(merged_item))
+    }
+
+    /// This function sorts the slots in the range by item type
+    /// and merges stacks that can be stacked together.
+    pub fn sort(&mut self, range: Range<usize>, items: &Items) -> Result<()> {
+        // all items that can be stacked together are first counted in a single stack
+        let mut counts: Vec<ItemStack> = Vec::new();
+        for item in self.items.get(range.clone()).ok_or_else(|| anyhow!("Out of bounds"))?.iter().flatten() {
+            if let Some(counted) = counts.iter_mut().find(|counted| counted.can_stack_with(item)) {
+                counted.count += item.count;
+            } else {
+                counts.push(item.clone());
+            }
+        }
+        counts.sort_by_key(|item| item.item);
+
+        let mut sorted = Vec::new();
+        for mut item in counts {
+            let max_stack = items.get_item_type(item.item)?.max_stack;
+            while item.count > 0 {
+                let stack_count = std::cmp::min(max_stack, item.count);
+                sorted.push(item.with_count(stack_count));
+                item.count -= stack_count;
+            }
+        }
+
+        let mut sorted = sorted.into_iter();
+        for index in range {
+            self.set_item(index, sorted.next())?;
+        }
+        Ok(())
+    }
+}
+
+/// This function moves as many items as possible from the item into the slots.
+///
+/// Stacks of the same item are filled first, then empty slots. Whatever
+/// doesn't fit stays in the item. It returns true if any items were moved.
+pub fn insert_into_slots(slots: &mut [Option<ItemStack>], item: &mut Option<ItemStack>, items: &Items) -> Result<bool> {
+    let Some(mut remaining) = item.clone() else {
+        return Ok(false);
+    };
+    let max_stack = items.get_item_type(remaining.item)?.max_stack;
+    let initial_count = remaining.count;
+
+    for slot in slots.iter_mut().flatten() {
+        if slot.can_stack_with(&remaining) && slot.count < max_stack {
+            let count = std::cmp::min(max_stack - slot.count, remaining.count);
+            slot.count += count;
+            remaining.count -= count;
+        }
+    }
+
+    for slot in slots.iter_mut() {
+        if slot.is_none() && remaining.count > 0 {
+            let count = std::cmp::min(max_stack, remaining.count);
+            *slot = Some(remaining.with_count(count));
+            remaining.count -= count;
+        }
+    }
+
+    let moved = remaining.count != initial_count;
+    *item = (remaining.count > 0).then_some(remaining);
+    Ok(moved)
+}
+
+/// Where the quick move puts the items, either a range of
+/// inventory slots or the inventory of a block.
+pub enum QuickMoveTarget {
+    Inventory(Range<usize>),
+    Block(i32, i32),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -195,3 +485,34 @@ pub struct InventorySwapPacket {
 pub struct InventoryCraftPacket {
     pub recipe: RecipeId,
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct InventorySplitPacket {
+    pub slot: Slot,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryTakeOnePacket {
+    pub slot: Slot,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryQuickMovePacket {
+    pub slot: Slot,
+    // the block whose inventory is opened, None if only the player inventory is opened
+    pub block: Option<(i32, i32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryMergePacket {
+    pub slot: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InventorySortPacket;
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryDropPacket {
+    // drops the whole selected stack instead of a single item
+    pub whole_stack: bool,
+}
@@ -1,3 +1,266 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #![cfg(test)]
+mod tests {
+    use crate::shared::inventory::{insert_into_slots, Inventory, QuickMoveTarget, Slot};
+    use crate::shared::items::{Item, ItemId, ItemStack};
+    use crate::shared::test_world::TestWorld;
 
-mod tests {}
+    // creates an inventory with 6 slots and a chest with 3 slots at (1, 1),
+    // it returns the inventory and the stone and dirt items, which stack up to 10
+    fn create_inventory(world: &mut TestWorld) -> (Inventory, ItemId, ItemId) {
+        world.place_inventory_block(1, 1, 3);
+        (Inventory::new(6), world.register_item("stone", 10), world.register_item("dirt", 10))
+    }
+
+    fn get_items(inventory: &Inventory) -> Vec<Option<ItemStack>> {
+        inventory.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_split() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 5))).unwrap();
+        inventory.selected_slot = Some(1);
+
+        inventory.split(&Slot::Inventory(0), &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 2)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 3)));
+
+        // the selected slot is not empty anymore, so nothing happens
+        inventory.split(&Slot::Inventory(0), &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 2)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 3)));
+    }
+
+    #[test]
+    fn test_split_single_item() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 1))).unwrap();
+        inventory.selected_slot = Some(1);
+
+        inventory.split(&Slot::Inventory(0), &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap().is_none());
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 1)));
+    }
+
+    #[test]
+    fn test_split_block_slot() {
+        let mut world = TestWorld::new();
+        let (mut inventory, _, dirt) = create_inventory(&mut world);
+        world.blocks.set_block_inventory_data(1, 1, vec![None, Some(ItemStack::new(dirt, 4)), None], &mut world.events).unwrap();
+        inventory.selected_slot = Some(2);
+
+        inventory.split(&Slot::Block(1, 1, 1), &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(2).unwrap() == Some(ItemStack::new(dirt, 2)));
+        assert!(world.blocks.get_block_inventory_data(1, 1).unwrap().unwrap() == &vec![None, Some(ItemStack::new(dirt, 2)), None]);
+    }
+
+    #[test]
+    fn test_take_one() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 2))).unwrap();
+        inventory.selected_slot = Some(1);
+
+        inventory.take_one(&Slot::Inventory(0), &world.items, &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 1)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 1)));
+
+        inventory.take_one(&Slot::Inventory(0), &world.items, &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap().is_none());
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 2)));
+    }
+
+    #[test]
+    fn test_take_one_different_or_full() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, dirt) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 2))).unwrap();
+        inventory.set_item(1, Some(ItemStack::new(dirt, 2))).unwrap();
+        inventory.set_item(2, Some(ItemStack::new(stone, 10))).unwrap();
+
+        inventory.selected_slot = Some(1);
+        inventory.take_one(&Slot::Inventory(0), &world.items, &mut world.blocks, &mut world.events).unwrap();
+        inventory.selected_slot = Some(2);
+        inventory.take_one(&Slot::Inventory(0), &world.items, &mut world.blocks, &mut world.events).unwrap();
+
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 2)));
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(dirt, 2)));
+        assert!(inventory.get_item(2).unwrap() == Some(ItemStack::new(stone, 10)));
+    }
+
+    #[test]
+    fn test_quick_move_inventory_range() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, dirt) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 8))).unwrap();
+        inventory.set_item(3, Some(ItemStack::new(stone, 7))).unwrap();
+        inventory.set_item(4, Some(ItemStack::new(dirt, 1))).unwrap();
+
+        inventory
+            .quick_move(&Slot::Inventory(0), &QuickMoveTarget::Inventory(3..6), &world.items, &mut world.blocks, &mut world.events)
+            .unwrap();
+        assert!(get_items(&inventory) == vec![None, None, None, Some(ItemStack::new(stone, 10)), Some(ItemStack::new(dirt, 1)), Some(ItemStack::new(stone, 5)),]);
+    }
+
+    #[test]
+    fn test_quick_move_leaves_rest() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, dirt) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 8))).unwrap();
+        inventory.set_item(4, Some(ItemStack::new(stone, 7))).unwrap();
+        inventory.set_item(5, Some(ItemStack::new(dirt, 1))).unwrap();
+
+        inventory
+            .quick_move(&Slot::Inventory(0), &QuickMoveTarget::Inventory(4..6), &world.items, &mut world.blocks, &mut world.events)
+            .unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 5)));
+        assert!(inventory.get_item(4).unwrap() == Some(ItemStack::new(stone, 10)));
+    }
+
+    #[test]
+    fn test_quick_move_between_block_and_inventory() {
+        let mut world = TestWorld::new();
+        let (mut inventory, _, dirt) = create_inventory(&mut world);
+        inventory.set_item(2, Some(ItemStack::new(dirt, 3))).unwrap();
+
+        inventory
+            .quick_move(&Slot::Inventory(2), &QuickMoveTarget::Block(1, 1), &world.items, &mut world.blocks, &mut world.events)
+            .unwrap();
+        assert!(inventory.get_item(2).unwrap().is_none());
+        assert!(world.blocks.get_block_inventory_data(1, 1).unwrap().unwrap() == &vec![Some(ItemStack::new(dirt, 3)), None, None]);
+
+        inventory
+            .quick_move(&Slot::Block(1, 1, 0), &QuickMoveTarget::Inventory(0..6), &world.items, &mut world.blocks, &mut world.events)
+            .unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(dirt, 3)));
+        assert!(world.blocks.get_block_inventory_data(1, 1).unwrap().unwrap() == &vec![None, None, None]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, dirt) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 3))).unwrap();
+        inventory.set_item(1, Some(ItemStack::new(dirt, 3))).unwrap();
+        inventory.set_item(2, Some(ItemStack::new(stone, 4))).unwrap();
+        inventory.set_item(5, Some(ItemStack::new(stone, 6))).unwrap();
+
+        inventory.merge(2, &world.items).unwrap();
+        assert!(get_items(&inventory) == vec![None, Some(ItemStack::new(dirt, 3)), Some(ItemStack::new(stone, 10)), None, None, Some(ItemStack::new(stone, 3))]);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, dirt) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(dirt, 1))).unwrap();
+        inventory.set_item(2, Some(ItemStack::new(dirt, 3))).unwrap();
+        inventory.set_item(3, Some(ItemStack::new(stone, 6))).unwrap();
+        inventory.set_item(5, Some(ItemStack::new(stone, 7))).unwrap();
+
+        // the first slot is not in the sorted range
+        inventory.sort(1..6, &world.items).unwrap();
+        assert!(
+            get_items(&inventory)
+                == vec![
+                    Some(ItemStack::new(dirt, 1)),
+                    Some(ItemStack::new(stone, 10)),
+                    Some(ItemStack::new(stone, 3)),
+                    Some(ItemStack::new(dirt, 3)),
+                    None,
+                    None,
+                ]
+        );
+    }
+
+    #[test]
+    fn test_insert_into_slots_full() {
+        let mut world = TestWorld::new();
+        let (_, stone, dirt) = create_inventory(&mut world);
+        let mut slots = vec![Some(ItemStack::new(stone, 10)), Some(ItemStack::new(dirt, 1))];
+        let mut item = Some(ItemStack::new(stone, 1));
+
+        assert!(!insert_into_slots(&mut slots, &mut item, &world.items).unwrap());
+        assert!(item == Some(ItemStack::new(stone, 1)));
+    }
+
+    #[test]
+    fn test_metadata_prevents_stacking() {
+        let mut world = TestWorld::new();
+        let (_, stone, _) = create_inventory(&mut world);
+        let mut named_stone = ItemStack::new(stone, 1);
+        named_stone.metadata.display_name = Some("Shiny stone".to_owned());
+
+        let mut slots = vec![Some(ItemStack::new(stone, 1)), None];
+        let mut item = Some(named_stone.clone());
+        assert!(insert_into_slots(&mut slots, &mut item, &world.items).unwrap());
+        assert!(item.is_none());
+        assert!(slots == vec![Some(ItemStack::new(stone, 1)), Some(named_stone.clone())]);
+
+        let mut item = Some(named_stone);
+        insert_into_slots(&mut slots, &mut item, &world.items).unwrap();
+        assert_eq!(slots.get(1).unwrap().as_ref().unwrap().count, 2);
+    }
+
+    #[test]
+    fn test_damage_selected_item() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        let mut pickaxe = Item::new();
+        pickaxe.max_stack = 1;
+        pickaxe.max_durability = Some(2);
+        let pickaxe = world.items.add_item_type(pickaxe);
+
+        inventory.set_item(0, Some(ItemStack::new(pickaxe, 1))).unwrap();
+        inventory.set_item(1, Some(ItemStack::new(stone, 1))).unwrap();
+
+        inventory.selected_slot = Some(0);
+        inventory.damage_selected_item(&world.items).unwrap();
+        assert_eq!(inventory.get_item(0).unwrap().unwrap().metadata.durability, Some(1));
+
+        // items without durability are not affected
+        inventory.selected_slot = Some(1);
+        inventory.damage_selected_item(&world.items).unwrap();
+        assert!(inventory.get_item(1).unwrap() == Some(ItemStack::new(stone, 1)));
+
+        inventory.selected_slot = Some(0);
+        inventory.damage_selected_item(&world.items).unwrap();
+        assert!(inventory.get_item(0).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_split_keeps_metadata() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        let mut stone = ItemStack::new(stone, 4);
+        stone.metadata.mod_data.insert("origin".to_owned(), "cave".to_owned());
+        inventory.set_item(0, Some(stone.clone())).unwrap();
+        inventory.selected_slot = Some(1);
+
+        inventory.split(&Slot::Inventory(0), &mut world.blocks, &mut world.events).unwrap();
+        assert!(inventory.get_item(0).unwrap() == Some(stone.with_count(2)));
+        assert!(inventory.get_item(1).unwrap() == Some(stone.with_count(2)));
+    }
+
+    #[test]
+    fn test_take_selected_item() {
+        let mut world = TestWorld::new();
+        let (mut inventory, stone, _) = create_inventory(&mut world);
+        inventory.set_item(0, Some(ItemStack::new(stone, 3))).unwrap();
+        assert!(inventory.take_selected_item(false).unwrap().is_none());
+
+        inventory.selected_slot = Some(0);
+        assert!(inventory.take_selected_item(false).unwrap() == Some(ItemStack::new(stone, 1)));
+        assert!(inventory.get_item(0).unwrap() == Some(ItemStack::new(stone, 2)));
+
+        assert!(inventory.take_selected_item(true).unwrap() == Some(ItemStack::new(stone, 2)));
+        assert!(inventory.get_item(0).unwrap().is_none());
+        assert!(inventory.take_selected_item(true).unwrap().is_none());
+
+        inventory.selected_slot = Some(999);
+        assert!(inventory.take_selected_item(true).unwrap().is_none());
+    }
+}
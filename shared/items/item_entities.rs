@@ -0,0 +1,139 @@
+use anyhow::Result;
+use hecs::Entity;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::{Event, EventManager};
+use crate::shared::entities::{Entities, EntityId, PositionComponent};
+use crate::shared::items::{ItemId, ItemStack, Items};
+
+// how long a dropped item can't be picked up, so it doesn't fly right back to the player
+pub const ITEM_PICKUP_DELAY_MS: i32 = 500;
+// how long item entities stay in the world, unless a mod changes it
+pub const DEFAULT_ITEM_LIFETIME_MS: i32 = 5 * 60 * 1000;
+// how close two item entities have to be to merge into one
+const ITEM_MERGE_DISTANCE: f32 = 1.0;
+
+/// Item component makes the entity an item stack lying in the world.
+pub struct ItemComponent {
+    stack: ItemStack,
+    pickup_delay_ms: i32,
+    age_ms: i32,
+}
+
+impl ItemComponent {
+    #[must_use]
+    pub const fn new(stack: ItemStack) -> Self {
+        Self { stack, pickup_delay_ms: 0, age_ms: 0 }
+    }
+
+    #[must_use]
+    pub const fn get_item_type(&self) -> ItemId {
+        self.stack.item
+    }
+
+    #[must_use]
+    pub const fn get_count(&self) -> i32 {
+        self.stack.count
+    }
+
+    #[must_use]
+    pub const fn get_stack(&self) -> &ItemStack {
+        &self.stack
+    }
+
+    /// this function changes how many items the entity holds and lets the clients know
+    pub fn set_count(&mut self, count: i32, events: &mut EventManager, entity: Entity) {
+        if self.stack.count != count {
+            self.stack.count = count;
+            events.push_event(Event::new(ItemCountChangeEvent { entity }));
+        }
+    }
+
+    #[must_use]
+    pub const fn get_pickup_delay_ms(&self) -> i32 {
+        self.pickup_delay_ms
+    }
+
+    pub const fn set_pickup_delay_ms(&mut self, pickup_delay_ms: i32) {
+        self.pickup_delay_ms = pickup_delay_ms;
+    }
+
+    #[must_use]
+    pub const fn can_be_picked_up(&self) -> bool {
+        self.pickup_delay_ms <= 0
+    }
+}
+
+/// this function counts down the pickup delays and ages all item entities
+pub fn update_item_timers(entities: &mut Entities, delta_ms: i32) {
+    for (_entity, item) in entities.ecs.query_mut::<&mut ItemComponent>() {
+        item.pickup_delay_ms = i32::max(item.pickup_delay_ms - delta_ms, 0);
+        item.age_ms = item.age_ms.saturating_add(delta_ms);
+    }
+}
+
+impl Items {
+    pub const fn set_item_lifetime(&mut self, lifetime_ms: i32) {
+        self.item_lifetime_ms = lifetime_ms;
+    }
+
+    #[must_use]
+    pub const fn get_item_lifetime(&self) -> i32 {
+        self.item_lifetime_ms
+    }
+
+    /// this function despawns item entities that are too old and merges
+    /// close item entities that can stack, it is only called on the server
+    pub fn update_item_entities(&self, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        let mut expired = Vec::new();
+        let mut stacks = Vec::new();
+        for (entity, (position, item)) in entities.ecs.query_mut::<(&PositionComponent, &ItemComponent)>() {
+            if item.age_ms >= self.item_lifetime_ms {
+                expired.push(entity);
+            } else {
+                stacks.push((entity, position.x(), position.y(), item.stack.clone()));
+            }
+        }
+
+        // every stack is merged into the first close stack it fits into,
+        // the stacks that didn't merge can take the items of later ones
+        let mut targets: Vec<(Entity, f32, f32, ItemStack, bool)> = Vec::new();
+        for (entity, x, y, stack) in stacks {
+            let max_stack = self.get_item_type(stack.item)?.max_stack;
+            let target = targets
+                .iter_mut()
+                .find(|(_, target_x, target_y, target, _)| f32::hypot(x - target_x, y - target_y) < ITEM_MERGE_DISTANCE && target.can_stack_with(&stack) && target.count + stack.count <= max_stack);
+            if let Some((_, _, _, target, changed)) = target {
+                target.count += stack.count;
+                *changed = true;
+                expired.push(entity);
+            } else {
+                targets.push((entity, x, y, stack, false));
+            }
+        }
+
+        for (entity, _, _, stack, changed) in targets {
+            if changed {
+                entities.ecs.get::<&mut ItemComponent>(entity)?.set_count(stack.count, events, entity);
+            }
+        }
+
+        for entity in expired {
+            let id = entities.get_id_from_entity(entity)?;
+            entities.despawn_entity(id, events)?;
+        }
+        Ok(())
+    }
+}
+
+/// this event is sent when the count of an item entity changes,
+/// because it merged with another one or was partly picked up
+pub struct ItemCountChangeEvent {
+    pub entity: Entity,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemCountChangePacket {
+    pub id: EntityId,
+    pub count: i32,
+}
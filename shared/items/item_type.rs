@@ -11,6 +11,10 @@ pub struct Item {
     pub places_wall: Option<WallId>,
     pub tool: Option<ToolId>,
     pub tool_power: i32,
+    // how many blocks the item can break before it breaks, None means it never breaks
+    pub max_durability: Option<i32>,
+    // None means the item can't be used as a weapon
+    pub weapon: Option<Weapon>,
     pub(super) id: ItemId,
 }
 
@@ -25,6 +29,8 @@ impl Item {
             places_wall: None,
             tool: None,
             tool_power: 0,
+            max_durability: None,
+            weapon: None,
             id: ItemId::new(),
         }
     }
@@ -34,3 +40,31 @@ impl Item {
         self.id
     }
 }
+
+/// Weapon describes how an item attacks, melee weapons hit the entity at
+/// the cursor and ranged weapons shoot a projectile towards it.
+#[derive(Clone)]
+pub struct Weapon {
+    pub damage: i32,
+    // how far in blocks from the center of the attacker the melee weapon reaches
+    pub reach: f32,
+    // how long the attacker has to wait between attacks
+    pub cooldown_ms: i32,
+    // the speed the hit entity is pushed away with
+    pub knockback: f32,
+    // the speed of the shot projectiles, None means the weapon is melee
+    pub projectile_speed: Option<f32>,
+}
+
+impl Weapon {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            damage: 1,
+            reach: 2.0,
+            cooldown_ms: 400,
+            knockback: 10.0,
+            projectile_speed: None,
+        }
+    }
+}
@@ -1,18 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::{anyhow, bail, Result};
 use hecs::Entity;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::libraries::events::{Event, EventManager};
-use crate::shared::blocks::BlockId;
+use crate::shared::blocks::{BlockId, Blocks};
 use crate::shared::entities::{Entities, EntityId, PhysicsComponent, PositionComponent};
-use crate::shared::items::Item;
+use crate::shared::items::{Item, ItemComponent, LootPool, LootTable, ProcessingBlock, ProcessingRecipe, Weapon, DEFAULT_ITEM_LIFETIME_MS, ITEM_PICKUP_DELAY_MS};
 use crate::shared::walls::WallId;
 
 const VELOCITY_RANGE: f32 = 5.0;
+/// how many blocks away from the player the crafting station of a recipe can be
+pub const CRAFTING_STATION_RANGE: i32 = 6;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ItemId {
     id: i32,
 }
@@ -24,29 +26,74 @@ impl ItemId {
     }
 }
 
+/// Item metadata is the data that is specific to a single item stack.
+/// Only stacks with equal metadata can stack together.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ItemMetadata {
+    // durability left, None means the item is not used yet and has max durability
+    pub durability: Option<i32>,
+    // name that is shown instead of the display name of the item type
+    pub display_name: Option<String>,
+    // arbitrary data that mods can attach to the stack
+    pub mod_data: BTreeMap<String, String>,
+}
+
+impl ItemMetadata {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            durability: None,
+            display_name: None,
+            mod_data: BTreeMap::new(),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ItemStack {
     pub item: ItemId,
     pub count: i32,
+    pub metadata: ItemMetadata,
+}
+
+/// Legacy item stack is how item stacks were saved before they had metadata,
+/// it is only used to load worlds saved in that format.
+#[derive(Deserialize)]
+pub struct LegacyItemStack {
+    item: ItemId,
+    count: i32,
+}
+
+impl From<LegacyItemStack> for ItemStack {
+    fn from(stack: LegacyItemStack) -> Self {
+        Self::new(stack.item, stack.count)
+    }
 }
 
 impl ItemStack {
     #[must_use]
     pub const fn new(item_type: ItemId, stack: i32) -> Self {
-        Self { item: item_type, count: stack }
+        Self {
+            item: item_type,
+            count: stack,
+            metadata: ItemMetadata::new(),
+        }
     }
-}
 
-#[derive(Clone)]
-pub struct TileDrop {
-    pub item: ItemId,
-    pub chance: f32,
-}
+    /// Returns the same stack with a different count, the metadata is kept.
+    #[must_use]
+    pub fn with_count(&self, count: i32) -> Self {
+        Self {
+            item: self.item,
+            count,
+            metadata: self.metadata.clone(),
+        }
+    }
 
-impl TileDrop {
+    /// Checks if the two stacks have the same item and metadata, so they can be stacked together.
     #[must_use]
-    pub const fn new(drop: ItemId, chance: f32) -> Self {
-        Self { item: drop, chance }
+    pub fn can_stack_with(&self, other: &Self) -> bool {
+        self.item == other.item && self.metadata == other.metadata
     }
 }
 
@@ -66,6 +113,8 @@ impl RecipeId {
 pub struct Recipe {
     pub result: ItemStack,
     pub ingredients: HashMap<ItemId, i32>,
+    // block that has to be near the player to craft the recipe, None means it can be crafted anywhere
+    pub station: Option<BlockId>,
     id: RecipeId,
 }
 
@@ -75,6 +124,7 @@ impl Recipe {
         Self {
             result: ItemStack::new(ItemId::new(), 0),
             ingredients: HashMap::new(),
+            station: None,
             id: RecipeId::new(),
         }
     }
@@ -83,13 +133,27 @@ impl Recipe {
     pub const fn get_id(&self) -> RecipeId {
         self.id
     }
+
+    /// Checks if the crafting station of the recipe is in range of the
+    /// given position, which is in blocks. Recipes without a station
+    /// can be crafted anywhere.
+    pub fn is_station_in_range(&self, blocks: &Blocks, x: f32, y: f32) -> Result<bool> {
+        self.station.map_or(Ok(true), |station| blocks.is_block_in_range(station, x as i32, y as i32, CRAFTING_STATION_RANGE))
+    }
 }
 
 pub struct Items {
     pub(super) item_types: Vec<Item>,
-    pub(super) block_drops: HashMap<BlockId, TileDrop>,
+    pub(super) block_drops: HashMap<BlockId, LootTable>,
     recipes: Vec<Recipe>,
-    wall_drops: HashMap<WallId, TileDrop>,
+    wall_drops: HashMap<WallId, LootTable>,
+    pub(super) processing_blocks: HashMap<BlockId, ProcessingBlock>,
+    pub(super) processing_recipes: Vec<ProcessingRecipe>,
+    pub(super) fuels: HashMap<ItemId, i32>,
+    // the part of a millisecond processing blocks didn't use yet, so short frames still add up
+    pub(super) processing_time_left_over: f32,
+    // how long item entities stay in the world before they despawn
+    pub(super) item_lifetime_ms: i32,
 }
 
 impl Items {
@@ -100,12 +164,22 @@ impl Items {
             block_drops: HashMap::new(),
             wall_drops: HashMap::new(),
             recipes: Vec::new(),
+            processing_blocks: HashMap::new(),
+            processing_recipes: Vec::new(),
+            fuels: HashMap::new(),
+            processing_time_left_over: 0.0,
+            item_lifetime_ms: DEFAULT_ITEM_LIFETIME_MS,
         }
     }
 
     /// this function spawns an item into the world
     pub fn spawn_item(&mut self, events: &mut EventManager, entities: &mut Entities, item_id: ItemId, x: f32, y: f32, id: EntityId) -> Result<Entity> {
-        let entity = entities.ecs.spawn((PositionComponent::new(x, y), PhysicsComponent::new(1.0, 1.0), ItemComponent::new(item_id)));
+        self.spawn_item_stack(events, entities, &ItemStack::new(item_id, 1), x, y, id)
+    }
+
+    /// this function spawns a single entity for the whole item stack into the world
+    pub fn spawn_item_stack(&mut self, events: &mut EventManager, entities: &mut Entities, stack: &ItemStack, x: f32, y: f32, id: EntityId) -> Result<Entity> {
+        let entity = entities.ecs.spawn((PositionComponent::new(x, y), PhysicsComponent::new(1.0, 1.0), ItemComponent::new(stack.clone())));
 
         entities.assign_id(entity, id)?;
 
@@ -117,16 +191,28 @@ impl Items {
 
     /// spawns an item with random velocity
     pub fn drop_item(&mut self, events: &mut EventManager, entities: &mut Entities, item: ItemId, x: f32, y: f32) -> Result<()> {
-        let id = entities.new_id();
-        let entity = self.spawn_item(events, entities, item, x, y, id)?;
+        self.drop_item_stack(events, entities, &ItemStack::new(item, 1), x, y)
+    }
+
+    /// spawns an item stack as one entity with random velocity, it can't be picked up right away
+    pub fn drop_item_stack(&mut self, events: &mut EventManager, entities: &mut Entities, stack: &ItemStack, x: f32, y: f32) -> Result<()> {
         let velocity_x = rand::random::<f32>() * 2.0 * VELOCITY_RANGE - VELOCITY_RANGE;
         let velocity_y = -rand::random::<f32>() * 4.0 * VELOCITY_RANGE;
+        self.throw_item_stack(events, entities, stack, (x, y), (velocity_x, velocity_y))?;
+        Ok(())
+    }
+
+    /// spawns an item stack as one entity with the given velocity, it can't be picked up right away
+    pub fn throw_item_stack(&mut self, events: &mut EventManager, entities: &mut Entities, stack: &ItemStack, pos: (f32, f32), velocity: (f32, f32)) -> Result<Entity> {
+        let id = entities.new_id();
+        let entity = self.spawn_item_stack(events, entities, stack, pos.0, pos.1, id)?;
+        entities.ecs.get::<&mut ItemComponent>(entity)?.set_pickup_delay_ms(ITEM_PICKUP_DELAY_MS);
 
         let mut physics = entities.ecs.get::<&mut PhysicsComponent>(entity)?;
-        physics.velocity_x = velocity_x;
-        physics.velocity_y = velocity_y;
+        physics.velocity_x = velocity.0;
+        physics.velocity_y = velocity.1;
 
-        Ok(())
+        Ok(entity)
     }
 
     /// this function registers an item type
@@ -138,11 +224,22 @@ impl Items {
         id
     }
 
+    /// this function registers an item type into these items
+    pub fn add_item_type(&mut self, item_type: Item) -> ItemId {
+        Self::register_new_item_type(&mut self.item_types, item_type)
+    }
+
     /// this function returns the item type with the given id
     pub fn get_item_type(&self, id: ItemId) -> Result<Item> {
         Ok(self.item_types.get(id.id as usize).ok_or_else(|| anyhow!("item type not found"))?.clone())
     }
 
+    /// this function makes the item type with the given id a weapon
+    pub fn set_weapon(&mut self, id: ItemId, weapon: Weapon) -> Result<()> {
+        self.item_types.get_mut(id.id as usize).ok_or_else(|| anyhow!("item type not found"))?.weapon = Some(weapon);
+        Ok(())
+    }
+
     /// this function returns the item type with the given name
     pub fn get_item_type_by_name(&self, name: &str) -> Result<Item> {
         for item_type in &self.item_types {
@@ -155,28 +252,40 @@ impl Items {
 
     /// this function returns the number of item types
     #[must_use]
-    pub fn get_num_item_types(&self) -> usize {
+    pub const fn get_num_item_types(&self) -> usize {
         self.item_types.len()
     }
 
-    /// this function sets the block drop for the given block type
-    pub fn set_block_drop(&mut self, block_type: BlockId, drop: TileDrop) {
+    /// this function sets the loot table for the given block type, replacing the previous one
+    pub fn set_block_drop(&mut self, block_type: BlockId, drop: LootTable) {
         self.block_drops.insert(block_type, drop);
     }
 
-    /// this function returns the block drop for the given block type
-    pub fn get_block_drop(&self, block_type: BlockId) -> Result<TileDrop> {
-        Ok(self.block_drops.get(&block_type).ok_or_else(|| anyhow!("block drop not found"))?.clone())
+    /// this function adds a pool to the loot table of the given block type
+    pub fn add_block_loot_pool(&mut self, block_type: BlockId, pool: LootPool) {
+        self.block_drops.entry(block_type).or_insert_with(LootTable::new).pools.push(pool);
+    }
+
+    /// this function returns the loot table for the given block type
+    #[must_use]
+    pub fn get_block_drop(&self, block_type: BlockId) -> Option<&LootTable> {
+        self.block_drops.get(&block_type)
     }
 
-    /// this function sets the wall drop for the given wall type
-    pub fn set_wall_drop(&mut self, wall_type: WallId, drop: TileDrop) {
+    /// this function sets the loot table for the given wall type, replacing the previous one
+    pub fn set_wall_drop(&mut self, wall_type: WallId, drop: LootTable) {
         self.wall_drops.insert(wall_type, drop);
     }
 
-    /// this function returns the wall drop for the given wall type
-    pub fn get_wall_drop(&self, wall_type: WallId) -> Result<&TileDrop> {
-        self.wall_drops.get(&wall_type).ok_or_else(|| anyhow!("wall drop not found"))
+    /// this function adds a pool to the loot table of the given wall type
+    pub fn add_wall_loot_pool(&mut self, wall_type: WallId, pool: LootPool) {
+        self.wall_drops.entry(wall_type).or_insert_with(LootTable::new).pools.push(pool);
+    }
+
+    /// this function returns the loot table for the given wall type
+    #[must_use]
+    pub fn get_wall_drop(&self, wall_type: WallId) -> Option<&LootTable> {
+        self.wall_drops.get(&wall_type)
     }
 
     #[must_use]
@@ -211,6 +320,8 @@ pub struct ItemSpawnEvent {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemSpawnPacket {
     pub item_type: ItemId,
+    pub count: i32,
+    pub pickup_delay_ms: i32,
     pub x: f32,
     pub y: f32,
     pub velocity_x: f32,
@@ -218,22 +329,6 @@ pub struct ItemSpawnPacket {
     pub id: EntityId,
 }
 
-pub struct ItemComponent {
-    item_type: ItemId,
-}
-
-impl ItemComponent {
-    #[must_use]
-    pub const fn new(item_type: ItemId) -> Self {
-        Self { item_type }
-    }
-
-    #[must_use]
-    pub const fn get_item_type(&self) -> ItemId {
-        self.item_type
-    }
-}
-
 pub struct ItemCreationEvent {
     pub item_id: u32,
 }
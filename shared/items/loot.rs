@@ -0,0 +1,122 @@
+use rand::Rng;
+
+use crate::shared::blocks::ToolId;
+use crate::shared::items::{ItemId, ItemStack};
+
+/// Loot entry is one of the possible drops of a loot pool.
+/// An entry without an item drops nothing, which is used
+/// to make drops happen only at some chance.
+#[derive(Clone)]
+pub struct LootEntry {
+    pub item: Option<ItemId>,
+    pub min_count: i32,
+    pub max_count: i32,
+    // how likely the entry is chosen compared to the other entries of the pool
+    pub weight: f32,
+    // the entry can only be chosen if the tile was broken with this tool
+    pub tool: Option<ToolId>,
+    // the entry can only be chosen if the tile was broken with at least this tool power
+    pub min_tool_power: i32,
+}
+
+impl LootEntry {
+    #[must_use]
+    pub const fn new(item: Option<ItemId>, weight: f32) -> Self {
+        Self {
+            item,
+            min_count: 1,
+            max_count: 1,
+            weight,
+            tool: None,
+            min_tool_power: 0,
+        }
+    }
+
+    /// Checks if the entry can be chosen with the given tool.
+    #[must_use]
+    pub fn meets_conditions(&self, tool: Option<ToolId>, tool_power: i32) -> bool {
+        (self.tool.is_none() || self.tool == tool) && tool_power >= self.min_tool_power
+    }
+}
+
+/// Loot pool chooses one of its entries by weight, once for every roll.
+#[derive(Clone)]
+pub struct LootPool {
+    pub rolls: i32,
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootPool {
+    fn roll<R: Rng>(&self, rng: &mut R, tool: Option<ToolId>, tool_power: i32, drops: &mut Vec<ItemStack>) {
+        let entries: Vec<&LootEntry> = self.entries.iter().filter(|entry| entry.meets_conditions(tool, tool_power) && entry.weight > 0.0).collect();
+        if entries.is_empty() {
+            return;
+        }
+        let total_weight: f32 = entries.iter().map(|entry| entry.weight).sum();
+
+        for _ in 0..self.rolls {
+            let mut chosen = rng.gen_range(0.0..total_weight);
+            let entry = entries
+                .iter()
+                .find(|entry| {
+                    if chosen < entry.weight {
+                        return true;
+                    }
+                    chosen -= entry.weight;
+                    false
+                })
+                // rounding errors could make the chosen weight miss the last entry
+                .or_else(|| entries.last());
+
+            if let Some(LootEntry {
+                item: Some(item),
+                min_count,
+                max_count,
+                ..
+            }) = entry
+            {
+                let count = rng.gen_range(*min_count..=(*max_count).max(*min_count));
+                if count > 0 {
+                    drops.push(ItemStack::new(*item, count));
+                }
+            }
+        }
+    }
+}
+
+/// Loot table decides what a block or a wall drops when it is broken.
+/// Every pool of the table is rolled independently, so a tile can
+/// drop multiple item types.
+#[derive(Clone)]
+pub struct LootTable {
+    pub pools: Vec<LootPool>,
+}
+
+impl LootTable {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { pools: Vec::new() }
+    }
+
+    /// Creates a loot table that drops a single item at a chance
+    /// between 0 and 1, which is what `set_block_drop` registers.
+    #[must_use]
+    pub fn single(item: ItemId, chance: f32) -> Self {
+        let chance = chance.clamp(0.0, 1.0);
+        Self {
+            pools: vec![LootPool {
+                rolls: 1,
+                entries: vec![LootEntry::new(Some(item), chance), LootEntry::new(None, 1.0 - chance)],
+            }],
+        }
+    }
+
+    /// Rolls all the pools and returns the dropped items.
+    pub fn roll<R: Rng>(&self, rng: &mut R, tool: Option<ToolId>, tool_power: i32) -> Vec<ItemStack> {
+        let mut drops = Vec::new();
+        for pool in &self.pools {
+            pool.roll(rng, tool, tool_power, &mut drops);
+        }
+        drops
+    }
+}
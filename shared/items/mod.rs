@@ -1,9 +1,17 @@
 // import * from all the modules
+pub use item_entities::*;
 pub use item_type::*;
 pub use items::*;
+pub use loot::*;
 pub use mod_interface::*;
+pub use player_inventories::*;
+pub use processing::*;
 
+mod item_entities;
 mod item_type;
 mod items;
+mod loot;
 mod mod_interface;
+mod player_inventories;
+mod processing;
 mod tests;
@@ -2,21 +2,120 @@ use std::sync::{Arc, Mutex, PoisonError};
 
 use anyhow::Result;
 
-use crate::shared::blocks::{BlockId, ToolId};
-use crate::shared::items::{Item, ItemId, ItemStack, Items, Recipe, TileDrop};
+use crate::shared::blocks::{BlockId, Blocks, ToolId};
+use crate::shared::items::{Item, ItemId, ItemMetadata, ItemStack, Items, LootEntry, LootPool, LootTable, PlayerInventories, ProcessingBlock, ProcessingRecipe, Recipe, Weapon};
 use crate::shared::mod_manager::ModManager;
 use crate::shared::walls::WallId;
 
 // make ItemId lua compatible
-impl rlua::UserData for ItemId {}
+impl rlua::UserData for ItemId {
+    // implement equals comparison for ItemId, so ids in event payloads can be compared
+    fn add_methods<'lua, M: rlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_meta_method(rlua::MetaMethod::Eq, |_, this, other: Self| Ok(*this == other));
+    }
+}
+
+// loot entries are passed from lua as tables, missing fields have default values
+impl<'lua> rlua::FromLua<'lua> for LootEntry {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("loot entry must be a table".to_owned()));
+        };
+
+        let weight = table.get::<_, Option<f32>>("weight")?.unwrap_or(1.0);
+        // a negative or infinite weight would break choosing an entry by weight
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(rlua::Error::RuntimeError(format!("loot entry weight must be a non-negative number, got {weight}")));
+        }
+
+        let mut entry = Self::new(table.get("item")?, weight);
+        entry.min_count = table.get::<_, Option<i32>>("min_count")?.unwrap_or(1);
+        entry.max_count = table.get::<_, Option<i32>>("max_count")?.unwrap_or(entry.min_count);
+        entry.tool = table.get("tool")?;
+        entry.min_tool_power = table.get::<_, Option<i32>>("min_tool_power")?.unwrap_or(0);
+        Ok(entry)
+    }
+}
+
+// weapons are passed from lua as tables, missing fields have default values
+impl<'lua> rlua::FromLua<'lua> for Weapon {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("weapon must be a table".to_owned()));
+        };
+
+        let mut weapon = Self::new();
+        weapon.damage = table.get::<_, Option<i32>>("damage")?.unwrap_or(weapon.damage);
+        weapon.reach = table.get::<_, Option<f32>>("reach")?.unwrap_or(weapon.reach);
+        weapon.cooldown_ms = table.get::<_, Option<i32>>("cooldown")?.unwrap_or(weapon.cooldown_ms);
+        weapon.knockback = table.get::<_, Option<f32>>("knockback")?.unwrap_or(weapon.knockback);
+        weapon.projectile_speed = table.get("projectile_speed")?;
+        Ok(weapon)
+    }
+}
+
+// item metadata is passed to lua as a table with durability, display_name and data fields
+impl<'lua> rlua::ToLua<'lua> for ItemMetadata {
+    fn to_lua(self, lua: rlua::Context<'lua>) -> rlua::Result<rlua::Value<'lua>> {
+        let table = lua.create_table()?;
+        table.set("durability", self.durability)?;
+        table.set("display_name", self.display_name)?;
+        table.set("data", self.mod_data)?;
+        Ok(rlua::Value::Table(table))
+    }
+}
+
+// missing fields of item metadata from lua are empty
+impl<'lua> rlua::FromLua<'lua> for ItemMetadata {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("item metadata must be a table".to_owned()));
+        };
+
+        let mut metadata = Self::new();
+        metadata.durability = table.get("durability")?;
+        metadata.display_name = table.get("display_name")?;
+        metadata.mod_data = table.get::<_, Option<_>>("data")?.unwrap_or_default();
+        Ok(metadata)
+    }
+}
+
+/// this function checks that the block has all the inventory slots a processing block uses
+fn check_processing_slots(blocks: &Arc<Mutex<Blocks>>, block_id: BlockId, slots: impl Iterator<Item = usize>) -> rlua::Result<()> {
+    let slot_count = blocks
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_block_type(block_id)
+        .map_err(|err| rlua::Error::RuntimeError(err.to_string()))?
+        .inventory_slots
+        .len();
+    for slot in slots {
+        if slot >= slot_count {
+            return Err(rlua::Error::RuntimeError(format!(
+                "processing block slot {slot} is out of range, the block has {slot_count} inventory slots"
+            )));
+        }
+    }
+    Ok(())
+}
 
 /// this function initializes the items mod interface
-/// it adds lua functions to the lua context
-pub fn init_items_mod_interface(items: &Arc<Mutex<Items>>, mods: &mut ModManager) -> Result<()> {
+/// it adds lua functions to the lua context, blocks are needed to check processing block slots
+pub fn init_items_mod_interface(items: &Arc<Mutex<Items>>, blocks: &Arc<Mutex<Blocks>>, mods: &mut ModManager) -> Result<()> {
     let items_clone = items.clone();
     mods.add_global_function(
         "register_item_type",
-        move |_lua, (name, display_name, max_stack, places_block, places_wall, tool, tool_power): (String, String, i32, Option<BlockId>, Option<WallId>, Option<ToolId>, i32)| {
+        move |_lua,
+              (name, display_name, max_stack, places_block, places_wall, tool, tool_power, max_durability): (
+            String,
+            String,
+            i32,
+            Option<BlockId>,
+            Option<WallId>,
+            Option<ToolId>,
+            i32,
+            Option<i32>,
+        )| {
             let mut item_type = Item::new();
 
             item_type.name = name;
@@ -26,6 +125,7 @@ pub fn init_items_mod_interface(items: &Arc<Mutex<Items>>, mods: &mut ModManager
             item_type.places_wall = places_wall;
             item_type.tool = tool;
             item_type.tool_power = tool_power;
+            item_type.max_durability = max_durability;
 
             let result = Items::register_new_item_type(&mut items_clone.lock().unwrap_or_else(PoisonError::into_inner).item_types, item_type);
             Ok(result)
@@ -44,18 +144,25 @@ pub fn init_items_mod_interface(items: &Arc<Mutex<Items>>, mods: &mut ModManager
         Err(rlua::Error::RuntimeError("Item type not found".to_owned()))
     })?;
 
+    // takes a table with damage, reach, cooldown, knockback and projectile_speed fields
     let items_clone = items.clone();
-    mods.add_global_function("set_block_drop", move |_lua, (block_id, item_id, chance): (BlockId, ItemId, f32)| {
-        items_clone.lock().unwrap_or_else(PoisonError::into_inner).block_drops.insert(block_id, TileDrop::new(item_id, chance));
-        Ok(())
+    mods.add_global_function("set_item_weapon", move |_lua, (item_id, weapon): (ItemId, Weapon)| {
+        items_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_weapon(item_id, weapon)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))
     })?;
 
+    init_loot_mod_interface(items, mods)?;
+
     let items_clone = items.clone();
     mods.add_global_function(
         "register_recipe",
-        move |_lua, (result, result_count, ingredients, ingredients_count): (ItemId, i32, Vec<ItemId>, Vec<i32>)| {
+        move |_lua, (result, result_count, ingredients, ingredients_count, station): (ItemId, i32, Vec<ItemId>, Vec<i32>, Option<BlockId>)| {
             let mut recipe = Recipe::new();
             recipe.result = ItemStack::new(result, result_count);
+            recipe.station = station;
 
             for (item, count) in ingredients.iter().zip(ingredients_count.iter()) {
                 recipe.ingredients.insert(*item, *count);
@@ -67,5 +174,103 @@ pub fn init_items_mod_interface(items: &Arc<Mutex<Items>>, mods: &mut ModManager
         },
     )?;
 
+    // slots are indices into the block's inventory slots, starting with 0
+    let items_clone = items.clone();
+    let blocks_clone = blocks.clone();
+    mods.add_global_function(
+        "register_processing_block",
+        move |_lua, (block_id, input_slot, output_slot, fuel_slot): (BlockId, usize, usize, Option<usize>)| {
+            check_processing_slots(&blocks_clone, block_id, [Some(input_slot), Some(output_slot), fuel_slot].into_iter().flatten())?;
+
+            let processing_block = ProcessingBlock { input_slot, output_slot, fuel_slot };
+            items_clone.lock().unwrap_or_else(PoisonError::into_inner).set_processing_block(block_id, processing_block);
+            Ok(())
+        },
+    )?;
+
+    let items_clone = items.clone();
+    mods.add_global_function(
+        "register_processing_recipe",
+        move |_lua, (block_id, result, result_count, ingredient, ingredient_count, processing_time): (BlockId, ItemId, i32, ItemId, i32, i32)| {
+            let recipe = ProcessingRecipe {
+                block: block_id,
+                ingredient: ItemStack::new(ingredient, ingredient_count),
+                result: ItemStack::new(result, result_count),
+                processing_time,
+            };
+            items_clone.lock().unwrap_or_else(PoisonError::into_inner).add_processing_recipe(recipe);
+            Ok(())
+        },
+    )?;
+
+    let items_clone = items.clone();
+    mods.add_global_function("register_fuel", move |_lua, (item_id, burn_time): (ItemId, i32)| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).set_fuel(item_id, burn_time);
+        Ok(())
+    })?;
+
+    // items lying in the world despawn after this many milliseconds
+    let items_clone = items.clone();
+    mods.add_global_function("set_item_lifetime", move |_lua, lifetime_ms: i32| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).set_item_lifetime(lifetime_ms);
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// this function adds the lua functions for block and wall drops
+fn init_loot_mod_interface(items: &Arc<Mutex<Items>>, mods: &mut ModManager) -> Result<()> {
+    let items_clone = items.clone();
+    mods.add_global_function("set_block_drop", move |_lua, (block_id, item_id, chance): (BlockId, ItemId, f32)| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).set_block_drop(block_id, LootTable::single(item_id, chance));
+        Ok(())
+    })?;
+
+    // entries are tables with item, weight, min_count, max_count, tool and min_tool_power fields
+    let items_clone = items.clone();
+    mods.add_global_function("add_block_loot", move |_lua, (block_id, rolls, entries): (BlockId, i32, Vec<LootEntry>)| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).add_block_loot_pool(block_id, LootPool { rolls, entries });
+        Ok(())
+    })?;
+
+    let items_clone = items.clone();
+    mods.add_global_function("set_wall_drop", move |_lua, (wall_id, item_id, chance): (WallId, ItemId, f32)| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).set_wall_drop(wall_id, LootTable::single(item_id, chance));
+        Ok(())
+    })?;
+
+    let items_clone = items.clone();
+    mods.add_global_function("add_wall_loot", move |_lua, (wall_id, rolls, entries): (WallId, i32, Vec<LootEntry>)| {
+        items_clone.lock().unwrap_or_else(PoisonError::into_inner).add_wall_loot_pool(wall_id, LootPool { rolls, entries });
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// this function adds the lua functions that read and change the metadata of the
+/// stacks in the inventories of players, only the server has the inventories of all players
+pub fn init_item_metadata_mod_interface(player_inventories: &Arc<Mutex<PlayerInventories>>, mods: &mut ModManager) -> Result<()> {
+    // metadata of the stack in a slot of a player's inventory, slots start with 0 and empty slots have no metadata
+    let inventories_clone = player_inventories.clone();
+    mods.add_global_function("get_item_metadata", move |_lua, (player, slot): (String, usize)| {
+        inventories_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_metadata(&player, slot)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
+    // the player gets the new metadata on the next tick
+    let inventories_clone = player_inventories.clone();
+    mods.add_global_function("set_item_metadata", move |_lua, (player, slot, metadata): (String, usize, ItemMetadata)| {
+        inventories_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_metadata(&player, slot, metadata)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
     Ok(())
 }
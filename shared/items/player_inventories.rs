@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::shared::entities::Entities;
+use crate::shared::inventory::Inventory;
+use crate::shared::items::{ItemId, ItemMetadata};
+use crate::shared::players::PlayerComponent;
+
+/// Metadata change is a change a mod made to the metadata of a stack in the inventory of a player.
+struct MetadataChange {
+    player: String,
+    slot: usize,
+    // the change is only applied if the slot still has this item
+    item: ItemId,
+    metadata: ItemMetadata,
+}
+
+/// Player inventories hold copies of the inventories of all players by their name,
+/// so mods can read and change item metadata without access to the entities.
+///
+/// The copies are refreshed every tick and the changes are applied to the players then.
+pub struct PlayerInventories {
+    inventories: HashMap<String, Inventory>,
+    changes: Vec<MetadataChange>,
+}
+
+impl PlayerInventories {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            inventories: HashMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// this function applies the metadata changes mods made to the players and copies their inventories again
+    pub fn update(&mut self, entities: &mut Entities) -> Result<()> {
+        let changes = std::mem::take(&mut self.changes);
+        for (_entity, (player, inventory)) in entities.ecs.query_mut::<(&PlayerComponent, &mut Inventory)>() {
+            for change in changes.iter().filter(|change| change.player == player.get_name()) {
+                // the player could have moved the item in the meantime
+                if let Some(mut stack) = inventory.get_item(change.slot)?.filter(|stack| stack.item == change.item) {
+                    stack.metadata = change.metadata.clone();
+                    inventory.set_item(change.slot, Some(stack))?;
+                }
+            }
+        }
+
+        self.inventories.clear();
+        for (_entity, (player, inventory)) in entities.ecs.query_mut::<(&PlayerComponent, &Inventory)>() {
+            self.inventories.insert(player.get_name().to_owned(), inventory.clone());
+        }
+        Ok(())
+    }
+
+    /// this function returns the metadata of the stack in the slot, None if the slot is empty
+    pub fn get_metadata(&self, player: &str, slot: usize) -> Result<Option<ItemMetadata>> {
+        let inventory = self.inventories.get(player).ok_or_else(|| anyhow!("player {player} is not online"))?;
+        Ok(inventory.get_item(slot)?.map(|stack| stack.metadata))
+    }
+
+    /// this function changes the metadata of the stack in the slot, it fails if the slot is empty
+    pub fn set_metadata(&mut self, player: &str, slot: usize, metadata: ItemMetadata) -> Result<()> {
+        let inventory = self.inventories.get_mut(player).ok_or_else(|| anyhow!("player {player} is not online"))?;
+        let Some(mut stack) = inventory.get_item(slot)? else {
+            bail!("slot {slot} is empty");
+        };
+        stack.metadata = metadata.clone();
+        let item = stack.item;
+        inventory.set_item(slot, Some(stack))?;
+        self.changes.push(MetadataChange {
+            player: player.to_owned(),
+            slot,
+            item,
+            metadata,
+        });
+        Ok(())
+    }
+}
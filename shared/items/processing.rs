@@ -0,0 +1,215 @@
+use anyhow::{anyhow, Result};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::EventManager;
+use crate::shared::blocks::{BlockId, Blocks};
+use crate::shared::items::{ItemId, ItemStack, Items};
+
+/// Processing block is a block that processes items in its
+/// inventory over time, for example a furnace. The slots are
+/// indices into the block's inventory slots.
+#[derive(Clone)]
+pub struct ProcessingBlock {
+    pub input_slot: usize,
+    pub output_slot: usize,
+    // slot that holds the fuel, None means the block doesn't need fuel
+    pub fuel_slot: Option<usize>,
+}
+
+/// Processing recipe is a recipe that can only be made in
+/// a processing block and takes some time to finish.
+#[derive(Clone)]
+pub struct ProcessingRecipe {
+    pub block: BlockId,
+    pub ingredient: ItemStack,
+    pub result: ItemStack,
+    // how much time it takes to process the recipe in ms
+    pub processing_time: i32,
+}
+
+/// Processing state is stored in the block data of a processing block.
+/// It is synced to the clients so they can render the progress.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessingState {
+    // how long the current recipe has been processing in ms
+    pub progress: i32,
+    // how long the current recipe takes to process in ms, 0 if nothing is processing
+    pub processing_time: i32,
+    // how much burn time the current fuel has left in ms
+    pub fuel_left: i32,
+    // total burn time of the current fuel in ms
+    pub fuel_time: i32,
+}
+
+impl ProcessingState {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            progress: 0,
+            processing_time: 0,
+            fuel_left: 0,
+            fuel_time: 0,
+        }
+    }
+
+    /// Reads the processing state from block data, empty block data means a fresh state.
+    pub fn from_block_data(data: &[u8]) -> Result<Self> {
+        if data.is_empty() {
+            return Ok(Self::new());
+        }
+        Ok(bincode::deserialize(data)?)
+    }
+
+    /// Serializes the processing state into block data, a fresh state is stored as empty block data.
+    pub fn to_block_data(&self) -> Result<Vec<u8>> {
+        if *self == Self::new() {
+            return Ok(Vec::new());
+        }
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Returns the progress of the current recipe as a number between 0 and 1.
+    #[must_use]
+    pub fn get_progress(&self) -> f32 {
+        if self.processing_time <= 0 {
+            return 0.0;
+        }
+        (self.progress as f32 / self.processing_time as f32).clamp(0.0, 1.0)
+    }
+
+    /// Returns how much of the current fuel is left as a number between 0 and 1.
+    #[must_use]
+    pub fn get_fuel(&self) -> f32 {
+        if self.fuel_time <= 0 {
+            return 0.0;
+        }
+        (self.fuel_left as f32 / self.fuel_time as f32).clamp(0.0, 1.0)
+    }
+}
+
+impl Items {
+    /// this function makes the block type a processing block
+    pub fn set_processing_block(&mut self, block_type: BlockId, processing_block: ProcessingBlock) {
+        self.processing_blocks.insert(block_type, processing_block);
+    }
+
+    /// this function returns the processing block info for the given block type
+    #[must_use]
+    pub fn get_processing_block(&self, block_type: BlockId) -> Option<&ProcessingBlock> {
+        self.processing_blocks.get(&block_type)
+    }
+
+    /// this function adds a processing recipe
+    pub fn add_processing_recipe(&mut self, recipe: ProcessingRecipe) {
+        self.processing_recipes.push(recipe);
+    }
+
+    #[must_use]
+    pub const fn get_processing_recipes(&self) -> &Vec<ProcessingRecipe> {
+        &self.processing_recipes
+    }
+
+    /// this function sets how long an item burns when used as fuel
+    pub fn set_fuel(&mut self, item: ItemId, burn_time: i32) {
+        self.fuels.insert(item, burn_time);
+    }
+
+    /// this function returns the burn time of the item, None if it is not a fuel
+    #[must_use]
+    pub fn get_fuel(&self, item: ItemId) -> Option<i32> {
+        self.fuels.get(&item).copied()
+    }
+
+    /// this function finds the recipe the block type can process with
+    /// the given input, while the output slot still has space for the result
+    fn find_processing_recipe(&self, block_type: BlockId, input: Option<&ItemStack>, output: Option<&ItemStack>) -> Result<Option<&ProcessingRecipe>> {
+        let Some(input) = input else {
+            return Ok(None);
+        };
+
+        for recipe in &self.processing_recipes {
+            if recipe.block != block_type || recipe.ingredient.item != input.item || recipe.ingredient.count > input.count {
+                continue;
+            }
+
+            let fits_output = match output {
+                None => true,
+                Some(output) => output.can_stack_with(&recipe.result) && output.count + recipe.result.count <= self.get_item_type(output.item)?.max_stack,
+            };
+
+            if fits_output {
+                return Ok(Some(recipe));
+            }
+        }
+        Ok(None)
+    }
+
+    /// this function updates all processing blocks in the world, it consumes
+    /// fuel and input over time and puts the result into the output slot
+    pub fn update_processing_blocks(&mut self, blocks: &mut Blocks, events: &mut EventManager, frame_length: f32) -> Result<()> {
+        self.processing_time_left_over += frame_length;
+        let elapsed_ms = self.processing_time_left_over.floor();
+        self.processing_time_left_over -= elapsed_ms;
+        let elapsed_ms = elapsed_ms as i32;
+
+        for (x, y) in blocks.get_block_inventory_coords()? {
+            let block_type = blocks.get_block(x, y)?;
+            let Some(processing_block) = self.get_processing_block(block_type) else {
+                continue;
+            };
+
+            let mut inventory = blocks.get_block_inventory_data(x, y)?.ok_or_else(|| anyhow!("processing block has no inventory"))?.clone();
+            let mut state = ProcessingState::from_block_data(&blocks.get_block_data(x, y)?)?;
+
+            let input = inventory.get(processing_block.input_slot).ok_or_else(|| anyhow!("invalid input slot"))?.clone();
+            let output = inventory.get(processing_block.output_slot).ok_or_else(|| anyhow!("invalid output slot"))?.clone();
+            let recipe = self.find_processing_recipe(block_type, input.as_ref(), output.as_ref())?.cloned();
+
+            if let Some(recipe) = recipe {
+                // light up a new fuel item if the previous one burned out
+                if let Some(fuel_slot) = processing_block.fuel_slot {
+                    if state.fuel_left <= 0 {
+                        let fuel_item = inventory.get_mut(fuel_slot).ok_or_else(|| anyhow!("invalid fuel slot"))?;
+                        if let Some(fuel) = fuel_item.clone() {
+                            if let Some(burn_time) = self.get_fuel(fuel.item) {
+                                state.fuel_left = burn_time;
+                                state.fuel_time = burn_time;
+                                *fuel_item = (fuel.count > 1).then(|| fuel.with_count(fuel.count - 1));
+                            }
+                        }
+                    }
+                }
+
+                if processing_block.fuel_slot.is_none() || state.fuel_left > 0 {
+                    state.processing_time = recipe.processing_time;
+                    state.progress += elapsed_ms;
+                }
+
+                if state.progress >= recipe.processing_time {
+                    state.progress = 0;
+
+                    let input_slot = inventory.get_mut(processing_block.input_slot).ok_or_else(|| anyhow!("invalid input slot"))?;
+                    *input_slot = input.as_ref().map(|input| input.with_count(input.count - recipe.ingredient.count)).filter(|input| input.count > 0);
+
+                    let output_slot = inventory.get_mut(processing_block.output_slot).ok_or_else(|| anyhow!("invalid output slot"))?;
+                    let output_count = output.as_ref().map_or(0, |output| output.count) + recipe.result.count;
+                    *output_slot = Some(recipe.result.with_count(output_count));
+                }
+            } else {
+                state.progress = 0;
+                state.processing_time = 0;
+            }
+
+            if processing_block.fuel_slot.is_some() && state.fuel_left > 0 {
+                state.fuel_left = (state.fuel_left - elapsed_ms).max(0);
+                if state.fuel_left == 0 {
+                    state.fuel_time = 0;
+                }
+            }
+
+            blocks.set_block_inventory_data(x, y, inventory, events)?;
+            blocks.set_block_data(x, y, state.to_block_data()?, events)?;
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,252 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #![cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
-mod tests {}
+    use crate::shared::blocks::{BlockId, Tool};
+    use crate::shared::items::{ItemId, ItemStack, LootEntry, LootPool, LootTable, ProcessingBlock, ProcessingRecipe, ProcessingState, Recipe, CRAFTING_STATION_RANGE};
+    use crate::shared::test_world::TestWorld;
+
+    // the furnace at (2, 2) turns ore into ingots, coal is its fuel if it needs one.
+    // It returns the furnace and the ore, ingot and coal items
+    fn place_furnace(world: &mut TestWorld, needs_fuel: bool) -> (BlockId, ItemId, ItemId, ItemId) {
+        let furnace = world.place_inventory_block(2, 2, 3);
+        let ore = world.register_item("ore", 99);
+        let ingot = world.register_item("ingot", 99);
+        let coal = world.register_item("coal", 99);
+
+        world.items.set_processing_block(
+            furnace,
+            ProcessingBlock {
+                input_slot: 0,
+                output_slot: 2,
+                fuel_slot: needs_fuel.then_some(1),
+            },
+        );
+        world.items.add_processing_recipe(ProcessingRecipe {
+            block: furnace,
+            ingredient: ItemStack::new(ore, 1),
+            result: ItemStack::new(ingot, 1),
+            processing_time: 100,
+        });
+        world.items.set_fuel(coal, 150);
+        (furnace, ore, ingot, coal)
+    }
+
+    fn get_inventory(world: &TestWorld) -> Vec<Option<ItemStack>> {
+        world.blocks.get_block_inventory_data(2, 2).unwrap().unwrap().clone()
+    }
+
+    fn get_state(world: &TestWorld) -> ProcessingState {
+        ProcessingState::from_block_data(&world.blocks.get_block_data(2, 2).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_processing_without_fuel() {
+        let mut world = TestWorld::new();
+        let (_, ore, ingot, _) = place_furnace(&mut world, false);
+        world.blocks.set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 2)), None, None], &mut world.events).unwrap();
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 60.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 2)), None, None]);
+        assert_eq!(get_state(&world).progress, 60);
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 60.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 1)), None, Some(ItemStack::new(ingot, 1))]);
+        assert_eq!(get_state(&world).progress, 0);
+
+        for _ in 0..2 {
+            world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 60.0).unwrap();
+        }
+        assert!(get_inventory(&world) == vec![None, None, Some(ItemStack::new(ingot, 2))]);
+
+        // nothing left to process, so the state is reset and block data is cleared
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 60.0).unwrap();
+        assert!(world.blocks.get_block_data(2, 2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_processing_needs_fuel() {
+        let mut world = TestWorld::new();
+        let (_, ore, ingot, coal) = place_furnace(&mut world, true);
+        world.blocks.set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 3)), None, None], &mut world.events).unwrap();
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 200.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 3)), None, None]);
+        assert_eq!(get_state(&world).progress, 0);
+
+        world
+            .blocks
+            .set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 3)), Some(ItemStack::new(coal, 1)), None], &mut world.events)
+            .unwrap();
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 100.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 2)), None, Some(ItemStack::new(ingot, 1))]);
+        assert_eq!(get_state(&world).fuel_left, 50);
+
+        // the fuel burns out halfway through the second item
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 50.0).unwrap();
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 50.0).unwrap();
+        assert_eq!(get_state(&world).progress, 50);
+        assert_eq!(get_state(&world).fuel_left, 0);
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 2)), None, Some(ItemStack::new(ingot, 1))]);
+    }
+
+    #[test]
+    fn test_processing_adds_up_short_frames() {
+        let mut world = TestWorld::new();
+        let (_, ore, _, _) = place_furnace(&mut world, false);
+        world.blocks.set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 1)), None, None], &mut world.events).unwrap();
+
+        // frames shorter than a millisecond still make progress
+        for _ in 0..120 {
+            world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 0.5).unwrap();
+        }
+        assert_eq!(get_state(&world).progress, 60);
+    }
+
+    #[test]
+    fn test_processing_output_full() {
+        let mut world = TestWorld::new();
+        let (_, ore, ingot, _) = place_furnace(&mut world, false);
+        world
+            .blocks
+            .set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 1)), None, Some(ItemStack::new(ingot, 99))], &mut world.events)
+            .unwrap();
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 200.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 1)), None, Some(ItemStack::new(ingot, 99))]);
+    }
+
+    #[test]
+    fn test_processing_only_in_its_block() {
+        let mut world = TestWorld::new();
+        let (furnace, ore, _, _) = place_furnace(&mut world, false);
+        // a block with the same slots and processing replaces the furnace
+        let processing_block = world.items.get_processing_block(furnace).unwrap().clone();
+        let other_block = world.place_inventory_block(2, 2, 3);
+        world.items.set_processing_block(other_block, processing_block);
+        world.blocks.set_block_inventory_data(2, 2, vec![Some(ItemStack::new(ore, 1)), None, None], &mut world.events).unwrap();
+
+        world.items.update_processing_blocks(&mut world.blocks, &mut world.events, 200.0).unwrap();
+        assert!(get_inventory(&world) == vec![Some(ItemStack::new(ore, 1)), None, None]);
+    }
+
+    #[test]
+    fn test_recipe_station_in_range() {
+        let mut world = TestWorld::new();
+        let (furnace, _, ingot, _) = place_furnace(&mut world, false);
+        let mut recipe = Recipe::new();
+        recipe.result = ItemStack::new(ingot, 1);
+        assert!(recipe.is_station_in_range(&world.blocks, 9.5, 9.5).unwrap());
+
+        recipe.station = Some(furnace);
+        assert!(recipe.is_station_in_range(&world.blocks, 2.5, 2.5).unwrap());
+        assert!(recipe.is_station_in_range(&world.blocks, 2.0 + CRAFTING_STATION_RANGE as f32, 2.0).unwrap());
+        assert!(!recipe.is_station_in_range(&world.blocks, 3.0 + CRAFTING_STATION_RANGE as f32, 2.0).unwrap());
+    }
+
+    fn count_drops(drops: &[ItemStack], item: ItemId) -> i32 {
+        drops.iter().filter(|drop| drop.item == item).map(|drop| drop.count).sum()
+    }
+
+    #[test]
+    fn test_loot_single_drop() {
+        let mut world = TestWorld::new();
+        let ore = world.register_item("ore", 99);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let always = LootTable::single(ore, 1.0);
+        let never = LootTable::single(ore, 0.0);
+        for _ in 0..100 {
+            assert!(always.roll(&mut rng, None, 0) == vec![ItemStack::new(ore, 1)]);
+            assert!(never.roll(&mut rng, None, 0).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_loot_weights_and_counts() {
+        let mut world = TestWorld::new();
+        let ore = world.register_item("ore", 99);
+        let coal = world.register_item("coal", 99);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ore_entry = LootEntry::new(Some(ore), 3.0);
+        ore_entry.min_count = 2;
+        ore_entry.max_count = 4;
+        let table = LootTable {
+            pools: vec![LootPool {
+                rolls: 1,
+                entries: vec![ore_entry, LootEntry::new(Some(coal), 1.0)],
+            }],
+        };
+
+        let mut ore_rolls = 0;
+        let mut coal_rolls = 0;
+        for _ in 0..1000 {
+            let drops = table.roll(&mut rng, None, 0);
+            assert_eq!(drops.len(), 1);
+            let ore_count = count_drops(&drops, ore);
+            if ore_count > 0 {
+                assert!((2..=4).contains(&ore_count));
+                ore_rolls += 1;
+            } else {
+                assert_eq!(count_drops(&drops, coal), 1);
+                coal_rolls += 1;
+            }
+        }
+        // ore is three times as likely as coal
+        assert!((700..800).contains(&ore_rolls));
+        assert_eq!(ore_rolls + coal_rolls, 1000);
+    }
+
+    #[test]
+    fn test_loot_tool_conditions() {
+        let mut world = TestWorld::new();
+        let ore = world.register_item("ore", 99);
+        let coal = world.register_item("coal", 99);
+        let pickaxe = world.blocks.register_new_tool_type(Tool::new());
+        let axe = world.blocks.register_new_tool_type(Tool::new());
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let mut ore_entry = LootEntry::new(Some(ore), 1.0);
+        ore_entry.tool = Some(pickaxe);
+        ore_entry.min_tool_power = 10;
+        let table = LootTable {
+            pools: vec![
+                LootPool { rolls: 1, entries: vec![ore_entry] },
+                LootPool {
+                    rolls: 2,
+                    entries: vec![LootEntry::new(Some(coal), 1.0)],
+                },
+            ],
+        };
+
+        let drops = table.roll(&mut rng, Some(pickaxe), 10);
+        assert_eq!(count_drops(&drops, ore), 1);
+        assert_eq!(count_drops(&drops, coal), 2);
+
+        for (tool, tool_power) in [(None, 10), (Some(axe), 10), (Some(pickaxe), 5)] {
+            let drops = table.roll(&mut rng, tool, tool_power);
+            assert_eq!(count_drops(&drops, ore), 0);
+            assert_eq!(count_drops(&drops, coal), 2);
+        }
+    }
+
+    #[test]
+    fn test_loot_is_deterministic_with_seed() {
+        let mut world = TestWorld::new();
+        let ore = world.register_item("ore", 99);
+        let mut entry = LootEntry::new(Some(ore), 1.0);
+        entry.max_count = 10;
+        let mut table = LootTable::single(ore, 0.5);
+        table.pools.push(LootPool { rolls: 3, entries: vec![entry] });
+
+        let mut first_rng = StdRng::seed_from_u64(123);
+        let mut second_rng = StdRng::seed_from_u64(123);
+        for _ in 0..20 {
+            assert!(table.roll(&mut first_rng, None, 0) == table.roll(&mut second_rng, None, 0));
+        }
+    }
+}
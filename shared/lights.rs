@@ -61,6 +61,7 @@ pub struct Lights {
     light_chunks: Vec<LightChunk>,
     map: WorldMap,
     sky_heights: Vec<i32>,
+    sky_color: LightColor,
 }
 
 impl Lights {
@@ -71,6 +72,7 @@ impl Lights {
             light_chunks: Vec::new(),
             map: WorldMap::new_empty(),
             sky_heights: Vec::new(),
+            sky_color: LightColor::new(255, 255, 255),
         }
     }
 
@@ -145,6 +147,28 @@ impl Lights {
         Ok(())
     }
 
+    /// sets the color of the light coming from the sky and schedules
+    /// light updates for everything the sky shines on, if it changed
+    pub fn set_sky_color(&mut self, color: LightColor) {
+        if self.sky_color == color {
+            return;
+        }
+        self.sky_color = color;
+
+        for x in 0..self.get_width() as i32 {
+            let sky_height = *self.sky_heights.get(x as usize).unwrap_or(&-1);
+            for y in 0..=sky_height {
+                // ignore all out of bounds errors
+                self.schedule_light_update(x, y).ok();
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn get_sky_color(&self) -> LightColor {
+        self.sky_color
+    }
+
     /// updates the light at the given coordinate
     pub fn update_light(&mut self, x: i32, y: i32, blocks: &Blocks) -> Result<()> {
         if self.get_light_mut(x, y)?.scheduled_light_update {
@@ -244,15 +268,57 @@ impl Lights {
         let mut light_emission_b = block_type.light_emission_b;
 
         if y <= *self.sky_heights.get(x as usize).unwrap_or(&-1) {
-            light_emission_r = u8::max(light_emission_r, 255);
-            light_emission_g = u8::max(light_emission_g, 255);
-            light_emission_b = u8::max(light_emission_b, 255);
+            light_emission_r = u8::max(light_emission_r, self.sky_color.r);
+            light_emission_g = u8::max(light_emission_g, self.sky_color.g);
+            light_emission_b = u8::max(light_emission_b, self.sky_color.b);
         }
 
         self.set_light_source(x, y, LightColor::new(light_emission_r, light_emission_g, light_emission_b))?;
         Ok(())
     }
 
+    /// updates all the scheduled lights in the given chunk, returns true if any light was updated
+    pub fn update_chunk(&mut self, chunk_x: i32, chunk_y: i32, blocks: &Blocks) -> Result<bool> {
+        if self.get_light_chunk(chunk_x, chunk_y)?.scheduled_light_update_count == 0 {
+            return Ok(false);
+        }
+
+        let mut updated = false;
+        for x in chunk_x * CHUNK_SIZE..(chunk_x + 1) * CHUNK_SIZE {
+            for y in chunk_y * CHUNK_SIZE..(chunk_y + 1) * CHUNK_SIZE {
+                self.update_light_emitter(x, y, blocks)?;
+                if self.get_light(x, y)?.scheduled_light_update {
+                    self.update_light(x, y, blocks)?;
+                    updated = true;
+                }
+            }
+        }
+        Ok(updated)
+    }
+
+    /// updates the lights around the given coordinate until they settle and returns
+    /// the brightest color component of the light there. Lights are only computed
+    /// where they are needed, so this is how the server gets the light level.
+    pub fn get_light_level(&mut self, x: i32, y: i32, radius: i32, blocks: &Blocks) -> Result<u8> {
+        let start_chunk_x = i32::max(0, x - radius) / CHUNK_SIZE;
+        let start_chunk_y = i32::max(0, y - radius) / CHUNK_SIZE;
+        let end_chunk_x = i32::min(self.get_width() as i32 - 1, x + radius) / CHUNK_SIZE;
+        let end_chunk_y = i32::min(self.get_height() as i32 - 1, y + radius) / CHUNK_SIZE;
+
+        let mut updated = true;
+        while updated {
+            updated = false;
+            for chunk_x in start_chunk_x..=end_chunk_x {
+                for chunk_y in start_chunk_y..=end_chunk_y {
+                    updated |= self.update_chunk(chunk_x, chunk_y, blocks)?;
+                }
+            }
+        }
+
+        let color = self.get_light(x, y)?.color;
+        Ok(color.r.max(color.g).max(color.b))
+    }
+
     pub fn on_event(&mut self, event: &Event, blocks: &Blocks) -> Result<()> {
         if let Some(event) = event.downcast::<BlockChangeEvent>() {
             let curr_block_transparent = blocks.get_block_type_at(event.x, event.y)?.transparent;
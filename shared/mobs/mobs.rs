@@ -0,0 +1,348 @@
+use anyhow::{anyhow, Result};
+use hecs::Entity;
+use rand::Rng;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::{Event, EventManager};
+use crate::shared::blocks::Blocks;
+use crate::shared::entities::{collides_with_blocks, is_touching_ground, Entities, EntityId, HealthComponent, PhysicsComponent, PositionComponent, FRICTION_COEFFICIENT};
+use crate::shared::items::LootTable;
+use crate::shared::players::{PlayerComponent, PLAYER_HEIGHT, PLAYER_WIDTH};
+
+pub const MOB_JUMP_SPEED: f32 = 20.0;
+// how far in front of the mob the obstacles are checked
+const OBSTACLE_CHECK_DISTANCE: f32 = 0.1;
+// how many ticks the mob keeps walking in a direction when wandering
+const MIN_WANDER_TICKS: i32 = 20;
+const MAX_WANDER_TICKS: i32 = 100;
+// if the player is closer than this horizontally, the mob doesn't move towards it anymore
+const FOLLOW_DISTANCE: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MobId {
+    id: i32,
+}
+
+impl MobId {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { id: -1 }
+    }
+}
+
+/// Ai behaviour is the built-in logic that decides where the mob walks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AiBehaviour {
+    Idle,
+    Wander,
+    // follows the nearest player in sight, wanders otherwise
+    FollowPlayer,
+    // runs away from the nearest player in sight, wanders otherwise
+    Flee,
+}
+
+/// Spawn time decides if a mob spawns during the day, the night or any time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnTime {
+    Any,
+    Day,
+    Night,
+}
+
+/// Spawn rule describes where and when a mob can spawn naturally.
+#[derive(Clone)]
+pub struct SpawnRule {
+    // biome ids the mob spawns in, empty means all biomes
+    pub biomes: Vec<i32>,
+    pub min_light: u8,
+    pub max_light: u8,
+    pub time: SpawnTime,
+    // the chance that the mob spawns on a spawn attempt where the rule matches
+    pub chance: f32,
+    // the mob stops spawning, when there are this many mobs of its type in the world
+    pub max_count: usize,
+}
+
+impl SpawnRule {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            biomes: Vec::new(),
+            min_light: 0,
+            max_light: u8::MAX,
+            time: SpawnTime::Any,
+            chance: 1.0,
+            max_count: 10,
+        }
+    }
+
+    /// Checks if a mob can spawn at a place with the given biome and light level at the given time.
+    /// If the biome is not known, only rules for all biomes match.
+    #[must_use]
+    pub fn matches(&self, biome: Option<i32>, light: u8, is_night: bool) -> bool {
+        let biome_matches = self.biomes.is_empty() || biome.is_some_and(|biome| self.biomes.contains(&biome));
+        let time_matches = match self.time {
+            SpawnTime::Any => true,
+            SpawnTime::Day => !is_night,
+            SpawnTime::Night => is_night,
+        };
+        biome_matches && time_matches && (self.min_light..=self.max_light).contains(&light)
+    }
+}
+
+/// Mob type holds everything about a kind of mob, that mods can define.
+#[derive(Clone)]
+pub struct MobType {
+    pub name: String,
+    // size of the mob in blocks
+    pub width: f32,
+    pub height: f32,
+    pub max_health: i32,
+    // the speed in blocks per second the mob walks at
+    pub speed: f32,
+    pub behaviour: AiBehaviour,
+    pub jumps_over_obstacles: bool,
+    // how far away in blocks the mob notices players
+    pub sight_range: f32,
+    // how much the mob hurts players it touches
+    pub damage: i32,
+    // lua function that is called every tick to override the built-in behaviour
+    pub ai_function: Option<String>,
+    pub mod_id: i32,
+    pub drops: LootTable,
+    pub spawn_rules: Vec<SpawnRule>,
+    id: MobId,
+}
+
+impl MobType {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            name: String::new(),
+            width: 1.0,
+            height: 1.0,
+            max_health: 10,
+            speed: 3.0,
+            behaviour: AiBehaviour::Wander,
+            jumps_over_obstacles: true,
+            sight_range: 16.0,
+            damage: 0,
+            ai_function: None,
+            mod_id: -1,
+            drops: LootTable::new(),
+            spawn_rules: Vec::new(),
+            id: MobId::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn get_id(&self) -> MobId {
+        self.id
+    }
+}
+
+/// Mob component marks an entity as a mob of the given type.
+pub struct MobComponent {
+    mob_type: MobId,
+}
+
+impl MobComponent {
+    #[must_use]
+    pub const fn new(mob_type: MobId) -> Self {
+        Self { mob_type }
+    }
+
+    #[must_use]
+    pub const fn get_mob_type(&self) -> MobId {
+        self.mob_type
+    }
+}
+
+/// Ai component decides where the entity walks. The direction is
+/// chosen every tick and the entity walks in it every millisecond.
+pub struct AiComponent {
+    pub behaviour: AiBehaviour,
+    // -1 is left, 1 is right and 0 is standing still
+    pub direction: f32,
+    // if set, the entity jumps as soon as it touches the ground
+    pub jumping: bool,
+    pub speed: f32,
+    pub jumps_over_obstacles: bool,
+    pub sight_range: f32,
+    wander_ticks: i32,
+}
+
+impl AiComponent {
+    #[must_use]
+    pub const fn new(behaviour: AiBehaviour, speed: f32) -> Self {
+        Self {
+            behaviour,
+            direction: 0.0,
+            jumping: false,
+            speed,
+            jumps_over_obstacles: true,
+            sight_range: 16.0,
+            wander_ticks: 0,
+        }
+    }
+
+    /// Decides the direction of the entity from its built-in behaviour.
+    /// The player position is the center of the nearest player in sight.
+    pub fn update<R: Rng>(&mut self, rng: &mut R, center_x: f32, nearest_player: Option<(f32, f32)>) {
+        self.jumping = false;
+        let player_dx = nearest_player.map(|player| player.0 - center_x);
+
+        match (self.behaviour, player_dx) {
+            (AiBehaviour::Idle, _) => self.direction = 0.0,
+            (AiBehaviour::FollowPlayer, Some(dx)) => {
+                self.direction = if dx.abs() < FOLLOW_DISTANCE { 0.0 } else { dx.signum() };
+            }
+            (AiBehaviour::Flee, Some(dx)) => self.direction = -dx.signum(),
+            (AiBehaviour::Wander | AiBehaviour::FollowPlayer | AiBehaviour::Flee, _) => self.wander(rng),
+        }
+    }
+
+    fn wander<R: Rng>(&mut self, rng: &mut R) {
+        self.wander_ticks -= 1;
+        if self.wander_ticks <= 0 {
+            self.direction = rng.gen_range(-1..=1) as f32;
+            self.wander_ticks = rng.gen_range(MIN_WANDER_TICKS..=MAX_WANDER_TICKS);
+        }
+    }
+}
+
+/// Mobs holds all the mob types.
+pub struct Mobs {
+    pub mob_types: Vec<MobType>,
+}
+
+impl Mobs {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { mob_types: Vec::new() }
+    }
+
+    /// this function registers a mob type
+    pub fn register_new_mob_type(mob_types: &mut Vec<MobType>, mut mob_type: MobType) -> MobId {
+        let id = MobId { id: mob_types.len() as i32 };
+        mob_type.id = id;
+        mob_types.push(mob_type);
+        id
+    }
+
+    /// this function returns the mob type with the given id
+    pub fn get_mob_type(&self, id: MobId) -> Result<&MobType> {
+        self.mob_types.get(id.id as usize).ok_or_else(|| anyhow!("mob type not found"))
+    }
+
+    /// this function returns the mutable mob type with the given id
+    pub fn get_mob_type_mut(&mut self, id: MobId) -> Result<&mut MobType> {
+        self.mob_types.get_mut(id.id as usize).ok_or_else(|| anyhow!("mob type not found"))
+    }
+
+    /// this function returns the mob type with the given name
+    pub fn get_mob_type_by_name(&self, name: &str) -> Result<&MobType> {
+        self.mob_types.iter().find(|mob_type| mob_type.name == name).ok_or_else(|| anyhow!("mob type {name} not found"))
+    }
+
+    #[must_use]
+    pub fn get_all_mob_type_ids(&self) -> Vec<MobId> {
+        self.mob_types.iter().map(MobType::get_id).collect()
+    }
+
+    /// this function spawns a mob into the world
+    pub fn spawn_mob(&self, events: &mut EventManager, entities: &mut Entities, mob: MobId, x: f32, y: f32, id: EntityId) -> Result<Entity> {
+        let mob_type = self.get_mob_type(mob)?;
+        let mut ai = AiComponent::new(mob_type.behaviour, mob_type.speed);
+        ai.jumps_over_obstacles = mob_type.jumps_over_obstacles;
+        ai.sight_range = mob_type.sight_range;
+
+        let entity = entities.ecs.spawn((
+            PositionComponent::new(x, y),
+            PhysicsComponent::new(mob_type.width, mob_type.height),
+            HealthComponent::new(mob_type.max_health, mob_type.max_health),
+            MobComponent::new(mob),
+            ai,
+        ));
+        entities.assign_id(entity, id)?;
+
+        events.push_event(Event::new(MobSpawnEvent { entity }));
+
+        Ok(entity)
+    }
+
+    /// this function returns how many mobs of the given type are in the world
+    #[must_use]
+    pub fn count_mobs(entities: &Entities, mob: MobId) -> usize {
+        entities.ecs.query::<&MobComponent>().iter().filter(|(_, component)| component.mob_type == mob).count()
+    }
+}
+
+/// Returns the center of the player nearest to the given point, if it is within the range.
+#[must_use]
+pub fn get_nearest_player(entities: &Entities, x: f32, y: f32, range: f32) -> Option<(f32, f32)> {
+    let mut nearest = None;
+    let mut nearest_distance = range;
+    for (_, (position, _player)) in &mut entities.ecs.query::<(&PositionComponent, &PlayerComponent)>() {
+        let player = (position.x() + PLAYER_WIDTH / 2.0, position.y() + PLAYER_HEIGHT / 2.0);
+        let distance = f32::hypot(player.0 - x, player.1 - y);
+        if distance <= nearest_distance {
+            nearest_distance = distance;
+            nearest = Some(player);
+        }
+    }
+    nearest
+}
+
+/// This function decides the direction of all entities with
+/// an ai component. It is called every tick.
+pub fn update_ai<R: Rng>(entities: &mut Entities, rng: &mut R) {
+    let mut ai_entities = Vec::new();
+    for (entity, (position, physics, _ai)) in &mut entities.ecs.query::<(&PositionComponent, &PhysicsComponent, &AiComponent)>() {
+        ai_entities.push((entity, position.x() + physics.collision_width() / 2.0, position.y() + physics.collision_height() / 2.0));
+    }
+
+    for (entity, center_x, center_y) in ai_entities {
+        let Ok(mut ai) = entities.ecs.get::<&mut AiComponent>(entity) else {
+            continue;
+        };
+        let nearest_player = get_nearest_player(entities, center_x, center_y, ai.sight_range);
+        ai.update(rng, center_x, nearest_player);
+    }
+}
+
+/// This function moves all entities with an ai component in their
+/// direction and makes them jump over obstacles. It is called every
+/// millisecond before the entities are updated.
+pub fn update_ai_ms(entities: &mut Entities, blocks: &Blocks) {
+    for (_, (position, physics, ai)) in entities.ecs.query_mut::<(&PositionComponent, &mut PhysicsComponent, &mut AiComponent)>() {
+        if !is_touching_ground(position, physics, blocks) {
+            // without friction the velocity settles at the acceleration
+            physics.acceleration_x = ai.direction * ai.speed;
+            continue;
+        }
+        // the ground slows the entity down by gravity times the friction coefficient
+        physics.acceleration_x = ai.direction * (ai.speed + physics.acceleration_y * FRICTION_COEFFICIENT);
+
+        let in_front = PositionComponent::new(position.x() + ai.direction * OBSTACLE_CHECK_DISTANCE, position.y());
+        let is_blocked = ai.direction != 0.0 && collides_with_blocks(&in_front, physics, blocks);
+        if ai.jumping || (ai.jumps_over_obstacles && is_blocked) {
+            physics.velocity_y = -MOB_JUMP_SPEED;
+            ai.jumping = false;
+        }
+    }
+}
+
+pub struct MobSpawnEvent {
+    pub entity: Entity,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MobSpawnPacket {
+    pub mob_type: MobId,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    pub id: EntityId,
+}
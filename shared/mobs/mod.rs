@@ -0,0 +1,7 @@
+// import * from all the modules
+pub use mobs::*;
+pub use mod_interface::*;
+
+mod mobs;
+mod mod_interface;
+mod tests;
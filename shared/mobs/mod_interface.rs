@@ -0,0 +1,132 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::Result;
+
+use crate::shared::items::{ItemId, LootEntry, LootPool, LootTable};
+use crate::shared::mobs::{AiBehaviour, MobId, MobType, Mobs, SpawnRule, SpawnTime};
+use crate::shared::mod_manager::{get_mod_id, ModManager};
+
+// make MobId lua compatible
+impl rlua::UserData for MobId {
+    // implement equals comparison for MobId, so ids in event payloads can be compared
+    fn add_methods<'lua, M: rlua::UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_meta_method(rlua::MetaMethod::Eq, |_, this, other: Self| Ok(*this == other));
+    }
+}
+
+// mob types are passed from lua as tables, missing fields have default values
+impl<'lua> rlua::FromLua<'lua> for MobType {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("mob type must be a table".to_owned()));
+        };
+
+        let mut mob_type = Self::new();
+        mob_type.name = table.get("name")?;
+        mob_type.width = table.get::<_, Option<f32>>("width")?.unwrap_or(mob_type.width);
+        mob_type.height = table.get::<_, Option<f32>>("height")?.unwrap_or(mob_type.height);
+        mob_type.max_health = table.get::<_, Option<i32>>("max_health")?.unwrap_or(mob_type.max_health);
+        mob_type.speed = table.get::<_, Option<f32>>("speed")?.unwrap_or(mob_type.speed);
+        mob_type.jumps_over_obstacles = table.get::<_, Option<bool>>("jumps_over_obstacles")?.unwrap_or(mob_type.jumps_over_obstacles);
+        mob_type.sight_range = table.get::<_, Option<f32>>("sight_range")?.unwrap_or(mob_type.sight_range);
+        mob_type.damage = table.get::<_, Option<i32>>("damage")?.unwrap_or(mob_type.damage);
+        mob_type.ai_function = table.get("ai_function")?;
+
+        if let Some(behaviour) = table.get::<_, Option<String>>("behaviour")? {
+            mob_type.behaviour = match behaviour.as_str() {
+                "idle" => AiBehaviour::Idle,
+                "wander" => AiBehaviour::Wander,
+                "follow_player" => AiBehaviour::FollowPlayer,
+                "flee" => AiBehaviour::Flee,
+                _ => return Err(rlua::Error::RuntimeError(format!("{behaviour} is not a valid mob behaviour"))),
+            };
+        }
+        Ok(mob_type)
+    }
+}
+
+// spawn rules are passed from lua as tables, missing fields have default values
+impl<'lua> rlua::FromLua<'lua> for SpawnRule {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("spawn rule must be a table".to_owned()));
+        };
+
+        let mut rule = Self::new();
+        rule.biomes = table.get::<_, Option<Vec<i32>>>("biomes")?.unwrap_or_default();
+        rule.min_light = table.get::<_, Option<u8>>("min_light")?.unwrap_or(rule.min_light);
+        rule.max_light = table.get::<_, Option<u8>>("max_light")?.unwrap_or(rule.max_light);
+        rule.chance = table.get::<_, Option<f32>>("chance")?.unwrap_or(rule.chance);
+        rule.max_count = table.get::<_, Option<usize>>("max_count")?.unwrap_or(rule.max_count);
+
+        if let Some(time) = table.get::<_, Option<String>>("time")? {
+            rule.time = match time.as_str() {
+                "any" => SpawnTime::Any,
+                "day" => SpawnTime::Day,
+                "night" => SpawnTime::Night,
+                _ => return Err(rlua::Error::RuntimeError(format!("{time} is not a valid spawn time"))),
+            };
+        }
+        Ok(rule)
+    }
+}
+
+/// this function initializes the mobs mod interface
+/// it adds lua functions to the lua context
+pub fn init_mobs_mod_interface(mobs: &Arc<Mutex<Mobs>>, mods: &mut ModManager) -> Result<()> {
+    // takes a table with name, width, height, max_health, speed, behaviour,
+    // jumps_over_obstacles, sight_range, damage and ai_function fields
+    let mobs_clone = mobs.clone();
+    mods.add_global_function("register_mob_type", move |lua, mut mob_type: MobType| {
+        mob_type.mod_id = get_mod_id(lua)?;
+        let result = Mobs::register_new_mob_type(&mut mobs_clone.lock().unwrap_or_else(PoisonError::into_inner).mob_types, mob_type);
+        Ok(result)
+    })?;
+
+    let mobs_clone = mobs.clone();
+    mods.add_global_function("get_mob_id_by_name", move |_lua, name: String| {
+        let mobs = mobs_clone.lock().unwrap_or_else(PoisonError::into_inner);
+        mobs.get_mob_type_by_name(&name).map(MobType::get_id).map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
+    let mobs_clone = mobs.clone();
+    mods.add_global_function("set_mob_drop", move |_lua, (mob, item, chance): (MobId, ItemId, f32)| {
+        mobs_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mob_type_mut(mob)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))?
+            .drops = LootTable::single(item, chance);
+        Ok(())
+    })?;
+
+    // entries are tables with item, weight, min_count and max_count fields
+    let mobs_clone = mobs.clone();
+    mods.add_global_function("add_mob_loot", move |_lua, (mob, rolls, entries): (MobId, i32, Vec<LootEntry>)| {
+        mobs_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mob_type_mut(mob)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))?
+            .drops
+            .pools
+            .push(LootPool { rolls, entries });
+        Ok(())
+    })?;
+
+    // takes a table with biomes, min_light, max_light, time, chance and max_count fields,
+    // it should be called in init_server, because that is where the biomes are registered
+    let mobs_clone = mobs.clone();
+    mods.add_global_function("add_mob_spawn_rule", move |_lua, (mob, rule): (MobId, SpawnRule)| {
+        mobs_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mob_type_mut(mob)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))?
+            .spawn_rules
+            .push(rule);
+        Ok(())
+    })?;
+
+    Ok(())
+}
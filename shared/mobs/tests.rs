@@ -0,0 +1,160 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use hecs::Entity;
+
+    use crate::shared::entities::{HealthComponent, PositionComponent};
+    use crate::shared::mobs::{update_ai, update_ai_ms, AiBehaviour, AiComponent, MobComponent, MobId, MobSpawnEvent, MobType, Mobs, SpawnRule, SpawnTime};
+    use crate::shared::players::spawn_player;
+    use crate::shared::test_world::{TestWorld, GROUND_Y};
+
+    /// registers a slime, the only mob type of the tests
+    fn register_slime(behaviour: AiBehaviour) -> (Mobs, MobId) {
+        let mut mobs = Mobs::new();
+        let mut mob_type = MobType::new();
+        mob_type.name = "slime".to_owned();
+        mob_type.behaviour = behaviour;
+        mob_type.speed = 5.0;
+        let slime = Mobs::register_new_mob_type(&mut mobs.mob_types, mob_type);
+        (mobs, slime)
+    }
+
+    fn spawn_mob(world: &mut TestWorld, mobs: &Mobs, mob: MobId, x: f32) -> Entity {
+        let id = world.entities.new_id();
+        mobs.spawn_mob(&mut world.events, &mut world.entities, mob, x, GROUND_Y as f32 - 1.0, id).unwrap()
+    }
+
+    fn spawn_test_player(world: &mut TestWorld, x: f32) {
+        let id = world.entities.new_id();
+        spawn_player(&mut world.entities, x, GROUND_Y as f32 - 2.0, "player", id, HealthComponent::new(100, 100)).unwrap();
+    }
+
+    /// simulates the given number of ticks, each is 50 ms
+    fn simulate(world: &mut TestWorld, ticks: i32) {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..ticks {
+            update_ai(&mut world.entities, &mut rng);
+            for _ in 0..10 {
+                update_ai_ms(&mut world.entities, &world.blocks);
+                world.entities.update_entities_ms(&world.blocks, &mut world.events).unwrap();
+            }
+        }
+    }
+
+    fn get_x(world: &TestWorld, entity: Entity) -> f32 {
+        world.entities.ecs.get::<&PositionComponent>(entity).unwrap().x()
+    }
+
+    #[test]
+    fn test_spawn_rule_matches() {
+        let mut rule = SpawnRule::new();
+        assert!(rule.matches(None, 0, false));
+
+        rule.biomes = vec![1, 2];
+        rule.max_light = 100;
+        rule.time = SpawnTime::Night;
+        assert!(rule.matches(Some(2), 50, true));
+        assert!(!rule.matches(Some(3), 50, true));
+        assert!(!rule.matches(None, 50, true));
+        assert!(!rule.matches(Some(1), 150, true));
+        assert!(!rule.matches(Some(1), 50, false));
+
+        rule.time = SpawnTime::Day;
+        assert!(rule.matches(Some(1), 50, false));
+    }
+
+    #[test]
+    fn test_spawn_mob() {
+        let mut world = TestWorld::new();
+        let (mobs, slime) = register_slime(AiBehaviour::Wander);
+        let mob = spawn_mob(&mut world, &mobs, slime, 5.0);
+
+        assert_eq!(world.entities.ecs.get::<&MobComponent>(mob).unwrap().get_mob_type(), slime);
+        assert_eq!(world.entities.ecs.get::<&HealthComponent>(mob).unwrap().health(), 10);
+        assert_eq!(world.entities.ecs.get::<&AiComponent>(mob).unwrap().behaviour, AiBehaviour::Wander);
+        assert!(world.events.pop_event().unwrap().downcast::<MobSpawnEvent>().is_some());
+        assert_eq!(Mobs::count_mobs(&world.entities, slime), 1);
+    }
+
+    #[test]
+    fn test_mob_type_by_name() {
+        let (mobs, slime) = register_slime(AiBehaviour::Wander);
+        assert_eq!(mobs.get_mob_type_by_name("slime").unwrap().get_id(), slime);
+        assert!(mobs.get_mob_type_by_name("zombie").is_err());
+    }
+
+    #[test]
+    fn test_ai_direction() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut follow = AiComponent::new(AiBehaviour::FollowPlayer, 1.0);
+        follow.update(&mut rng, 5.0, Some((8.0, 5.0)));
+        assert_eq!(follow.direction as i32, 1);
+        follow.update(&mut rng, 5.0, Some((5.2, 5.0)));
+        assert_eq!(follow.direction as i32, 0);
+
+        let mut flee = AiComponent::new(AiBehaviour::Flee, 1.0);
+        flee.update(&mut rng, 5.0, Some((8.0, 5.0)));
+        assert_eq!(flee.direction as i32, -1);
+
+        let mut idle = AiComponent::new(AiBehaviour::Idle, 1.0);
+        idle.update(&mut rng, 5.0, Some((8.0, 5.0)));
+        assert_eq!(idle.direction as i32, 0);
+    }
+
+    #[test]
+    fn test_wander_changes_direction() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut ai = AiComponent::new(AiBehaviour::Wander, 1.0);
+        let mut directions = Vec::new();
+        for _ in 0..1000 {
+            ai.update(&mut rng, 5.0, None);
+            if !directions.contains(&ai.direction) {
+                directions.push(ai.direction);
+            }
+        }
+        assert!(directions.len() > 1);
+    }
+
+    #[test]
+    fn test_mob_follows_and_flees_player() {
+        let mut world = TestWorld::new();
+        let (mobs, slime) = register_slime(AiBehaviour::FollowPlayer);
+        let mob = spawn_mob(&mut world, &mobs, slime, 5.0);
+        spawn_test_player(&mut world, 12.0);
+        simulate(&mut world, 20);
+        assert!(get_x(&world, mob) > 6.0);
+
+        let mut world = TestWorld::new();
+        let (mobs, slime) = register_slime(AiBehaviour::Flee);
+        let mob = spawn_mob(&mut world, &mobs, slime, 5.0);
+        spawn_test_player(&mut world, 8.0);
+        simulate(&mut world, 40);
+        assert!(get_x(&world, mob) < 4.0);
+    }
+
+    #[test]
+    fn test_mob_jumps_over_obstacle() {
+        let mut world = TestWorld::new();
+        let (mut mobs, slime) = register_slime(AiBehaviour::FollowPlayer);
+        let dirt = world.blocks.get_block_id_by_name("dirt").unwrap();
+        world.blocks.set_block(&mut world.events, 8, GROUND_Y - 1, dirt).unwrap();
+
+        let mob = spawn_mob(&mut world, &mobs, slime, 5.0);
+        spawn_test_player(&mut world, 14.0);
+        simulate(&mut world, 100);
+        assert!(get_x(&world, mob) > 9.0);
+
+        // without jumping the mob stays stuck in front of the obstacle
+        let mut world = TestWorld::new();
+        mobs.get_mob_type_mut(slime).unwrap().jumps_over_obstacles = false;
+        world.blocks.set_block(&mut world.events, 8, GROUND_Y - 1, dirt).unwrap();
+
+        let mob = spawn_mob(&mut world, &mobs, slime, 5.0);
+        spawn_test_player(&mut world, 14.0);
+        simulate(&mut world, 100);
+        assert!(get_x(&world, mob) < 8.0);
+    }
+}
@@ -1,13 +1,18 @@
 pub mod blocks;
 pub mod chat;
+pub mod combat;
 pub mod entities;
 pub mod inventory;
 pub mod items;
 pub mod lights;
 pub mod liquids;
+pub mod mobs;
 pub mod mod_manager;
 pub mod packet;
 pub mod players;
+pub mod stats;
+mod test_world;
 pub mod versions;
 pub mod walls;
 pub mod world_map;
+pub mod world_time;
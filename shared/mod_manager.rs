@@ -1,18 +1,51 @@
 use std::collections::HashMap;
 use std::slice::{Iter, IterMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use rlua::prelude::LuaError;
-use rlua::{Context, FromLuaMulti, Lua, ToLuaMulti};
+use rlua::{Context, FromLuaMulti, HookTriggers, Lua, MultiValue, RegistryKey, StdLib, ToLuaMulti};
 use serde::{Deserialize, Serialize};
 
 static MOD_ID_IDENT: &str = "__TERRALISTIC_MOD_ID";
+// the registry key of the table with the functions the mod subscribed to each event
+static EVENT_HANDLERS_IDENT: &str = "__TERRALISTIC_EVENT_HANDLERS";
+
+// mods come from servers the player doesn't know, so they don't get the io, package
+// and debug libraries, which could be used to reach the files of the player
+const MOD_LIBRARIES: StdLib = StdLib::BASE
+    .union(StdLib::COROUTINE)
+    .union(StdLib::TABLE)
+    .union(StdLib::OS)
+    .union(StdLib::STRING)
+    .union(StdLib::UTF8)
+    .union(StdLib::MATH);
+// the functions of the os library mods can use, the others run programs and change files
+const SAFE_OS_FUNCTIONS: [&str; 4] = ["clock", "date", "difftime", "time"];
+// the functions of the base library that load code from files or precompiled chunks
+const UNSAFE_BASE_FUNCTIONS: [&str; 3] = ["dofile", "load", "loadfile"];
+// how many instructions a call into a mod can run before it is stopped, so an endless loop doesn't freeze the game
+const MAX_INSTRUCTIONS_PER_CALL: u64 = 20_000_000;
+// how often the instruction count is checked
+const INSTRUCTION_CHECK_INTERVAL: u32 = 10_000;
+const MOD_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+// how many times a mod can fail within the failure window before it is disabled, unless the mod manager is strict
+const MAX_MOD_FAILURES: usize = 10;
+// only failures this recent count, so a mod that fails once in a while isn't disabled eventually
+const MOD_FAILURE_WINDOW: Duration = Duration::from_mins(1);
 
 #[derive(serde_derive::Serialize, serde_derive::Deserialize)]
 pub struct ModsWelcomePacket {
     pub mods: Vec<Vec<u8>>,
 }
 
+/// This packet tells the clients that the server has reloaded its mods,
+/// they have to join again to get the new mods and their resources.
+#[derive(serde_derive::Serialize, serde_derive::Deserialize)]
+pub struct ModsReloadPacket;
+
 /// Returns the mod id of the current mod from its lua context.
 /// This is used to identify which mod is calling a function.
 pub fn get_mod_id(context: Context) -> Result<i32, LuaError> {
@@ -20,7 +53,19 @@ pub fn get_mod_id(context: Context) -> Result<i32, LuaError> {
     globals.get::<_, i32>(MOD_ID_IDENT)
 }
 
+/// Returns the message of the error without the tracebacks of the
+/// rust functions it passed through on its way out of the mod.
+fn describe_error(error: &LuaError) -> String {
+    match error {
+        LuaError::CallbackError { cause, .. } => describe_error(cause),
+        LuaError::RuntimeError(message) => message.clone(),
+        LuaError::MemoryError(_) => format!("used more than {} MiB of memory", MOD_MEMORY_LIMIT / 1024 / 1024),
+        error => error.to_string(),
+    }
+}
+
 /// Game mod contains lua code that can be used to modify the game.
+///
 /// It also contains resources that can be used by the lua code.
 /// resources are a map of strings to byte arrays. The string key
 /// is the path of the resource file, relative to the `game_mod`
@@ -31,6 +76,12 @@ pub struct GameMod {
     resources: HashMap<String, Vec<u8>>,
     lua: Lua,
     id: i32,
+    // the instructions the mod has run since the game last called into it
+    instructions: Arc<AtomicU64>,
+    // when the mod failed within the failure window
+    failures: Vec<Instant>,
+    // disabled mods aren't called anymore
+    disabled: bool,
 }
 
 impl GameMod {
@@ -39,30 +90,110 @@ impl GameMod {
         Self {
             lua_code,
             resources,
-            lua: Lua::new(),
+            lua: Lua::new_with(MOD_LIBRARIES),
             id: -1,
+            instructions: Arc::new(AtomicU64::new(0)),
+            failures: Vec::new(),
+            disabled: false,
         }
     }
 
     /// This function runs the lua code in the game mod.
     /// It loads the code and resources into the lua state.
     /// It then runs the code and the init function.
-    fn init(&mut self, id: i32) -> Result<()> {
+    /// The mod can subscribe to the given events with `terralistic_on`.
+    fn init(&mut self, id: i32, event_names: &[String]) -> Result<()> {
         self.id = id;
+        self.sandbox().map_err(|error| self.name_error(&error))?;
+        self.instructions.store(0, Ordering::Relaxed);
+        let event_names = event_names.to_vec();
+        self.lua
+            .context(|lua| {
+                lua.set_named_registry_value(EVENT_HANDLERS_IDENT, lua.create_table()?)?;
+                // a method to subscribe a function to an event, it is called with the payload table of the event
+                let subscribe = lua.create_function(move |lua, (event_name, handler): (String, rlua::Function)| {
+                    if !event_names.contains(&event_name) {
+                        return Err(LuaError::RuntimeError(format!("unknown event {event_name}")));
+                    }
+                    let handlers: rlua::Table = lua.named_registry_value(EVENT_HANDLERS_IDENT)?;
+                    let event_handlers = if let Some(event_handlers) = handlers.get::<_, Option<rlua::Table>>(event_name.as_str())? {
+                        event_handlers
+                    } else {
+                        let event_handlers = lua.create_table()?;
+                        handlers.set(event_name, event_handlers.clone())?;
+                        event_handlers
+                    };
+                    event_handlers.set(event_handlers.raw_len() + 1, handler)
+                })?;
+                lua.globals().set("terralistic_on", subscribe)?;
+
+                // load the game mod code
+                lua.load(&self.lua_code).exec()?;
+                let globals = lua.globals();
+                // set the mod id
+                globals.set(MOD_ID_IDENT, self.id)?;
+                Ok(())
+            })
+            .map_err(|error| self.name_error(&error))?;
+
+        // execute the init function
+        self.call_function::<(), ()>("init", ())?;
+        Ok(())
+    }
+
+    /// This function removes the parts of the standard library that can reach outside the game
+    /// and limits how much memory the mod can use and how long each call into it can run.
+    fn sandbox(&self) -> Result<(), LuaError> {
         self.lua.context(|lua| {
-            // load the game mod code
-            lua.load(&self.lua_code).exec()?;
             let globals = lua.globals();
-            // set the mod id
-            globals.set(MOD_ID_IDENT, self.id)?;
-            anyhow::Ok(())
+            for name in UNSAFE_BASE_FUNCTIONS {
+                globals.set(name, rlua::Nil)?;
+            }
+            let os: rlua::Table = globals.get("os")?;
+            let safe_os = lua.create_table()?;
+            for name in SAFE_OS_FUNCTIONS {
+                safe_os.set(name, os.get::<_, rlua::Value>(name)?)?;
+            }
+            globals.set("os", safe_os)
         })?;
 
-        // execute the init function
-        self.call_function::<(), ()>("init", ())?;
+        self.lua.set_memory_limit(Some(MOD_MEMORY_LIMIT));
+        let instructions = self.instructions.clone();
+        let triggers = HookTriggers {
+            every_nth_instruction: Some(INSTRUCTION_CHECK_INTERVAL),
+            ..HookTriggers::default()
+        };
+        self.lua.set_hook(triggers, move |_, _| {
+            if instructions.fetch_add(u64::from(INSTRUCTION_CHECK_INTERVAL), Ordering::Relaxed) >= MAX_INSTRUCTIONS_PER_CALL {
+                return Err(LuaError::RuntimeError(format!("ran more than {MAX_INSTRUCTIONS_PER_CALL} instructions without returning")));
+            }
+            Ok(())
+        });
         Ok(())
     }
 
+    /// This function returns the name the mod has given itself, or its id if it has no name.
+    #[must_use]
+    pub fn get_name(&self) -> String {
+        self.lua
+            .context(|lua| lua.globals().get::<_, Option<String>>("MOD_NAME"))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| format!("#{}", self.id))
+    }
+
+    /// Checks if the mod was disabled because it failed too many times.
+    #[must_use]
+    pub const fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// This function adds the name of the mod to an error it caused,
+    /// so the player knows which mod broke the rules or crashed.
+    fn name_error(&self, error: &LuaError) -> LuaError {
+        LuaError::external(format!("mod {}: {}", self.get_name(), describe_error(error)))
+    }
+
     /// This function adds a global function to the game mod.
     /// It takes the name of the function and the closure as input.
     pub fn add_global_function<F, A, R>(&mut self, name: &str, func: F) -> Result<()>
@@ -78,6 +209,20 @@ impl GameMod {
         })
     }
 
+    /// This function adds a global function to the game mod, which gets the lua values of its arguments.
+    /// Unlike `add_global_function`, the arguments can be values that live in the lua state, like functions.
+    pub fn add_global_function_with_values<F, R>(&mut self, name: &str, func: F) -> Result<()>
+    where
+        F: 'static + Send + for<'lua> Fn(Context<'lua>, MultiValue<'lua>) -> Result<R, LuaError>,
+        R: for<'lua> ToLuaMulti<'lua>,
+    {
+        self.lua.context(|lua| {
+            let globals = lua.globals();
+            globals.set(name, lua.create_function(func)?)?;
+            Ok(())
+        })
+    }
+
     /// This function calls a function in the game mod with args and returns the result.
     /// It takes the name of the function and the arguments as input.
     pub fn call_function<A, R>(&mut self, name: &str, args: A) -> Result<R, LuaError>
@@ -85,11 +230,30 @@ impl GameMod {
         A: for<'lua> ToLuaMulti<'lua>,
         R: for<'lua> FromLuaMulti<'lua>,
     {
-        self.lua.context(|lua| {
-            let globals = lua.globals();
-            let func = globals.get::<_, rlua::Function>(name)?;
-            func.call(args)
-        })
+        self.instructions.store(0, Ordering::Relaxed);
+        self.lua
+            .context(|lua| {
+                let globals = lua.globals();
+                let func = globals.get::<_, rlua::Function>(name)?;
+                func.call(args)
+            })
+            .map_err(|error| self.name_error(&error))
+    }
+
+    /// This function calls a function the mod stored in the lua registry with args and returns the result.
+    /// It is used for functions the mod passed to the game instead of naming a global.
+    pub fn call_registry_function<A, R>(&mut self, key: &RegistryKey, args: A) -> Result<R, LuaError>
+    where
+        A: for<'lua> ToLuaMulti<'lua>,
+        R: for<'lua> FromLuaMulti<'lua>,
+    {
+        self.instructions.store(0, Ordering::Relaxed);
+        self.lua
+            .context(|lua| {
+                let func = lua.registry_value::<rlua::Function>(key)?;
+                func.call(args)
+            })
+            .map_err(|error| self.name_error(&error))
     }
 
     /// Checks if a symbol is defined in the game mod.
@@ -100,10 +264,39 @@ impl GameMod {
         })?)
     }
 
+    /// This function calls the functions the mod subscribed to the event with the payload table.
+    /// The payload is only created if the mod subscribed to the event. Handlers cancel the event
+    /// by setting the cancelled field of the payload, the returned value tells if it is cancelled.
+    pub fn fire_event<F>(&mut self, event_name: &str, cancelled: bool, create_payload: &F) -> Result<bool, LuaError>
+    where
+        F: for<'lua> Fn(Context<'lua>) -> Result<rlua::Table<'lua>, LuaError>,
+    {
+        self.instructions.store(0, Ordering::Relaxed);
+        self.lua
+            .context(|lua| {
+                let Some(handlers) = lua.named_registry_value::<_, Option<rlua::Table>>(EVENT_HANDLERS_IDENT)? else {
+                    return Ok(cancelled);
+                };
+                let Some(event_handlers) = handlers.get::<_, Option<rlua::Table>>(event_name)? else {
+                    return Ok(cancelled);
+                };
+                let payload = create_payload(lua)?;
+                payload.set("cancelled", cancelled)?;
+                for handler in event_handlers.sequence_values::<rlua::Function>() {
+                    handler?.call::<_, ()>(payload.clone())?;
+                }
+                Ok(payload.get::<_, Option<bool>>("cancelled")?.unwrap_or(false))
+            })
+            .map_err(|error| self.name_error(&error))
+    }
+
     /// This function updates the game mod.
-    /// It runs the update function in the lua code.
+    /// It runs the update function in the lua code, if the mod has one.
     fn update(&mut self) -> Result<()> {
-        Ok(self.call_function::<(), ()>("update", ())?)
+        if self.is_symbol_defined("update")? {
+            self.call_function::<(), ()>("update", ())?;
+        }
+        Ok(())
     }
 
     /// This function stops the game mod.
@@ -112,6 +305,42 @@ impl GameMod {
         Ok(self.call_function::<(), ()>("stop", ())?)
     }
 
+    /// This function calls the function in the lua code, if the mod has it.
+    fn call_function_if_defined<A>(&mut self, name: &str, args: A) -> Result<()>
+    where
+        A: for<'lua> ToLuaMulti<'lua>,
+    {
+        if self.is_symbol_defined(name)? {
+            self.call_function::<_, ()>(name, args)?;
+        }
+        Ok(())
+    }
+
+    /// This function runs the code of the mod without calling any of its functions
+    /// and reads what the mod tells about itself from its global variables.
+    pub fn read_manifest(&self) -> Result<ModManifest> {
+        self.sandbox().map_err(|error| anyhow!(describe_error(&error)))?;
+        self.instructions.store(0, Ordering::Relaxed);
+        self.lua
+            .context(|lua| {
+                lua.load(&self.lua_code).exec()?;
+                let globals = lua.globals();
+                Ok(ModManifest {
+                    name: globals.get("MOD_NAME")?,
+                    description: globals.get("MOD_DESCRIPTION")?,
+                    version: globals.get("VERSION")?,
+                    has_init: matches!(globals.get("init")?, rlua::Value::Function(_)),
+                })
+            })
+            .map_err(|error| anyhow!(describe_error(&error)))
+    }
+
+    /// This function returns all resources of the mod by their path.
+    #[must_use]
+    pub const fn get_resources(&self) -> &HashMap<String, Vec<u8>> {
+        &self.resources
+    }
+
     /// This function gets the resource with the given path.
     /// It returns a byte array with the contents of the resource.
     fn get_resource(&self, path: &str) -> Option<&Vec<u8>> {
@@ -147,22 +376,154 @@ impl<'de> Deserialize<'de> for GameMod {
         Ok(Self {
             lua_code: data.lua_code,
             resources: data.resources,
-            lua: Lua::new(),
+            lua: Lua::new_with(MOD_LIBRARIES),
             id: -1,
+            instructions: Arc::new(AtomicU64::new(0)),
+            failures: Vec::new(),
+            disabled: false,
         })
     }
 }
 
+/// Mod manifest is what a mod tells about itself in its global variables.
+pub struct ModManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub version: Option<String>,
+    // mods without an init function can't be loaded
+    pub has_init: bool,
+}
+
+/// Mod error is an error a mod caused while the mod manager wasn't strict.
+/// The game keeps running and it is shown to the player instead.
+pub struct ModError {
+    pub mod_name: String,
+    pub message: String,
+    // the mod failed too many times and isn't called anymore
+    pub disabled: bool,
+}
+
 /// Mod manager is responsible for loading mods and managing them.
 pub struct ModManager {
     mods: Vec<GameMod>,
+    // the events mods can subscribe to
+    event_names: Vec<String>,
+    // strict mod managers return the first error of a mod, which stops the game,
+    // so mod developers notice it right away
+    pub strict: bool,
+    errors: Vec<ModError>,
 }
 
 impl ModManager {
     /// Creates a new mod manager.
     #[must_use]
     pub const fn new(mods: Vec<GameMod>) -> Self {
-        Self { mods }
+        Self {
+            mods,
+            event_names: Vec::new(),
+            strict: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// This function adds an event the mods can subscribe to with `terralistic_on`,
+    /// it has to be called before the mods are initialized.
+    pub fn add_event(&mut self, event_name: &str) {
+        self.event_names.push(event_name.to_owned());
+    }
+
+    /// This function calls the handlers of the event in all mods, each mod gets its own payload
+    /// table created by the closure. Every mod sees if an earlier one has cancelled the event
+    /// and the returned value tells if the event is cancelled after all of them.
+    pub fn fire_event<F>(&mut self, event_name: &str, create_payload: F) -> Result<bool>
+    where
+        F: for<'lua> Fn(Context<'lua>) -> Result<rlua::Table<'lua>, LuaError>,
+    {
+        let mut cancelled = false;
+        for id in 0..self.mods.len() {
+            let Some(game_mod) = self.mods.get_mut(id).filter(|game_mod| !game_mod.disabled) else {
+                continue;
+            };
+            let result = game_mod.fire_event(event_name, cancelled, &create_payload).map_err(anyhow::Error::from);
+            // a mod that failed doesn't change whether the event is cancelled
+            if let Some(mod_cancelled) = self.handle_result(id, result, false)? {
+                cancelled = mod_cancelled;
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// This function calls a function of the mod with the given id. Errors are handled like all other errors
+    /// of mods, so None is returned if the function failed in a mod manager that isn't strict or if the mod is disabled.
+    pub fn call_function<A, R>(&mut self, id: i32, name: &str, args: A) -> Result<Option<R>>
+    where
+        A: for<'lua> ToLuaMulti<'lua>,
+        R: for<'lua> FromLuaMulti<'lua>,
+    {
+        let game_mod = self.get_mod(id).ok_or_else(|| anyhow!("invalid mod id"))?;
+        if game_mod.disabled {
+            return Ok(None);
+        }
+        let result = game_mod.call_function(name, args).map_err(anyhow::Error::from);
+        self.handle_result(id as usize, result, false)
+    }
+
+    /// This function calls a function the mod with the given id stored in the lua registry,
+    /// errors and disabled mods are handled like in `call_function`.
+    pub fn call_registry_function<A, R>(&mut self, id: i32, key: &RegistryKey, args: A) -> Result<Option<R>>
+    where
+        A: for<'lua> ToLuaMulti<'lua>,
+        R: for<'lua> FromLuaMulti<'lua>,
+    {
+        let game_mod = self.get_mod(id).ok_or_else(|| anyhow!("invalid mod id"))?;
+        if game_mod.disabled {
+            return Ok(None);
+        }
+        let result = game_mod.call_registry_function(key, args).map_err(anyhow::Error::from);
+        self.handle_result(id as usize, result, false)
+    }
+
+    /// This function calls the function in every mod that has it.
+    pub fn call_function_in_mods<A>(&mut self, name: &str, args: A) -> Result<()>
+    where
+        A: for<'lua> ToLuaMulti<'lua> + Clone,
+    {
+        for id in 0..self.mods.len() {
+            let Some(game_mod) = self.mods.get_mut(id).filter(|game_mod| !game_mod.disabled) else {
+                continue;
+            };
+            let result = game_mod.call_function_if_defined(name, args.clone());
+            self.handle_result(id, result, false)?;
+        }
+        Ok(())
+    }
+
+    /// This function decides what happens with the error of a mod. A strict mod manager returns it,
+    /// otherwise it is kept until the game takes it to show it and the mod is disabled once it has
+    /// failed too many times within the failure window. Some errors leave the mod broken, so it is disabled right away.
+    fn handle_result<T>(&mut self, id: usize, result: Result<T>, disable: bool) -> Result<Option<T>> {
+        let error = match result {
+            Ok(value) => return Ok(Some(value)),
+            Err(error) if self.strict => return Err(error),
+            Err(error) => error,
+        };
+        if let Some(game_mod) = self.mods.get_mut(id) {
+            let now = Instant::now();
+            game_mod.failures.retain(|failure| now.duration_since(*failure) < MOD_FAILURE_WINDOW);
+            game_mod.failures.push(now);
+            game_mod.disabled = disable || game_mod.failures.len() >= MAX_MOD_FAILURES;
+            self.errors.push(ModError {
+                mod_name: game_mod.get_name(),
+                message: error.to_string(),
+                disabled: game_mod.disabled,
+            });
+        }
+        Ok(None)
+    }
+
+    /// This function returns the errors mods caused since it was last called.
+    pub fn take_errors(&mut self) -> Vec<ModError> {
+        std::mem::take(&mut self.errors)
     }
 
     /// This function adds a lua function to the mod manager, which will be added to all the mods.
@@ -178,26 +539,50 @@ impl ModManager {
         Ok(())
     }
 
-    /// This function initializes all the mods.
+    /// This function adds a lua function that gets the lua values of its arguments to all the mods.
+    pub fn add_global_function_with_values<F, R>(&mut self, name: &str, func: F) -> Result<()>
+    where
+        F: 'static + Send + Clone + for<'lua> Fn(Context<'lua>, MultiValue<'lua>) -> Result<R, LuaError>,
+        R: for<'lua> ToLuaMulti<'lua>,
+    {
+        for mod_ in &mut self.mods {
+            mod_.add_global_function_with_values(&("terralistic_".to_owned() + name), func.clone())?;
+        }
+        Ok(())
+    }
+
+    /// This function initializes all the mods, a mod that fails to initialize is disabled.
     pub fn init(&mut self) -> Result<()> {
-        for (id, mod_) in self.mods.iter_mut().enumerate() {
-            mod_.init(id as i32)?;
+        for id in 0..self.mods.len() {
+            let Some(game_mod) = self.mods.get_mut(id) else {
+                continue;
+            };
+            let result = game_mod.init(id as i32, &self.event_names);
+            self.handle_result(id, result, true)?;
         }
         Ok(())
     }
 
     /// This function updates all the mods.
     pub fn update(&mut self) -> Result<()> {
-        for mod_ in &mut self.mods {
-            mod_.update()?;
+        for id in 0..self.mods.len() {
+            let Some(game_mod) = self.mods.get_mut(id).filter(|game_mod| !game_mod.disabled) else {
+                continue;
+            };
+            let result = game_mod.update();
+            self.handle_result(id, result, false)?;
         }
         Ok(())
     }
 
     /// This function stops all the mods.
     pub fn stop(&mut self) -> Result<()> {
-        for mod_ in &mut self.mods {
-            mod_.stop()?;
+        for id in 0..self.mods.len() {
+            let Some(game_mod) = self.mods.get_mut(id).filter(|game_mod| !game_mod.disabled) else {
+                continue;
+            };
+            let result = game_mod.stop();
+            self.handle_result(id, result, false)?;
         }
         Ok(())
     }
@@ -219,12 +604,12 @@ impl ModManager {
     }
 
     /// Get mods iterator.
-    pub fn mods_iter(&self) -> Iter<GameMod> {
+    pub fn mods_iter(&self) -> Iter<'_, GameMod> {
         self.mods.iter()
     }
 
     /// Get mutable mods iterator.
-    pub fn mods_iter_mut(&mut self) -> IterMut<GameMod> {
+    pub fn mods_iter_mut(&mut self) -> IterMut<'_, GameMod> {
         self.mods.iter_mut()
     }
 }
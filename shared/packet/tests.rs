@@ -1,3 +1,4 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #[cfg(test)]
 mod tests {
     use crate::shared::packet::{Packet, WelcomeCompletePacket};
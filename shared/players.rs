@@ -2,11 +2,11 @@ use anyhow::Result;
 use hecs::Entity;
 use serde_derive::{Deserialize, Serialize};
 
-use crate::libraries::events::EventManager;
+use crate::libraries::events::{Event, EventManager};
 use crate::shared::blocks::{Blocks, BLOCK_WIDTH};
 use crate::shared::entities::{is_touching_ground, reduce_by, Entities, EntityId, HealthComponent, PhysicsComponent, PositionComponent};
 use crate::shared::inventory::Inventory;
-use crate::shared::items::{ItemComponent, ItemStack, Items};
+use crate::shared::items::{ItemComponent, ItemId, ItemStack, Items};
 
 pub const PLAYER_HEIGHT: f32 = 28.0 / BLOCK_WIDTH;
 pub const PLAYER_WIDTH: f32 = 18.0 / BLOCK_WIDTH;
@@ -18,6 +18,12 @@ pub const PLAYER_PICKUP_RADIUS: f32 = 6.0;
 pub const PLAYER_PICKUP_COEFFICIENT: f32 = 0.005;
 pub const PLAYER_PICKUP_MIN_SPEED: f32 = 0.8;
 pub const PLAYER_INVENTORY_SIZE: usize = 20;
+pub const PLAYER_THROW_SPEED_X: f32 = 15.0;
+pub const PLAYER_THROW_SPEED_Y: f32 = 8.0;
+// thrown items have to land out of the pickup radius before they can be picked up
+pub const PLAYER_THROW_PICKUP_DELAY_MS: i32 = 1500;
+// the first slots of the player inventory are the hotbar
+pub const PLAYER_HOTBAR_SIZE: usize = 10;
 
 #[derive(PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum MovingType {
@@ -100,7 +106,10 @@ pub fn update_players_ms(entities: &mut Entities, blocks: &Blocks) {
         // the speed change is applied to the item's velocity
         // in the direction of the player
 
-        for (_, (item_position, item_physics, _item)) in entities.ecs.query_mut::<(&PositionComponent, &mut PhysicsComponent, &ItemComponent)>() {
+        for (_, (item_position, item_physics, item)) in entities.ecs.query_mut::<(&PositionComponent, &mut PhysicsComponent, &ItemComponent)>() {
+            if !item.can_be_picked_up() {
+                continue;
+            }
             let dx = player_position.0 - item_position.x() - 0.5;
             let dy = player_position.1 - item_position.y() - 0.5;
             let d2 = dx * dx + dy * dy;
@@ -118,6 +127,22 @@ pub fn update_players_ms(entities: &mut Entities, blocks: &Blocks) {
     }
 }
 
+/// this function throws the item stack out of the player's hands in the direction they are facing
+pub fn throw_item_from_player(entities: &mut Entities, events: &mut EventManager, items: &mut Items, player: Entity, stack: &ItemStack) -> Result<Entity> {
+    let (x, y) = {
+        let position = entities.ecs.get::<&PositionComponent>(player)?;
+        (position.x() + PLAYER_WIDTH / 2.0 - 0.5, position.y() + PLAYER_HEIGHT / 4.0)
+    };
+    let direction = match entities.ecs.get::<&PlayerComponent>(player)?.direction {
+        Direction::Left => -1.0,
+        Direction::Right => 1.0,
+    };
+
+    let item = items.throw_item_stack(events, entities, stack, (x, y), (direction * PLAYER_THROW_SPEED_X, -PLAYER_THROW_SPEED_Y))?;
+    entities.ecs.get::<&mut ItemComponent>(item)?.set_pickup_delay_ms(PLAYER_THROW_PICKUP_DELAY_MS);
+    Ok(item)
+}
+
 pub fn remove_all_picked_items(entities: &mut Entities, events: &mut EventManager, items: &mut Items) -> Result<()> {
     let mut positions = Vec::new();
     for (entity, (position, _player)) in entities.ecs.query_mut::<(&PositionComponent, &PlayerComponent)>() {
@@ -126,30 +151,50 @@ pub fn remove_all_picked_items(entities: &mut Entities, events: &mut EventManage
 
     for (player_position, player_entity) in positions {
         let mut items_to_remove = Vec::new();
-        for (entity, (item_position, _item)) in entities.ecs.query_mut::<(&PositionComponent, &ItemComponent)>() {
+        for (entity, (item_position, item)) in entities.ecs.query_mut::<(&PositionComponent, &ItemComponent)>() {
             let dx = player_position.0 - item_position.x() - 0.5;
             let dy = player_position.1 - item_position.y() - 0.5;
             let d2 = dx * dx + dy * dy;
 
-            if d2 < 0.3 {
+            if d2 < 0.3 && item.can_be_picked_up() {
                 items_to_remove.push(entity);
             }
         }
 
         for entity in items_to_remove {
-            let item_type = entities.ecs.get::<&ItemComponent>(entity)?.get_item_type();
-            let mut inventory = (*entities.ecs.get::<&Inventory>(player_entity)?).clone();
-            inventory.give_item(ItemStack::new(item_type, 1), player_position, items, entities, events)?;
-            *entities.ecs.get::<&mut Inventory>(player_entity)? = inventory;
+            let stack = entities.ecs.get::<&ItemComponent>(entity)?.get_stack().clone();
+            // if the inventory is full, the rest of the stack stays on the ground
+            let leftover = entities.ecs.get::<&mut Inventory>(player_entity)?.insert_item(stack.clone(), items)?;
+
+            let picked_count = stack.count - leftover.as_ref().map_or(0, |leftover| leftover.count);
+            if picked_count > 0 {
+                events.push_event(Event::new(ItemPickupEvent {
+                    player: player_entity,
+                    item: stack.item,
+                    count: picked_count,
+                }));
+            }
 
-            let item_id = entities.get_id_from_entity(entity)?;
-            entities.despawn_entity(item_id, events)?;
+            if let Some(leftover) = leftover {
+                entities.ecs.get::<&mut ItemComponent>(entity)?.set_count(leftover.count, events, entity);
+            } else {
+                let item_id = entities.get_id_from_entity(entity)?;
+                entities.despawn_entity(item_id, events)?;
+            }
         }
     }
 
     Ok(())
 }
 
+/// this event is sent when a player picks up an item from the ground,
+/// the count is how many of the items fit into the inventory
+pub struct ItemPickupEvent {
+    pub player: Entity,
+    pub item: ItemId,
+    pub count: i32,
+}
+
 pub enum Direction {
     Left,
     Right,
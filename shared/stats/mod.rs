@@ -0,0 +1,8 @@
+pub use mod_interface::*;
+pub use player_stats::*;
+pub use stats::*;
+
+mod mod_interface;
+mod player_stats;
+mod stats;
+mod tests;
@@ -0,0 +1,82 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::Result;
+
+use crate::shared::mod_manager::ModManager;
+use crate::shared::stats::{PlayerStats, StatId, StatType, Stats};
+
+// make StatId lua compatible
+impl rlua::UserData for StatId {}
+
+// stat types are passed from lua as tables, missing fields have default values
+impl<'lua> rlua::FromLua<'lua> for StatType {
+    fn from_lua(value: rlua::Value<'lua>, _lua: rlua::Context<'lua>) -> rlua::Result<Self> {
+        let rlua::Value::Table(table) = value else {
+            return Err(rlua::Error::RuntimeError("stat type must be a table".to_owned()));
+        };
+
+        let mut stat_type = Self::new();
+        stat_type.name = table.get("name")?;
+        stat_type.max_value = table.get("max")?;
+        stat_type.initial_value = table.get::<_, Option<i32>>("initial")?.unwrap_or(stat_type.max_value);
+        stat_type.regen = table.get::<_, Option<f32>>("regen")?.unwrap_or(stat_type.regen);
+        stat_type.empty_damage = table.get::<_, Option<f32>>("empty_damage")?.unwrap_or(stat_type.empty_damage);
+        Ok(stat_type)
+    }
+}
+
+/// this function initializes the stats mod interface
+/// it adds lua functions to the lua context
+pub fn init_stats_mod_interface(stats: &Arc<Mutex<Stats>>, mods: &mut ModManager) -> Result<()> {
+    let stats_clone = stats.clone();
+    mods.add_global_function("register_stat", move |_lua, stat_type: StatType| {
+        Ok(stats_clone.lock().unwrap_or_else(PoisonError::into_inner).register_stat_type(stat_type))
+    })?;
+
+    // health is a stat as well, so mods can get its id to make it regenerate
+    let stats_clone = stats.clone();
+    mods.add_global_function("get_stat_id_by_name", move |_lua, name: String| {
+        let stats = stats_clone.lock().unwrap_or_else(PoisonError::into_inner);
+        stats.get_stat_type_by_name(&name).map(StatType::get_id).map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
+    // regen is in units per second, negative values drain the stat
+    let stats_clone = stats.clone();
+    mods.add_global_function("set_stat_regen", move |_lua, (stat, regen): (StatId, f32)| {
+        stats_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_stat_type_mut(stat)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))?
+            .regen = regen;
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// this function adds the lua functions that read and change the stats of players,
+/// only the server has the stats of all players
+pub fn init_player_stats_mod_interface(player_stats: &Arc<Mutex<PlayerStats>>, mods: &mut ModManager) -> Result<()> {
+    // the whole value of a stat of a player, health included
+    let stats_clone = player_stats.clone();
+    mods.add_global_function("get_player_stat", move |_lua, (player, stat): (String, StatId)| {
+        stats_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_value(&player, stat)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
+    // the value is clamped between 0 and the max value, the player gets it on the next tick
+    let stats_clone = player_stats.clone();
+    mods.add_global_function("set_player_stat", move |_lua, (player, stat, value): (String, StatId, f32)| {
+        stats_clone
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_value(&player, stat, value)
+            .map_err(|err| rlua::Error::RuntimeError(err.to_string()))
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use hecs::Entity;
+
+use crate::libraries::events::EventManager;
+use crate::shared::entities::{Entities, HealthComponent};
+use crate::shared::players::PlayerComponent;
+use crate::shared::stats::{StatId, StatsComponent, HEALTH_STAT};
+
+/// Stat change is a value a mod set for a stat of a player.
+struct StatChange {
+    player: String,
+    stat: StatId,
+    value: f32,
+}
+
+/// Player stats hold the values and max values of the stats of all players by their name,
+/// so mods can read and change them without access to the entities.
+///
+/// The values are refreshed every tick and the changes are applied to the players then.
+pub struct PlayerStats {
+    values: HashMap<String, HashMap<StatId, (i32, i32)>>,
+    changes: Vec<StatChange>,
+}
+
+impl PlayerStats {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// this function applies the stat changes mods made to the players and copies their stats again
+    pub fn update(&mut self, entities: &mut Entities, events: &mut EventManager) -> Result<()> {
+        let mut players: HashMap<String, Entity> = HashMap::new();
+        for (entity, player) in &mut entities.ecs.query::<&PlayerComponent>() {
+            players.insert(player.get_name().to_owned(), entity);
+        }
+
+        for change in std::mem::take(&mut self.changes) {
+            let Some(entity) = players.get(&change.player).copied() else {
+                continue;
+            };
+            if change.stat == HEALTH_STAT {
+                let id = entities.get_id_from_entity(entity)?;
+                let mut health = entities.ecs.get::<&mut HealthComponent>(entity)?;
+                // dead players wait for their respawn
+                if health.health() > 0 {
+                    health.set_health(change.value as i32, events, id);
+                }
+            } else {
+                entities.ecs.get::<&mut StatsComponent>(entity)?.set_value(change.stat, change.value, events, entity)?;
+            }
+        }
+
+        self.values.clear();
+        for (name, entity) in players {
+            let health = entities.ecs.get::<&HealthComponent>(entity)?;
+            let mut values = HashMap::from([(HEALTH_STAT, (health.health(), health.max_health()))]);
+            for (id, stat) in entities.ecs.get::<&StatsComponent>(entity)?.iter() {
+                values.insert(id, (stat.get_value(), stat.get_max_value()));
+            }
+            self.values.insert(name, values);
+        }
+        Ok(())
+    }
+
+    /// this function returns the whole value of the stat of the player
+    pub fn get_value(&self, player: &str, stat: StatId) -> Result<i32> {
+        let values = self.values.get(player).ok_or_else(|| anyhow!("player {player} is not online"))?;
+        Ok(values.get(&stat).ok_or_else(|| anyhow!("player doesn't have this stat"))?.0)
+    }
+
+    /// this function sets the value of the stat of the player, clamped between 0 and its max value
+    pub fn set_value(&mut self, player: &str, stat: StatId, value: f32) -> Result<()> {
+        let values = self.values.get_mut(player).ok_or_else(|| anyhow!("player {player} is not online"))?;
+        let (old_value, max_value) = values.get_mut(&stat).ok_or_else(|| anyhow!("player doesn't have this stat"))?;
+        let value = value.clamp(0.0, *max_value as f32);
+        *old_value = value.floor() as i32;
+        self.changes.push(StatChange {
+            player: player.to_owned(),
+            stat,
+            value,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use hecs::Entity;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::libraries::events::{Event, EventManager};
+use crate::shared::combat::LastDamageComponent;
+use crate::shared::entities::{Entities, HealthComponent};
+use crate::shared::players::PLAYER_MAX_HEALTH;
+
+// health is registered before any mod stats, its value is kept in the health component
+pub const HEALTH_STAT: StatId = StatId { id: 0 };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StatId {
+    id: i32,
+}
+
+impl StatId {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { id: -1 }
+    }
+}
+
+/// Stat type is a kind of value every player has, such as mana, hunger or breath.
+#[derive(Clone)]
+pub struct StatType {
+    pub name: String,
+    pub max_value: i32,
+    // the value new players start with and players get after respawning
+    pub initial_value: i32,
+    // how much the value changes every second, negative values drain it
+    pub regen: f32,
+    // how much health the player loses every second while the stat is empty
+    pub empty_damage: f32,
+    id: StatId,
+}
+
+impl StatType {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            name: String::new(),
+            max_value: 0,
+            initial_value: 0,
+            regen: 0.0,
+            empty_damage: 0.0,
+            id: StatId::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn get_id(&self) -> StatId {
+        self.id
+    }
+}
+
+/// Stats hold all registered stat types.
+pub struct Stats {
+    stat_types: Vec<StatType>,
+}
+
+impl Stats {
+    #[must_use]
+    pub fn new() -> Self {
+        let mut stats = Self { stat_types: Vec::new() };
+        stats.register_stat_type(StatType {
+            name: "health".to_owned(),
+            max_value: PLAYER_MAX_HEALTH,
+            initial_value: PLAYER_MAX_HEALTH,
+            ..StatType::new()
+        });
+        stats
+    }
+
+    /// this function registers a stat type and returns its id
+    pub fn register_stat_type(&mut self, mut stat_type: StatType) -> StatId {
+        stat_type.id = StatId { id: self.stat_types.len() as i32 };
+        let id = stat_type.id;
+        self.stat_types.push(stat_type);
+        id
+    }
+
+    pub fn get_stat_type(&self, id: StatId) -> Result<&StatType> {
+        self.stat_types.get(id.id as usize).ok_or_else(|| anyhow!("stat type not found"))
+    }
+
+    pub fn get_stat_type_mut(&mut self, id: StatId) -> Result<&mut StatType> {
+        self.stat_types.get_mut(id.id as usize).ok_or_else(|| anyhow!("stat type not found"))
+    }
+
+    pub fn get_stat_type_by_name(&self, name: &str) -> Result<&StatType> {
+        for stat_type in &self.stat_types {
+            if stat_type.name == name {
+                return Ok(stat_type);
+            }
+        }
+        bail!("stat type not found")
+    }
+
+    /// this function returns the ids of all stat types, health included
+    #[must_use]
+    pub fn get_all_stat_ids(&self) -> Vec<StatId> {
+        self.stat_types.iter().map(StatType::get_id).collect()
+    }
+
+    /// this function adds stats that were registered after the component was created,
+    /// for example by a new mod, removes stats that don't exist anymore and
+    /// updates the max value and regen in case a mod has changed them
+    pub fn update_component(&self, component: &mut StatsComponent) {
+        component.stats.retain(|id, _| *id != HEALTH_STAT && self.get_stat_type(*id).is_ok());
+        for stat_type in self.stat_types.iter().skip(1) {
+            let stat = component.stats.entry(stat_type.id).or_insert_with(|| Stat::new(stat_type));
+            stat.max_value = stat_type.max_value;
+            stat.regen = stat_type.regen;
+        }
+    }
+}
+
+/// Stat is the value of a single stat type for one player.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Stat {
+    value: f32,
+    max_value: i32,
+    regen: f32,
+}
+
+impl Stat {
+    #[must_use]
+    pub const fn new(stat_type: &StatType) -> Self {
+        Self {
+            value: stat_type.initial_value as f32,
+            max_value: stat_type.max_value,
+            regen: stat_type.regen,
+        }
+    }
+
+    /// the value is shown and sent to the clients as a whole number
+    #[must_use]
+    pub const fn get_value(&self) -> i32 {
+        self.value.floor() as i32
+    }
+
+    #[must_use]
+    pub const fn get_max_value(&self) -> i32 {
+        self.max_value
+    }
+
+    #[must_use]
+    pub const fn get_regen(&self) -> f32 {
+        self.regen
+    }
+}
+
+/// Stats component holds the mod stats of a player, health is not included.
+#[derive(Clone)]
+pub struct StatsComponent {
+    stats: HashMap<StatId, Stat>,
+    // the part of health that was regenerated or lost but is not a whole number yet
+    health_progress: f32,
+    // loaded stats have to be sent to the client again
+    synced: bool,
+}
+
+/// Saved stats are the stats of a player as they are saved with the world. They are
+/// kept by the name of their stat type, because ids depend on the order mods register stats in.
+#[derive(Serialize, Deserialize)]
+pub struct SavedStats {
+    stats: HashMap<String, Stat>,
+    health_progress: f32,
+}
+
+impl StatsComponent {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stats: HashMap::new(),
+            health_progress: 0.0,
+            synced: false,
+        }
+    }
+
+    /// this function converts the stats to the way they are saved
+    pub fn save(&self, stats: &Stats) -> Result<SavedStats> {
+        let mut saved_stats = HashMap::new();
+        for (id, stat) in &self.stats {
+            saved_stats.insert(stats.get_stat_type(*id)?.name.clone(), stat.clone());
+        }
+        Ok(SavedStats {
+            stats: saved_stats,
+            health_progress: self.health_progress,
+        })
+    }
+
+    /// this function loads saved stats, stats whose type is not registered anymore are left out
+    #[must_use]
+    pub fn load(saved_stats: SavedStats, stats: &Stats) -> Self {
+        Self {
+            stats: saved_stats
+                .stats
+                .into_iter()
+                .filter_map(|(name, stat)| Some((stats.get_stat_type_by_name(&name).ok()?.get_id(), stat)))
+                .collect(),
+            health_progress: saved_stats.health_progress,
+            synced: false,
+        }
+    }
+
+    /// this function makes the next update send all stats to the client again,
+    /// it is needed when a player rejoins with stats that were kept in memory
+    pub const fn mark_unsynced(&mut self) {
+        self.synced = false;
+    }
+
+    #[must_use]
+    pub fn get_stat(&self, id: StatId) -> Option<&Stat> {
+        self.stats.get(&id)
+    }
+
+    /// this function returns the stats of the player with their ids
+    pub fn iter(&self) -> impl Iterator<Item = (StatId, &Stat)> {
+        self.stats.iter().map(|(id, stat)| (*id, stat))
+    }
+
+    /// this function sets the value of the stat, clamped between 0 and its max value
+    pub fn set_value(&mut self, id: StatId, value: f32, events: &mut EventManager, entity: Entity) -> Result<()> {
+        let stat = self.stats.get_mut(&id).ok_or_else(|| anyhow!("player doesn't have this stat"))?;
+        let old_value = stat.get_value();
+        stat.value = value.clamp(0.0, stat.max_value as f32);
+        if stat.get_value() != old_value {
+            events.push_event(Event::new(StatChangeEvent { entity, stat: id }));
+        }
+        Ok(())
+    }
+}
+
+/// this function regenerates or drains the stats of all players and
+/// changes their health if it regenerates or if some of their stats are empty
+pub fn update_stats(entities: &mut Entities, events: &mut EventManager, stats: &Stats, delta_ms: f32) -> Result<()> {
+    let health_regen = stats.get_stat_type(HEALTH_STAT)?.regen;
+    let mut health_changes = Vec::new();
+    for (entity, component) in entities.ecs.query_mut::<&mut StatsComponent>() {
+        stats.update_component(component);
+        if !component.synced {
+            component.synced = true;
+            for id in component.stats.keys() {
+                events.push_event(Event::new(StatChangeEvent { entity, stat: *id }));
+            }
+            events.push_event(Event::new(StatChangeEvent { entity, stat: HEALTH_STAT }));
+        }
+
+        let mut empty_damage = 0.0;
+        let ids: Vec<StatId> = component.stats.keys().copied().collect();
+        for id in ids {
+            let Some(stat) = component.get_stat(id) else {
+                continue;
+            };
+            let value = stat.value + stat.regen * delta_ms / 1000.0;
+            component.set_value(id, value, events, entity)?;
+            if component.get_stat(id).is_some_and(|stat| stat.value <= 0.0) {
+                empty_damage += stats.get_stat_type(id)?.empty_damage;
+            }
+        }
+
+        component.health_progress += (health_regen - empty_damage) * delta_ms / 1000.0;
+        let whole_health = component.health_progress.trunc();
+        component.health_progress -= whole_health;
+        if whole_health != 0.0 {
+            health_changes.push((entity, whole_health as i32, empty_damage > 0.0));
+        }
+    }
+
+    for (entity, change, is_empty_damage) in health_changes {
+        let id = entities.get_id_from_entity(entity)?;
+        let Ok(mut health) = entities.ecs.get::<&mut HealthComponent>(entity) else {
+            continue;
+        };
+        // dead players don't regenerate
+        if health.health() == 0 {
+            continue;
+        }
+        health.increase_health(change, events, id);
+        drop(health);
+        // whoever hurt the player before is not the reason they starved
+        if is_empty_damage && change < 0 {
+            entities.ecs.remove_one::<LastDamageComponent>(entity).ok();
+        }
+    }
+    Ok(())
+}
+
+/// this event is sent when the whole part of a stat value changes,
+/// it is sent with the health stat as well when the stats are loaded
+pub struct StatChangeEvent {
+    pub entity: Entity,
+    pub stat: StatId,
+}
+
+/// this packet tells the client the value of one of their stats, health included
+#[derive(Serialize, Deserialize)]
+pub struct StatChangePacket {
+    pub stat: StatId,
+    pub value: i32,
+    pub max_value: i32,
+}
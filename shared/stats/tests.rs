@@ -0,0 +1,166 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use hecs::Entity;
+
+    use crate::shared::entities::HealthComponent;
+    use crate::shared::stats::{update_stats, StatChangeEvent, StatId, StatType, Stats, StatsComponent, HEALTH_STAT};
+    use crate::shared::test_world::TestWorld;
+
+    fn register_stat(stats: &mut Stats, name: &str, max_value: i32, initial_value: i32, regen: f32, empty_damage: f32) -> StatId {
+        let mut stat_type = StatType::new();
+        stat_type.name = name.to_owned();
+        stat_type.max_value = max_value;
+        stat_type.initial_value = initial_value;
+        stat_type.regen = regen;
+        stat_type.empty_damage = empty_damage;
+        stats.register_stat_type(stat_type)
+    }
+
+    // mana fills up by 2 every second and hunger drains by 1 every second
+    fn register_test_stats() -> (Stats, StatId, StatId) {
+        let mut stats = Stats::new();
+        let mana = register_stat(&mut stats, "mana", 10, 0, 2.0, 0.0);
+        let hunger = register_stat(&mut stats, "hunger", 20, 3, -1.0, 4.0);
+        (stats, mana, hunger)
+    }
+
+    fn spawn_test_player(world: &mut TestWorld) -> Entity {
+        world.spawn_entity((HealthComponent::new(50, 100), StatsComponent::new()))
+    }
+
+    fn update(world: &mut TestWorld, stats: &Stats, delta_ms: f32) {
+        update_stats(&mut world.entities, &mut world.events, stats, delta_ms).unwrap();
+    }
+
+    fn get_value(world: &TestWorld, player: Entity, stat: StatId) -> i32 {
+        world.entities.ecs.get::<&StatsComponent>(player).unwrap().get_stat(stat).unwrap().get_value()
+    }
+
+    fn get_health(world: &TestWorld, player: Entity) -> i32 {
+        world.entities.ecs.get::<&HealthComponent>(player).unwrap().health()
+    }
+
+    fn take_changed_stats(world: &mut TestWorld) -> Vec<StatId> {
+        let mut changed = Vec::new();
+        while let Some(event) = world.events.pop_event() {
+            if let Some(event) = event.downcast::<StatChangeEvent>() {
+                changed.push(event.stat);
+            }
+        }
+        changed.sort();
+        changed
+    }
+
+    #[test]
+    fn test_health_is_registered() {
+        let stats = Stats::new();
+        assert_eq!(stats.get_stat_type_by_name("health").unwrap().get_id(), HEALTH_STAT);
+        assert_eq!(stats.get_all_stat_ids(), vec![HEALTH_STAT]);
+    }
+
+    #[test]
+    fn test_first_update_fills_and_syncs_stats() {
+        let mut world = TestWorld::new();
+        let (stats, mana, hunger) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        update(&mut world, &stats, 0.0);
+
+        assert_eq!(get_value(&world, player, mana), 0);
+        assert_eq!(get_value(&world, player, hunger), 3);
+        assert_eq!(take_changed_stats(&mut world), vec![HEALTH_STAT, mana, hunger]);
+
+        // the stats are only sent again when they change
+        update(&mut world, &stats, 0.0);
+        assert!(take_changed_stats(&mut world).is_empty());
+    }
+
+    #[test]
+    fn test_stats_regenerate_up_to_max() {
+        let mut world = TestWorld::new();
+        let (stats, mana, hunger) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        update(&mut world, &stats, 0.0);
+        take_changed_stats(&mut world);
+
+        update(&mut world, &stats, 250.0);
+        assert_eq!(get_value(&world, player, mana), 0);
+        assert_eq!(get_value(&world, player, hunger), 2);
+        assert_eq!(take_changed_stats(&mut world), vec![hunger]);
+
+        update(&mut world, &stats, 250.0);
+        assert_eq!(get_value(&world, player, mana), 1);
+        assert_eq!(get_value(&world, player, hunger), 2);
+        assert_eq!(take_changed_stats(&mut world), vec![mana]);
+
+        update(&mut world, &stats, 10000.0);
+        assert_eq!(get_value(&world, player, mana), 10);
+        assert_eq!(get_value(&world, player, hunger), 0);
+    }
+
+    #[test]
+    fn test_empty_stat_damages_health() {
+        let mut world = TestWorld::new();
+        let (stats, _, hunger) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        update(&mut world, &stats, 3000.0);
+        assert_eq!(get_value(&world, player, hunger), 0);
+        let health = get_health(&world, player);
+
+        update(&mut world, &stats, 1000.0);
+        assert_eq!(get_health(&world, player), health - 4);
+    }
+
+    #[test]
+    fn test_health_regenerates() {
+        let mut world = TestWorld::new();
+        let (mut stats, _, hunger) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        stats.get_stat_type_mut(hunger).unwrap().empty_damage = 0.0;
+        stats.get_stat_type_mut(HEALTH_STAT).unwrap().regen = 0.5;
+
+        update(&mut world, &stats, 1000.0);
+        assert_eq!(get_health(&world, player), 50);
+        update(&mut world, &stats, 1000.0);
+        assert_eq!(get_health(&world, player), 51);
+
+        // dead players stay dead
+        let id = world.entities.get_id_from_entity(player).unwrap();
+        world.entities.ecs.get::<&mut HealthComponent>(player).unwrap().set_health(0, &mut world.events, id);
+        update(&mut world, &stats, 10000.0);
+        assert_eq!(get_health(&world, player), 0);
+    }
+
+    #[test]
+    fn test_changed_stat_type_applies_to_players() {
+        let mut world = TestWorld::new();
+        let (mut stats, mana, hunger) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        update(&mut world, &stats, 0.0);
+        stats.get_stat_type_mut(mana).unwrap().regen = -2.0;
+        stats.get_stat_type_mut(hunger).unwrap().max_value = 2;
+
+        update(&mut world, &stats, 1000.0);
+        assert_eq!(get_value(&world, player, mana), 0);
+        let component = world.entities.ecs.get::<&StatsComponent>(player).unwrap();
+        assert!((component.get_stat(mana).unwrap().get_regen() + 2.0).abs() < f32::EPSILON);
+        assert_eq!(component.get_stat(hunger).unwrap().get_max_value(), 2);
+    }
+
+    #[test]
+    fn test_saved_stats_are_loaded_by_name() {
+        let mut world = TestWorld::new();
+        let (stats, _, _) = register_test_stats();
+        let player = spawn_test_player(&mut world);
+        update(&mut world, &stats, 2000.0);
+        let component = world.entities.ecs.get::<&StatsComponent>(player).unwrap();
+        let saved_stats = bincode::serialize(&component.save(&stats).unwrap()).unwrap();
+
+        // a mod registers hunger first and mana is not registered anymore
+        let mut stats = Stats::new();
+        let hunger = register_stat(&mut stats, "hunger", 20, 3, -1.0, 4.0);
+        let component = StatsComponent::load(bincode::deserialize(&saved_stats).unwrap(), &stats);
+        assert_eq!(component.get_stat(hunger).unwrap().get_value(), 1);
+        assert_eq!(component.iter().count(), 1);
+    }
+}
@@ -0,0 +1,70 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+
+use hecs::{DynamicBundle, Entity};
+
+use crate::libraries::events::EventManager;
+use crate::shared::blocks::{Block, BlockId, Blocks};
+use crate::shared::entities::Entities;
+use crate::shared::items::{Item, ItemId, Items};
+
+pub const WORLD_SIZE: u32 = 30;
+// the height of the dirt floor, entities standing on it are right above it
+pub const GROUND_Y: i32 = 20;
+
+/// Test world is a small world for the tests of the shared modules. It has
+/// a floor of dirt, but no entities, item types or events yet.
+pub struct TestWorld {
+    pub blocks: Blocks,
+    pub entities: Entities,
+    pub events: EventManager,
+    pub items: Items,
+}
+
+impl TestWorld {
+    pub fn new() -> Self {
+        let mut world = Self {
+            blocks: Blocks::new(),
+            entities: Entities::new(),
+            events: EventManager::new(),
+            items: Items::new(),
+        };
+        world.blocks.create(WORLD_SIZE, WORLD_SIZE);
+        let mut dirt = Block::new();
+        dirt.name = "dirt".to_owned();
+        let dirt = world.blocks.register_new_block_type(dirt);
+        for x in 0..WORLD_SIZE as i32 {
+            world.blocks.set_block(&mut world.events, x, GROUND_Y, dirt).unwrap();
+        }
+        // the tests only see the events they cause themselves
+        while world.events.pop_event().is_some() {}
+        world
+    }
+
+    /// this function registers an item type that stacks up to `max_stack`
+    pub fn register_item(&mut self, name: &str, max_stack: i32) -> ItemId {
+        let mut item = Item::new();
+        item.name = name.to_owned();
+        item.max_stack = max_stack;
+        self.items.add_item_type(item)
+    }
+
+    /// this function registers a block type with an inventory of the given size and places it
+    pub fn place_inventory_block(&mut self, x: i32, y: i32, slots: usize) -> BlockId {
+        let mut block = Block::new();
+        block.inventory_slots = vec![(0, 0); slots];
+        let block = self.blocks.register_new_block_type(block);
+        self.blocks.set_block(&mut self.events, x, y, block).unwrap();
+        self.blocks.update_block(x, y, &mut self.events).unwrap();
+        while self.events.pop_event().is_some() {}
+        block
+    }
+
+    /// this function spawns an entity with an id, like all entities of the game have
+    pub fn spawn_entity(&mut self, components: impl DynamicBundle) -> Entity {
+        let entity = self.entities.ecs.spawn(components);
+        let id = self.entities.new_id();
+        self.entities.assign_id(entity, id).unwrap();
+        entity
+    }
+}
@@ -1,13 +1,17 @@
 use anyhow::{anyhow, Result};
 
-use crate::libraries::events::EventManager;
-use crate::shared::walls::Walls;
+use crate::libraries::events::{Event, EventManager};
+use crate::shared::blocks::ToolId;
+use crate::shared::walls::{WallBreakEvent, Walls};
 
 /// Stores the info about a breaking progress about a wall.
 pub struct BreakingWall {
     pub break_progress: i32,
     pub is_breaking: bool,
     pub coord: (i32, i32),
+    // the tool the wall is being broken with
+    pub tool: Option<ToolId>,
+    pub tool_power: i32,
 }
 
 impl BreakingWall {
@@ -17,6 +21,8 @@ impl BreakingWall {
             break_progress: 0,
             is_breaking: false,
             coord: (0, 0),
+            tool: None,
+            tool_power: 0,
         }
     }
 
@@ -45,8 +51,9 @@ impl Walls {
     }
 
     /// Includes the necessary steps to start breaking a wall, such as adding it to the
-    /// `breaking_walls` list, setting `is_breaking` to true and sending the `WallStartedBreakingEvent`
-    pub fn start_breaking_wall(&mut self, x: i32, y: i32) -> Result<()> {
+    /// `breaking_walls` list, setting `is_breaking` to true and sending the `WallStartedBreakingEvent`.
+    /// The tool decides what the wall drops.
+    pub fn start_breaking_wall(&mut self, x: i32, y: i32, tool: Option<ToolId>, tool_power: i32) -> Result<()> {
         if self.get_wall_type_at(x, y)?.break_time.is_none() {
             return Ok(());
         }
@@ -71,6 +78,8 @@ impl Walls {
         };
 
         breaking_wall.is_breaking = true;
+        breaking_wall.tool = tool;
+        breaking_wall.tool_power = tool_power;
         Ok(())
 
         //self.wall_started_breaking_event.send(WallStartedBreakingEvent::new(x, y));
@@ -92,7 +101,7 @@ impl Walls {
 
     /// Updates breaking walls by increasing break
     /// progress and breaking walls if necessary
-    pub fn update_breaking_walls(&mut self, frame_length: f32, _events: &mut EventManager) -> Result<()> {
+    pub fn update_breaking_walls(&mut self, frame_length: f32, events: &mut EventManager) -> Result<()> {
         for breaking_wall in &mut self.breaking_walls {
             if breaking_wall.is_breaking {
                 breaking_wall.break_progress += frame_length as i32;
@@ -102,19 +111,23 @@ impl Walls {
         let mut broken_walls = Vec::new();
         for breaking_wall in &self.breaking_walls {
             if breaking_wall.break_progress > self.get_wall_type_at(breaking_wall.get_coord().0, breaking_wall.get_coord().1)?.break_time.unwrap_or(1) {
-                broken_walls.push(breaking_wall.get_coord());
+                broken_walls.push((breaking_wall.get_coord(), breaking_wall.tool, breaking_wall.tool_power));
             }
         }
 
-        for broken_wall in &broken_walls {
-            let (x, y) = *broken_wall;
-
-            //let _event = WallBreakEvent::new(x, y);
-            //self.wall_break_event.send(event);
+        for ((x, y), tool, tool_power) in broken_walls {
+            let event = WallBreakEvent {
+                x,
+                y,
+                prev_wall_id: self.get_wall(x, y)?,
+                tool,
+                tool_power,
+            };
+            events.push_event(Event::new(event));
 
             self.set_wall_type(x, y, self.clear)?;
 
-            self.breaking_walls.retain(|breaking_wall| breaking_wall.get_coord() != *broken_wall);
+            self.breaking_walls.retain(|breaking_wall| breaking_wall.get_coord() != (x, y));
         }
 
         Ok(())
@@ -67,11 +67,11 @@ impl Walls {
         };
 
         let mut clear = Wall::new();
-        clear.name = "clear".to_owned();
+        "clear".clone_into(&mut clear.name);
         result.clear = Self::register_new_wall_type(&mut result.wall_types, clear);
 
         let mut hammer = Tool::new();
-        hammer.name = "hammer".to_owned();
+        "hammer".clone_into(&mut hammer.name);
         result.hammer = blocks.register_new_tool_type(hammer);
 
         result
@@ -84,7 +84,7 @@ impl Walls {
     }
 
     /// Returns the wall id at the given position.
-    fn get_wall(&self, x: i32, y: i32) -> Result<WallId> {
+    pub fn get_wall(&self, x: i32, y: i32) -> Result<WallId> {
         Ok(*self
             .walls_data
             .walls
@@ -213,6 +213,10 @@ pub struct WallChangeEvent {
 pub struct WallBreakEvent {
     pub x: i32,
     pub y: i32,
+    pub prev_wall_id: WallId,
+    // the tool the wall was broken with
+    pub tool: Option<ToolId>,
+    pub tool_power: i32,
 }
 
 pub struct WallStartedBreakingEvent {
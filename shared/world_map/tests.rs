@@ -1,3 +1,4 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
 #[cfg(test)]
 mod tests {
     use crate::shared::world_map::world_map::WorldMap;
@@ -18,6 +19,17 @@ mod tests {
         map.translate_coords(1234, 1234).unwrap_err();
     }
 
+    #[test]
+    fn test_translate_index() {
+        let map = WorldMap::new(10, 20);
+
+        for (x, y) in [(0, 0), (9, 19), (5, 7), (3, 0), (0, 12)] {
+            let index = map.translate_coords(x, y).unwrap();
+            assert_eq!(map.translate_index(index).unwrap(), (x, y));
+        }
+        map.translate_index(200).unwrap_err();
+    }
+
     #[test]
     fn test_new_empty() {
         let map = WorldMap::new_empty();
@@ -41,6 +41,15 @@ impl WorldMap {
         Ok((x * self.height as i32 + y) as usize)
     }
 
+    /// Translates a single number back to a x y coordinate, the inverse of `translate_coords`.
+    pub fn translate_index(&self, index: usize) -> Result<(i32, i32)> {
+        if index >= (self.width * self.height) as usize {
+            bail!("Index is out of bounds! index: {}", index);
+        }
+
+        Ok(((index / self.height as usize) as i32, (index % self.height as usize) as i32))
+    }
+
     /// Same as `translate_coords` but for chunks
     pub fn translate_chunk_coords(&self, x: i32, y: i32) -> Result<usize> {
         if x < 0 || y < 0 || x >= self.width as i32 / CHUNK_SIZE || y >= self.height as i32 / CHUNK_SIZE {
@@ -0,0 +1,6 @@
+pub use mod_interface::*;
+pub use world_time::*;
+
+mod mod_interface;
+mod tests;
+mod world_time;
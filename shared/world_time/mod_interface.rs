@@ -0,0 +1,24 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::Result;
+
+use crate::shared::mod_manager::ModManager;
+use crate::shared::world_time::WorldTime;
+
+/// this function initializes the world time mod interface
+/// it adds lua functions to the lua context
+pub fn init_world_time_mod_interface(world_time: &Arc<Mutex<WorldTime>>, mods: &mut ModManager) -> Result<()> {
+    let time_clone = world_time.clone();
+    mods.add_global_function("get_world_time", move |_lua, ()| Ok(time_clone.lock().unwrap_or_else(PoisonError::into_inner).get_time_ms()))?;
+
+    let time_clone = world_time.clone();
+    mods.add_global_function("get_time_of_day", move |_lua, ()| Ok(time_clone.lock().unwrap_or_else(PoisonError::into_inner).get_time_of_day_ms()))?;
+
+    let time_clone = world_time.clone();
+    mods.add_global_function("get_day", move |_lua, ()| Ok(time_clone.lock().unwrap_or_else(PoisonError::into_inner).get_day()))?;
+
+    let time_clone = world_time.clone();
+    mods.add_global_function("is_night", move |_lua, ()| Ok(time_clone.lock().unwrap_or_else(PoisonError::into_inner).is_night()))?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+#![allow(clippy::unwrap_used, reason = "a failed unwrap fails the test")]
+#![cfg(test)]
+mod tests {
+    use crate::shared::lights::LightColor;
+    use crate::shared::world_time::{WorldTime, DAY_LENGTH_MS, NIGHT_START_MS};
+
+    #[test]
+    fn test_day_and_night() {
+        let mut time = WorldTime::new(0);
+        assert!(!time.is_night());
+        assert_eq!(time.get_day(), 0);
+
+        time.set_time_ms(NIGHT_START_MS);
+        assert!(time.is_night());
+
+        time.add_time_ms(DAY_LENGTH_MS);
+        assert!(time.is_night());
+        assert_eq!(time.get_day(), 1);
+        assert_eq!(time.get_time_of_day_ms(), NIGHT_START_MS);
+
+        time.add_time_ms(-3 * DAY_LENGTH_MS);
+        assert_eq!(time.get_time_ms(), 0);
+    }
+
+    #[test]
+    fn test_sky_color() {
+        let noon = WorldTime::new(DAY_LENGTH_MS / 4);
+        let midnight = WorldTime::new(DAY_LENGTH_MS * 3 / 4);
+        assert!(noon.get_sky_color() == LightColor::new(255, 255, 255));
+        assert!(midnight.get_sky_color().r < 100);
+        assert!(midnight.get_sky_color().b > midnight.get_sky_color().r);
+
+        // the sky darkens gradually in the evening
+        let mut previous = noon.get_sky_color();
+        for time in (DAY_LENGTH_MS / 4..DAY_LENGTH_MS * 3 / 4).step_by(1000) {
+            let color = WorldTime::new(time).get_sky_color();
+            assert!(color.g <= previous.g);
+            assert!(previous.g - color.g < 5);
+            previous = color;
+        }
+
+        // the day continues smoothly into the next one
+        assert!(WorldTime::new(DAY_LENGTH_MS - 1).get_sky_color() == WorldTime::new(DAY_LENGTH_MS).get_sky_color());
+    }
+}
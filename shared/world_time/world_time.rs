@@ -0,0 +1,90 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::shared::lights::LightColor;
+
+// how long a day and night cycle lasts, the day starts with the sunrise and the second half of it is night
+pub const DAY_LENGTH_MS: i64 = 20 * 60 * 1000;
+pub const NIGHT_START_MS: i64 = DAY_LENGTH_MS / 2;
+
+// sky colors at points of the day, given as fractions of the day, the colors in between are interpolated
+const SKY_COLORS: [(f32, LightColor); 7] = [
+    (0.0, LightColor::new(255, 170, 120)),
+    (0.1, LightColor::new(255, 255, 255)),
+    (0.4, LightColor::new(255, 255, 255)),
+    (0.5, LightColor::new(255, 130, 90)),
+    (0.6, LightColor::new(40, 40, 80)),
+    (0.9, LightColor::new(40, 40, 80)),
+    (1.0, LightColor::new(255, 170, 120)),
+];
+
+/// World time counts how long the world has been running. The server advances it
+/// and sends it to the clients, which only advance it between the updates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldTime {
+    time_ms: i64,
+}
+
+impl WorldTime {
+    #[must_use]
+    pub const fn new(time_ms: i64) -> Self {
+        Self { time_ms }
+    }
+
+    /// returns the total time of the world in milliseconds
+    #[must_use]
+    pub const fn get_time_ms(&self) -> i64 {
+        self.time_ms
+    }
+
+    /// sets the total time, negative times are clamped to 0
+    pub fn set_time_ms(&mut self, time_ms: i64) {
+        self.time_ms = i64::max(0, time_ms);
+    }
+
+    pub fn add_time_ms(&mut self, time_ms: i64) {
+        self.set_time_ms(self.time_ms + time_ms);
+    }
+
+    /// returns how long ago the current day started
+    #[must_use]
+    pub const fn get_time_of_day_ms(&self) -> i64 {
+        self.time_ms.rem_euclid(DAY_LENGTH_MS)
+    }
+
+    /// returns how many days have passed
+    #[must_use]
+    pub const fn get_day(&self) -> i64 {
+        self.time_ms.div_euclid(DAY_LENGTH_MS)
+    }
+
+    #[must_use]
+    pub const fn is_night(&self) -> bool {
+        self.get_time_of_day_ms() >= NIGHT_START_MS
+    }
+
+    /// returns the color of the sky light at this time
+    #[must_use]
+    pub fn get_sky_color(&self) -> LightColor {
+        let progress = self.get_time_of_day_ms() as f32 / DAY_LENGTH_MS as f32;
+        for pair in SKY_COLORS.windows(2) {
+            if let [(start, start_color), (end, end_color)] = pair {
+                if progress >= *start && progress <= *end {
+                    let factor = (progress - start) / (end - start);
+                    let interpolate = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * factor).round() as u8;
+                    return LightColor::new(
+                        interpolate(start_color.r, end_color.r),
+                        interpolate(start_color.g, end_color.g),
+                        interpolate(start_color.b, end_color.b),
+                    );
+                }
+            }
+        }
+        LightColor::new(255, 255, 255)
+    }
+}
+
+/// the server sends the world time to the clients
+#[derive(Serialize, Deserialize)]
+pub struct WorldTimePacket {
+    pub time: WorldTime,
+}